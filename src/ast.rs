@@ -1,20 +1,167 @@
+use crate::boolean;
+use crate::cte::{self, Materialization};
+use crate::decorrelate;
+use crate::dialect::IdentNormalization;
+use crate::equivalence;
+use crate::eval::{self, LikePiece, Value};
+use crate::functions;
+use crate::range;
 use crate::simplify::Simplify;
+use crate::token::Word;
+use crate::walk;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::mem;
+
+/// A single identifier, such as the name of a column, table or schema.
+///
+/// The value is stored as written, without quotes and with escaped quotes resolved.
+/// `quote_style` records the opening quote of quoted identifiers (`"`, `` ` `` or `[`),
+/// so they can be printed back and are exempt from case folding.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) struct Ident {
+    pub(crate) value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) quote_style: Option<char>,
+}
+
+impl Ident {
+    /// Creates an unquoted identifier.
+    pub(crate) fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            quote_style: None,
+        }
+    }
+
+    /// Creates an identifier quoted with the given opening quote.
+    #[cfg(test)]
+    pub(crate) fn with_quote(value: impl Into<String>, quote: char) -> Self {
+        Self {
+            value: value.into(),
+            quote_style: Some(quote),
+        }
+    }
+
+    /// Returns the name the identifier refers to under a normalization policy.
+    /// Quoted identifiers are never folded.
+    pub(crate) fn normalized(&self, policy: IdentNormalization) -> String {
+        match (self.quote_style, policy) {
+            (None, IdentNormalization::Upper) => self.value.to_uppercase(),
+            (None, IdentNormalization::Lower) => self.value.to_lowercase(),
+            _ => self.value.clone(),
+        }
+    }
+}
 
-#[derive(Debug, Clone, PartialEq)]
+impl From<&Word<'_>> for Ident {
+    fn from(word: &Word<'_>) -> Self {
+        Self {
+            value: word.value.to_string(),
+            quote_style: word.quote_style,
+        }
+    }
+}
+
+impl From<&str> for Ident {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Prints the identifier as it was written, quoting it back and doubling any closing quote inside it.
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_identifier(f, &self.value, self.quote_style)
+    }
+}
+
+/// Writes an identifier with its quotes, doubling any closing quote inside it.
+pub(crate) fn write_identifier(
+    f: &mut fmt::Formatter<'_>,
+    value: &str,
+    quote_style: Option<char>,
+) -> fmt::Result {
+    match quote_style {
+        Some(quote) => {
+            let end = if quote == '[' { ']' } else { quote };
+            let escaped = value.replace(end, &format!("{end}{end}"));
+            write!(f, "{quote}{escaped}{end}")
+        }
+        None => write!(f, "{}", value),
+    }
+}
+
+/// A possibly qualified name of a database object: `[catalog.][schema.]name`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) struct ObjectName(pub(crate) Vec<Ident>);
+
+impl ObjectName {
+    /// Returns true if `other` names this object, possibly omitting some of its leading parts.
+    /// `events`, `public.events` and `analytics.public.events` all match `analytics.public.events`.
+    pub(crate) fn matches(&self, other: &ObjectName, policy: IdentNormalization) -> bool {
+        other.0.len() <= self.0.len()
+            && self
+                .0
+                .iter()
+                .rev()
+                .zip(other.0.iter().rev())
+                .all(|(a, b)| a.normalized(policy) == b.normalized(policy))
+    }
+}
+
+/// Builds a name from its dotted representation: `"public.users".into()`.
+impl From<&str> for ObjectName {
+    fn from(value: &str) -> Self {
+        Self(value.split('.').map(Ident::from).collect())
+    }
+}
+
+impl fmt::Display for ObjectName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, part) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{part}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An expression. A chain of operators such as `a OR b OR c ...` is a left-deep tree as high as
+/// it has operands, so expressions are dropped, copied and compared with a loop down the first
+/// operands of chains rather than recursively, and converted to and from JSON on a stack that
+/// grows as needed.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(remote = "Self", tag = "type", content = "value")]
 pub(crate) enum Expr {
     // Literals
     Number(f64),
     String(String),
+    /// `E'...'`: string with backslash escapes, stored with the escapes resolved.
+    EscapeString(String),
+    /// `N'...'`: national character string.
+    NationalString(String),
+    /// `X'...'`: binary string written in hexadecimal.
+    HexString(Vec<u8>),
+    /// `B'...'`: bit string, stored as its binary digits.
+    BitString(String),
+    /// `U&'...'`: string with Unicode escapes, stored with the escapes resolved.
+    UnicodeString(String),
+    /// `$tag$...$tag$`: dollar-quoted string. The tag may be empty.
+    DollarString { tag: String, value: String },
     Boolean(bool),
     Null,
 
     // Identifiers and columns
-    Identifier(String),
+    Identifier(Ident),
     QualifiedIdentifier {
-        table: String,
-        column: String,
+        table: ObjectName,
+        column: Ident,
     },
     Star,
+    QualifiedStar(ObjectName),
 
     // Binary operations
     BinaryOp {
@@ -59,171 +206,799 @@ pub(crate) enum Expr {
 
     // EXISTS
     Exists(Box<SelectStatement>),
+
+    /// Pattern of LIKE, ILIKE or SIMILAR TO with an ESCAPE clause, as the right operand of the
+    /// operator: `'a!%%' ESCAPE '!'`.
+    Escape {
+        pattern: Box<Expr>,
+        escape: Box<Expr>,
+    },
+
+    /// Placeholder for an expression that could not be parsed, left by error recovery.
+    Error,
 }
 
-impl Simplify for Expr {
-    fn simplify(&mut self) -> Result<(), String> {
+impl Expr {
+    /// Copies the expression, with `first` as the left operand of a binary operator instead of its
+    /// own.
+    fn copy_with(&self, first: Expr) -> Expr {
         match self {
-            // Simplify binary operations.
-            Expr::BinaryOp { left, op, right } => {
-                // First, recursively simplify the children
-                left.simplify()?;
+            Expr::BinaryOp { op, right, .. } => Expr::BinaryOp {
+                left: Box::new(first),
+                op: op.clone(),
+                right: right.clone(),
+            },
+            Expr::Number(n) => Expr::Number(*n),
+            Expr::String(s) => Expr::String(s.clone()),
+            Expr::EscapeString(s) => Expr::EscapeString(s.clone()),
+            Expr::NationalString(s) => Expr::NationalString(s.clone()),
+            Expr::HexString(bytes) => Expr::HexString(bytes.clone()),
+            Expr::BitString(s) => Expr::BitString(s.clone()),
+            Expr::UnicodeString(s) => Expr::UnicodeString(s.clone()),
+            Expr::DollarString { tag, value } => Expr::DollarString {
+                tag: tag.clone(),
+                value: value.clone(),
+            },
+            Expr::Boolean(b) => Expr::Boolean(*b),
+            Expr::Null => Expr::Null,
+            Expr::Identifier(name) => Expr::Identifier(name.clone()),
+            Expr::QualifiedIdentifier { table, column } => Expr::QualifiedIdentifier {
+                table: table.clone(),
+                column: column.clone(),
+            },
+            Expr::Star => Expr::Star,
+            Expr::QualifiedStar(table) => Expr::QualifiedStar(table.clone()),
+            Expr::UnaryOp { op, expr } => Expr::UnaryOp {
+                op: op.clone(),
+                expr: expr.clone(),
+            },
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => Expr::Between {
+                expr: expr.clone(),
+                negated: *negated,
+                low: low.clone(),
+                high: high.clone(),
+            },
+            Expr::FunctionCall {
+                name,
+                args,
+                distinct,
+            } => Expr::FunctionCall {
+                name: name.clone(),
+                args: args.clone(),
+                distinct: *distinct,
+            },
+            Expr::Case {
+                operand,
+                when_clauses,
+                else_clause,
+            } => Expr::Case {
+                operand: operand.clone(),
+                when_clauses: when_clauses.clone(),
+                else_clause: else_clause.clone(),
+            },
+            Expr::Subquery(query) => Expr::Subquery(query.clone()),
+            Expr::List(items) => Expr::List(items.clone()),
+            Expr::Exists(query) => Expr::Exists(query.clone()),
+            Expr::Escape { pattern, escape } => Expr::Escape {
+                pattern: pattern.clone(),
+                escape: escape.clone(),
+            },
+            Expr::Error => Expr::Error,
+        }
+    }
+
+    /// Compares the expressions, except for the left operands of binary operators.
+    fn same_node(&self, other: &Expr) -> bool {
+        match (self, other) {
+            (
+                Expr::BinaryOp { op, right, .. },
+                Expr::BinaryOp {
+                    op: other_op,
+                    right: other_right,
+                    ..
+                },
+            ) => op == other_op && right == other_right,
+            (Expr::Number(a), Expr::Number(b)) => a == b,
+            (Expr::String(a), Expr::String(b))
+            | (Expr::EscapeString(a), Expr::EscapeString(b))
+            | (Expr::NationalString(a), Expr::NationalString(b))
+            | (Expr::BitString(a), Expr::BitString(b))
+            | (Expr::UnicodeString(a), Expr::UnicodeString(b)) => a == b,
+            (Expr::HexString(a), Expr::HexString(b)) => a == b,
+            (
+                Expr::DollarString { tag, value },
+                Expr::DollarString {
+                    tag: other_tag,
+                    value: other_value,
+                },
+            ) => tag == other_tag && value == other_value,
+            (Expr::Boolean(a), Expr::Boolean(b)) => a == b,
+            (Expr::Identifier(a), Expr::Identifier(b)) => a == b,
+            (
+                Expr::QualifiedIdentifier { table, column },
+                Expr::QualifiedIdentifier {
+                    table: other_table,
+                    column: other_column,
+                },
+            ) => table == other_table && column == other_column,
+            (Expr::QualifiedStar(a), Expr::QualifiedStar(b)) => a == b,
+            (
+                Expr::UnaryOp { op, expr },
+                Expr::UnaryOp {
+                    op: other_op,
+                    expr: other_expr,
+                },
+            ) => op == other_op && expr == other_expr,
+            (
+                Expr::Between {
+                    expr,
+                    negated,
+                    low,
+                    high,
+                },
+                Expr::Between {
+                    expr: other_expr,
+                    negated: other_negated,
+                    low: other_low,
+                    high: other_high,
+                },
+            ) => {
+                expr == other_expr
+                    && negated == other_negated
+                    && low == other_low
+                    && high == other_high
+            }
+            (
+                Expr::FunctionCall {
+                    name,
+                    args,
+                    distinct,
+                },
+                Expr::FunctionCall {
+                    name: other_name,
+                    args: other_args,
+                    distinct: other_distinct,
+                },
+            ) => name == other_name && args == other_args && distinct == other_distinct,
+            (
+                Expr::Case {
+                    operand,
+                    when_clauses,
+                    else_clause,
+                },
+                Expr::Case {
+                    operand: other_operand,
+                    when_clauses: other_when_clauses,
+                    else_clause: other_else_clause,
+                },
+            ) => {
+                operand == other_operand
+                    && when_clauses == other_when_clauses
+                    && else_clause == other_else_clause
+            }
+            (Expr::Subquery(a), Expr::Subquery(b)) | (Expr::Exists(a), Expr::Exists(b)) => a == b,
+            (Expr::List(a), Expr::List(b)) => a == b,
+            (
+                Expr::Escape { pattern, escape },
+                Expr::Escape {
+                    pattern: other_pattern,
+                    escape: other_escape,
+                },
+            ) => pattern == other_pattern && escape == other_escape,
+            (Expr::Null, Expr::Null) | (Expr::Star, Expr::Star) | (Expr::Error, Expr::Error) => {
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Space left on the stack below which JSON conversions of chains move to a new stack.
+const RED_ZONE: usize = 64 * 1024;
+
+/// Size of the stacks that JSON conversions of chains move to.
+const STACK_SIZE: usize = 1024 * 1024;
+
+impl Serialize for Expr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        stacker::maybe_grow(RED_ZONE, STACK_SIZE, || Expr::serialize(self, serializer))
+    }
+}
+
+impl<'de> Deserialize<'de> for Expr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        stacker::maybe_grow(RED_ZONE, STACK_SIZE, || Expr::deserialize(deserializer))
+    }
+}
+
+impl Drop for Expr {
+    fn drop(&mut self) {
+        // Left operands are detached before their binary operator is dropped, so each drop only
+        // recurses into the right operand.
+        let Expr::BinaryOp { left, .. } = self else {
+            return;
+        };
+        let mut next = mem::replace(&mut **left, Expr::Null);
+        while let Expr::BinaryOp { left, .. } = &mut next {
+            let left = mem::replace(&mut **left, Expr::Null);
+            next = left;
+        }
+    }
+}
+
+impl Clone for Expr {
+    fn clone(&self) -> Self {
+        let mut links = Vec::new();
+        let mut expr = self;
+        while let Expr::BinaryOp { left, .. } = expr {
+            links.push(expr);
+            expr = left;
+        }
+        let mut copy = expr.copy_with(Expr::Null);
+        for link in links.into_iter().rev() {
+            copy = link.copy_with(copy);
+        }
+        copy
+    }
+}
+
+impl PartialEq for Expr {
+    fn eq(&self, other: &Self) -> bool {
+        let (mut expr, mut other) = (self, other);
+        loop {
+            if !expr.same_node(other) {
+                return false;
+            }
+            match (expr, other) {
+                (
+                    Expr::BinaryOp { left, .. },
+                    Expr::BinaryOp {
+                        left: other_left, ..
+                    },
+                ) => (expr, other) = (left, other_left),
+                _ => return true,
+            }
+        }
+    }
+}
+
+impl Expr {
+    /// Data type of a literal, or None if the expression is not a typed literal.
+    #[cfg(test)]
+    pub(crate) fn literal_type(&self) -> Option<DataType> {
+        match self {
+            Expr::Number(n) if n.fract() == 0.0 => Some(DataType::Integer),
+            Expr::Number(_) => Some(DataType::Double),
+            Expr::String(_)
+            | Expr::EscapeString(_)
+            | Expr::NationalString(_)
+            | Expr::UnicodeString(_)
+            | Expr::DollarString { .. } => Some(DataType::Text),
+            Expr::HexString(_) => Some(DataType::Blob),
+            Expr::BitString(_) => Some(DataType::Custom("BIT".to_string())),
+            Expr::Boolean(_) => Some(DataType::Boolean),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the expression is a column reference: `x` or `t.x`.
+    pub(crate) fn is_column(&self) -> bool {
+        matches!(self, Expr::Identifier(_) | Expr::QualifiedIdentifier { .. })
+    }
+
+    /// The operands of the expression. Subqueries are not included, as they are not expressions.
+    pub(crate) fn children(&self) -> Vec<&Expr> {
+        match self {
+            Expr::BinaryOp { left, right, .. } => vec![left, right],
+            Expr::UnaryOp { expr, .. } => vec![expr],
+            Expr::FunctionCall { args, .. } | Expr::List(args) => args.iter().collect(),
+            Expr::Case {
+                operand,
+                when_clauses,
+                else_clause,
+            } => {
+                let mut children: Vec<&Expr> = Vec::new();
+                children.extend(operand.as_deref());
+                for clause in when_clauses {
+                    children.push(&clause.condition);
+                    children.push(&clause.result);
+                }
+                children.extend(else_clause.as_deref());
+                children
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => vec![expr, low, high],
+            Expr::Escape { pattern, escape } => vec![pattern, escape],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Mutable version of [Expr::children].
+    pub(crate) fn children_mut(&mut self) -> Vec<&mut Expr> {
+        match self {
+            Expr::BinaryOp { left, right, .. } => vec![left, right],
+            Expr::UnaryOp { expr, .. } => vec![expr],
+            Expr::FunctionCall { args, .. } | Expr::List(args) => args.iter_mut().collect(),
+            Expr::Case {
+                operand,
+                when_clauses,
+                else_clause,
+            } => {
+                let mut children: Vec<&mut Expr> = Vec::new();
+                children.extend(operand.as_deref_mut());
+                for clause in when_clauses {
+                    children.push(&mut clause.condition);
+                    children.push(&mut clause.result);
+                }
+                children.extend(else_clause.as_deref_mut());
+                children
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => vec![expr, low, high],
+            Expr::Escape { pattern, escape } => vec![pattern, escape],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Rewrites a call to COALESCE or NULLIF as the CASE expression it stands for, for engines
+    /// that lack them: `COALESCE(a, b)` is `CASE WHEN a IS NOT NULL THEN a ELSE b END`, and
+    /// `NULLIF(a, b)` is `CASE WHEN a = b THEN NULL ELSE a END`. Other expressions are unchanged.
+    pub(crate) fn expand_null_function(&mut self) {
+        let Expr::FunctionCall {
+            name,
+            args,
+            distinct: false,
+        } = self
+        else {
+            return;
+        };
+        let case = |when_clauses, else_clause: &Expr| Expr::Case {
+            operand: None,
+            when_clauses,
+            else_clause: Some(Box::new(else_clause.clone())),
+        };
+        *self = match (name.to_uppercase().as_str(), args.as_slice()) {
+            ("COALESCE", [arg]) => arg.clone(),
+            ("COALESCE", [args @ .., last]) => case(
+                args.iter()
+                    .map(|arg| WhenClause {
+                        condition: Expr::BinaryOp {
+                            left: Box::new(arg.clone()),
+                            op: BinaryOperator::IsNot,
+                            right: Box::new(Expr::Null),
+                        },
+                        result: arg.clone(),
+                    })
+                    .collect(),
+                last,
+            ),
+            ("NULLIF", [a, b]) => case(
+                vec![WhenClause {
+                    condition: Expr::BinaryOp {
+                        left: Box::new(a.clone()),
+                        op: BinaryOperator::Eq,
+                        right: Box::new(b.clone()),
+                    },
+                    result: Expr::Null,
+                }],
+                a,
+            ),
+            _ => return,
+        };
+    }
+}
+
+impl Expr {
+    /// Simplifies a condition used as a filter, in which NULL rejects a row as FALSE does, so
+    /// predicates that no value satisfies can be replaced with FALSE.
+    fn simplify_condition(&mut self) -> Result<(), String> {
+        self.simplify()?;
+        range::simplify_filter(self);
+        Ok(())
+    }
+
+    /// Simplifies a left-deep chain of the same AND or OR operator: `a OR b OR c ...`.
+    /// The chain is unlinked into its operands, which are simplified one by one and folded back
+    /// from the left, giving the same result as the recursive rules without deep recursion.
+    fn simplify_chain(&mut self) -> Result<(), String> {
+        let Expr::BinaryOp { op, .. } = self else {
+            return self.simplify();
+        };
+        let op = op.clone();
+
+        // Operands are pushed from the rightmost one, so the leftmost operand ends up last.
+        let mut operands = Vec::new();
+        let mut current = mem::replace(self, Expr::Null);
+        while let Expr::BinaryOp {
+            left,
+            op: chain_op,
+            right,
+        } = &mut current
+            && *chain_op == op
+        {
+            operands.push(mem::replace(&mut **right, Expr::Null));
+            let left = mem::replace(&mut **left, Expr::Null);
+            current = left;
+        }
+        operands.push(current);
+
+        let mut result = operands.pop().unwrap_or(Expr::Null);
+        result.simplify()?;
+        while let Some(mut operand) = operands.pop() {
+            operand.simplify()?;
+            result = Expr::BinaryOp {
+                left: Box::new(result),
+                op: op.clone(),
+                right: Box::new(operand),
+            };
+            result.fold_binary();
+        }
+
+        *self = result;
+        boolean::normalize(self);
+        range::simplify(self, false);
+        Ok(())
+    }
+
+    /// Simplifies a chain of other operators, such as `a + b - c ...`. The operators are detached
+    /// from their left operands and simplified from the innermost one, each after its operands,
+    /// which gives the same result as the recursive rules without deep recursion.
+    fn simplify_links(&mut self) -> Result<(), String> {
+        let mut links = Vec::new();
+        let mut current = mem::replace(self, Expr::Null);
+        while let Expr::BinaryOp { left, op, .. } = &mut current
+            && !matches!(op, BinaryOperator::And | BinaryOperator::Or)
+        {
+            let left = mem::replace(&mut **left, Expr::Null);
+            links.push(current);
+            current = left;
+        }
+
+        current.simplify()?;
+        while let Some(mut link) = links.pop() {
+            if let Expr::BinaryOp { left, right, .. } = &mut link {
                 right.simplify()?;
+                **left = current;
+            }
+            link.fold_binary();
+            current = link;
+        }
+        *self = current;
+        Ok(())
+    }
 
-                // Attempt to simplify with basic algebraic rules.
-                // For literals, we can apply a simple basic arithmetic to unnest the query and apply the operations in place.
-                match (&**left, op, &**right) {
-                    (Expr::Number(a), BinaryOperator::Plus, Expr::Number(b)) if *b == 0.0 => {
-                        *self = Expr::Number(*a);
-                    }
-                    (Expr::Number(a), BinaryOperator::Plus, Expr::Number(b)) if *a == 0.0 => {
-                        *self = Expr::Number(*b);
-                    }
-                    (Expr::Number(a), BinaryOperator::Minus, Expr::Number(b)) if *b == 0.0 => {
-                        *self = Expr::Number(*a);
-                    }
-                    (Expr::Number(a), BinaryOperator::Minus, Expr::Number(b)) if *a == 0.0 => {
-                        *self = Expr::Number(*b);
-                    }
-                    (Expr::Number(a), BinaryOperator::Multiply, Expr::Number(b))
-                        if *b == 0.0 || *a == 0.0 =>
-                    {
-                        *self = Expr::Number(0.0);
-                    }
-                    (Expr::Number(a), BinaryOperator::Multiply, Expr::Number(b)) if *a == 1.0 => {
-                        *self = Expr::Number(*b);
-                    }
-                    (Expr::Number(a), BinaryOperator::Multiply, Expr::Number(b)) if *b == 1.0 => {
-                        *self = Expr::Number(*a);
-                    }
-                    (Expr::Number(a), BinaryOperator::Divide, Expr::Number(b)) if *b == 1.0 => {
-                        *self = Expr::Number(*a);
-                    }
-                    (Expr::Number(a), BinaryOperator::Minus, Expr::Number(b)) if *b == 0.0 => {
-                        *self = Expr::Number(*a);
-                    }
-                    (Expr::Number(a), BinaryOperator::Minus, Expr::Number(b)) if *a == 0.0 => {
-                        *self = Expr::Number(*b);
-                    }
-                    (Expr::Number(a), BinaryOperator::Plus, Expr::Number(b)) => {
-                        *self = Expr::Number(a + b);
-                    }
-                    (Expr::Number(a), BinaryOperator::Minus, Expr::Number(b)) => {
-                        *self = Expr::Number(a - b);
-                    }
-                    (Expr::Number(a), BinaryOperator::Multiply, Expr::Number(b)) => {
-                        *self = Expr::Number(a * b);
-                    }
-                    (Expr::Number(a), BinaryOperator::Divide, Expr::Number(b)) if *b != 0.0 => {
-                        *self = Expr::Number(a / b);
-                    }
-                    (Expr::Number(a), BinaryOperator::Eq, Expr::Number(b)) => {
-                        *self = Expr::Boolean(a == b)
-                    }
-                    (Expr::Number(a), BinaryOperator::Gt, Expr::Number(b)) => {
-                        *self = Expr::Boolean(a > b)
-                    }
-                    (Expr::Number(a), BinaryOperator::Lt, Expr::Number(b)) => {
-                        *self = Expr::Boolean(a < b)
-                    }
-                    (Expr::Number(a), BinaryOperator::Ge, Expr::Number(b)) => {
-                        *self = Expr::Boolean(a >= b)
-                    }
-                    (Expr::Number(a), BinaryOperator::Le, Expr::Number(b)) => {
-                        *self = Expr::Boolean(a <= b)
-                    }
-                    (Expr::Number(a), BinaryOperator::Modulo, Expr::Number(b)) => {
-                        *self = Expr::Number(a % b)
-                    }
-                    (Expr::Boolean(a), BinaryOperator::And, Expr::Boolean(b)) => {
-                        *self = Expr::Boolean(*a && *b)
-                    }
-                    (Expr::Boolean(a), BinaryOperator::Or, Expr::Boolean(b)) => {
-                        *self = Expr::Boolean(*a || *b)
-                    }
-                    // TRUE AND x → x
-                    (Expr::Boolean(true), BinaryOperator::And, right) => {
-                        *self = (*right).clone();
-                    }
-                    // x AND TRUE → x
-                    (left, BinaryOperator::And, Expr::Boolean(true)) => {
-                        *self = (*left).clone();
-                    }
-                    // FALSE AND x → FALSE
-                    (Expr::Boolean(false), BinaryOperator::And, _) => {
-                        *self = Expr::Boolean(false);
-                    }
-                    // x AND FALSE → FALSE
-                    (_, BinaryOperator::And, Expr::Boolean(false)) => {
-                        *self = Expr::Boolean(false);
-                    }
+    /// Applies the algebraic rules to a binary operation whose operands are already simplified.
+    /// Constant operations are evaluated with the NULL semantics of [crate::eval].
+    fn fold_binary(&mut self) {
+        let Expr::BinaryOp { left, op, right } = self else {
+            return;
+        };
+        if let (Some(a), Some(b)) = (Value::from_expr(left), Value::from_expr(right)) {
+            if let Some(value) = eval::binary(&a, op, &b) {
+                *self = value.into_expr();
+            }
+            return;
+        }
+        if let Expr::Escape { pattern, escape } = &**right {
+            let operands = (
+                Value::from_expr(left),
+                Value::from_expr(pattern),
+                Value::from_expr(escape),
+            );
+            if let (Some(value), Some(pattern), Some(escape)) = operands {
+                if let Some(result) = eval::escaped(&value, op, &pattern, &escape) {
+                    *self = result.into_expr();
+                }
+                return;
+            }
+        }
+        match (&**left, &*op, &**right) {
+            // x + NULL → NULL, whatever x is.
+            (Expr::Null, op, _) | (_, op, Expr::Null) if eval::propagates_null(op) => {
+                *self = Expr::Null;
+            }
+            // TRUE AND x → x
+            (Expr::Boolean(true), BinaryOperator::And, right) => {
+                *self = (*right).clone();
+            }
+            // x AND TRUE → x
+            (left, BinaryOperator::And, Expr::Boolean(true)) => {
+                *self = (*left).clone();
+            }
+            // FALSE AND x → FALSE
+            (Expr::Boolean(false), BinaryOperator::And, _) => {
+                *self = Expr::Boolean(false);
+            }
+            // x AND FALSE → FALSE
+            (_, BinaryOperator::And, Expr::Boolean(false)) => {
+                *self = Expr::Boolean(false);
+            }
 
-                    // TRUE OR x → TRUE
-                    (Expr::Boolean(true), BinaryOperator::Or, _) => {
-                        *self = Expr::Boolean(true);
-                    }
-                    // x OR TRUE → TRUE
-                    (_, BinaryOperator::Or, Expr::Boolean(true)) => {
-                        *self = Expr::Boolean(true);
-                    }
-                    // FALSE OR x → x
-                    (Expr::Boolean(false), BinaryOperator::Or, right) => {
-                        *self = (*right).clone();
-                    }
-                    // x OR FALSE → x
-                    (left, BinaryOperator::Or, Expr::Boolean(false)) => {
-                        *self = (*left).clone();
-                    }
-                    (Expr::String(a), BinaryOperator::Concat, Expr::String(b)) => {
-                        *self = Expr::String(format!("{a}{b}"));
-                    }
-                    (Expr::String(a), BinaryOperator::Like, Expr::String(pattern)) => {
-                        let regex = pattern.replace('%', ".*").replace('_', ".");
-                        let re = regex::Regex::new(&format!("^{regex}$")).unwrap();
-                        *self = Expr::Boolean(re.is_match(a));
-                    }
-                    (Expr::String(a), BinaryOperator::NotLike, Expr::String(pattern)) => {
-                        let regex = pattern.replace('%', ".*").replace('_', ".");
-                        let re = regex::Regex::new(&format!("^{regex}$")).unwrap();
-                        *self = Expr::Boolean(!re.is_match(a));
-                    }
-                    // x IN (x, y, z) → TRUE
-                    (Expr::Number(a), BinaryOperator::In, Expr::List(items)) => {
-                        let result = items
-                            .iter()
-                            .any(|e| matches!(e, Expr::Number(b) if *a == *b));
-                        *self = Expr::Boolean(result);
-                    }
-                    (Expr::String(a), BinaryOperator::In, Expr::List(items)) => {
-                        let result = items
-                            .iter()
-                            .any(|e| matches!(e, Expr::String(b) if *a == *b));
-                        *self = Expr::Boolean(result);
-                    }
-                    (Expr::String(a), BinaryOperator::NotIn, Expr::List(items)) => {
-                        let result = !items.iter().any(|e| matches!(e, Expr::String(b) if a == b));
-                        *self = Expr::Boolean(result);
-                    }
-                    (Expr::Number(a), BinaryOperator::NotIn, Expr::List(items)) => {
-                        let result = !items.iter().any(|e| matches!(e, Expr::Number(b) if a == b));
-                        *self = Expr::Boolean(result);
+            // TRUE OR x → TRUE
+            (Expr::Boolean(true), BinaryOperator::Or, _) => {
+                *self = Expr::Boolean(true);
+            }
+            // x OR TRUE → TRUE
+            (_, BinaryOperator::Or, Expr::Boolean(true)) => {
+                *self = Expr::Boolean(true);
+            }
+            // FALSE OR x → x
+            (Expr::Boolean(false), BinaryOperator::Or, right) => {
+                *self = (*right).clone();
+            }
+            // x OR FALSE → x
+            (left, BinaryOperator::Or, Expr::Boolean(false)) => {
+                *self = (*left).clone();
+            }
+            // 1 IN (1, x) → TRUE, 2 NOT IN (1, NULL) → NULL
+            (left, BinaryOperator::In | BinaryOperator::NotIn, Expr::List(items)) => {
+                let Some(value) = Value::from_expr(left) else {
+                    return;
+                };
+                let items: Vec<_> = items.iter().map(Value::from_expr).collect();
+                let negated = *op == BinaryOperator::NotIn;
+                if let Some(result) = eval::in_list(&value, &items, negated) {
+                    *self = result.into_expr();
+                }
+            }
+            // x LIKE 'abc' → x = 'abc', x LIKE 'abc%' → x >= 'abc' AND x < 'abd'
+            (left, BinaryOperator::Like | BinaryOperator::NotLike, pattern) => {
+                if let Some(rewritten) = rewrite_like(left, op, pattern) {
+                    *self = rewritten;
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
+
+impl Expr {
+    /// Applies the rules of CASE to an expression whose operands are already simplified. Branches
+    /// that cannot be taken are removed, the first branch that is always taken ends the
+    /// expression, and adjacent branches with the same result are merged. What remains is replaced
+    /// by its only possible value, or by the COALESCE or NULLIF call it spells out.
+    fn fold_case(&mut self) {
+        let Expr::Case {
+            operand,
+            when_clauses,
+            else_clause,
+        } = self
+        else {
+            return;
+        };
+        if else_clause.as_deref() == Some(&Expr::Null) {
+            *else_clause = None;
+        }
+
+        let mut clauses = Vec::new();
+        for clause in mem::take(when_clauses) {
+            // Branches whose condition is NULL are not taken, like those whose condition is FALSE.
+            let condition = match operand.as_deref() {
+                None => Value::from_expr(&clause.condition),
+                Some(operand) => match (
+                    Value::from_expr(operand),
+                    Value::from_expr(&clause.condition),
+                ) {
+                    (_, Some(Value::Null)) => Some(Value::Null),
+                    (Some(operand), Some(value)) => {
+                        eval::binary(&operand, &BinaryOperator::Eq, &value)
                     }
+                    _ => None,
+                },
+            };
+            match condition {
+                Some(Value::Boolean(false) | Value::Null) => {}
+                // Later branches can never be reached.
+                Some(Value::Boolean(true)) => {
+                    *else_clause = Some(Box::new(clause.result));
+                    break;
+                }
+                _ => clauses.push(clause),
+            }
+        }
 
-                    _ => {}
+        // WHEN a THEN x WHEN b THEN x → WHEN a OR b THEN x
+        if operand.is_none() {
+            let mut merged: Vec<WhenClause> = Vec::new();
+            for clause in clauses {
+                match merged.last_mut() {
+                    Some(last) if last.result == clause.result => {
+                        let mut condition = Expr::BinaryOp {
+                            left: Box::new(mem::replace(&mut last.condition, Expr::Null)),
+                            op: BinaryOperator::Or,
+                            right: Box::new(clause.condition),
+                        };
+                        condition.fold_binary();
+                        last.condition = condition;
+                    }
+                    _ => merged.push(clause),
                 }
             }
+            clauses = merged;
+        }
 
-            // Simplify unary ops
-            Expr::UnaryOp { op, expr } => match (op, expr.as_mut()) {
-                (UnaryOperator::Minus, Expr::Number(a)) => {
-                    *self = Expr::Number(*a * (-1.0));
+        // The last branches are not needed when they give the same result as ELSE.
+        let null = Expr::Null;
+        let fallback = else_clause.as_deref().unwrap_or(&null);
+        while clauses
+            .last()
+            .is_some_and(|clause| clause.result == *fallback)
+        {
+            clauses.pop();
+        }
+
+        if clauses.is_empty() {
+            *self = else_clause.take().map_or(Expr::Null, |expr| *expr);
+            return;
+        }
+        *when_clauses = clauses;
+        if let Some(call) = self.null_function() {
+            *self = call;
+        }
+    }
+
+    /// The call to COALESCE or NULLIF that a searched CASE expression spells out, if any:
+    ///
+    /// - `CASE WHEN x IS NULL THEN y ELSE x END` is `COALESCE(x, y)`.
+    /// - `CASE WHEN x IS NOT NULL THEN x WHEN y IS NOT NULL THEN y ELSE z END` is
+    ///   `COALESCE(x, y, z)`.
+    /// - `CASE WHEN x = y THEN NULL ELSE x END` is `NULLIF(x, y)`.
+    fn null_function(&self) -> Option<Expr> {
+        let Expr::Case {
+            operand: None,
+            when_clauses,
+            else_clause,
+        } = self
+        else {
+            return None;
+        };
+        let null = Expr::Null;
+        let else_value = else_clause.as_deref().unwrap_or(&null);
+        let call = |name: &str, args| Expr::FunctionCall {
+            name: name.to_string(),
+            args,
+            distinct: false,
+        };
+
+        if let [
+            WhenClause {
+                condition: Expr::BinaryOp { left, op, right },
+                result,
+            },
+        ] = when_clauses.as_slice()
+        {
+            match (op, &**right) {
+                (BinaryOperator::Is, Expr::Null) if **left == *else_value => {
+                    return Some(call("COALESCE", vec![else_value.clone(), result.clone()]));
                 }
-                (UnaryOperator::Plus, Expr::Number(a)) => {
-                    *self = Expr::Number(*a);
+                (BinaryOperator::Eq, _) if *result == Expr::Null && **left == *else_value => {
+                    return Some(call("NULLIF", vec![(**left).clone(), (**right).clone()]));
                 }
-                (UnaryOperator::Not, Expr::Boolean(a)) => *self = Expr::Boolean(!*a),
                 _ => {}
-            },
+            }
+        }
+
+        let mut args = Vec::new();
+        for clause in when_clauses {
+            match &clause.condition {
+                Expr::BinaryOp {
+                    left,
+                    op: BinaryOperator::IsNot,
+                    right,
+                } if **right == Expr::Null && **left == clause.result => {
+                    args.push(clause.result.clone());
+                }
+                _ => return None,
+            }
+        }
+        args.extend(else_clause.as_deref().cloned());
+        match args.len() {
+            1 => args.pop(),
+            _ => Some(call("COALESCE", args)),
+        }
+    }
+}
+
+/// Rewrites `x [NOT] LIKE 'abc'` into `x = 'abc'` or `x <> 'abc'`, and `col LIKE 'abc%'` into the
+/// range `col >= 'abc' AND col < 'abd'`, which can use an index on the column.
+///
+/// Patterns ending with a space are kept, as some databases ignore trailing spaces when comparing
+/// strings but not in LIKE. The range assumes strings are ordered by code point, as in binary
+/// collations.
+fn rewrite_like(left: &Expr, op: &BinaryOperator, pattern: &Expr) -> Option<Expr> {
+    let (pattern, escape) = match pattern {
+        Expr::Escape { pattern, escape } => (&**pattern, Some(&**escape)),
+        pattern => (pattern, None),
+    };
+    let Some(Value::String(pattern)) = Value::from_expr(pattern) else {
+        return None;
+    };
+    let escape = match escape.map(Value::from_expr) {
+        Some(Some(Value::String(escape))) => Some(eval::single_char(&escape)?),
+        Some(_) => return None,
+        // Without an ESCAPE clause, backslashes are escapes in some databases only.
+        None if pattern.contains('\\') => return None,
+        None => None,
+    };
+    let pieces = eval::like_pieces(&pattern, escape)?;
+    let (literal, prefix) = match pieces.split_last() {
+        Some((LikePiece::Any, literal)) => (literal, true),
+        _ => (&pieces[..], false),
+    };
+    let literal: String = literal
+        .iter()
+        .map(|piece| match piece {
+            LikePiece::Char(c) => Some(*c),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    if literal.is_empty() || literal.ends_with(' ') {
+        return None;
+    }
+
+    let compare = |op, value| Expr::BinaryOp {
+        left: Box::new(left.clone()),
+        op,
+        right: Box::new(Expr::String(value)),
+    };
+    match (op, prefix) {
+        (BinaryOperator::Like, false) => Some(compare(BinaryOperator::Eq, literal)),
+        (BinaryOperator::NotLike, false) => Some(compare(BinaryOperator::Neq, literal)),
+        (BinaryOperator::Like, true)
+            if matches!(left, Expr::Identifier(_) | Expr::QualifiedIdentifier { .. }) =>
+        {
+            let upper = prefix_upper_bound(&literal)?;
+            Some(Expr::BinaryOp {
+                left: Box::new(compare(BinaryOperator::Ge, literal)),
+                op: BinaryOperator::And,
+                right: Box::new(compare(BinaryOperator::Lt, upper)),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Smallest string greater than every string starting with the prefix, in code point order: the
+/// prefix with its last character incremented.
+fn prefix_upper_bound(prefix: &str) -> Option<String> {
+    let mut chars: Vec<char> = prefix.chars().collect();
+    let last = chars.pop()?;
+    chars.push(char::from_u32(u32::from(last) + 1)?);
+    Some(chars.into_iter().collect())
+}
+
+impl Simplify for Expr {
+    fn simplify(&mut self) -> Result<(), String> {
+        match self {
+            // Simplify binary operations.
+            // Flat AND/OR chains are simplified with a loop, so long generated chains don't recurse.
+            Expr::BinaryOp {
+                op: BinaryOperator::And | BinaryOperator::Or,
+                ..
+            } => self.simplify_chain()?,
+
+            // Chains of other operators are simplified with a loop too.
+            Expr::BinaryOp { .. } => self.simplify_links()?,
+
+            // Simplify unary ops
+            Expr::UnaryOp { op, expr } => {
+                expr.simplify()?;
+                if let Some(value) = Value::from_expr(expr).and_then(|v| eval::unary(op, &v)) {
+                    *self = value.into_expr();
+                } else if *op == UnaryOperator::Not {
+                    // NOT (a AND b) → NOT a OR NOT b, NOT a < b → a >= b
+                    *self = boolean::negate(mem::replace(expr, Expr::Null));
+                    boolean::normalize(self);
+                }
+            }
 
             // Simplify lists
             Expr::List(items) => {
@@ -242,12 +1017,22 @@ impl Simplify for Expr {
                     op.simplify()?;
                 }
                 for clause in when_clauses {
-                    clause.condition.simplify()?;
+                    if operand.is_some() {
+                        clause.condition.simplify()?;
+                    } else {
+                        clause.condition.simplify_condition()?;
+                    }
                     clause.result.simplify()?;
                 }
                 if let Some(else_expr) = else_clause {
                     else_expr.simplify()?;
                 }
+                self.fold_case();
+            }
+
+            Expr::Escape { pattern, escape } => {
+                pattern.simplify()?;
+                escape.simplify()?;
             }
 
             // Simplify subqueries
@@ -258,18 +1043,49 @@ impl Simplify for Expr {
 
             // Simplify BETWEEN
             Expr::Between {
-                expr, low, high, ..
+                expr,
+                negated,
+                low,
+                high,
             } => {
                 expr.simplify()?;
                 low.simplify()?;
                 high.simplify()?;
+                let (Some(value), Some(low), Some(high)) = (
+                    Value::from_expr(expr),
+                    Value::from_expr(low),
+                    Value::from_expr(high),
+                ) else {
+                    return Ok(());
+                };
+                if let Some(result) = eval::between(&value, &low, &high, *negated) {
+                    *self = result.into_expr();
+                }
             }
 
-            // In function calls we can simplify the arguments.
-            Expr::FunctionCall { args, .. } => {
-                for arg in args {
+            // In function calls we can simplify the arguments, and evaluate deterministic
+            // functions once all of them are literals.
+            Expr::FunctionCall {
+                name,
+                args,
+                distinct,
+            } => {
+                for arg in args.iter_mut() {
                     arg.simplify()?;
                 }
+                if *distinct {
+                    return Ok(());
+                }
+                let Some(values) = args
+                    .iter()
+                    .map(Value::from_expr)
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return Ok(());
+                };
+                if let Some(result) = functions::registry().evaluate(name, &values) {
+                    *self = result.into_expr();
+                }
             }
 
             // Literals and identifiers cannot be simplified.
@@ -280,13 +1096,13 @@ impl Simplify for Expr {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct WhenClause {
     pub(crate) condition: Expr,
     pub(crate) result: Expr,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum BinaryOperator {
     // Arithmetic
     Plus,
@@ -310,6 +1126,14 @@ pub(crate) enum BinaryOperator {
     // String
     Like,
     NotLike,
+    /// Case-insensitive LIKE.
+    ILike,
+    NotILike,
+    SimilarTo,
+    NotSimilarTo,
+    /// Regular expression match: `~`, or `~*` ignoring case.
+    RegexMatch,
+    RegexIMatch,
     Concat,
 
     // Set
@@ -321,14 +1145,55 @@ pub(crate) enum BinaryOperator {
     IsNot,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl BinaryOperator {
+    /// The operator giving the negation of this one, as `>=` for `<` or `IS NOT` for `IS`.
+    /// Comparisons are NULL whenever their negation is, so `NOT a < b` is always `a >= b`.
+    pub(crate) fn negated(&self) -> Option<BinaryOperator> {
+        use BinaryOperator::*;
+        let negated = match self {
+            Eq => Neq,
+            Neq => Eq,
+            Lt => Ge,
+            Ge => Lt,
+            Gt => Le,
+            Le => Gt,
+            Like => NotLike,
+            NotLike => Like,
+            ILike => NotILike,
+            NotILike => ILike,
+            SimilarTo => NotSimilarTo,
+            NotSimilarTo => SimilarTo,
+            In => NotIn,
+            NotIn => In,
+            Is => IsNot,
+            IsNot => Is,
+            _ => return None,
+        };
+        Some(negated)
+    }
+
+    /// Returns true if the operator matches a pattern that can have an ESCAPE clause.
+    pub(crate) fn accepts_escape(&self) -> bool {
+        matches!(
+            self,
+            BinaryOperator::Like
+                | BinaryOperator::NotLike
+                | BinaryOperator::ILike
+                | BinaryOperator::NotILike
+                | BinaryOperator::SimilarTo
+                | BinaryOperator::NotSimilarTo
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum UnaryOperator {
     Plus,
     Minus,
     Not,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct SelectStatement {
     pub(crate) distinct: bool,
     pub(crate) columns: Vec<SelectItem>,
@@ -351,75 +1216,317 @@ impl Simplify for SelectStatement {
         };
 
         if let Some(clause) = self.where_clause.as_mut() {
-            clause.simplify()?;
+            clause.simplify_condition()?;
         };
+        // Equalities imply predicates on the other columns of their class, which can in turn be
+        // merged with the ranges of those columns.
+        if equivalence::infer_predicates(self)
+            && let Some(clause) = self.where_clause.as_mut()
+        {
+            range::simplify_filter(clause);
+        }
 
         for g_clause in self.group_by.iter_mut() {
             g_clause.simplify()?;
         }
 
         if let Some(having_clause) = self.having.as_mut() {
-            having_clause.simplify()?;
+            having_clause.simplify_condition()?;
         };
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub(crate) enum SelectItem {
-    Star,
-    ExprWithAlias { expr: Expr, alias: Option<String> },
+    Star(WildcardOptions),
+    QualifiedWildcard(ObjectName, WildcardOptions),
+    ExprWithAlias { expr: Expr, alias: Option<Ident> },
 }
 
 impl Simplify for SelectItem {
     fn simplify(&mut self) -> Result<(), String> {
-        if let Self::ExprWithAlias { expr, .. } = self {
-            expr.simplify()?;
+        match self {
+            Self::ExprWithAlias { expr, .. } => expr.simplify()?,
+            Self::Star(options) | Self::QualifiedWildcard(_, options) => options.simplify()?,
         }
         Ok(())
     }
 }
-#[derive(Debug, Clone, PartialEq)]
+
+/// Modifiers that can follow a wildcard in a select list.
+/// ```sql
+/// SELECT * EXCLUDE (a, b) ...
+/// SELECT t.* EXCEPT (a) ...
+/// SELECT * REPLACE (price * 2 AS price) ...
+/// SELECT * RENAME (a AS b) ...
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) struct WildcardOptions {
+    pub(crate) exclude: Vec<Ident>,
+    /// Same as [WildcardOptions::exclude], kept apart to preserve the BigQuery spelling.
+    pub(crate) except: Vec<Ident>,
+    pub(crate) replace: Vec<ReplaceItem>,
+    pub(crate) rename: Vec<RenameItem>,
+}
+
+impl Simplify for WildcardOptions {
+    fn simplify(&mut self) -> Result<(), String> {
+        for item in self.replace.iter_mut() {
+            item.expr.simplify()?;
+        }
+        Ok(())
+    }
+}
+
+/// `expr AS column` inside a `REPLACE` wildcard modifier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ReplaceItem {
+    pub(crate) expr: Expr,
+    pub(crate) column: Ident,
+}
+
+/// `column AS alias` inside a `RENAME` wildcard modifier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RenameItem {
+    pub(crate) column: Ident,
+    pub(crate) alias: Ident,
+}
+/// A relation of a FROM clause. Joins are left-deep trees as high as they have joins, so table
+/// references are dropped, copied and compared with a loop down the left sides of joins, converted
+/// to and from JSON like [Expr], and the passes over them follow [TableReference::join_chain].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(remote = "Self", tag = "type", content = "value")]
 pub(crate) enum TableReference {
     Table {
-        name: String,
-        alias: Option<String>,
+        name: ObjectName,
+        alias: Option<Ident>,
     },
     Join {
         left: Box<TableReference>,
         join_type: JoinType,
         right: Box<TableReference>,
-        on: Option<Expr>,
+        constraint: JoinConstraint,
     },
     Subquery {
         query: Box<SelectStatement>,
-        alias: String,
+        alias: Ident,
+        lateral: bool,
     },
+    /// Placeholder for a FROM clause that could not be parsed, left by error recovery.
+    Error,
 }
 
-impl Simplify for TableReference {
-    fn simplify(&mut self) -> Result<(), String> {
+impl TableReference {
+    /// The first relation of a chain of joins, and the joins that follow it in the order they are
+    /// written, each with the relation it adds. A relation that is not a join is a chain of one.
+    pub(crate) fn join_chain(
+        &self,
+    ) -> (
+        &TableReference,
+        Vec<(&JoinType, &TableReference, &JoinConstraint)>,
+    ) {
+        let mut joins = Vec::new();
+        let mut first = self;
+        while let TableReference::Join {
+            left,
+            join_type,
+            right,
+            constraint,
+        } = first
+        {
+            joins.push((join_type, &**right, constraint));
+            first = left;
+        }
+        joins.reverse();
+        (first, joins)
+    }
+
+    /// Mutable version of [TableReference::join_chain].
+    pub(crate) fn join_chain_mut(
+        &mut self,
+    ) -> (
+        &mut TableReference,
+        Vec<(&mut JoinType, &mut TableReference, &mut JoinConstraint)>,
+    ) {
+        let mut joins = Vec::new();
+        let mut first = self;
+        while let TableReference::Join {
+            left,
+            join_type,
+            right,
+            constraint,
+        } = first
+        {
+            joins.push((join_type, &mut **right, constraint));
+            first = left;
+        }
+        joins.reverse();
+        (first, joins)
+    }
+
+    /// Copies the table reference, with `left` as the left side of a join instead of its own.
+    fn copy_with(&self, left: TableReference) -> TableReference {
         match self {
-            Self::Join {
-                left, right, on, ..
-            } => {
-                left.as_mut().simplify()?;
-                right.as_mut().simplify()?;
+            TableReference::Join {
+                join_type,
+                right,
+                constraint,
+                ..
+            } => TableReference::Join {
+                left: Box::new(left),
+                join_type: join_type.clone(),
+                right: right.clone(),
+                constraint: constraint.clone(),
+            },
+            TableReference::Table { name, alias } => TableReference::Table {
+                name: name.clone(),
+                alias: alias.clone(),
+            },
+            TableReference::Subquery {
+                query,
+                alias,
+                lateral,
+            } => TableReference::Subquery {
+                query: query.clone(),
+                alias: alias.clone(),
+                lateral: *lateral,
+            },
+            TableReference::Error => TableReference::Error,
+        }
+    }
 
-                if let Some(on_expr) = on {
-                    on_expr.simplify()?;
-                };
+    /// Compares the table references, except for the left sides of joins.
+    fn same_node(&self, other: &TableReference) -> bool {
+        match (self, other) {
+            (
+                TableReference::Join {
+                    join_type,
+                    right,
+                    constraint,
+                    ..
+                },
+                TableReference::Join {
+                    join_type: other_join_type,
+                    right: other_right,
+                    constraint: other_constraint,
+                    ..
+                },
+            ) => {
+                join_type == other_join_type
+                    && right == other_right
+                    && constraint == other_constraint
             }
-            Self::Subquery { query, .. } => {
-                query.as_mut().simplify()?;
+            (
+                TableReference::Table { name, alias },
+                TableReference::Table {
+                    name: other_name,
+                    alias: other_alias,
+                },
+            ) => name == other_name && alias == other_alias,
+            (
+                TableReference::Subquery {
+                    query,
+                    alias,
+                    lateral,
+                },
+                TableReference::Subquery {
+                    query: other_query,
+                    alias: other_alias,
+                    lateral: other_lateral,
+                },
+            ) => query == other_query && alias == other_alias && lateral == other_lateral,
+            (TableReference::Error, TableReference::Error) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Serialize for TableReference {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        stacker::maybe_grow(RED_ZONE, STACK_SIZE, || {
+            TableReference::serialize(self, serializer)
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for TableReference {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        stacker::maybe_grow(RED_ZONE, STACK_SIZE, || {
+            TableReference::deserialize(deserializer)
+        })
+    }
+}
+
+impl Drop for TableReference {
+    fn drop(&mut self) {
+        // Left sides are detached before their join is dropped, as for [Expr].
+        let TableReference::Join { left, .. } = self else {
+            return;
+        };
+        let mut next = mem::replace(&mut **left, TableReference::Error);
+        while let TableReference::Join { left, .. } = &mut next {
+            let left = mem::replace(&mut **left, TableReference::Error);
+            next = left;
+        }
+    }
+}
+
+impl Clone for TableReference {
+    fn clone(&self) -> Self {
+        let mut joins = Vec::new();
+        let mut table = self;
+        while let TableReference::Join { left, .. } = table {
+            joins.push(table);
+            table = left;
+        }
+        let mut copy = table.copy_with(TableReference::Error);
+        for join in joins.into_iter().rev() {
+            copy = join.copy_with(copy);
+        }
+        copy
+    }
+}
+
+impl PartialEq for TableReference {
+    fn eq(&self, other: &Self) -> bool {
+        let (mut table, mut other) = (self, other);
+        loop {
+            if !table.same_node(other) {
+                return false;
             }
-            _ => {}
+            match (table, other) {
+                (
+                    TableReference::Join { left, .. },
+                    TableReference::Join {
+                        left: other_left, ..
+                    },
+                ) => (table, other) = (left, other_left),
+                _ => return true,
+            }
+        }
+    }
+}
+
+impl Simplify for TableReference {
+    fn simplify(&mut self) -> Result<(), String> {
+        let (first, joins) = self.join_chain_mut();
+        if let Self::Subquery { query, .. } = first {
+            query.as_mut().simplify()?;
+        }
+        for (_, right, constraint) in joins {
+            right.simplify()?;
+
+            if let JoinConstraint::On(on_expr) = constraint {
+                on_expr.simplify_condition()?;
+            };
         }
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum JoinType {
     Inner,
     Left,
@@ -428,7 +1535,20 @@ pub(crate) enum JoinType {
     Cross,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Condition attached to a join.
+///
+/// USING and NATURAL are kept as written by the parser. The binder rewrites them into explicit
+/// [JoinConstraint::On] predicates once the schemas of both sides are known.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub(crate) enum JoinConstraint {
+    On(Expr),
+    Using(Vec<Ident>),
+    Natural,
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct OrderByExpr {
     pub(crate) expr: Expr,
     pub(crate) asc: bool,
@@ -441,7 +1561,8 @@ impl Simplify for OrderByExpr {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub(crate) enum Statement {
     With(WithStatement),
     Select(SelectStatement),
@@ -455,6 +1576,30 @@ pub(crate) enum Statement {
     Transaction(TransactionStatement),
 }
 
+impl Statement {
+    /// Rewrites every call to COALESCE and NULLIF as a CASE expression, with
+    /// [Expr::expand_null_function].
+    pub(crate) fn expand_null_functions(&mut self) {
+        walk::walk_statement(self, &mut Expr::expand_null_function);
+    }
+
+    /// Rewrites the subqueries correlated through equalities as joins with derived tables, with
+    /// [decorrelate::decorrelate].
+    pub(crate) fn decorrelate(&mut self) {
+        decorrelate::decorrelate(self);
+    }
+
+    /// Merges identical CTEs, removes unused ones and inlines those that are not materialized,
+    /// with [cte::optimize]. `default` decides for the CTEs without `AS [NOT] MATERIALIZED`.
+    pub(crate) fn optimize_ctes(&mut self, default: Materialization) {
+        cte::optimize(self, default);
+    }
+
+    /// Moves repeated derived tables to shared CTEs, with [cte::extract].
+    pub(crate) fn extract_ctes(&mut self) {
+        cte::extract(self);
+    }
+}
 
 impl Simplify for Statement {
     fn simplify(&mut self) -> Result<(), String> {
@@ -466,22 +1611,31 @@ impl Simplify for Statement {
             Self::Select(s) => s.simplify(),
             Self::Update(s) => s.simplify(),
             Self::CreateTable(s) => s.simplify(),
-            _ => Ok(())
-
+            _ => Ok(()),
         }
     }
 }
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct WithStatement {
     pub(crate) recursive: bool,
-    pub(crate) ctes: Vec<(String, SelectStatement)>,
+    pub(crate) ctes: Vec<Cte>,
     pub(crate) body: Box<SelectStatement>,
 }
 
+/// A common table expression: `name AS [[NOT] MATERIALIZED] (query)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Cte {
+    pub(crate) name: Ident,
+    /// True for `AS MATERIALIZED`, false for `AS NOT MATERIALIZED`, and None when the query leaves
+    /// the choice to the planner.
+    pub(crate) materialized: Option<bool>,
+    pub(crate) query: SelectStatement,
+}
+
 impl Simplify for WithStatement {
     fn simplify(&mut self) -> Result<(), String> {
-        for (_, clause) in self.ctes.iter_mut() {
-            clause.simplify()?;
+        for cte in self.ctes.iter_mut() {
+            cte.query.simplify()?;
         }
 
         self.body.as_mut().simplify()?;
@@ -489,10 +1643,10 @@ impl Simplify for WithStatement {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct InsertStatement {
-    pub(crate) table: String,
-    pub(crate) columns: Option<Vec<String>>,
+    pub(crate) table: ObjectName,
+    pub(crate) columns: Option<Vec<Ident>>,
     pub(crate) values: Values,
 }
 
@@ -502,7 +1656,8 @@ impl Simplify for InsertStatement {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub(crate) enum Values {
     Values(Vec<Vec<Expr>>),
     Query(Box<SelectStatement>),
@@ -526,29 +1681,29 @@ impl Simplify for Values {
         Ok(())
     }
 }
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct UpdateStatement {
-    pub(crate) table: String,
+    pub(crate) table: ObjectName,
     pub(crate) set_clauses: Vec<SetClause>,
     pub(crate) where_clause: Option<Expr>,
 }
 
 impl Simplify for UpdateStatement {
     fn simplify(&mut self) -> Result<(), String> {
-        for item in self.set_clauses.iter_mut(){
+        for item in self.set_clauses.iter_mut() {
             item.simplify()?;
         }
 
         if let Some(clause) = self.where_clause.as_mut() {
-            clause.simplify()?;
+            clause.simplify_condition()?;
         }
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct SetClause {
-    pub(crate) column: String,
+    pub(crate) column: Ident,
     pub(crate) value: Expr,
 }
 
@@ -558,43 +1713,43 @@ impl Simplify for SetClause {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct DeleteStatement {
-    pub(crate) table: String,
+    pub(crate) table: ObjectName,
     pub(crate) where_clause: Option<Expr>,
 }
 
 impl Simplify for DeleteStatement {
     fn simplify(&mut self) -> Result<(), String> {
         if let Some(clause) = self.where_clause.as_mut() {
-            clause.simplify()?;
+            clause.simplify_condition()?;
         };
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct CreateTableStatement {
-    pub(crate) table: String,
+    pub(crate) table: ObjectName,
     pub(crate) columns: Vec<ColumnDef>,
     pub(crate) constraints: Vec<TableConstraint>,
 }
 
-impl Simplify for CreateTableStatement{
+impl Simplify for CreateTableStatement {
     fn simplify(&mut self) -> Result<(), String> {
-        for ct in self.columns.iter_mut(){
+        for ct in self.columns.iter_mut() {
             ct.simplify()?;
         }
-        for ct in self.constraints.iter_mut(){
+        for ct in self.constraints.iter_mut() {
             ct.simplify()?;
         }
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct ColumnDef {
-    pub(crate) name: String,
+    pub(crate) name: Ident,
     pub(crate) data_type: DataType,
     pub(crate) constraints: Vec<ColumnConstraint>,
 }
@@ -608,7 +1763,8 @@ impl Simplify for ColumnDef {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub(crate) enum DataType {
     Integer,
     BigInt,
@@ -632,12 +1788,13 @@ pub(crate) enum DataType {
     Custom(String),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub(crate) enum ColumnConstraint {
     NotNull,
     Unique,
     PrimaryKey,
-    ForeignKey { table: String, column: String },
+    ForeignKey { table: ObjectName, column: Ident },
     Check(Expr),
     Default(Expr),
 }
@@ -645,20 +1802,21 @@ pub(crate) enum ColumnConstraint {
 impl Simplify for ColumnConstraint {
     fn simplify(&mut self) -> Result<(), String> {
         match self {
-            Self::Check(expr) | Self::Default(expr ) => expr.simplify(),
-            _=> Ok(())
+            Self::Check(expr) | Self::Default(expr) => expr.simplify(),
+            _ => Ok(()),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub(crate) enum TableConstraint {
-    PrimaryKey(Vec<String>),
-    Unique(Vec<String>),
+    PrimaryKey(Vec<Ident>),
+    Unique(Vec<Ident>),
     ForeignKey {
-        columns: Vec<String>,
-        ref_table: String,
-        ref_columns: Vec<String>,
+        columns: Vec<Ident>,
+        ref_table: ObjectName,
+        ref_columns: Vec<Ident>,
     },
     Check(Expr),
 }
@@ -672,22 +1830,21 @@ impl Simplify for TableConstraint {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct AlterTableStatement {
-    pub(crate) table: String,
+    pub(crate) table: ObjectName,
     pub(crate) action: AlterAction,
 }
 
-
 impl Simplify for AlterTableStatement {
     fn simplify(&mut self) -> Result<(), String> {
         self.action.simplify()
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct AlterColumnStatement {
-    pub(crate) name: String,
+    pub(crate) name: Ident,
     pub(crate) action: AlterColumnAction,
 }
 
@@ -697,16 +1854,16 @@ impl Simplify for AlterColumnStatement {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub(crate) enum AlterAction {
     AddColumn(ColumnDef),
-    DropColumn(String),
+    DropColumn(Ident),
     AlterColumn(AlterColumnStatement),
     AddConstraint(TableConstraint),
-    DropConstraint(String),
+    DropConstraint(Ident),
 }
 
-
 impl Simplify for AlterAction {
     fn simplify(&mut self) -> Result<(), String> {
         match self {
@@ -718,7 +1875,8 @@ impl Simplify for AlterAction {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub(crate) enum AlterColumnAction {
     SetDataType(DataType),
     SetDefault(Expr),
@@ -736,35 +1894,35 @@ impl Simplify for AlterColumnAction {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct DropTableStatement {
-    pub(crate) table: String,
+    pub(crate) table: ObjectName,
     pub(crate) if_exists: bool,
     pub(crate) cascade: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct CreateIndexStatement {
-    pub(crate) name: String,
-    pub(crate) table: String,
+    pub(crate) name: Ident,
+    pub(crate) table: ObjectName,
     pub(crate) columns: Vec<IndexColumn>,
     pub(crate) unique: bool,
     pub(crate) if_not_exists: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct IndexColumn {
-    pub(crate) name: String,
+    pub(crate) name: Ident,
     pub(crate) order: Option<OrderDirection>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum OrderDirection {
     Asc,
     Desc,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum TransactionStatement {
     Begin,
     Commit,
@@ -0,0 +1,900 @@
+use crate::ast::*;
+use crate::catalog::Catalog;
+use crate::diagnostics::{did_you_mean, suggest};
+use crate::dialect::IdentNormalization;
+use crate::walk;
+use std::collections::HashMap;
+
+/// A column produced by a FROM clause, together with the expression that references it.
+#[derive(Debug, Clone)]
+struct OutputColumn {
+    name: Ident,
+    expr: Expr,
+    /// Whether the column merges the columns of a USING or NATURAL join.
+    merged: bool,
+}
+
+/// Columns visible in a FROM clause.
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    /// Output columns of the FROM clause, with the columns of USING and NATURAL joins merged.
+    columns: Vec<OutputColumn>,
+    /// Columns of every relation, keyed by the name the relation is referenced by.
+    relations: Vec<(ObjectName, Vec<OutputColumn>)>,
+    /// ON predicates of the USING and NATURAL joins, in the order the joins are bound. They replace
+    /// the constraints once the query is known not to need them.
+    joins: Vec<Expr>,
+}
+
+/// Names a column reference can resolve to in one of the queries enclosing it.
+#[derive(Debug, Clone, Default)]
+struct Names {
+    /// Columns of the FROM clause, or None while they are unknown: before the FROM clause is bound,
+    /// or when it reads from a relation that is not in the catalog.
+    scope: Option<Scope>,
+    /// Aliases of the select list, which GROUP BY, HAVING and ORDER BY may refer to.
+    aliases: Vec<Ident>,
+    /// Set when a merged column is referenced where it cannot be rewritten, so that the USING
+    /// and NATURAL joins of the query are kept.
+    keep_using: bool,
+}
+
+/// Resolves names in a statement against a [Catalog] and rewrites the constructs that depend on
+/// the schema:
+///
+/// - `JOIN ... USING (cols)` and `NATURAL JOIN` become explicit `ON` equality predicates, and
+///   unqualified references to their merged columns become the column of the preserved side,
+///   or `COALESCE(l, r)` for a FULL join. Joins whose merged columns are referenced from nested
+///   queries or join conditions keep their USING clause, as the reference would be ambiguous.
+/// - `SELECT *` over such joins is expanded into the merged output columns, so each join
+///   column appears only once, as the SQL standard requires.
+/// - When wildcard expansion is enabled, every `*` and `table.*` is rewritten into an explicit
+///   column list, with its EXCLUDE, EXCEPT, REPLACE and RENAME modifiers applied.
+///
+/// Relations that are not found in the catalog are left untouched. Names that look like misspellings
+/// of known ones, and column references that resolve to nothing while every relation is known, are
+/// reported as [warnings](Binder::warnings) with a suggestion.
+pub(crate) struct Binder<'a> {
+    catalog: &'a Catalog,
+    /// Output columns of the CTEs in scope, keyed by normalized name.
+    ctes: HashMap<String, Vec<Ident>>,
+    /// Names of the CTEs in scope as written, including those whose columns are unknown.
+    cte_names: Vec<Ident>,
+    expand_wildcards: bool,
+    /// Whether the catalog is known to hold every table, so that any other relation is reported.
+    complete_schema: bool,
+    /// Policy used to compare names, taken from the catalog.
+    normalization: IdentNormalization,
+    /// Names visible in the queries being bound, innermost last.
+    names: Vec<Names>,
+    warnings: Vec<String>,
+}
+
+impl<'a> Binder<'a> {
+    pub(crate) fn new(catalog: &'a Catalog) -> Self {
+        Self {
+            catalog,
+            ctes: HashMap::new(),
+            cte_names: Vec::new(),
+            expand_wildcards: false,
+            complete_schema: false,
+            normalization: catalog.normalization(),
+            names: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Unknown names found by the last calls to [Binder::bind], which do not prevent binding.
+    pub(crate) fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Enables the star-expansion pass, which rewrites all wildcards into explicit column lists.
+    pub(crate) fn with_wildcard_expansion(mut self) -> Self {
+        self.expand_wildcards = true;
+        self
+    }
+
+    /// Reports every relation that is neither a CTE nor a table of the catalog, not only likely
+    /// misspellings. Used to validate statements against a full schema.
+    pub(crate) fn with_complete_schema(mut self) -> Self {
+        self.complete_schema = true;
+        self
+    }
+
+    /// Binds a full statement in place.
+    pub(crate) fn bind(&mut self, stmt: &mut Statement) -> Result<(), String> {
+        match stmt {
+            Statement::With(with) => {
+                for cte in with.ctes.iter_mut() {
+                    self.cte_names.push(cte.name.clone());
+                    let columns = self.bind_select(&mut cte.query)?;
+                    if let Some(columns) = columns {
+                        self.ctes
+                            .insert(cte.name.normalized(self.normalization), columns);
+                    }
+                }
+                self.bind_select(&mut with.body)?;
+                self.ctes.clear();
+                self.cte_names.clear();
+            }
+            Statement::Select(select) => {
+                self.bind_select(select)?;
+            }
+            Statement::Insert(insert) => {
+                if let Values::Query(query) = &mut insert.values {
+                    self.bind_select(query)?;
+                }
+                self.push_target(&insert.table)?;
+                for column in insert.columns.iter().flatten() {
+                    self.check_column(None, column);
+                }
+                self.names.pop();
+            }
+            Statement::Update(update) => {
+                self.push_target(&update.table)?;
+                for clause in update.set_clauses.iter_mut() {
+                    self.check_column(None, &clause.column);
+                    self.bind_expr(&mut clause.value)?;
+                }
+                if let Some(expr) = update.where_clause.as_mut() {
+                    self.bind_expr(expr)?;
+                }
+                self.names.pop();
+            }
+            Statement::Delete(delete) => {
+                self.push_target(&delete.table)?;
+                if let Some(expr) = delete.where_clause.as_mut() {
+                    self.bind_expr(expr)?;
+                }
+                self.names.pop();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Brings the columns of the table an INSERT, UPDATE or DELETE writes to into scope. The caller
+    /// pops them once the statement is bound.
+    fn push_target(&mut self, table: &ObjectName) -> Result<(), String> {
+        let mut table = TableReference::Table {
+            name: table.clone(),
+            alias: None,
+        };
+        let scope = self.bind_table_ref(&mut table)?;
+        self.names.push(Names {
+            scope,
+            ..Names::default()
+        });
+        Ok(())
+    }
+
+    /// Binds a SELECT statement and returns the names of its output columns,
+    /// or None if they cannot be determined without a schema.
+    fn bind_select(&mut self, select: &mut SelectStatement) -> Result<Option<Vec<Ident>>, String> {
+        // Names of the enclosing queries are hidden from the FROM clause, so that LATERAL subqueries
+        // and ON conditions that refer to its other relations are not reported.
+        self.names.push(Names::default());
+        let result = self.bind_query(select);
+        self.names.pop();
+        result
+    }
+
+    fn bind_query(&mut self, select: &mut SelectStatement) -> Result<Option<Vec<Ident>>, String> {
+        let scope = match select.from.as_mut() {
+            Some(from) => {
+                let merged = has_merged_columns(from);
+                let scope = self.bind_table_ref(from)?;
+
+                // Expanding `*` is always needed when a join merged some of its columns.
+                if let Some(scope) = &scope
+                    && (merged || self.expand_wildcards)
+                {
+                    expand_wildcards(
+                        &mut select.columns,
+                        scope,
+                        self.expand_wildcards,
+                        self.normalization,
+                    )?;
+                }
+                scope
+            }
+            None => Some(Scope::default()),
+        };
+        *self
+            .names
+            .last_mut()
+            .expect("names of the query are pushed") = Names {
+            scope: scope.clone(),
+            keep_using: false,
+            aliases: select
+                .columns
+                .iter()
+                .filter_map(|item| match item {
+                    SelectItem::ExprWithAlias {
+                        alias: Some(alias), ..
+                    } => Some(alias.clone()),
+                    _ => None,
+                })
+                .collect(),
+        };
+
+        for item in select.columns.iter_mut() {
+            if let SelectItem::ExprWithAlias { expr, .. } = item {
+                self.bind_expr(expr)?;
+            }
+        }
+        if let Some(expr) = select.where_clause.as_mut() {
+            self.bind_expr(expr)?;
+        }
+        for expr in select.group_by.iter_mut() {
+            self.bind_expr(expr)?;
+        }
+        if let Some(expr) = select.having.as_mut() {
+            self.bind_expr(expr)?;
+        }
+        for item in select.order_by.iter_mut() {
+            self.bind_expr(&mut item.expr)?;
+        }
+        if let Some(scope) = &scope
+            && !scope.joins.is_empty()
+        {
+            let names = self.names.last().expect("names of the query are pushed");
+            if !names.keep_using && !needs_using(select, scope, &names.aliases, self.normalization)
+            {
+                resolve_merged_columns(select, scope, self.normalization);
+            }
+        }
+
+        let mut names = Vec::new();
+        for item in &select.columns {
+            match item {
+                SelectItem::Star(options) | SelectItem::QualifiedWildcard(_, options) => {
+                    let Some(scope) = &scope else {
+                        return Ok(None);
+                    };
+                    let qualifier = match item {
+                        SelectItem::QualifiedWildcard(qualifier, _) => Some(qualifier),
+                        _ => None,
+                    };
+                    for column in wildcard_columns(scope, qualifier, options, self.normalization)? {
+                        if let Some(name) = select_item_name(&column) {
+                            names.push(name);
+                        }
+                    }
+                }
+                // Unnamed expressions cannot be referenced from outside.
+                item => names.extend(select_item_name(item)),
+            }
+        }
+        Ok(Some(names))
+    }
+
+    /// Binds a table reference and returns the columns it exposes,
+    /// or None if any of the underlying relations is unknown.
+    fn bind_table_ref(&mut self, table_ref: &mut TableReference) -> Result<Option<Scope>, String> {
+        match table_ref {
+            TableReference::Table { name, alias } => {
+                let qualifier = match alias {
+                    Some(alias) => ObjectName(vec![alias.clone()]),
+                    None => name.clone(),
+                };
+                let cte = match name.0.as_slice() {
+                    [cte] => self.ctes.get(&cte.normalized(self.normalization)),
+                    _ => None,
+                };
+                let columns = if let Some(columns) = cte {
+                    columns.clone()
+                } else if let Some(table) = self.catalog.table(name) {
+                    table.column_names()
+                } else {
+                    self.check_table(name);
+                    return Ok(None);
+                };
+                Ok(Some(Scope::relation(qualifier, columns)))
+            }
+            TableReference::Subquery { query, alias, .. } => Ok(self
+                .bind_select(query)?
+                .map(|columns| Scope::relation(ObjectName(vec![alias.clone()]), columns))),
+            // The relations of a broken FROM clause are unknown.
+            TableReference::Error => Ok(None),
+            TableReference::Join { .. } => {
+                // Chains of joins are bound with a loop, as they can be long.
+                let (first, joins) = table_ref.join_chain_mut();
+                let mut scope = self.bind_table_ref(first)?;
+                for (join_type, right, constraint) in joins {
+                    let right = self.bind_table_ref(right)?;
+                    if let JoinConstraint::On(expr) = constraint {
+                        self.bind_expr(expr)?;
+                    }
+                    scope = match (scope, right) {
+                        (Some(left), Some(right)) => {
+                            Some(self.join_scopes(left, right, join_type, constraint)?)
+                        }
+                        _ => None,
+                    };
+                }
+                Ok(scope)
+            }
+        }
+    }
+
+    /// Combines the columns exposed by the two sides of a join.
+    fn join_scopes(
+        &self,
+        left: Scope,
+        right: Scope,
+        join_type: &mut JoinType,
+        constraint: &mut JoinConstraint,
+    ) -> Result<Scope, String> {
+        let relations = left
+            .relations
+            .iter()
+            .chain(right.relations.iter())
+            .cloned()
+            .collect();
+        let mut joins: Vec<Expr> = left.joins.into_iter().chain(right.joins).collect();
+
+        let using = match constraint {
+            JoinConstraint::Using(columns) => columns.clone(),
+            JoinConstraint::Natural => {
+                let mut common: Vec<Ident> = Vec::new();
+                for column in &left.columns {
+                    if find_column(&right.columns, &column.name, self.normalization).is_some()
+                        && find_name(&common, &column.name, self.normalization).is_none()
+                    {
+                        common.push(column.name.clone());
+                    }
+                }
+                common
+            }
+            _ => {
+                return Ok(Scope {
+                    columns: left.columns.into_iter().chain(right.columns).collect(),
+                    relations,
+                    joins,
+                });
+            }
+        };
+
+        // A NATURAL JOIN without common columns degenerates into a cross join.
+        if using.is_empty() {
+            *join_type = JoinType::Cross;
+            *constraint = JoinConstraint::None;
+            return Ok(Scope {
+                columns: left.columns.into_iter().chain(right.columns).collect(),
+                relations,
+                joins,
+            });
+        }
+
+        let mut predicate: Option<Expr> = None;
+        let mut merged = Vec::new();
+        for name in &using {
+            let l = find_column(&left.columns, name, self.normalization).ok_or_else(|| format!(
+                "Column '{name}' specified in USING does not exist on the left side of the join{}",
+                suggest_column(&left.columns, name)
+            ))?;
+            let r = find_column(&right.columns, name, self.normalization).ok_or_else(|| format!(
+                "Column '{name}' specified in USING does not exist on the right side of the join{}",
+                suggest_column(&right.columns, name)
+            ))?;
+
+            let eq = Expr::BinaryOp {
+                left: Box::new(l.expr.clone()),
+                op: BinaryOperator::Eq,
+                right: Box::new(r.expr.clone()),
+            };
+            predicate = Some(match predicate {
+                Some(acc) => Expr::BinaryOp {
+                    left: Box::new(acc),
+                    op: BinaryOperator::And,
+                    right: Box::new(eq),
+                },
+                None => eq,
+            });
+
+            // The merged column comes from the side that is preserved by the join.
+            let expr = match join_type {
+                JoinType::Right => r.expr.clone(),
+                JoinType::Full => Expr::FunctionCall {
+                    name: "COALESCE".to_string(),
+                    args: vec![l.expr.clone(), r.expr.clone()],
+                    distinct: false,
+                },
+                _ => l.expr.clone(),
+            };
+            merged.push(OutputColumn {
+                name: l.name.clone(),
+                expr,
+                merged: true,
+            });
+        }
+
+        joins.push(predicate.expect("USING list is not empty"));
+
+        let is_merged = |c: &OutputColumn| find_name(&using, &c.name, self.normalization).is_some();
+        merged.extend(left.columns.iter().filter(|c| !is_merged(c)).cloned());
+        merged.extend(right.columns.iter().filter(|c| !is_merged(c)).cloned());
+        Ok(Scope {
+            columns: merged,
+            relations,
+            joins,
+        })
+    }
+
+    /// Binds the subqueries nested inside an expression.
+    fn bind_expr(&mut self, expr: &mut Expr) -> Result<(), String> {
+        match expr {
+            Expr::Identifier(name) => self.check_column(None, name),
+            Expr::QualifiedIdentifier { table, column } => self.check_column(Some(table), column),
+            Expr::Subquery(query) | Expr::Exists(query) => {
+                self.bind_select(query)?;
+            }
+            Expr::BinaryOp { .. } => {
+                // Chains of operators are bound with a loop down their left operands, as they can
+                // be long.
+                let mut rights = Vec::new();
+                let mut operand = expr;
+                while let Expr::BinaryOp { left, right, .. } = operand {
+                    rights.push(right);
+                    operand = left;
+                }
+                self.bind_expr(operand)?;
+                for right in rights.into_iter().rev() {
+                    self.bind_expr(right)?;
+                }
+            }
+            Expr::UnaryOp { expr, .. } => self.bind_expr(expr)?,
+            Expr::FunctionCall { args, .. } | Expr::List(args) => {
+                for arg in args {
+                    self.bind_expr(arg)?;
+                }
+            }
+            Expr::Case {
+                operand,
+                when_clauses,
+                else_clause,
+            } => {
+                if let Some(operand) = operand {
+                    self.bind_expr(operand)?;
+                }
+                for clause in when_clauses {
+                    self.bind_expr(&mut clause.condition)?;
+                    self.bind_expr(&mut clause.result)?;
+                }
+                if let Some(else_clause) = else_clause {
+                    self.bind_expr(else_clause)?;
+                }
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                self.bind_expr(expr)?;
+                self.bind_expr(low)?;
+                self.bind_expr(high)?;
+            }
+            Expr::Escape { pattern, escape } => {
+                self.bind_expr(pattern)?;
+                self.bind_expr(escape)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Checks for unknown names, which are reported as warnings with the closest known name.
+impl Binder<'_> {
+    /// Warns about a relation that is neither a CTE nor a table of the catalog when it looks like a
+    /// misspelling of one. Other unknown relations are expected when the schema is incomplete, and
+    /// are only reported with [Binder::with_complete_schema].
+    fn check_table(&mut self, name: &ObjectName) {
+        let Some(table) = name.0.last() else {
+            return;
+        };
+        if name.0.len() == 1 && find_name(&self.cte_names, table, self.normalization).is_some() {
+            return;
+        }
+        let candidates = self
+            .catalog
+            .tables()
+            .filter_map(|schema| schema.name.0.last())
+            .chain(self.cte_names.iter())
+            .map(|name| name.value.as_str());
+        let suggestion = suggest(&table.value, candidates);
+        if suggestion.is_some() || self.complete_schema {
+            self.warnings.push(format!(
+                "Unknown table '{name}'{}",
+                did_you_mean(suggestion)
+            ));
+        }
+    }
+
+    /// Warns about a column reference that resolves to nothing. References are looked up in the
+    /// query they appear in, then in the enclosing ones, and are only reported when every
+    /// relation they could come from is known.
+    fn check_column(&mut self, table: Option<&ObjectName>, column: &Ident) {
+        if self.names.is_empty() {
+            return;
+        }
+        let innermost = self.names.len() - 1;
+        for depth in (0..=innermost).rev() {
+            let names = &self.names[depth];
+            let Some(scope) = &names.scope else {
+                // The reference may as well come from an enclosing query.
+                if table.is_none() {
+                    self.keep_using(column, depth);
+                }
+                return;
+            };
+            match table {
+                None => {
+                    let found = find_column(&scope.columns, column, self.normalization);
+                    if found.is_some()
+                        || find_name(&names.aliases, column, self.normalization).is_some()
+                    {
+                        // Merged columns are only rewritten in the query that joins them.
+                        if depth < innermost && found.is_some_and(|c| c.merged) {
+                            self.names[depth].keep_using = true;
+                        }
+                        return;
+                    }
+                }
+                Some(table) => {
+                    let relation = scope
+                        .relations
+                        .iter()
+                        .find(|(name, _)| name.matches(table, self.normalization));
+                    if let Some((_, columns)) = relation {
+                        if find_column(columns, column, self.normalization).is_none() {
+                            let warning = format!(
+                                "Unknown column '{table}.{column}'{}",
+                                suggest_column(columns, column)
+                            );
+                            self.warnings.push(warning);
+                        }
+                        return;
+                    }
+                }
+            }
+        }
+
+        let names = self.names.last().expect("names are not empty");
+        let scope = names.scope.as_ref().expect("scopes are known");
+        let warning = match table {
+            None => {
+                let candidates = scope
+                    .columns
+                    .iter()
+                    .map(|c| &c.name)
+                    .chain(names.aliases.iter())
+                    .map(|name| name.value.as_str());
+                format!(
+                    "Unknown column '{column}'{}",
+                    did_you_mean(suggest(&column.value, candidates))
+                )
+            }
+            Some(table) => format!(
+                "Unknown table '{table}' in {table}.{column}{}",
+                suggest_relation(scope, table)
+            ),
+        };
+        self.warnings.push(warning);
+    }
+}
+
+impl Binder<'_> {
+    /// Keeps the USING and NATURAL joins of the queries enclosing `depth` that merge a column
+    /// named `column`, which an unresolved reference may refer to.
+    fn keep_using(&mut self, column: &Ident, depth: usize) {
+        let policy = self.normalization;
+        for names in &mut self.names[..depth] {
+            let merged = names.scope.as_ref().is_some_and(|scope| {
+                find_column(&scope.columns, column, policy).is_some_and(|c| c.merged)
+            });
+            if merged {
+                names.keep_using = true;
+            }
+        }
+    }
+}
+
+/// Returns true if the USING and NATURAL joins of a query must be kept because one of their
+/// merged columns is referenced where it cannot be rewritten: in a join condition, in a LATERAL
+/// subquery, or in GROUP BY, HAVING or ORDER BY when a select alias has the same name.
+fn needs_using(
+    select: &mut SelectStatement,
+    scope: &Scope,
+    aliases: &[Ident],
+    policy: IdentNormalization,
+) -> bool {
+    let is_merged =
+        |name: &Ident| find_column(&scope.columns, name, policy).is_some_and(|c| c.merged);
+    let mut found = false;
+    let mut visit = |expr: &mut Expr| {
+        if let Expr::Identifier(name) = expr {
+            found |= is_merged(name);
+        }
+    };
+    if let Some(from) = select.from.as_mut() {
+        join_parts(from, &mut visit);
+    }
+
+    let mut aliased = false;
+    let mut visit = |expr: &mut Expr| {
+        if let Expr::Identifier(name) = expr {
+            aliased |= is_merged(name) && find_name(aliases, name, policy).is_some();
+        }
+    };
+    for expr in select
+        .group_by
+        .iter_mut()
+        .chain(select.having.as_mut())
+        .chain(select.order_by.iter_mut().map(|item| &mut item.expr))
+    {
+        walk::walk_expr(expr, &mut visit);
+    }
+    found || aliased
+}
+
+/// Calls `f` on the expressions of the join conditions and LATERAL subqueries of a FROM clause.
+fn join_parts(table: &mut TableReference, f: &mut dyn FnMut(&mut Expr)) {
+    let (first, joins) = table.join_chain_mut();
+    if let TableReference::Subquery {
+        query,
+        lateral: true,
+        ..
+    } = first
+    {
+        walk::walk_select(query, f);
+    }
+    for (_, right, constraint) in joins {
+        join_parts(right, f);
+        if let JoinConstraint::On(expr) = constraint {
+            walk::walk_expr(expr, f);
+        }
+    }
+}
+
+/// Replaces the USING and NATURAL joins of a query by their ON predicates, and the unqualified
+/// references to their merged columns by the expressions the columns stand for.
+fn resolve_merged_columns(select: &mut SelectStatement, scope: &Scope, policy: IdentNormalization) {
+    if let Some(from) = select.from.as_mut() {
+        replace_using(from, &mut scope.joins.iter());
+    }
+
+    let resolve = |expr: &mut Expr| resolve_merged(expr, scope, policy);
+    for item in select.columns.iter_mut() {
+        if let SelectItem::ExprWithAlias { expr, alias } = item {
+            // The column keeps its name when it becomes an expression.
+            if alias.is_none()
+                && let Expr::Identifier(name) = expr
+                && find_column(&scope.columns, name, policy).is_some_and(|c| {
+                    c.merged && !matches!(c.expr, Expr::QualifiedIdentifier { .. })
+                })
+            {
+                *alias = Some(name.clone());
+            }
+            resolve(expr);
+        }
+    }
+    select.where_clause.iter_mut().for_each(resolve);
+    select.group_by.iter_mut().for_each(resolve);
+    select.having.iter_mut().for_each(resolve);
+    for item in select.order_by.iter_mut() {
+        resolve(&mut item.expr);
+    }
+}
+
+/// Replaces the constraints of the USING and NATURAL joins of a FROM clause, in the order they
+/// were bound.
+fn replace_using<'a>(table: &mut TableReference, joins: &mut impl Iterator<Item = &'a Expr>) {
+    let (_, chain) = table.join_chain_mut();
+    for (_, right, constraint) in chain {
+        replace_using(right, joins);
+        if matches!(
+            constraint,
+            JoinConstraint::Using(_) | JoinConstraint::Natural
+        ) {
+            let predicate = joins.next().expect("every merging join has a predicate");
+            *constraint = JoinConstraint::On(predicate.clone());
+        }
+    }
+}
+
+/// Rewrites the unqualified references to merged columns of an expression, leaving its
+/// subqueries alone.
+fn resolve_merged(expr: &mut Expr, scope: &Scope, policy: IdentNormalization) {
+    if let Expr::Identifier(name) = expr {
+        if let Some(column) = find_column(&scope.columns, name, policy)
+            && column.merged
+        {
+            *expr = column.expr.clone();
+        }
+        return;
+    }
+    for child in expr.children_mut() {
+        resolve_merged(child, scope, policy);
+    }
+}
+
+/// Suggests the column of `columns` that `name` is probably a misspelling of.
+fn suggest_column(columns: &[OutputColumn], name: &Ident) -> String {
+    did_you_mean(suggest(
+        &name.value,
+        columns.iter().map(|c| c.name.value.as_str()),
+    ))
+}
+
+/// Suggests the relation of a scope that `name` is probably a misspelling of.
+fn suggest_relation(scope: &Scope, name: &ObjectName) -> String {
+    let Some(last) = name.0.last() else {
+        return String::new();
+    };
+    did_you_mean(suggest(
+        &last.value,
+        scope
+            .relations
+            .iter()
+            .filter_map(|(name, _)| name.0.last())
+            .map(|name| name.value.as_str()),
+    ))
+}
+
+impl Scope {
+    /// Builds the scope of a single relation referenced as `qualifier`.
+    fn relation(qualifier: ObjectName, columns: Vec<Ident>) -> Self {
+        let columns: Vec<OutputColumn> = columns
+            .into_iter()
+            .map(|column| OutputColumn {
+                expr: Expr::QualifiedIdentifier {
+                    table: qualifier.clone(),
+                    column: column.clone(),
+                },
+                name: column,
+                merged: false,
+            })
+            .collect();
+        Self {
+            relations: vec![(qualifier, columns.clone())],
+            columns,
+            joins: Vec::new(),
+        }
+    }
+}
+
+fn find_column<'c>(
+    columns: &'c [OutputColumn],
+    name: &Ident,
+    policy: IdentNormalization,
+) -> Option<&'c OutputColumn> {
+    let name = name.normalized(policy);
+    columns.iter().find(|c| c.name.normalized(policy) == name)
+}
+
+fn find_name<'n>(
+    names: &'n [Ident],
+    name: &Ident,
+    policy: IdentNormalization,
+) -> Option<&'n Ident> {
+    let name = name.normalized(policy);
+    names.iter().find(|n| n.normalized(policy) == name)
+}
+
+/// Returns true if the table reference contains a join that merges columns (USING or NATURAL).
+fn has_merged_columns(table_ref: &TableReference) -> bool {
+    let (_, joins) = table_ref.join_chain();
+    joins.into_iter().any(|(_, right, constraint)| {
+        matches!(
+            constraint,
+            JoinConstraint::Using(_) | JoinConstraint::Natural
+        ) || has_merged_columns(right)
+    })
+}
+
+/// Name under which a select item can be referenced from outside of its query.
+fn select_item_name(item: &SelectItem) -> Option<Ident> {
+    match item {
+        SelectItem::ExprWithAlias {
+            alias: Some(alias), ..
+        } => Some(alias.clone()),
+        SelectItem::ExprWithAlias {
+            expr: Expr::Identifier(name),
+            ..
+        }
+        | SelectItem::ExprWithAlias {
+            expr: Expr::QualifiedIdentifier { column: name, .. },
+            ..
+        } => Some(name.clone()),
+        _ => None,
+    }
+}
+
+/// Rewrites the wildcards of a select list into explicit columns.
+/// Qualified wildcards are only expanded when `all` is set.
+fn expand_wildcards(
+    items: &mut Vec<SelectItem>,
+    scope: &Scope,
+    all: bool,
+    policy: IdentNormalization,
+) -> Result<(), String> {
+    let mut expanded = Vec::with_capacity(items.len());
+    for item in items.drain(..) {
+        match item {
+            SelectItem::Star(options) => {
+                expanded.extend(wildcard_columns(scope, None, &options, policy)?);
+            }
+            SelectItem::QualifiedWildcard(qualifier, options) if all => {
+                expanded.extend(wildcard_columns(scope, Some(&qualifier), &options, policy)?);
+            }
+            item => expanded.push(item),
+        }
+    }
+    *items = expanded;
+    Ok(())
+}
+
+/// Computes the columns a wildcard stands for, after applying its modifiers.
+fn wildcard_columns(
+    scope: &Scope,
+    qualifier: Option<&ObjectName>,
+    options: &WildcardOptions,
+    policy: IdentNormalization,
+) -> Result<Vec<SelectItem>, String> {
+    let columns = match qualifier {
+        Some(qualifier) => {
+            &scope
+                .relations
+                .iter()
+                .find(|(name, _)| name.matches(qualifier, policy))
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown table '{qualifier}' in {qualifier}.*{}",
+                        suggest_relation(scope, qualifier)
+                    )
+                })?
+                .1
+        }
+        None => &scope.columns,
+    };
+
+    let modified = options
+        .exclude
+        .iter()
+        .chain(options.except.iter())
+        .chain(options.replace.iter().map(|r| &r.column))
+        .chain(options.rename.iter().map(|r| &r.column));
+    for name in modified {
+        if find_column(columns, name, policy).is_none() {
+            return Err(format!(
+                "Column '{name}' in wildcard modifier does not exist{}",
+                suggest_column(columns, name)
+            ));
+        }
+    }
+
+    let mut items = Vec::with_capacity(columns.len());
+    for column in columns {
+        let column_name = column.name.normalized(policy);
+        let is = |name: &Ident| name.normalized(policy) == column_name;
+        if options.exclude.iter().chain(options.except.iter()).any(is) {
+            continue;
+        }
+
+        let mut expr = column.expr.clone();
+        let mut alias = match &column.expr {
+            Expr::QualifiedIdentifier { .. } => None,
+            _ => Some(column.name.clone()),
+        };
+        if let Some(replace) = options.replace.iter().find(|r| is(&r.column)) {
+            expr = replace.expr.clone();
+            alias = Some(column.name.clone());
+        }
+        if let Some(rename) = options.rename.iter().find(|r| is(&r.column)) {
+            alias = Some(rename.alias.clone());
+        }
+        items.push(SelectItem::ExprWithAlias { expr, alias });
+    }
+    Ok(items)
+}
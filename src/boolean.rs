@@ -0,0 +1,368 @@
+//! Normalization of conditions, used by simplification.
+//!
+//! Three-valued logic keeps most laws of boolean algebra: De Morgan, double negation, idempotence,
+//! absorption and distribution all hold when NULL is read as "unknown". The law of the excluded
+//! middle does not, so `a OR NOT a` is not always TRUE and is left alone.
+//!
+//! Rewrites that merge repeated operands into one, such as `a AND a` into `a`, are only applied to
+//! deterministic operands: `RANDOM() < 0.5 AND RANDOM() < 0.5` calls the function twice, and has a
+//! different result than a single call.
+use crate::ast::{
+    BinaryOperator, Expr, JoinConstraint, SelectStatement, Statement, TableReference, UnaryOperator,
+};
+use crate::functions;
+use crate::walk;
+use std::collections::HashSet;
+use std::mem;
+
+/// Largest number of clauses produced by [to_cnf]. Distributing OR over AND can grow a condition
+/// exponentially, so conditions that would need more clauses are not converted.
+pub(crate) const MAX_CNF_CLAUSES: usize = 64;
+
+/// Operands of a chain of `op`, with nested chains of the same operator flattened:
+/// `a AND (b AND c)` gives `[a, b, c]`. Any other expression is a chain of one operand.
+pub(crate) fn operands(expr: Expr, op: &BinaryOperator) -> Vec<Expr> {
+    let mut operands = Vec::new();
+    // The right operand is pushed first, so operands come out in the order they are written.
+    let mut stack = vec![expr];
+    while let Some(mut expr) = stack.pop() {
+        match &mut expr {
+            Expr::BinaryOp {
+                left,
+                op: expr_op,
+                right,
+            } if expr_op == op => {
+                stack.push(mem::replace(&mut **right, Expr::Null));
+                stack.push(mem::replace(&mut **left, Expr::Null));
+            }
+            _ => operands.push(expr),
+        }
+    }
+    operands
+}
+
+/// Operands of a chain of `op`, as in [operands], borrowed from the expression.
+pub(crate) fn operand_refs<'a>(expr: &'a Expr, op: &BinaryOperator) -> Vec<&'a Expr> {
+    let mut operands = Vec::new();
+    let mut stack = vec![expr];
+    while let Some(expr) = stack.pop() {
+        match expr {
+            Expr::BinaryOp {
+                left,
+                op: expr_op,
+                right,
+            } if expr_op == op => {
+                stack.push(right);
+                stack.push(left);
+            }
+            operand => operands.push(operand),
+        }
+    }
+    operands
+}
+
+/// Left-deep chain of `op` over the operands, as the parser builds it. An empty AND chain is TRUE,
+/// and an empty OR chain is FALSE.
+pub(crate) fn chain(operands: Vec<Expr>, op: &BinaryOperator) -> Expr {
+    let mut operands = operands.into_iter();
+    let Some(first) = operands.next() else {
+        return Expr::Boolean(*op == BinaryOperator::And);
+    };
+    operands.fold(first, |left, right| Expr::BinaryOp {
+        left: Box::new(left),
+        op: op.clone(),
+        right: Box::new(right),
+    })
+}
+
+/// AND for OR, and OR for AND.
+fn dual(op: &BinaryOperator) -> BinaryOperator {
+    match op {
+        BinaryOperator::And => BinaryOperator::Or,
+        _ => BinaryOperator::And,
+    }
+}
+
+/// Returns true if the expression gives the same result each time it is evaluated on the same row.
+///
+/// Functions missing from the registry may be volatile, and so may subqueries, which can call
+/// them.
+pub(crate) fn is_deterministic(expr: &Expr) -> bool {
+    match expr {
+        Expr::FunctionCall { name, .. } => {
+            // The registry lock is released before looking at the arguments.
+            let deterministic = functions::registry()
+                .get(name)
+                .is_some_and(|function| function.deterministic);
+            deterministic && expr.children().into_iter().all(is_deterministic)
+        }
+        Expr::Subquery(_) | Expr::Exists(_) => false,
+        _ => expr.children().into_iter().all(is_deterministic),
+    }
+}
+
+/// Negation of a condition, with NOT pushed down as far as it goes: through AND and OR with De
+/// Morgan's laws, and into the operators that have an opposite, so `NOT (a < b OR c IS NULL)` is
+/// `a >= b AND c IS NOT NULL`. Both forms are NULL when an operand of the comparison is NULL.
+pub(crate) fn negate(mut expr: Expr) -> Expr {
+    match &mut expr {
+        Expr::BinaryOp {
+            op: op @ (BinaryOperator::And | BinaryOperator::Or),
+            ..
+        } => {
+            let op = op.clone();
+            let negated = operands(expr, &op).into_iter().map(negate).collect();
+            chain(negated, &dual(&op))
+        }
+        Expr::BinaryOp { op, .. } => match op.negated() {
+            Some(negated) => {
+                *op = negated;
+                expr
+            }
+            None => not(expr),
+        },
+        Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            expr,
+        } => mem::replace(&mut **expr, Expr::Null),
+        Expr::Between { negated, .. } => {
+            *negated = !*negated;
+            expr
+        }
+        Expr::Boolean(value) => Expr::Boolean(!*value),
+        Expr::Null => Expr::Null,
+        _ => not(expr),
+    }
+}
+
+fn not(expr: Expr) -> Expr {
+    Expr::UnaryOp {
+        op: UnaryOperator::Not,
+        expr: Box::new(expr),
+    }
+}
+
+/// Normalizes an AND or OR chain whose operands are already normalized. Nested chains of the same
+/// operator are flattened, duplicate and absorbed operands are removed, and the operands common to
+/// every branch of an OR are factored out:
+///
+/// ```sql
+/// a AND (b AND a)          -- a AND b
+/// a OR (a AND b)           -- a
+/// (a AND b) OR (a AND c)   -- a AND (b OR c)
+/// ```
+pub(crate) fn normalize(expr: &mut Expr) {
+    let Expr::BinaryOp {
+        op: op @ (BinaryOperator::And | BinaryOperator::Or),
+        ..
+    } = expr
+    else {
+        return;
+    };
+    let op = op.clone();
+    let operands = absorb(operands(mem::replace(expr, Expr::Null), &op), &op);
+    *expr = match factor(&operands, &op) {
+        Some(factored) => factored,
+        None => chain(operands, &op),
+    };
+}
+
+/// Removes the operands of an `op` chain that the others make redundant: repeated operands, and
+/// chains of the dual operator that contain every operand of another chain, or another operand
+/// itself, as in `a AND (a OR b)`.
+fn absorb(operands: Vec<Expr>, op: &BinaryOperator) -> Vec<Expr> {
+    // Operands are compared by their SQL text, so long chains are deduplicated in linear time.
+    let mut seen = HashSet::new();
+    let operands: Vec<Expr> = operands
+        .into_iter()
+        .filter(|operand| !is_deterministic(operand) || seen.insert(operand.to_string()))
+        .collect();
+
+    // The dual chains, with their operands. Single operands are in `seen`.
+    let dual = dual(op);
+    let terms: Vec<Option<Vec<Expr>>> = operands
+        .iter()
+        .map(|operand| match operand {
+            Expr::BinaryOp { op, .. } if *op == dual => Some(self::operands(operand.clone(), op)),
+            _ => None,
+        })
+        .collect();
+    let absorbed = |index: usize, terms_of: &[Expr]| {
+        if terms_of.iter().any(|term| seen.contains(&term.to_string())) {
+            return true;
+        }
+        terms.iter().enumerate().any(|(other, other_terms)| {
+            let Some(other_terms) = other_terms else {
+                return false;
+            };
+            let subset = other_terms.iter().all(|term| terms_of.contains(term));
+            // Of two chains with the same operands, the first one is kept.
+            let smaller = other_terms.len() < terms_of.len() || other < index;
+            other != index && subset && smaller && is_deterministic(&operands[other])
+        })
+    };
+    let keep: Vec<bool> = terms
+        .iter()
+        .enumerate()
+        .map(|(index, terms_of)| terms_of.as_ref().is_none_or(|t| !absorbed(index, t)))
+        .collect();
+    operands
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(operand, keep)| keep.then_some(operand))
+        .collect()
+}
+
+/// Factors the operands common to every branch of an OR chain out of it:
+/// `(a AND b) OR (a AND c)` is `a AND (b OR c)`. Returns None for AND chains, and for OR chains
+/// without common operands.
+fn factor(disjuncts: &[Expr], op: &BinaryOperator) -> Option<Expr> {
+    if *op != BinaryOperator::Or || disjuncts.len() < 2 {
+        return None;
+    }
+    let conjuncts: Vec<Vec<Expr>> = disjuncts
+        .iter()
+        .map(|disjunct| operands(disjunct.clone(), &BinaryOperator::And))
+        .collect();
+    let common: Vec<Expr> = conjuncts[0]
+        .iter()
+        .filter(|term| conjuncts[1..].iter().all(|other| other.contains(term)))
+        .filter(|term| is_deterministic(term))
+        .cloned()
+        .collect();
+    if common.is_empty() {
+        return None;
+    }
+
+    let remainders: Vec<Vec<Expr>> = conjuncts
+        .into_iter()
+        .map(|terms| terms.into_iter().filter(|t| !common.contains(t)).collect())
+        .collect();
+    let mut factored = common;
+    // A branch made only of common operands absorbs the others.
+    if remainders.iter().all(|terms| !terms.is_empty()) {
+        let branches = remainders
+            .into_iter()
+            .map(|terms| chain(terms, &BinaryOperator::And))
+            .collect();
+        let mut rest = chain(branches, &BinaryOperator::Or);
+        normalize(&mut rest);
+        factored.push(rest);
+    }
+    Some(chain(factored, &BinaryOperator::And))
+}
+
+/// Conjunctive normal form of a condition: an AND of clauses that do not contain AND, as in
+/// `(a OR c) AND (b OR c)` for `(a AND b) OR c`. The clauses are normalized.
+///
+/// Returns None when the form would have more than [MAX_CNF_CLAUSES] clauses, or would repeat an
+/// operand that is not deterministic.
+pub(crate) fn to_cnf(expr: &Expr) -> Option<Expr> {
+    let clauses = clauses(expr.clone())?
+        .into_iter()
+        .map(|clause| {
+            let mut clause = chain(clause, &BinaryOperator::Or);
+            normalize(&mut clause);
+            clause
+        })
+        .collect();
+    let mut cnf = chain(clauses, &BinaryOperator::And);
+    normalize(&mut cnf);
+    Some(cnf)
+}
+
+/// Rewrites the conditions of a statement in [to_cnf] form: the WHERE, HAVING and JOIN ... ON
+/// conditions of its queries, and the WHERE condition of UPDATE and DELETE. Conditions that cannot
+/// be converted are left as they are.
+pub(crate) fn conditions_to_cnf(stmt: &mut Statement) {
+    match stmt {
+        Statement::Update(update) => condition_to_cnf(update.where_clause.as_mut()),
+        Statement::Delete(delete) => condition_to_cnf(delete.where_clause.as_mut()),
+        _ => {}
+    }
+    walk::walk_statement_queries(stmt, &mut |select: &mut SelectStatement| {
+        condition_to_cnf(select.where_clause.as_mut());
+        condition_to_cnf(select.having.as_mut());
+        if let Some(from) = &mut select.from {
+            join_conditions_to_cnf(from);
+        }
+    });
+}
+
+fn join_conditions_to_cnf(table: &mut TableReference) {
+    let (_, joins) = table.join_chain_mut();
+    for (_, right, constraint) in joins {
+        join_conditions_to_cnf(right);
+        if let JoinConstraint::On(condition) = constraint {
+            condition_to_cnf(Some(condition));
+        }
+    }
+}
+
+fn condition_to_cnf(condition: Option<&mut Expr>) {
+    if let Some(condition) = condition
+        && let Some(cnf) = to_cnf(condition)
+    {
+        *condition = cnf;
+    }
+}
+
+/// Clauses of the conjunctive normal form of a condition, each given by its operands.
+fn clauses(mut expr: Expr) -> Option<Vec<Vec<Expr>>> {
+    match &mut expr {
+        Expr::BinaryOp {
+            op: BinaryOperator::And,
+            ..
+        } => {
+            let mut clauses = Vec::new();
+            for conjunct in operands(expr, &BinaryOperator::And) {
+                clauses.extend(self::clauses(conjunct)?);
+                if clauses.len() > MAX_CNF_CLAUSES {
+                    return None;
+                }
+            }
+            Some(clauses)
+        }
+        // (a AND b) OR c → (a OR c) AND (b OR c): each clause of the left side with each clause
+        // of the right one.
+        Expr::BinaryOp {
+            op: BinaryOperator::Or,
+            ..
+        } => {
+            let mut product = vec![Vec::new()];
+            for disjunct in operands(expr, &BinaryOperator::Or) {
+                let clauses = self::clauses(disjunct)?;
+                if product.len() * clauses.len() > MAX_CNF_CLAUSES {
+                    return None;
+                }
+                let repeated = product.len() > 1 || clauses.len() > 1;
+                let deterministic = || {
+                    let mut terms = product.iter().chain(&clauses).flatten();
+                    terms.all(is_deterministic)
+                };
+                if repeated && !deterministic() {
+                    return None;
+                }
+                product = product
+                    .iter()
+                    .flat_map(|left| clauses.iter().map(move |right| [&left[..], right].concat()))
+                    .collect();
+            }
+            Some(product)
+        }
+        Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            expr,
+        } if matches!(
+            **expr,
+            Expr::BinaryOp {
+                op: BinaryOperator::And | BinaryOperator::Or,
+                ..
+            }
+        ) =>
+        {
+            clauses(negate(mem::replace(&mut **expr, Expr::Null)))
+        }
+        _ => Some(vec![vec![expr]]),
+    }
+}
@@ -0,0 +1,225 @@
+use crate::ast::*;
+use crate::dialect::{Dialect, IdentNormalization};
+use std::collections::HashMap;
+use std::mem;
+
+/// Catalog that unqualified names belong to.
+pub(crate) const DEFAULT_CATALOG: &str = "default";
+/// Schema that unqualified names belong to.
+pub(crate) const DEFAULT_SCHEMA: &str = "public";
+
+/// Schema of a single table, as declared by its CREATE TABLE statement.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TableSchema {
+    /// Fully qualified name of the table: catalog.schema.table.
+    pub(crate) name: ObjectName,
+    pub(crate) columns: Vec<ColumnDef>,
+}
+
+impl TableSchema {
+    /// Names of the columns of the table, in declaration order.
+    pub(crate) fn column_names(&self) -> Vec<Ident> {
+        self.columns.iter().map(|c| c.name.clone()).collect()
+    }
+}
+
+/// Reads a search path written as schemas separated by commas, the first one optionally
+/// qualified by the default catalog: `analytics.staging,public`. Returns the catalog, which is
+/// [DEFAULT_CATALOG] when none is given, and the schemas.
+pub(crate) fn parse_search_path(path: &str) -> Result<(String, Vec<String>), String> {
+    let (catalog, schemas) = match path.split_once('.') {
+        Some((catalog, schemas)) => (catalog.trim(), schemas),
+        None => (DEFAULT_CATALOG, path),
+    };
+    let schemas: Vec<String> = schemas.split(',').map(|s| s.trim().to_string()).collect();
+    if catalog.is_empty() || schemas.iter().any(|s| s.is_empty() || s.contains('.')) {
+        return Err(format!(
+            "Invalid search path '{path}', expected [CATALOG.]SCHEMA[,SCHEMA...]"
+        ));
+    }
+    Ok((catalog.to_string(), schemas))
+}
+
+/// Fully qualified, normalized table name used as the catalog key.
+type TableKey = (String, String, String);
+
+/// In-memory catalog of the tables known to the session.
+///
+/// The catalog is built by replaying DDL statements, so it always reflects
+/// the schema as it would look after running them in order.
+///
+/// Names with missing parts are resolved with a search path, made of a default catalog
+/// and a list of schemas that are tried in order. New tables with unqualified names are
+/// created in the first schema of the search path.
+///
+/// Names are compared after applying the normalization policy of the catalog, so with the
+/// default ANSI policy `users`, `USERS` and `"USERS"` all refer to the same table.
+#[derive(Debug, Clone)]
+pub(crate) struct Catalog {
+    tables: HashMap<TableKey, TableSchema>,
+    default_catalog: Ident,
+    search_path: Vec<Ident>,
+    normalization: IdentNormalization,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self {
+            tables: HashMap::new(),
+            default_catalog: Ident::new(DEFAULT_CATALOG),
+            search_path: vec![Ident::new(DEFAULT_SCHEMA)],
+            normalization: Dialect::default().normalization(),
+        }
+    }
+}
+
+impl Catalog {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the default catalog and the schemas searched for unqualified names.
+    pub(crate) fn set_search_path(&mut self, catalog: &str, schemas: &[impl AsRef<str>]) {
+        self.default_catalog = Ident::new(catalog);
+        self.search_path = schemas.iter().map(|s| Ident::new(s.as_ref())).collect();
+    }
+
+    /// The search path in the form read by [parse_search_path], such as `default.public`.
+    pub(crate) fn search_path(&self) -> String {
+        let schemas: Vec<String> = self.search_path.iter().map(|s| s.to_string()).collect();
+        format!("{}.{}", self.default_catalog, schemas.join(","))
+    }
+
+    /// Sets how unquoted identifiers are folded before names are compared.
+    /// The tables already in the catalog are keyed again with the new policy.
+    pub(crate) fn set_normalization(&mut self, normalization: IdentNormalization) {
+        self.normalization = normalization;
+        let tables = mem::take(&mut self.tables);
+        self.tables = tables
+            .into_values()
+            .map(|table| {
+                let [catalog, schema, name] = &table.name.0[..] else {
+                    unreachable!("catalog tables have fully qualified names");
+                };
+                (self.key(catalog, schema, name), table)
+            })
+            .collect();
+    }
+
+    /// Policy used to compare the names of tables and columns.
+    pub(crate) fn normalization(&self) -> IdentNormalization {
+        self.normalization
+    }
+
+    fn key(&self, catalog: &Ident, schema: &Ident, table: &Ident) -> TableKey {
+        (
+            catalog.normalized(self.normalization),
+            schema.normalized(self.normalization),
+            table.normalized(self.normalization),
+        )
+    }
+
+    /// Resolves the fully qualified name of a new object.
+    fn qualified_name(&self, name: &ObjectName) -> Result<ObjectName, String> {
+        let parts = match name.0.as_slice() {
+            [table] => vec![
+                self.default_catalog.clone(),
+                self.search_path
+                    .first()
+                    .cloned()
+                    .ok_or("The search path is empty".to_string())?,
+                table.clone(),
+            ],
+            [schema, table] => vec![self.default_catalog.clone(), schema.clone(), table.clone()],
+            [_, _, _] => name.0.clone(),
+            _ => return Err(format!("Invalid table name: {name}")),
+        };
+        Ok(ObjectName(parts))
+    }
+
+    /// Keys a name may refer to, in search path order.
+    fn lookup_keys(&self, name: &ObjectName) -> Vec<TableKey> {
+        match name.0.as_slice() {
+            [table] => self
+                .search_path
+                .iter()
+                .map(|schema| self.key(&self.default_catalog, schema, table))
+                .collect(),
+            [schema, table] => vec![self.key(&self.default_catalog, schema, table)],
+            [catalog, schema, table] => vec![self.key(catalog, schema, table)],
+            _ => vec![],
+        }
+    }
+
+    fn key_of(&self, name: &ObjectName) -> Option<TableKey> {
+        self.lookup_keys(name)
+            .into_iter()
+            .find(|key| self.tables.contains_key(key))
+    }
+
+    /// Applies the effect of a statement on the schema.
+    /// Statements that do not modify the schema are ignored.
+    pub(crate) fn apply(&mut self, stmt: &Statement) -> Result<(), String> {
+        match stmt {
+            Statement::CreateTable(create) => {
+                let name = self.qualified_name(&create.table)?;
+                let key = self.key(&name.0[0], &name.0[1], &name.0[2]);
+                self.tables.insert(
+                    key,
+                    TableSchema {
+                        name,
+                        columns: create.columns.clone(),
+                    },
+                );
+            }
+            Statement::DropTable(drop) => {
+                let removed = self
+                    .key_of(&drop.table)
+                    .and_then(|key| self.tables.remove(&key));
+                if removed.is_none() && !drop.if_exists {
+                    return Err(format!("Table '{}' does not exist", drop.table));
+                }
+            }
+            Statement::AlterTable(alter) => {
+                let policy = self.normalization;
+                let table = self
+                    .key_of(&alter.table)
+                    .and_then(|key| self.tables.get_mut(&key))
+                    .ok_or(format!("Table '{}' does not exist", alter.table))?;
+
+                match &alter.action {
+                    AlterAction::AddColumn(column) => table.columns.push(column.clone()),
+                    AlterAction::DropColumn(name) => {
+                        let name = name.normalized(policy);
+                        table.columns.retain(|c| c.name.normalized(policy) != name)
+                    }
+                    AlterAction::AlterColumn(AlterColumnStatement {
+                        name,
+                        action: AlterColumnAction::SetDataType(data_type),
+                    }) => {
+                        if let Some(column) = table
+                            .columns
+                            .iter_mut()
+                            .find(|c| c.name.normalized(policy) == name.normalized(policy))
+                        {
+                            column.data_type = data_type.clone();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Every table of the catalog, in no particular order.
+    pub(crate) fn tables(&self) -> impl Iterator<Item = &TableSchema> {
+        self.tables.values()
+    }
+
+    /// Looks up a table by name, resolving missing parts with the search path.
+    pub(crate) fn table(&self, name: &ObjectName) -> Option<&TableSchema> {
+        self.key_of(name).and_then(|key| self.tables.get(&key))
+    }
+}
@@ -0,0 +1,887 @@
+//! Non-interactive command-line interface, for pipelines, CI and pre-commit hooks.
+//!
+//! Each command reads SQL scripts from files, from the `.sql` files found in directories, or from
+//! the standard input, and writes its result to the standard output. Problems are reported one per
+//! line, either as `file:line:column: severity: message` or as JSON objects, and the exit status
+//! tells what kind of problem was found. See [USAGE] for the commands and their options.
+use crate::ast::Statement;
+use crate::binder::Binder;
+use crate::catalog::{Catalog, parse_search_path};
+use crate::cte::Materialization;
+use crate::dialect::Dialect;
+use crate::json::{Document, StatementNode};
+use crate::lexer::Lexer;
+use crate::parser::{Parser, ParserLimits};
+use crate::plan::plan;
+use crate::repl::statement_spans;
+use crate::simplify::{Rewrites, Simplify};
+use crate::token::{Location, Span, Token};
+use crate::tree::statement_tree;
+use crate::visitor::Visitor;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "\
+Usage: parser [COMMAND [OPTIONS] [FILES...]]
+
+Without a command, starts the interactive shell.
+
+Commands:
+  parse       Parse statements and print their syntax tree
+  format      Print statements in canonical form
+  simplify    Simplify statements and print them as SQL
+  lint        Report likely mistakes
+  explain     Print the logical plan of statements
+  tokens      Print the tokens read by the lexer
+  validate    Check table and column names against a schema
+
+Options:
+  -o, --output FORMAT   Output format: tree, ast, sql or json for parse; sql or json for
+                        simplify; text or json for lint, validate, tokens and format --check
+  --dialect NAME        SQL dialect: ansi, postgres, mysql, mssql or sqlite
+  --check               With format, report files that are not formatted instead of printing them
+  --from-json           With format, read AST documents, one per line, and print their statements
+  --schema FILE         With lint and validate, a DDL script creating the tables
+  --search-path PATH    With lint and validate, the schemas searched for unqualified table names,
+                        as [CATALOG.]SCHEMA[,SCHEMA...]; default.public by default
+  --ctes MODE           With simplify, merge identical CTEs, remove unused ones, and inline the
+                        others: those referenced once with auto, none with materialized, all
+                        with inline. AS [NOT] MATERIALIZED decides for a single CTE
+  --extract-ctes        With simplify, move the derived tables a statement repeats to CTEs
+  --decorrelate         With simplify, rewrite the subqueries correlated through equalities as
+                        joins with derived tables
+  --expand-null-functions
+                        With simplify, rewrite COALESCE and NULLIF as CASE expressions
+  --cnf                 With simplify, rewrite conditions as ANDs of ORs, unless that takes more
+                        than 64 clauses
+  --max-depth N         Maximum nesting of expressions and subqueries; 128 by default
+  --max-nodes N         Maximum number of expression nodes in a statement; 100000 by default
+  --max-tokens N        Maximum number of tokens in a statement; 1000000 by default
+  -h, --help            Show this message
+
+Statements over the limits are reported as syntax errors. Raising --max-depth lets deeper
+statements through, at the risk of running out of stack.
+
+FILES are SQL scripts, or directories searched for .sql files. Without FILES, or with -, the
+standard input is read.
+
+With json, parse and simplify write one AST document per script and per line, in the format
+described in the documentation of the json module. format --from-json reads them back.
+
+Problems are reported one per line, as 'file:line:column: severity: message' or as JSON objects.
+They go to the standard output for lint, validate and format --check, and to the standard error
+for the other commands.
+
+Exit status:
+  0  Success
+  1  Syntax errors
+  2  Semantic errors: unknown names, or problems found by lint
+  3  Files that are not formatted, with format --check
+  4  Invalid arguments, or files that cannot be read
+";
+
+/// Outcome of a command, reported as the exit status of the process.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Status {
+    Success,
+    /// Some statements could not be parsed.
+    SyntaxError,
+    /// Some statements refer to unknown names, or lint found likely mistakes.
+    SemanticError,
+    /// `format --check` found files that are not formatted.
+    Unformatted,
+    /// The arguments are invalid, or a file could not be read.
+    Failure,
+}
+
+impl Status {
+    pub(crate) fn code(self) -> i32 {
+        match self {
+            Status::Success => 0,
+            Status::SyntaxError => 1,
+            Status::SemanticError => 2,
+            Status::Unformatted => 3,
+            Status::Failure => 4,
+        }
+    }
+
+    /// Rank of the status when a command finds several kinds of problems. Syntax errors come before
+    /// semantic ones, as statements that do not parse cannot be checked any further.
+    fn severity(self) -> u8 {
+        match self {
+            Status::Success => 0,
+            Status::Unformatted => 1,
+            Status::SemanticError => 2,
+            Status::SyntaxError => 3,
+            Status::Failure => 4,
+        }
+    }
+
+    fn worst(self, other: Status) -> Status {
+        if other.severity() > self.severity() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+    Parse,
+    Format,
+    Simplify,
+    Lint,
+    Explain,
+    Tokens,
+    Validate,
+}
+
+impl Command {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "parse" => Some(Self::Parse),
+            "format" => Some(Self::Format),
+            "simplify" => Some(Self::Simplify),
+            "lint" => Some(Self::Lint),
+            "explain" => Some(Self::Explain),
+            "tokens" => Some(Self::Tokens),
+            "validate" => Some(Self::Validate),
+            _ => None,
+        }
+    }
+
+    /// Output formats the command accepts, the default one first.
+    fn outputs(self, check: bool) -> &'static [Output] {
+        match self {
+            Command::Parse => &[Output::Tree, Output::Ast, Output::Sql, Output::Json],
+            Command::Format if check => &[Output::Text, Output::Json],
+            Command::Format => &[Output::Sql],
+            Command::Simplify => &[Output::Sql, Output::Json],
+            Command::Explain => &[Output::Text],
+            Command::Lint | Command::Validate | Command::Tokens => &[Output::Text, Output::Json],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Output {
+    Text,
+    Json,
+    Sql,
+    Tree,
+    Ast,
+}
+
+impl Output {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            "sql" => Some(Self::Sql),
+            "tree" => Some(Self::Tree),
+            "ast" => Some(Self::Ast),
+            _ => None,
+        }
+    }
+}
+
+/// Arguments of a command.
+struct Options {
+    command: Command,
+    output: Output,
+    dialect: Dialect,
+    check: bool,
+    /// Whether format reads AST documents instead of SQL.
+    from_json: bool,
+    schema: Option<String>,
+    /// Default catalog and schemas searched for unqualified table names.
+    search_path: Option<(String, Vec<String>)>,
+    limits: ParserLimits,
+    /// Rewrites applied by simplify.
+    rewrites: Rewrites,
+    paths: Vec<String>,
+}
+
+impl Options {
+    /// Parses the arguments that follow the program name. Returns None if help was asked for.
+    fn parse(args: &[String]) -> Result<Option<Self>, String> {
+        let Some(name) = args.first() else {
+            return Ok(None);
+        };
+        if matches!(name.as_str(), "-h" | "--help" | "help") {
+            return Ok(None);
+        }
+        let command = Command::from_name(name).ok_or(format!("Unknown command '{name}'"))?;
+
+        let (mut output, mut dialect, mut check, mut from_json, mut schema) =
+            (None, Dialect::default(), false, false, None);
+        let mut search_path = None;
+        let mut limits = ParserLimits::default();
+        let mut rewrites = Rewrites::default();
+        let mut paths = Vec::new();
+        let mut args = args[1..].iter();
+        while let Some(arg) = args.next() {
+            // Options take their value either as the next argument or after an equals sign.
+            let (option, inline) = match arg.split_once('=') {
+                Some((option, value)) if option.starts_with("--") => (option, Some(value)),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline
+                    .map(str::to_string)
+                    .or_else(|| args.next().cloned())
+                    .ok_or(format!("Missing value for {option}"))
+            };
+            match option {
+                "-h" | "--help" => return Ok(None),
+                "-o" | "--output" => output = Some(value()?),
+                "--dialect" => {
+                    let name = value()?;
+                    dialect =
+                        Dialect::from_name(&name).ok_or(format!("Unknown dialect '{name}'"))?;
+                }
+                "--schema" => schema = Some(value()?),
+                "--search-path" => search_path = Some(parse_search_path(&value()?)?),
+                "--max-depth" => limits.max_depth = limit(option, &value()?)?,
+                "--max-nodes" => limits.max_nodes = limit(option, &value()?)?,
+                "--max-tokens" => limits.max_tokens = limit(option, &value()?)?,
+                "--ctes" => {
+                    let name = value()?;
+                    rewrites.ctes = Some(Materialization::from_name(&name).ok_or(format!(
+                        "Unknown CTE mode '{name}', expected auto, materialized or inline"
+                    ))?);
+                }
+                "--extract-ctes" => rewrites.extract_ctes = true,
+                "--decorrelate" => rewrites.decorrelate = true,
+                "--expand-null-functions" => rewrites.expand_null_functions = true,
+                "--cnf" => rewrites.cnf = true,
+                "--check" => check = true,
+                "--from-json" => from_json = true,
+                "--" => {
+                    paths.extend(args.by_ref().cloned());
+                }
+                option if option.starts_with('-') && option != "-" => {
+                    return Err(format!("Unknown option '{option}'"));
+                }
+                path => paths.push(path.to_string()),
+            }
+        }
+
+        let outputs = command.outputs(check);
+        let output = match output {
+            Some(output) => Output::from_name(&output)
+                .filter(|format| outputs.contains(format))
+                .ok_or(format!(
+                    "Output format '{output}' is not available for {name}"
+                ))?,
+            None => outputs[0],
+        };
+        if check && command != Command::Format {
+            return Err("--check is only available for format".to_string());
+        }
+        if from_json && (command != Command::Format || check) {
+            return Err("--from-json is only available for format, without --check".to_string());
+        }
+        if schema.is_some() && !matches!(command, Command::Lint | Command::Validate) {
+            return Err("--schema is only available for lint and validate".to_string());
+        }
+        if search_path.is_some() && !matches!(command, Command::Lint | Command::Validate) {
+            return Err("--search-path is only available for lint and validate".to_string());
+        }
+        if rewrites != Rewrites::default() && command != Command::Simplify {
+            return Err("Rewrite options are only available for simplify".to_string());
+        }
+        if schema.is_none() && command == Command::Validate {
+            return Err("validate needs a schema, given with --schema".to_string());
+        }
+        Ok(Some(Self {
+            command,
+            output,
+            dialect,
+            check,
+            from_json,
+            schema,
+            search_path,
+            limits,
+            rewrites,
+            paths,
+        }))
+    }
+}
+
+/// Parses the value of a limit option, a positive number.
+fn limit(option: &str, value: &str) -> Result<usize, String> {
+    value.parse().ok().filter(|&limit| limit > 0).ok_or(format!(
+        "Invalid value '{value}' for {option}, expected a positive number"
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Error,
+    Warning,
+}
+
+/// What a problem is about, which decides the exit status.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Kind {
+    Syntax,
+    Semantic,
+    Format,
+}
+
+/// A problem found in a script, located at the character where it was detected.
+#[derive(Serialize)]
+struct Problem<'f> {
+    file: &'f str,
+    #[serde(flatten)]
+    location: Location,
+    severity: Severity,
+    kind: Kind,
+    message: String,
+}
+
+impl Problem<'_> {
+    fn status(&self) -> Status {
+        match self.kind {
+            Kind::Syntax => Status::SyntaxError,
+            Kind::Semantic => Status::SemanticError,
+            Kind::Format => Status::Unformatted,
+        }
+    }
+
+    fn text(&self) -> String {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        format!(
+            "{}:{}:{}: {severity}: {}",
+            self.file, self.location.line, self.location.column, self.message
+        )
+    }
+
+    fn json(&self) -> String {
+        serde_json::to_string(self).expect("problems only hold strings and numbers")
+    }
+}
+
+/// A token printed by the tokens command as JSON.
+#[derive(Serialize)]
+struct TokenRecord<'s> {
+    file: &'s str,
+    #[serde(flatten)]
+    location: Location,
+    #[serde(flatten)]
+    span: Span,
+    kind: &'s str,
+    text: &'s str,
+}
+
+/// Runs a command with the arguments that follow the program name, and returns its outcome.
+pub(crate) fn run(
+    args: &[String],
+    stdin: &mut dyn Read,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Status {
+    let options = match Options::parse(args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            return match write!(out, "{USAGE}") {
+                Ok(()) => Status::Success,
+                Err(_) => Status::Failure,
+            };
+        }
+        Err(message) => {
+            // Writing to the error stream is best effort, the status already tells what happened.
+            writeln!(err, "error: {message}\nRun 'parser --help' for usage.").ok();
+            return Status::Failure;
+        }
+    };
+
+    let mut cli = Cli {
+        options,
+        out,
+        err,
+        status: Status::Success,
+        schema: Catalog::new(),
+    };
+    if let Err(error) = cli.run(stdin) {
+        // A closed pipe means the reader has seen enough, as with `parser tokens | head`.
+        if error.kind() != io::ErrorKind::BrokenPipe {
+            writeln!(cli.err, "error: {error}").ok();
+        }
+        cli.status = Status::Failure;
+    }
+    cli.status
+}
+
+/// State of a running command.
+struct Cli<'o> {
+    options: Options,
+    out: &'o mut dyn Write,
+    err: &'o mut dyn Write,
+    status: Status,
+    /// Tables created by the `--schema` script.
+    schema: Catalog,
+}
+
+impl Cli<'_> {
+    fn run(&mut self, stdin: &mut dyn Read) -> io::Result<()> {
+        self.schema
+            .set_normalization(self.options.dialect.normalization());
+        if let Some((catalog, schemas)) = &self.options.search_path {
+            self.schema.set_search_path(catalog, schemas);
+        }
+        if let Some(path) = self.options.schema.clone() {
+            let Some(source) = self.read(&path, stdin)? else {
+                return Ok(());
+            };
+            for (span, stmt) in self.parse(&path, &source)? {
+                if let Err(message) = self.schema.apply(&stmt) {
+                    let location = location(&source, span.start);
+                    self.report(Problem {
+                        file: &path,
+                        location,
+                        severity: Severity::Error,
+                        kind: Kind::Semantic,
+                        message,
+                    })?;
+                }
+            }
+        }
+
+        let mut paths = Vec::new();
+        for path in &self.options.paths {
+            if path != "-" && Path::new(path).is_dir() {
+                sql_files(Path::new(path), &mut paths)?;
+            } else {
+                paths.push(path.clone());
+            }
+        }
+        if self.options.paths.is_empty() {
+            paths.push("-".to_string());
+        }
+        for path in paths {
+            if let Some(source) = self.read(&path, stdin)? {
+                let file = if path == "-" { "<stdin>" } else { &path };
+                self.run_file(file, &source)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a script, from the standard input for `-`. A file that cannot be read is reported,
+    /// and None is returned for it.
+    fn read(&mut self, path: &str, stdin: &mut dyn Read) -> io::Result<Option<String>> {
+        let result = if path == "-" {
+            let mut source = String::new();
+            stdin.read_to_string(&mut source).map(|_| source)
+        } else {
+            fs::read_to_string(path)
+        };
+        match result {
+            Ok(source) => Ok(Some(source)),
+            Err(error) => {
+                writeln!(self.err, "error: Cannot read {path}: {error}")?;
+                self.status = self.status.worst(Status::Failure);
+                Ok(None)
+            }
+        }
+    }
+
+    fn run_file(&mut self, file: &str, source: &str) -> io::Result<()> {
+        if self.options.command == Command::Tokens {
+            return self.tokens(file, source);
+        }
+        if self.options.from_json {
+            return self.format_documents(file, source);
+        }
+
+        let statements = self.parse(file, source)?;
+        match self.options.command {
+            Command::Parse if self.options.output == Output::Json => {
+                self.write_document(file, statements)?
+            }
+            Command::Parse => {
+                for (_, stmt) in &statements {
+                    match self.options.output {
+                        Output::Ast => writeln!(self.out, "{stmt:#?}")?,
+                        Output::Sql => writeln!(self.out, "{stmt};")?,
+                        _ => write!(self.out, "{}", statement_tree(stmt))?,
+                    }
+                }
+            }
+            Command::Format => {
+                // A script with syntax errors is left alone, rather than formatted without the
+                // statements that do not parse.
+                if statements.len() < statement_spans(source, self.options.dialect).len() {
+                    return Ok(());
+                }
+                let formatted = formatted(source, &statements, self.options.dialect);
+                if !self.options.check {
+                    write!(self.out, "{formatted}")?;
+                } else if formatted != source {
+                    let offset = source
+                        .bytes()
+                        .zip(formatted.bytes())
+                        .position(|(a, b)| a != b)
+                        .unwrap_or(source.len().min(formatted.len()));
+                    self.report(Problem {
+                        file,
+                        location: location(source, offset),
+                        severity: Severity::Error,
+                        kind: Kind::Format,
+                        message: "File is not formatted".to_string(),
+                    })?;
+                }
+            }
+            Command::Simplify => {
+                let mut simplified = Vec::new();
+                for (span, mut stmt) in statements {
+                    match stmt.simplify() {
+                        Ok(()) => {
+                            self.options.rewrites.apply(&mut stmt);
+                            simplified.push((span, stmt));
+                        }
+                        Err(message) => self.report(Problem {
+                            file,
+                            location: location(
+                                source,
+                                statement_start(source, span, self.options.dialect),
+                            ),
+                            severity: Severity::Error,
+                            kind: Kind::Semantic,
+                            message,
+                        })?,
+                    }
+                }
+                if self.options.output == Output::Json {
+                    self.write_document(file, simplified)?;
+                } else {
+                    for (_, stmt) in simplified {
+                        writeln!(self.out, "{stmt};")?;
+                    }
+                }
+            }
+            Command::Explain => {
+                for (i, (_, stmt)) in statements.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(self.out)?;
+                    }
+                    write!(self.out, "{}", plan(stmt))?;
+                }
+            }
+            Command::Lint | Command::Validate => self.check(file, source, statements)?,
+            Command::Tokens => unreachable!("tokens are printed without parsing"),
+        }
+        Ok(())
+    }
+
+    /// Writes the statements of a script as a JSON document, on a single line.
+    fn write_document(&mut self, file: &str, statements: Vec<(Span, Statement)>) -> io::Result<()> {
+        let statements = statements
+            .into_iter()
+            .map(|(span, statement)| StatementNode {
+                span: Some(span),
+                statement,
+            })
+            .collect();
+        let document = Document::new(Some(file.to_string()), statements);
+        writeln!(self.out, "{}", document.to_json())
+    }
+
+    /// Parses the statements of a script, reporting syntax errors. Returns the statements that
+    /// parse, with their spans.
+    fn parse(&mut self, file: &str, source: &str) -> io::Result<Vec<(Span, Statement)>> {
+        let (dialect, limits) = (self.options.dialect, self.options.limits);
+        let mut statements = Vec::new();
+        for span in statement_spans(source, dialect) {
+            let sql = &source[span.start..span.end];
+            let mut parser = Parser::new(Lexer::with_dialect(sql, dialect)).with_limits(limits);
+            let message = match parser.visit() {
+                Ok(stmt) => {
+                    statements.push((span, stmt));
+                    continue;
+                }
+                Err(message) => message,
+            };
+
+            // Report every syntax error in the statement, not only the first one.
+            let (_, diagnostics) = Parser::new(Lexer::with_dialect(sql, dialect))
+                .with_limits(limits)
+                .parse_statements();
+            if diagnostics.is_empty() {
+                self.report(Problem {
+                    file,
+                    location: location(source, span.start),
+                    severity: Severity::Error,
+                    kind: Kind::Syntax,
+                    message,
+                })?;
+            }
+            for diagnostic in diagnostics {
+                self.report(Problem {
+                    file,
+                    location: location(source, span.start + diagnostic.span.start),
+                    severity: Severity::Error,
+                    kind: Kind::Syntax,
+                    message: diagnostic.message,
+                })?;
+            }
+        }
+        Ok(statements)
+    }
+
+    /// Prints the statements of the AST documents of a file, one document per line, as SQL.
+    /// Documents that cannot be read are reported as syntax errors.
+    fn format_documents(&mut self, file: &str, source: &str) -> io::Result<()> {
+        let mut start = 0;
+        for line in source.split_inclusive('\n') {
+            let offset = start;
+            start += line.len();
+            if line.trim().is_empty() {
+                continue;
+            }
+            match Document::from_json(line) {
+                Ok(document) => {
+                    for node in document.statements {
+                        writeln!(self.out, "{};", node.statement)?;
+                    }
+                }
+                Err(message) => self.report(Problem {
+                    file,
+                    location: location(source, offset),
+                    severity: Severity::Error,
+                    kind: Kind::Syntax,
+                    message,
+                })?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints the tokens of a script, and reports the invalid ones.
+    fn tokens(&mut self, file: &str, source: &str) -> io::Result<()> {
+        let mut lexer = Lexer::with_dialect(source, self.options.dialect);
+        loop {
+            let (token, span) = lexer.next_token_with_span();
+            let location = location(source, span.start);
+            match token {
+                Token::Eof => return Ok(()),
+                Token::Error { message, .. } => self.report(Problem {
+                    file,
+                    location,
+                    severity: Severity::Error,
+                    kind: Kind::Syntax,
+                    message,
+                })?,
+                token if self.options.output == Output::Json => {
+                    let debug = format!("{token:?}");
+                    let kind = debug
+                        .split(|c: char| !c.is_alphanumeric())
+                        .next()
+                        .unwrap_or_default();
+                    let record = TokenRecord {
+                        file,
+                        location,
+                        span,
+                        kind,
+                        text: &source[span.start..span.end],
+                    };
+                    let json = serde_json::to_string(&record)
+                        .expect("tokens only hold strings and numbers");
+                    writeln!(self.out, "{json}")?
+                }
+                token => writeln!(
+                    self.out,
+                    "{file}:{}:{}\t{token:?}",
+                    location.line, location.column
+                )?,
+            }
+        }
+    }
+
+    /// Binds the statements of a script against the schema and the tables the script creates,
+    /// reporting unknown names. Lint also reports likely mistakes, and validate reports every
+    /// relation missing from the schema as an error.
+    fn check(
+        &mut self,
+        file: &str,
+        source: &str,
+        statements: Vec<(Span, Statement)>,
+    ) -> io::Result<()> {
+        let validate = self.options.command == Command::Validate;
+        let mut catalog = self.schema.clone();
+        for (span, mut stmt) in statements {
+            let mut problems = Vec::new();
+            if !validate {
+                problems.extend(lint(&stmt).into_iter().map(|m| (Severity::Warning, m)));
+            }
+
+            let mut binder = Binder::new(&catalog);
+            if validate {
+                binder = binder.with_complete_schema();
+            }
+            let bound = binder.bind(&mut stmt);
+            let severity = if validate {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+            problems.extend(binder.warnings().iter().map(|m| (severity, m.clone())));
+            if let Err(message) = bound.and_then(|()| catalog.apply(&stmt)) {
+                problems.push((Severity::Error, message));
+            }
+
+            for (severity, message) in problems {
+                self.report(Problem {
+                    file,
+                    location: location(source, statement_start(source, span, self.options.dialect)),
+                    severity,
+                    kind: Kind::Semantic,
+                    message,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a problem in the output format, to the standard output when reporting problems is
+    /// what the command is for, and to the standard error otherwise.
+    fn report(&mut self, problem: Problem<'_>) -> io::Result<()> {
+        self.status = self.status.worst(problem.status());
+        let line = match self.options.output {
+            Output::Json => problem.json(),
+            _ => problem.text(),
+        };
+        let reports =
+            matches!(self.options.command, Command::Lint | Command::Validate) || self.options.check;
+        if reports {
+            writeln!(self.out, "{line}")
+        } else {
+            writeln!(self.err, "{line}")
+        }
+    }
+}
+
+/// Likely mistakes in statements that are otherwise valid.
+fn lint(stmt: &Statement) -> Vec<String> {
+    match stmt {
+        Statement::Update(update) if update.where_clause.is_none() => {
+            vec![format!(
+                "UPDATE without WHERE changes every row of {}",
+                update.table
+            )]
+        }
+        Statement::Delete(delete) if delete.where_clause.is_none() => {
+            vec![format!(
+                "DELETE without WHERE removes every row of {}",
+                delete.table
+            )]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Canonical text of a script: each statement printed on its own line, with the comments around
+/// it. Statements with comments or optimizer hints inside are kept as written, as printing them
+/// would drop those.
+fn formatted(source: &str, statements: &[(Span, Statement)], dialect: Dialect) -> String {
+    let mut text = String::new();
+    let mut end = 0;
+    for (span, stmt) in statements {
+        let sql = &source[span.start..span.end];
+        let tokens = token_bounds(sql, dialect);
+        push_comments(&mut text, &source[end..span.start + tokens.start]);
+        if tokens.comments {
+            text.push_str(sql[tokens.start..tokens.end].trim_end_matches(';'));
+            text.push_str(";\n");
+        } else {
+            text.push_str(&format!("{stmt};\n"));
+        }
+        end = span.start + tokens.end;
+    }
+    push_comments(&mut text, &source[end..]);
+    text
+}
+
+/// Adds the comments found between two statements, on lines of their own. A comment that starts
+/// on the line of the previous statement stays there.
+fn push_comments(text: &mut String, between: &str) {
+    let comments = between.trim();
+    if comments.is_empty() {
+        return;
+    }
+    let indent = &between[..between.len() - between.trim_start().len()];
+    if !text.is_empty() && !indent.contains('\n') {
+        text.pop();
+        text.push(' ');
+    }
+    text.push_str(comments);
+    text.push('\n');
+}
+
+/// Where the tokens of a statement start and end.
+struct TokenBounds {
+    start: usize,
+    end: usize,
+    /// Whether comments or optimizer hints appear between the tokens.
+    comments: bool,
+}
+
+fn token_bounds(sql: &str, dialect: Dialect) -> TokenBounds {
+    let mut lexer = Lexer::with_dialect(sql, dialect);
+    let (mut start, mut end, mut comments) = (None, 0, false);
+    loop {
+        let (token, span) = lexer.next_token_with_span();
+        if token == Token::Eof {
+            return TokenBounds {
+                start: start.unwrap_or(end),
+                end,
+                comments,
+            };
+        }
+        if start.is_none() {
+            start = Some(span.start);
+        } else if !sql[end..span.start].trim().is_empty() {
+            comments = true;
+        }
+        if let Token::Hint(_) = token {
+            comments = true;
+        }
+        end = span.end;
+    }
+}
+
+/// Offset of the first token of a statement, after the comments that precede it.
+fn statement_start(source: &str, span: Span, dialect: Dialect) -> usize {
+    span.start + token_bounds(&source[span.start..span.end], dialect).start
+}
+
+/// Line and column of a byte offset in a script.
+fn location(source: &str, offset: usize) -> Location {
+    Lexer::new(source).location(offset)
+}
+
+/// Collects the `.sql` files in a directory and its subdirectories, in alphabetical order.
+fn sql_files(dir: &Path, files: &mut Vec<String>) -> io::Result<()> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            sql_files(&path, files)?;
+        } else if path.extension().is_some_and(|extension| extension == "sql") {
+            files.push(path.to_string_lossy().into_owned());
+        }
+    }
+    Ok(())
+}
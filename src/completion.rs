@@ -0,0 +1,288 @@
+//! Tab completion for the REPL.
+//!
+//! Keywords are completed with the tokens the parser expected where the text before the cursor
+//! ends, and table and column names come from the catalog of the session.
+use crate::ast::{Ident, ObjectName};
+use crate::catalog::Catalog;
+use crate::dialect::Dialect;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::token::{KEYWORDS, Span, Token};
+use rustyline::completion::Completer;
+use rustyline::hint::Hinter;
+use rustyline::{Context, Helper};
+
+/// Kind of name expected at the cursor, depending on the clause it is in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NameContext {
+    Table,
+    Column,
+}
+
+/// Line editor helper of the REPL. Syntax highlighting and input validation are implemented in
+/// their own modules.
+pub(crate) struct SqlHelper {
+    catalog: Catalog,
+    /// Dialect of the session, which the input is tokenized in. Set after `.dialect` changes it.
+    pub(crate) dialect: Dialect,
+}
+
+impl SqlHelper {
+    pub(crate) fn new(catalog: Catalog) -> Self {
+        Self {
+            catalog,
+            dialect: Dialect::default(),
+        }
+    }
+
+    /// Replaces the catalog names are completed from, after a statement changed the schema.
+    pub(crate) fn set_catalog(&mut self, catalog: Catalog) {
+        self.catalog = catalog;
+    }
+
+    /// Returns the start of the word under the cursor and the candidates that complete it:
+    /// names first, then keywords, each in alphabetical order.
+    pub(crate) fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let start = word_start(line, pos);
+        let word = &line[start..pos];
+
+        // After `alias.`, only the columns of that relation can follow.
+        if let Some(before_dot) = line[..start].strip_suffix('.') {
+            let qualifier = &before_dot[word_start(before_dot, before_dot.len())..];
+            let columns = relations(&tokens(line, self.dialect).0)
+                .into_iter()
+                .filter(|(name, alias)| {
+                    let policy = self.catalog.normalization();
+                    let qualifier = Ident::new(qualifier).normalized(policy);
+                    alias
+                        .as_ref()
+                        .or(name.0.last())
+                        .map(|n| n.normalized(policy))
+                        == Some(qualifier)
+                })
+                .flat_map(|(name, _)| self.columns(&name))
+                .collect();
+            return (start, matching(columns, word));
+        }
+
+        // Only the statement the cursor is in is parsed.
+        let (tokens, spans) = tokens(&line[..start], self.dialect);
+        let first = tokens
+            .iter()
+            .rposition(|token| *token == Token::Semicolon)
+            .map_or(0, |i| i + 1);
+        let offset = first.checked_sub(1).map_or(0, |i| spans[i].end);
+        let (statement, prefix) = (&tokens[first..], &line[offset..start]);
+
+        let (expected, expects_name) =
+            Parser::new(Lexer::with_dialect(prefix, self.dialect)).expected();
+        let mut candidates = Vec::new();
+        if expects_name && let Some(context) = name_context(statement) {
+            let names = match context {
+                NameContext::Table => self
+                    .catalog
+                    .tables()
+                    .filter_map(|table| table.name.0.last().cloned())
+                    .collect(),
+                NameContext::Column => {
+                    let in_scope: Vec<Ident> = relations(&self::tokens(line, self.dialect).0)
+                        .iter()
+                        .flat_map(|(name, _)| self.columns(name))
+                        .collect();
+                    if in_scope.is_empty() {
+                        // Before the FROM clause is written, any column may be meant.
+                        self.catalog
+                            .tables()
+                            .flat_map(|table| table.column_names())
+                            .collect()
+                    } else {
+                        in_scope
+                    }
+                }
+            };
+            candidates.extend(matching(names, word));
+        }
+
+        let lowercase = word.starts_with(|c: char| c.is_lowercase());
+        let mut keywords = Vec::new();
+        // Non-reserved keywords that are only accepted as names are not expected as keywords.
+        for (name, token) in KEYWORDS {
+            if !name.to_lowercase().starts_with(&word.to_lowercase()) || !expected.contains(token) {
+                continue;
+            }
+            keywords.push(if lowercase {
+                name.to_lowercase()
+            } else {
+                name.to_uppercase()
+            });
+        }
+        keywords.sort();
+        candidates.extend(keywords);
+        (start, candidates)
+    }
+
+    /// Columns of a table of the catalog, or none if the table is unknown.
+    fn columns(&self, name: &ObjectName) -> Vec<Ident> {
+        self.catalog
+            .table(name)
+            .map(|table| table.column_names())
+            .unwrap_or_default()
+    }
+}
+
+impl Completer for SqlHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+
+impl Hinter for SqlHelper {
+    type Hint = String;
+}
+
+impl Helper for SqlHelper {}
+
+/// Byte offset where the word that ends at `pos` starts.
+fn word_start(line: &str, pos: usize) -> usize {
+    line[..pos]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+        .last()
+        .map_or(pos, |(i, _)| i)
+}
+
+/// Every token of the input with its span, without the final EOF.
+fn tokens(input: &str, dialect: Dialect) -> (Vec<Token<'_>>, Vec<Span>) {
+    let mut lexer = Lexer::with_dialect(input, dialect);
+    let (mut tokens, mut spans) = (Vec::new(), Vec::new());
+    loop {
+        match lexer.next_token_with_span() {
+            (Token::Eof, _) => return (tokens, spans),
+            (token, span) => {
+                tokens.push(token);
+                spans.push(span);
+            }
+        }
+    }
+}
+
+/// Kind of name that can follow the tokens of a statement, or None if a name cannot follow the
+/// last one: after another name, a literal or a closing parenthesis comes an alias or an operator.
+fn name_context(tokens: &[Token<'_>]) -> Option<NameContext> {
+    let last = tokens.last()?;
+    if !(last.is_keyword()
+        || matches!(
+            last,
+            Token::Comma
+                | Token::LParen
+                | Token::Eq
+                | Token::Neq
+                | Token::Lt
+                | Token::Gt
+                | Token::Le
+                | Token::Ge
+                | Token::Plus
+                | Token::Minus
+                | Token::Slash
+                | Token::Percent
+                | Token::Concat
+                | Token::Tilde
+                | Token::TildeStar
+        ))
+        || *last == Token::As
+    {
+        return None;
+    }
+
+    let mut context = NameContext::Column;
+    for token in tokens {
+        match token {
+            Token::From | Token::Join | Token::Into | Token::Update | Token::Table => {
+                context = NameContext::Table
+            }
+            Token::Select
+            | Token::Where
+            | Token::On
+            | Token::Using
+            | Token::Group
+            | Token::Order
+            | Token::Having
+            | Token::Set
+            | Token::LParen => context = NameContext::Column,
+            _ => {}
+        }
+    }
+    Some(context)
+}
+
+/// Relations referenced by a statement, with their aliases: `FROM users u JOIN orders AS o`.
+/// Only the tokens are looked at, so this works on statements that are not finished yet.
+fn relations(tokens: &[Token<'_>]) -> Vec<(ObjectName, Option<Ident>)> {
+    let mut relations = Vec::new();
+    let mut in_from = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        let starts_relation = match &tokens[i] {
+            Token::From | Token::Join | Token::Update | Token::Into => {
+                in_from = true;
+                true
+            }
+            Token::Comma => in_from,
+            token if token.is_keyword() => {
+                in_from = false;
+                false
+            }
+            _ => false,
+        };
+        i += 1;
+        if !starts_relation {
+            continue;
+        }
+
+        let mut parts = Vec::new();
+        while let Some(Token::Identifier(word)) = tokens.get(i) {
+            parts.push(Ident::from(word));
+            i += 1;
+            if tokens.get(i) != Some(&Token::Dot) {
+                break;
+            }
+            i += 1;
+        }
+        if parts.is_empty() {
+            continue;
+        }
+        if tokens.get(i) == Some(&Token::As) {
+            i += 1;
+        }
+        let alias = match tokens.get(i) {
+            Some(Token::Identifier(word)) => {
+                i += 1;
+                Some(Ident::from(word))
+            }
+            _ => None,
+        };
+        relations.push((ObjectName(parts), alias));
+    }
+    relations
+}
+
+/// Names that start with `word`, ignoring case, in alphabetical order and without duplicates.
+fn matching(names: Vec<Ident>, word: &str) -> Vec<String> {
+    let word = word.to_lowercase();
+    let mut matching: Vec<String> = names
+        .into_iter()
+        .filter(|name| name.value.to_lowercase().starts_with(&word))
+        .map(|name| name.to_string())
+        .collect();
+    matching.sort();
+    matching.dedup();
+    matching
+}
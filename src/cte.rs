@@ -0,0 +1,417 @@
+//! Optimization of the common table expressions of WITH statements.
+//!
+//! A CTE is either materialized, computed once and read by each of its references, or inlined, its
+//! query copied as a derived table in place of each reference. Inlining lets the planner push the
+//! filters of the outer query into the CTE, while materializing avoids computing it several times:
+//!
+//! ```sql
+//! WITH a AS (SELECT * FROM t) SELECT * FROM a WHERE a.x = 1
+//! -- inlined, since it is referenced once
+//! SELECT * FROM (SELECT * FROM t) AS a WHERE a.x = 1
+//! ```
+//!
+//! `AS MATERIALIZED` and `AS NOT MATERIALIZED` decide for a single CTE, and [Materialization] for
+//! those that say nothing. Before inlining, [optimize] merges the CTEs with identical queries and
+//! removes those that are never referenced. [extract] does the reverse of inlining, and moves the
+//! derived tables that a statement repeats to a single CTE.
+use crate::ast::*;
+use crate::dialect::IdentNormalization;
+use crate::functions;
+use crate::walk;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+/// CTE names are compared without a dialect, folded as in ANSI SQL.
+const NORMALIZATION: IdentNormalization = IdentNormalization::Upper;
+
+/// What to do with the CTEs that are neither `AS MATERIALIZED` nor `AS NOT MATERIALIZED`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Materialization {
+    /// Inline the CTEs referenced once, and materialize the others, as PostgreSQL does.
+    #[default]
+    Auto,
+    /// Materialize every CTE, as with `AS MATERIALIZED`.
+    Always,
+    /// Inline every CTE, as with `AS NOT MATERIALIZED`.
+    Never,
+}
+
+impl Materialization {
+    /// Reads the name of the option that chooses it: auto, materialized or inline.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "materialized" => Some(Self::Always),
+            "inline" => Some(Self::Never),
+            _ => None,
+        }
+    }
+}
+
+/// Merges the identical CTEs of the statement, removes the unused ones, and inlines those that
+/// are not materialized. A WITH statement left without CTEs becomes its query.
+pub(crate) fn optimize(stmt: &mut Statement, default: Materialization) {
+    let Statement::With(with) = stmt else {
+        return;
+    };
+    // A recursive CTE refers to itself, and may refer to the CTEs after it.
+    if !with.recursive {
+        merge_identical(with);
+    }
+    remove_unused(with);
+    if !with.recursive {
+        inline(with, default);
+    }
+    if with.ctes.is_empty() {
+        let body = mem::replace(&mut with.body, Box::new(empty_query()));
+        *stmt = Statement::Select(*body);
+    }
+}
+
+/// Moves the derived tables that the statement repeats to CTEs, the largest first. Derived tables
+/// that are LATERAL, refer to outer columns, or call volatile functions are left in place.
+pub(crate) fn extract(stmt: &mut Statement) {
+    match stmt {
+        Statement::Select(select) => {
+            let mut with = WithStatement {
+                recursive: false,
+                ctes: Vec::new(),
+                body: Box::new(mem::replace(select, empty_query())),
+            };
+            extract_repeated(&mut with);
+            *stmt = match with.ctes.is_empty() {
+                true => Statement::Select(*with.body),
+                false => Statement::With(with),
+            };
+        }
+        Statement::With(with) if !with.recursive => extract_repeated(with),
+        _ => {}
+    }
+}
+
+fn empty_query() -> SelectStatement {
+    SelectStatement {
+        distinct: false,
+        columns: Vec::new(),
+        from: None,
+        where_clause: None,
+        group_by: Vec::new(),
+        having: None,
+        order_by: Vec::new(),
+        limit: None,
+    }
+}
+
+fn key(name: &Ident) -> String {
+    name.normalized(NORMALIZATION)
+}
+
+/// Calls `f` on every table reference of the query and of the queries nested in it.
+fn walk_tables(select: &mut SelectStatement, f: &mut dyn FnMut(&mut TableReference)) {
+    fn tables(table: &mut TableReference, f: &mut dyn FnMut(&mut TableReference)) {
+        // Joins are detached from their left sides and visited from the innermost one, so that
+        // long chains of joins are walked with a loop.
+        let mut joins = Vec::new();
+        let mut current = mem::replace(table, TableReference::Error);
+        while let TableReference::Join { left, .. } = &mut current {
+            let left = mem::replace(&mut **left, TableReference::Error);
+            joins.push(current);
+            current = left;
+        }
+        f(&mut current);
+        while let Some(mut join) = joins.pop() {
+            if let TableReference::Join { left, right, .. } = &mut join {
+                tables(right, f);
+                **left = current;
+            }
+            f(&mut join);
+            current = join;
+        }
+        *table = current;
+    }
+    walk::walk_queries(select, &mut |query| {
+        if let Some(from) = &mut query.from {
+            tables(from, f);
+        }
+    });
+}
+
+/// Name of the CTE that the table reference may refer to.
+fn cte_name(table: &TableReference) -> Option<String> {
+    match table {
+        TableReference::Table { name, .. } => match name.0.as_slice() {
+            [name] => Some(key(name)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The queries of the CTEs from `start` on, and the query of the statement, which are those that
+/// may refer to the CTEs before `start`.
+fn queries_from(with: &mut WithStatement, start: usize) -> Vec<&mut SelectStatement> {
+    let mut queries: Vec<&mut SelectStatement> = with.ctes[start..]
+        .iter_mut()
+        .map(|cte| &mut cte.query)
+        .collect();
+    queries.push(&mut with.body);
+    queries
+}
+
+/// Number of references to the CTE from the queries.
+fn references(queries: Vec<&mut SelectStatement>, name: &str) -> usize {
+    let mut count = 0;
+    for query in queries {
+        walk_tables(query, &mut |table| {
+            if cte_name(table).as_deref() == Some(name) {
+                count += 1;
+            }
+        });
+    }
+    count
+}
+
+/// Replaces the references to the CTE in the queries.
+fn replace_references(
+    queries: Vec<&mut SelectStatement>,
+    name: &str,
+    f: &mut dyn FnMut(Option<Ident>) -> TableReference,
+) {
+    for query in queries {
+        walk_tables(query, &mut |table| {
+            if cte_name(table).as_deref() == Some(name)
+                && let TableReference::Table { alias, .. } = table
+            {
+                *table = f(alias.take());
+            }
+        });
+    }
+}
+
+/// Returns true if the query calls no volatile function, nor any function missing from the
+/// registry.
+fn is_deterministic(query: &SelectStatement) -> bool {
+    let mut deterministic = true;
+    walk::walk_select(&mut query.clone(), &mut |expr| {
+        if let Expr::FunctionCall { name, .. } = expr {
+            deterministic &= functions::registry()
+                .get(name)
+                .is_some_and(|function| function.deterministic);
+        }
+    });
+    deterministic
+}
+
+/// Replaces each CTE whose query is the same as the query of an earlier one by that one.
+fn merge_identical(with: &mut WithStatement) {
+    let mut i = 0;
+    while i < with.ctes.len() {
+        let cte = &with.ctes[i];
+        let same = with.ctes[..i].iter().position(|earlier| {
+            earlier.query == cte.query && earlier.materialized == cte.materialized
+        });
+        // Each copy of a volatile query has its own results.
+        let Some(same) = same.filter(|_| is_deterministic(&cte.query)) else {
+            i += 1;
+            continue;
+        };
+        let removed = with.ctes.remove(i);
+        let kept = with.ctes[same].name.clone();
+        // The references keep the name of the removed CTE as alias, for the columns it qualifies.
+        replace_references(queries_from(with, i), &key(&removed.name), &mut |alias| {
+            TableReference::Table {
+                name: ObjectName(vec![kept.clone()]),
+                alias: alias.or_else(|| Some(removed.name.clone())),
+            }
+        });
+    }
+}
+
+/// Removes the CTEs that neither the statement nor the other CTEs refer to, until all of those
+/// left are referenced.
+fn remove_unused(with: &mut WithStatement) {
+    loop {
+        let unused = (0..with.ctes.len()).rev().find(|&i| {
+            let name = key(&with.ctes[i].name);
+            // References of a CTE to itself do not count.
+            let mut queries: Vec<&mut SelectStatement> = Vec::new();
+            for (j, cte) in with.ctes.iter_mut().enumerate() {
+                if j != i {
+                    queries.push(&mut cte.query);
+                }
+            }
+            queries.push(&mut with.body);
+            references(queries, &name) == 0
+        });
+        match unused {
+            Some(i) => {
+                with.ctes.remove(i);
+            }
+            None => return,
+        }
+    }
+}
+
+/// Replaces the references to the CTEs that are not materialized by derived tables.
+fn inline(with: &mut WithStatement, default: Materialization) {
+    let mut i = 0;
+    while i < with.ctes.len() {
+        let name = key(&with.ctes[i].name);
+        let materialized = with.ctes[i].materialized.or(match default {
+            Materialization::Auto => None,
+            Materialization::Always => Some(true),
+            Materialization::Never => Some(false),
+        });
+        let inlined = match materialized {
+            Some(materialized) => !materialized,
+            // Inlined into a correlated subquery, a volatile query would run once per row.
+            None => {
+                is_deterministic(&with.ctes[i].query)
+                    && references(queries_from(with, i + 1), &name) == 1
+            }
+        };
+        if !inlined {
+            i += 1;
+            continue;
+        }
+        let cte = with.ctes.remove(i);
+        replace_references(queries_from(with, i), &name, &mut |alias| {
+            TableReference::Subquery {
+                query: Box::new(cte.query.clone()),
+                alias: alias.unwrap_or_else(|| cte.name.clone()),
+                lateral: false,
+            }
+        });
+    }
+}
+
+/// Returns true if the derived table can be computed once for the whole statement: it calls no
+/// volatile function, and its columns are all qualified with its own tables or not at all.
+fn is_shareable(query: &SelectStatement) -> bool {
+    if !is_deterministic(query) {
+        return false;
+    }
+    let mut query = query.clone();
+    let mut tables = HashSet::new();
+    walk_tables(&mut query, &mut |table| match table {
+        TableReference::Table {
+            alias: Some(alias), ..
+        }
+        | TableReference::Subquery { alias, .. } => {
+            tables.insert(key(alias));
+        }
+        TableReference::Table { name, alias: None } => {
+            tables.extend(name.0.last().map(key));
+        }
+        TableReference::Join { .. } | TableReference::Error => {}
+    });
+    let mut local = true;
+    walk::walk_select(&mut query, &mut |expr| {
+        if let Expr::QualifiedIdentifier { table, .. } = expr {
+            local &= table
+                .0
+                .last()
+                .is_some_and(|name| tables.contains(&key(name)));
+        }
+    });
+    local
+}
+
+/// Moves the repeated derived tables of the statement to new CTEs.
+fn extract_repeated(with: &mut WithStatement) {
+    let mut used: HashSet<String> = with.ctes.iter().map(|cte| key(&cte.name)).collect();
+    for query in queries_from(with, 0) {
+        walk_tables(query, &mut |table| match table {
+            TableReference::Table { name, alias } => {
+                used.extend(name.0.last().map(key));
+                used.extend(alias.as_ref().map(key));
+            }
+            TableReference::Subquery { alias, .. } => {
+                used.insert(key(alias));
+            }
+            TableReference::Join { .. } | TableReference::Error => {}
+        });
+    }
+    let mut count = 0;
+
+    loop {
+        // Each derived table by its SQL text, with its number of occurrences and the first query
+        // it appears in, in the order they are found.
+        let mut found: Vec<(String, usize, usize)> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for (position, query) in queries_from(with, 0).into_iter().enumerate() {
+            walk_tables(query, &mut |table| {
+                if let TableReference::Subquery {
+                    query,
+                    lateral: false,
+                    ..
+                } = table
+                {
+                    let text = query.to_string();
+                    match index.get(&text) {
+                        Some(&i) => found[i].1 += 1,
+                        None => {
+                            index.insert(text.clone(), found.len());
+                            found.push((text, 1, position));
+                        }
+                    }
+                }
+            });
+        }
+        found.sort_by_key(|(text, _, _)| Reverse(text.len()));
+        let mut repeated = found.into_iter().filter(|(_, count, _)| *count > 1);
+        let Some((text, position, query)) = repeated.find_map(|(text, _, position)| {
+            let query = first_derived_table(with, &text)?;
+            is_shareable(&query).then_some((text, position, query))
+        }) else {
+            return;
+        };
+
+        let name = loop {
+            count += 1;
+            let name = Ident::new(format!("cte{count}"));
+            if used.insert(key(&name)) {
+                break name;
+            }
+        };
+        for query in queries_from(with, 0) {
+            walk_tables(query, &mut |table| {
+                if let TableReference::Subquery { query, alias, .. } = table
+                    && query.to_string() == text
+                {
+                    *table = TableReference::Table {
+                        name: ObjectName(vec![name.clone()]),
+                        alias: Some(alias.clone()),
+                    };
+                }
+            });
+        }
+        // The CTE goes before the first query that refers to it, which is after the CTEs that
+        // the derived table refers to.
+        with.ctes.insert(
+            position,
+            Cte {
+                name,
+                materialized: None,
+                query,
+            },
+        );
+    }
+}
+
+/// The first derived table of the statement with the SQL text.
+fn first_derived_table(with: &mut WithStatement, text: &str) -> Option<SelectStatement> {
+    let mut first = None;
+    for query in queries_from(with, 0) {
+        walk_tables(query, &mut |table| {
+            if let TableReference::Subquery { query, .. } = table
+                && first.is_none()
+                && query.to_string() == text
+            {
+                first = Some((**query).clone());
+            }
+        });
+    }
+    first
+}
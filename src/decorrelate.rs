@@ -0,0 +1,577 @@
+//! Decorrelation of subqueries into joins with derived tables.
+//!
+//! A subquery that refers to the columns of the query around it is evaluated once per outer row.
+//! When it only compares its own columns with outer ones using `=`, the same result comes from
+//! computing it once for every key, in a derived table, and joining that table back on the keys:
+//!
+//! ```sql
+//! SELECT * FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.a = t.a AND u.b > 0)
+//! -- semi-join: at most one row of the derived table per key
+//! SELECT t.* FROM t JOIN (SELECT DISTINCT u.a AS key1 FROM u WHERE u.b > 0) AS subquery1
+//!   ON t.a = subquery1.key1
+//!
+//! SELECT t.x, (SELECT COUNT(*) FROM u WHERE u.a = t.a) FROM t
+//! -- grouped derived table, with the count of the keys it has no rows for
+//! SELECT t.x, COALESCE(subquery1.value, 0) FROM t
+//!   LEFT JOIN (SELECT u.a AS key1, COUNT(*) AS value FROM u GROUP BY u.a) AS subquery1
+//!   ON t.a = subquery1.key1
+//! ```
+//!
+//! `NOT EXISTS` becomes an anti-join: a LEFT JOIN that keeps the rows without a match. `NOT IN` is
+//! NULL when the list has a NULL or the tested value is NULL, so its anti-join also counts the rows
+//! and the NULLs of the subquery for each key.
+//!
+//! Columns are told apart by their qualifier, since there is no schema: those qualified with a
+//! table of the outer FROM clause that the subquery does not shadow are outer columns, and all the
+//! others belong to the subquery. Subqueries correlated in any other way are left as they are, and
+//! so are those with columns qualified with a table that neither the outer FROM clause nor their
+//! own FROM clauses name, which belong to queries further out.
+use crate::ast::*;
+use crate::boolean;
+use crate::functions;
+use crate::plan::has_aggregate;
+use crate::walk;
+use std::collections::HashSet;
+use std::mem;
+
+/// Rewrites the subqueries of the statement as joins, wherever they can be. The queries nested in
+/// a query are rewritten before it.
+pub(crate) fn decorrelate(stmt: &mut Statement) {
+    let mut aliases = Aliases::default();
+    walk::walk_statement_queries(stmt, &mut |select| unnest(select, &mut aliases));
+}
+
+/// Names of the derived tables, numbered across the statement.
+#[derive(Default)]
+struct Aliases {
+    count: usize,
+}
+
+impl Aliases {
+    /// Next name that is not one of the tables in scope.
+    fn next(&mut self, tables: &HashSet<String>) -> Ident {
+        loop {
+            self.count += 1;
+            let name = format!("subquery{}", self.count);
+            if !tables.contains(&name) {
+                return Ident::new(name);
+            }
+        }
+    }
+}
+
+/// Name that the columns of each table of the FROM clause are qualified with.
+fn table_names(table: &TableReference, names: &mut HashSet<String>) {
+    match table {
+        TableReference::Table {
+            alias: Some(alias), ..
+        }
+        | TableReference::Subquery { alias, .. } => {
+            names.insert(alias.value.clone());
+        }
+        TableReference::Table { name, alias: None } => {
+            names.extend(name.0.last().map(|part| part.value.clone()));
+        }
+        TableReference::Join { .. } => {
+            let (first, joins) = table.join_chain();
+            table_names(first, names);
+            for (_, right, _) in joins {
+                table_names(right, names);
+            }
+        }
+        TableReference::Error => {}
+    }
+}
+
+/// One qualified wildcard per table of the FROM clause, which select the same columns as `*`
+/// unless joins merge some of them.
+fn wildcards(table: &TableReference, items: &mut Vec<SelectItem>) -> Option<()> {
+    let name = match table {
+        TableReference::Table {
+            alias: Some(alias), ..
+        }
+        | TableReference::Subquery { alias, .. } => ObjectName(vec![alias.clone()]),
+        TableReference::Table { name, alias: None } => name.clone(),
+        TableReference::Join { .. } => {
+            let (first, joins) = table.join_chain();
+            wildcards(first, items)?;
+            for (_, right, constraint) in joins {
+                if matches!(
+                    constraint,
+                    JoinConstraint::Using(_) | JoinConstraint::Natural
+                ) {
+                    return None;
+                }
+                wildcards(right, items)?;
+            }
+            return Some(());
+        }
+        TableReference::Error => return None,
+    };
+    items.push(SelectItem::QualifiedWildcard(
+        name,
+        WildcardOptions::default(),
+    ));
+    Some(())
+}
+
+/// Returns true if the expression is a column of one of the tables.
+fn is_column_of(expr: &Expr, tables: &HashSet<String>) -> bool {
+    match expr {
+        Expr::QualifiedIdentifier { table, .. } => table
+            .0
+            .last()
+            .is_some_and(|part| tables.contains(&part.value)),
+        _ => false,
+    }
+}
+
+/// Returns true if the expression refers to a column of one of the tables, in subqueries too.
+fn refers_to(expr: &Expr, tables: &HashSet<String>) -> bool {
+    let mut found = false;
+    walk::walk_expr(&mut expr.clone(), &mut |expr| {
+        found |= is_column_of(expr, tables);
+    });
+    found
+}
+
+/// Returns true if a column of the query, or of the queries nested in it, is qualified with a
+/// table that is not one of the tables nor named in their FROM clauses.
+fn refers_elsewhere(query: &SelectStatement, tables: &HashSet<String>) -> bool {
+    let mut query = query.clone();
+    let mut known = tables.clone();
+    walk::walk_queries(&mut query, &mut |select| {
+        if let Some(from) = &select.from {
+            table_names(from, &mut known);
+        }
+    });
+    let mut found = false;
+    walk::walk_select(&mut query, &mut |expr| {
+        found |= matches!(expr, Expr::QualifiedIdentifier { .. }) && !is_column_of(expr, &known);
+    });
+    found
+}
+
+/// A subquery taken apart for a join: the equalities that correlate it with the outer query, and
+/// the rest of its filter.
+struct Correlated {
+    from: TableReference,
+    /// Expression of the subquery and the outer column it is equal to.
+    keys: Vec<(Expr, Expr)>,
+    filter: Option<Expr>,
+}
+
+impl Correlated {
+    /// Splits a subquery that is correlated only through equalities between its filter and the
+    /// columns of the outer tables, and whose rows are not grouped or limited.
+    fn new(query: &SelectStatement, outer: &HashSet<String>) -> Option<Self> {
+        let from = query.from.as_ref()?;
+        if !query.group_by.is_empty() || query.having.is_some() || query.limit.is_some() {
+            return None;
+        }
+        if refers_elsewhere(query, outer) {
+            return None;
+        }
+        let mut inner = HashSet::new();
+        table_names(from, &mut inner);
+        let outer: HashSet<String> = outer.difference(&inner).cloned().collect();
+        let mut rest = query.clone();
+        rest.where_clause = None;
+        let mut found = false;
+        walk::walk_select(&mut rest, &mut |expr| found |= is_column_of(expr, &outer));
+        if found {
+            return None;
+        }
+        let mut keys = Vec::new();
+        let mut filter = Vec::new();
+        let conjuncts = match &query.where_clause {
+            Some(condition) => boolean::operand_refs(condition, &BinaryOperator::And),
+            None => Vec::new(),
+        };
+        for conjunct in conjuncts {
+            if !refers_to(conjunct, &outer) {
+                filter.push(conjunct.clone());
+                continue;
+            }
+            let Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Eq,
+                right,
+            } = conjunct
+            else {
+                return None;
+            };
+            let key = match (&**left, &**right) {
+                (inner, column) | (column, inner)
+                    if is_column_of(column, &outer) && !refers_to(inner, &outer) =>
+                {
+                    (inner.clone(), column.clone())
+                }
+                _ => return None,
+            };
+            keys.push(key);
+        }
+        Some(Correlated {
+            from: from.clone(),
+            keys,
+            filter: (!filter.is_empty()).then(|| boolean::chain(filter, &BinaryOperator::And)),
+        })
+    }
+
+    /// Derived table of the columns, followed by the keys, over the rows of the subquery.
+    fn derived(
+        &self,
+        columns: Vec<(Expr, &str)>,
+        distinct: bool,
+        grouped: bool,
+    ) -> SelectStatement {
+        let keys = self
+            .keys
+            .iter()
+            .enumerate()
+            .map(|(i, (inner, _))| (inner.clone(), format!("key{}", i + 1)));
+        let columns = columns
+            .into_iter()
+            .map(|(expr, name)| (expr, name.to_string()))
+            .chain(keys)
+            .map(|(expr, name)| SelectItem::ExprWithAlias {
+                expr,
+                alias: Some(Ident::new(name)),
+            })
+            .collect();
+        let group_by = match grouped {
+            true => self.keys.iter().map(|(inner, _)| inner.clone()).collect(),
+            false => Vec::new(),
+        };
+        SelectStatement {
+            distinct,
+            columns,
+            from: Some(self.from.clone()),
+            where_clause: self.filter.clone(),
+            group_by,
+            having: None,
+            order_by: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Equalities between the outer columns and the keys of the derived table.
+    fn join_condition(&self, alias: &Ident) -> Vec<Expr> {
+        self.keys
+            .iter()
+            .enumerate()
+            .map(|(i, (_, outer))| equal(outer.clone(), column(alias, &format!("key{}", i + 1))))
+            .collect()
+    }
+}
+
+fn column(table: &Ident, name: &str) -> Expr {
+    Expr::QualifiedIdentifier {
+        table: ObjectName(vec![table.clone()]),
+        column: Ident::new(name),
+    }
+}
+
+fn binary(left: Expr, op: BinaryOperator, right: Expr) -> Expr {
+    Expr::BinaryOp {
+        left: Box::new(left),
+        op,
+        right: Box::new(right),
+    }
+}
+
+fn equal(left: Expr, right: Expr) -> Expr {
+    binary(left, BinaryOperator::Eq, right)
+}
+
+fn is_null(expr: Expr, null: bool) -> Expr {
+    let op = match null {
+        true => BinaryOperator::Is,
+        false => BinaryOperator::IsNot,
+    };
+    binary(expr, op, Expr::Null)
+}
+
+fn count(args: Vec<Expr>) -> Expr {
+    Expr::FunctionCall {
+        name: "COUNT".to_string(),
+        args,
+        distinct: false,
+    }
+}
+
+/// The joins added to a query, in order.
+#[derive(Default)]
+struct Joins(Vec<(JoinType, SelectStatement, Ident, Option<Expr>)>);
+
+impl Joins {
+    /// Adds a join with the derived table on the conditions. Without conditions, an inner join is
+    /// a cross join and a left join is made on TRUE.
+    fn add(&mut self, join_type: JoinType, query: SelectStatement, alias: &Ident, on: Vec<Expr>) {
+        let condition = match (on.is_empty(), &join_type) {
+            (true, JoinType::Inner) => None,
+            (true, _) => Some(Expr::Boolean(true)),
+            (false, _) => Some(boolean::chain(on, &BinaryOperator::And)),
+        };
+        let join_type = match condition {
+            None => JoinType::Cross,
+            Some(_) => join_type,
+        };
+        self.0.push((join_type, query, alias.clone(), condition));
+    }
+}
+
+/// Rewrites the subqueries of the query that are correlated with its FROM clause.
+fn unnest(select: &mut SelectStatement, aliases: &mut Aliases) {
+    let Some(from) = &select.from else {
+        return;
+    };
+    let mut outer = HashSet::new();
+    table_names(from, &mut outer);
+    // The derived tables must not add columns to `*`.
+    let mut star = None;
+    if select
+        .columns
+        .iter()
+        .any(|item| matches!(item, SelectItem::Star(_)))
+    {
+        let plain = select.columns.iter().all(|item| match item {
+            SelectItem::Star(options) => *options == WildcardOptions::default(),
+            _ => true,
+        });
+        let mut items = Vec::new();
+        if !plain || wildcards(from, &mut items).is_none() {
+            return;
+        }
+        star = Some(items);
+    }
+
+    let mut joins = Joins::default();
+    if let Some(condition) = select.where_clause.take() {
+        let conjuncts = boolean::operands(condition, &BinaryOperator::And)
+            .into_iter()
+            .filter_map(|conjunct| unnest_predicate(conjunct, &outer, aliases, &mut joins))
+            .collect::<Vec<_>>();
+        if !conjuncts.is_empty() {
+            select.where_clause = Some(boolean::chain(conjuncts, &BinaryOperator::And));
+        }
+    }
+    // A derived table of aggregates adds columns that a grouped query could not select.
+    let grouped = !select.group_by.is_empty()
+        || select.having.is_some()
+        || select.columns.iter().any(|item| match item {
+            SelectItem::ExprWithAlias { expr, .. } => has_aggregate(expr),
+            _ => false,
+        });
+    if !grouped {
+        let mut exprs: Vec<&mut Expr> = Vec::new();
+        for item in &mut select.columns {
+            if let SelectItem::ExprWithAlias { expr, .. } = item {
+                exprs.push(expr);
+            }
+        }
+        exprs.extend(select.where_clause.as_mut());
+        exprs.extend(select.order_by.iter_mut().map(|item| &mut item.expr));
+        unnest_scalars(exprs, &outer, aliases, &mut joins);
+    }
+
+    if joins.0.is_empty() {
+        return;
+    }
+    if let Some(items) = star {
+        select.columns = mem::take(&mut select.columns)
+            .into_iter()
+            .flat_map(|item| match item {
+                SelectItem::Star(_) => items.clone(),
+                item => vec![item],
+            })
+            .collect();
+    }
+    let mut table = select.from.take();
+    for (join_type, query, alias, condition) in joins.0 {
+        table = table.map(|left| TableReference::Join {
+            left: Box::new(left),
+            join_type,
+            right: Box::new(TableReference::Subquery {
+                query: Box::new(query),
+                alias,
+                lateral: false,
+            }),
+            constraint: condition.map_or(JoinConstraint::None, JoinConstraint::On),
+        });
+    }
+    select.from = table;
+}
+
+/// Replaces an EXISTS or IN predicate of the filter by a join. Returns what remains of the
+/// predicate in the filter.
+fn unnest_predicate(
+    conjunct: Expr,
+    outer: &HashSet<String>,
+    aliases: &mut Aliases,
+    joins: &mut Joins,
+) -> Option<Expr> {
+    let (negated, value, query) = match &conjunct {
+        Expr::Exists(query) => (false, None, query),
+        Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            expr,
+        } => match &**expr {
+            Expr::Exists(query) => (true, None, query),
+            _ => return Some(conjunct),
+        },
+        Expr::BinaryOp { left, op, right } => match (op, &**right) {
+            (BinaryOperator::In, Expr::Subquery(query)) => (false, Some(&**left), query),
+            (BinaryOperator::NotIn, Expr::Subquery(query)) => (true, Some(&**left), query),
+            _ => return Some(conjunct),
+        },
+        _ => return Some(conjunct),
+    };
+    let Some(correlated) = Correlated::new(query, outer) else {
+        return Some(conjunct);
+    };
+    let alias = aliases.next(outer);
+    let Some(value) = value else {
+        // EXISTS: the keys of the rows of the subquery, or any row if it is not correlated.
+        let columns = match correlated.keys.is_empty() {
+            true => vec![(Expr::Number(1.0), "present")],
+            false => Vec::new(),
+        };
+        let marker = match correlated.keys.is_empty() {
+            true => "present",
+            false => "key1",
+        };
+        let query = correlated.derived(columns, true, false);
+        let on = correlated.join_condition(&alias);
+        if !negated {
+            joins.add(JoinType::Inner, query, &alias, on);
+            return None;
+        }
+        joins.add(JoinType::Left, query, &alias, on);
+        return Some(is_null(column(&alias, marker), true));
+    };
+
+    let [SelectItem::ExprWithAlias { expr: item, .. }] = query.columns.as_slice() else {
+        return Some(conjunct);
+    };
+    if has_aggregate(item) || (negated && !boolean::is_deterministic(value)) {
+        return Some(conjunct);
+    }
+    let value = value.clone();
+    let matches = correlated.derived(vec![(item.clone(), "value")], true, false);
+    let mut on = vec![equal(value.clone(), column(&alias, "value"))];
+    on.extend(correlated.join_condition(&alias));
+    if !negated {
+        joins.add(JoinType::Inner, matches, &alias, on);
+        return None;
+    }
+
+    // NOT IN is TRUE for an empty subquery, and otherwise NULL unless the value is not NULL and
+    // the subquery has neither the value nor NULL.
+    joins.add(JoinType::Left, matches, &alias, on);
+    let counts_alias = aliases.next(outer);
+    let counts = correlated.derived(
+        vec![
+            (count(vec![Expr::Star]), "total"),
+            (count(vec![item.clone()]), "non_null"),
+        ],
+        false,
+        true,
+    );
+    let total = column(&counts_alias, "total");
+    // A key without rows has no group, and no row in the left join.
+    let empty = match correlated.keys.is_empty() {
+        true => equal(total.clone(), Expr::Number(0.0)),
+        false => is_null(total.clone(), true),
+    };
+    let complete = binary(
+        is_null(value, false),
+        BinaryOperator::And,
+        equal(total, column(&counts_alias, "non_null")),
+    );
+    // Without keys, the counts are a single row.
+    let counts_join = match correlated.keys.is_empty() {
+        true => JoinType::Inner,
+        false => JoinType::Left,
+    };
+    joins.add(
+        counts_join,
+        counts,
+        &counts_alias,
+        correlated.join_condition(&counts_alias),
+    );
+    Some(binary(
+        is_null(column(&alias, "value"), true),
+        BinaryOperator::And,
+        binary(empty, BinaryOperator::Or, complete),
+    ))
+}
+
+/// Replaces the correlated scalar subqueries of a single aggregate in the expressions by the
+/// column of a grouped derived table.
+fn unnest_scalars(
+    mut exprs: Vec<&mut Expr>,
+    outer: &HashSet<String>,
+    aliases: &mut Aliases,
+    joins: &mut Joins,
+) {
+    while let Some(expr) = exprs.pop() {
+        match expr {
+            Expr::Subquery(query) => {
+                if let Some(replacement) = unnest_scalar(query, outer, aliases, joins) {
+                    *expr = replacement;
+                }
+            }
+            Expr::Exists(_) => {}
+            Expr::BinaryOp { left, op, right } => {
+                exprs.push(left);
+                // The subquery of IN is a list, not a value.
+                let list = matches!(op, BinaryOperator::In | BinaryOperator::NotIn)
+                    && matches!(**right, Expr::Subquery(_));
+                if !list {
+                    exprs.push(right);
+                }
+            }
+            expr => exprs.extend(expr.children_mut()),
+        }
+    }
+}
+
+fn unnest_scalar(
+    query: &SelectStatement,
+    outer: &HashSet<String>,
+    aliases: &mut Aliases,
+    joins: &mut Joins,
+) -> Option<Expr> {
+    let [SelectItem::ExprWithAlias { expr: item, .. }] = query.columns.as_slice() else {
+        return None;
+    };
+    let Expr::FunctionCall { name, .. } = item else {
+        return None;
+    };
+    if !functions::registry().is_aggregate(name) {
+        return None;
+    }
+    let correlated = Correlated::new(query, outer)?;
+    if correlated.keys.is_empty() {
+        return None;
+    }
+    let alias = aliases.next(outer);
+    let derived = correlated.derived(vec![(item.clone(), "value")], false, true);
+    joins.add(
+        JoinType::Left,
+        derived,
+        &alias,
+        correlated.join_condition(&alias),
+    );
+    let value = column(&alias, "value");
+    // The other aggregates are NULL over no rows, like the column of a missing key.
+    if !name.eq_ignore_ascii_case("COUNT") {
+        return Some(value);
+    }
+    Some(Expr::FunctionCall {
+        name: "COALESCE".to_string(),
+        args: vec![value, Expr::Number(0.0)],
+        distinct: false,
+    })
+}
@@ -0,0 +1,89 @@
+//! Problems found while parsing, collected by error recovery instead of stopping at the first one,
+//! and "did you mean" suggestions for misspelled keywords and names.
+use crate::token::{KEYWORDS, Location, Span};
+use std::fmt;
+
+/// A syntax or lexical error, located at the token where it was detected.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Diagnostic {
+    pub(crate) message: String,
+    pub(crate) location: Location,
+    pub(crate) span: Span,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.location)
+    }
+}
+
+/// Case-insensitive edit distance between two words, counting insertions, deletions,
+/// substitutions and transpositions of adjacent characters as one edit each, so `FORM` is one
+/// edit away from `FROM`.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().flat_map(char::to_lowercase).collect();
+    let b: Vec<char> = b.chars().flat_map(char::to_lowercase).collect();
+
+    // Three rows of the distance matrix: two rows back, the previous one and the current one.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        (before, previous, current) = (previous, current, before);
+    }
+    previous[b.len()]
+}
+
+/// Number of edits up to which a word is taken for a misspelling: none for very short words,
+/// which are close to too many names, and more for longer ones.
+fn max_distance(word: &str) -> usize {
+    match word.chars().count() {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+/// Returns the candidate closest to `word` if it is close enough to be what was meant.
+/// Ties go to the first candidate, and a candidate spelled exactly like the word is never suggested.
+pub(crate) fn suggest<'c>(
+    word: &str,
+    candidates: impl IntoIterator<Item = &'c str>,
+) -> Option<&'c str> {
+    let max = max_distance(word);
+    let mut best: Option<(usize, &'c str)> = None;
+    for candidate in candidates {
+        if candidate == word {
+            continue;
+        }
+        let distance = edit_distance(word, candidate);
+        if distance <= max && best.is_none_or(|(d, _)| distance < d) {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+/// Returns the keyword a word is probably a misspelling of, in upper case.
+pub(crate) fn suggest_keyword(word: &str) -> Option<String> {
+    suggest(word, KEYWORDS.iter().map(|(name, _)| *name)).map(str::to_uppercase)
+}
+
+/// Formats the end of a message that proposes a name: `, did you mean 'name'?`.
+/// Returns an empty string when there is nothing to propose.
+pub(crate) fn did_you_mean(suggestion: Option<&str>) -> String {
+    match suggestion {
+        Some(suggestion) => format!(", did you mean '{suggestion}'?"),
+        None => String::new(),
+    }
+}
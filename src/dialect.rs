@@ -0,0 +1,59 @@
+/// How unquoted identifiers are case folded before names are compared.
+/// Quoted identifiers are never folded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IdentNormalization {
+    /// ANSI SQL: unquoted identifiers are folded to upper case.
+    Upper,
+    /// PostgreSQL: unquoted identifiers are folded to lower case.
+    Lower,
+    /// Identifiers are kept as written and compared exactly.
+    Preserve,
+}
+
+/// SQL dialects understood by the lexer.
+///
+/// Dialects only differ in lexical details, such as which characters may quote identifiers
+/// and how unquoted identifiers are normalized. The grammar is the same for all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Dialect {
+    #[default]
+    Ansi,
+    Postgres,
+    MySql,
+    MsSql,
+    Sqlite,
+}
+
+impl Dialect {
+    /// Looks up a dialect by its (case insensitive) name.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "ansi" => Some(Self::Ansi),
+            "postgres" | "postgresql" => Some(Self::Postgres),
+            "mysql" => Some(Self::MySql),
+            "mssql" | "sqlserver" => Some(Self::MsSql),
+            "sqlite" => Some(Self::Sqlite),
+            _ => None,
+        }
+    }
+
+    /// Returns the closing quote for an identifier opened with `ch`, if `ch` opens a quoted
+    /// identifier in this dialect. Double quotes are accepted by every dialect.
+    pub(crate) fn identifier_quote_end(&self, ch: char) -> Option<char> {
+        match (self, ch) {
+            (_, '"') => Some('"'),
+            (Self::MySql | Self::Sqlite, '`') => Some('`'),
+            (Self::MsSql | Self::Sqlite, '[') => Some(']'),
+            _ => None,
+        }
+    }
+
+    /// Default normalization policy for unquoted identifiers.
+    pub(crate) fn normalization(&self) -> IdentNormalization {
+        match self {
+            Self::Ansi => IdentNormalization::Upper,
+            Self::Postgres => IdentNormalization::Lower,
+            Self::MySql | Self::MsSql | Self::Sqlite => IdentNormalization::Preserve,
+        }
+    }
+}
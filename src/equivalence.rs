@@ -0,0 +1,226 @@
+//! Inference of predicates from the equalities of a query, used by simplification.
+//!
+//! The columns that a query's filters compare with `=` form equivalence classes, whose members
+//! all have the same value in every row that passes the filters. Each class gives predicates that
+//! the query does not state:
+//!
+//! ```sql
+//! a.id = b.id AND a.id = 42   -- b.id = 42, which restricts the scan of b
+//! a = b AND b = c             -- a = c, which joins a and c directly
+//! ```
+//!
+//! Equalities come from WHERE and from the ON conditions of inner joins. The condition of an outer
+//! join holds only for the rows it matches, not for the rows it fills with NULLs, and neither do
+//! the conditions of the joins on its nullable side, so they are left out.
+use crate::ast::*;
+use crate::boolean;
+use crate::eval::Value;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+/// Largest class whose columns are all paired with each other. The number of pairs grows with the
+/// square of the size of the class, and the equalities written in the query already connect it.
+pub(crate) const MAX_PAIRED_COLUMNS: usize = 8;
+
+/// Columns known to be equal to each other, and to constants.
+#[derive(Debug, Default)]
+pub(crate) struct EquivalenceClasses {
+    classes: Vec<Class>,
+    /// Class of each column, by its SQL text.
+    index: HashMap<String, usize>,
+}
+
+#[derive(Debug, Default)]
+struct Class {
+    columns: Vec<Expr>,
+    /// Constants equal to the columns. More than one means no row passes the filters.
+    constants: Vec<Expr>,
+}
+
+impl EquivalenceClasses {
+    /// Classes of the equalities among the conjuncts: `column = column` and `column = constant`.
+    pub(crate) fn new(conjuncts: &[&Expr]) -> Self {
+        let mut classes = EquivalenceClasses::default();
+        for conjunct in conjuncts {
+            match equality(conjunct) {
+                Some((left, Term::Column(right))) => classes.union(left, right),
+                Some((column, Term::Constant(constant))) => {
+                    let class = classes.class_of(column);
+                    let constants = &mut classes.classes[class].constants;
+                    if !constants.contains(constant) {
+                        constants.push(constant.clone());
+                    }
+                }
+                None => {}
+            }
+        }
+        classes
+    }
+
+    fn class_of(&mut self, column: &Expr) -> usize {
+        let classes = &mut self.classes;
+        *self.index.entry(column.to_string()).or_insert_with(|| {
+            classes.push(Class {
+                columns: vec![column.clone()],
+                constants: Vec::new(),
+            });
+            classes.len() - 1
+        })
+    }
+
+    /// Records that two columns are equal, merging their classes.
+    fn union(&mut self, a: &Expr, b: &Expr) {
+        let (a, b) = (self.class_of(a), self.class_of(b));
+        if a == b {
+            return;
+        }
+        // The smaller class is moved into the larger one, which keeps its place.
+        let (into, from) = if self.classes[a].columns.len() >= self.classes[b].columns.len() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let moved = mem::take(&mut self.classes[from]);
+        for column in &moved.columns {
+            self.index.insert(column.to_string(), into);
+        }
+        let class = &mut self.classes[into];
+        class.columns.extend(moved.columns);
+        for constant in moved.constants {
+            if !class.constants.contains(&constant) {
+                class.constants.push(constant);
+            }
+        }
+    }
+
+    /// Equalities implied by the classes that are not among the conjuncts: each column of a class
+    /// with its constants, or with the other columns of a class without constants.
+    pub(crate) fn derived(&self, conjuncts: &[&Expr]) -> Vec<Expr> {
+        let mut known: HashSet<(String, String)> = HashSet::new();
+        for conjunct in conjuncts {
+            if let Some((left, right)) = equality(conjunct) {
+                let right = match right {
+                    Term::Column(expr) | Term::Constant(expr) => expr,
+                };
+                known.insert((left.to_string(), right.to_string()));
+                known.insert((right.to_string(), left.to_string()));
+            }
+        }
+        let mut derived = Vec::new();
+        let mut derive = |left: &Expr, right: &Expr| {
+            if known.insert((left.to_string(), right.to_string())) {
+                known.insert((right.to_string(), left.to_string()));
+                derived.push(Expr::BinaryOp {
+                    left: Box::new(left.clone()),
+                    op: BinaryOperator::Eq,
+                    right: Box::new(right.clone()),
+                });
+            }
+        };
+        for class in &self.classes {
+            if class.constants.is_empty() {
+                if class.columns.len() > MAX_PAIRED_COLUMNS {
+                    continue;
+                }
+                for (i, left) in class.columns.iter().enumerate() {
+                    for right in &class.columns[i + 1..] {
+                        derive(left, right);
+                    }
+                }
+            } else {
+                for column in &class.columns {
+                    for constant in &class.constants {
+                        derive(column, constant);
+                    }
+                }
+            }
+        }
+        derived
+    }
+}
+
+enum Term<'a> {
+    Column(&'a Expr),
+    Constant(&'a Expr),
+}
+
+/// The column and the other side of an equality between two columns or with a constant.
+fn equality(expr: &Expr) -> Option<(&Expr, Term<'_>)> {
+    let Expr::BinaryOp {
+        left,
+        op: BinaryOperator::Eq,
+        right,
+    } = expr
+    else {
+        return None;
+    };
+    let constant = |expr: &Expr| Value::from_expr(expr).is_some_and(|value| value != Value::Null);
+    match (&**left, &**right) {
+        (left, right) if left == right => None,
+        (left, right) if left.is_column() && right.is_column() => Some((left, Term::Column(right))),
+        (column, constant_side) if column.is_column() && constant(constant_side) => {
+            Some((column, Term::Constant(constant_side)))
+        }
+        (constant_side, column) if column.is_column() && constant(constant_side) => {
+            Some((column, Term::Constant(constant_side)))
+        }
+        _ => None,
+    }
+}
+
+/// Adds to the WHERE clause of the query the equalities implied by those of its filters. Returns
+/// true if any was added.
+pub(crate) fn infer_predicates(select: &mut SelectStatement) -> bool {
+    let mut conjuncts = match &select.where_clause {
+        Some(condition) => boolean::operand_refs(condition, &BinaryOperator::And),
+        None => Vec::new(),
+    };
+    if let Some(from) = &select.from {
+        join_conjuncts(from, &mut conjuncts);
+    }
+    let derived = EquivalenceClasses::new(&conjuncts).derived(&conjuncts);
+    if derived.is_empty() {
+        return false;
+    }
+    let mut condition = match select.where_clause.take() {
+        Some(condition) => boolean::operands(condition, &BinaryOperator::And),
+        None => Vec::new(),
+    };
+    condition.extend(derived);
+    select.where_clause = Some(boolean::chain(condition, &BinaryOperator::And));
+    true
+}
+
+/// Collects the conjuncts of the ON conditions that hold for every row of the joined tables: those
+/// of inner joins outside of the nullable side of an outer join.
+fn join_conjuncts<'a>(table: &'a TableReference, conjuncts: &mut Vec<&'a Expr>) {
+    // Chains of joins are followed with a loop from the last join, and the conjuncts of each join
+    // are added after those of the joins before it.
+    let mut joins = Vec::new();
+    let mut table = table;
+    while let TableReference::Join {
+        left,
+        join_type,
+        right,
+        constraint,
+    } = table
+    {
+        match join_type {
+            JoinType::Inner | JoinType::Cross => {
+                let mut found = Vec::new();
+                join_conjuncts(right, &mut found);
+                if let JoinConstraint::On(condition) = constraint {
+                    found.extend(boolean::operand_refs(condition, &BinaryOperator::And));
+                }
+                joins.push(found);
+                table = left;
+            }
+            JoinType::Left => table = left,
+            JoinType::Right => table = right,
+            JoinType::Full => break,
+        }
+    }
+    for found in joins.into_iter().rev() {
+        conjuncts.extend(found);
+    }
+}
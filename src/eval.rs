@@ -0,0 +1,384 @@
+//! Evaluation of constant expressions with SQL semantics, used by constant folding.
+//!
+//! NULL stands for a value that is not known. Arithmetic, concatenation, comparisons and LIKE
+//! return NULL when an operand is NULL, and the logical operators follow three-valued logic, in
+//! which NULL is the unknown truth value:
+//!
+//! | a     | b     | a AND b | a OR b | NOT a |
+//! |-------|-------|---------|--------|-------|
+//! | TRUE  | NULL  | NULL    | TRUE   | FALSE |
+//! | FALSE | NULL  | FALSE   | NULL   | TRUE  |
+//! | NULL  | NULL  | NULL    | NULL   | NULL  |
+//!
+//! `IS [NOT] NULL` and `IS [NOT] TRUE`, `FALSE` or `UNKNOWN` never return NULL.
+//!
+//! Evaluation gives up, returning None, when the result depends on the database rather than on
+//! the operands: a division by zero or an invalid pattern, which fail at run time, or a comparison
+//! between values of different types, which depends on the implicit casts of each database.
+use crate::ast::{BinaryOperator, Expr, UnaryOperator};
+use std::cmp::Ordering;
+
+/// A constant value.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+}
+
+impl Value {
+    /// Value of a literal, or None if the expression is not a literal the evaluator knows.
+    pub(crate) fn from_expr(expr: &Expr) -> Option<Value> {
+        match expr {
+            Expr::Null => Some(Value::Null),
+            Expr::Boolean(b) => Some(Value::Boolean(*b)),
+            Expr::Number(n) => Some(Value::Number(*n)),
+            Expr::String(s)
+            | Expr::EscapeString(s)
+            | Expr::NationalString(s)
+            | Expr::UnicodeString(s)
+            | Expr::DollarString { value: s, .. } => Some(Value::String(s.clone())),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn into_expr(self) -> Expr {
+        match self {
+            Value::Null => Expr::Null,
+            Value::Boolean(b) => Expr::Boolean(b),
+            Value::Number(n) => Expr::Number(n),
+            Value::String(s) => Expr::String(s),
+        }
+    }
+
+    /// Truth value of a boolean, with None standing for NULL. Returns None for other values.
+    fn truth(&self) -> Option<Option<bool>> {
+        match self {
+            Value::Null => Some(None),
+            Value::Boolean(b) => Some(Some(*b)),
+            _ => None,
+        }
+    }
+}
+
+/// Boolean value of a truth value, NULL for unknown.
+fn from_truth(truth: Option<bool>) -> Value {
+    truth.map_or(Value::Null, Value::Boolean)
+}
+
+/// Returns true if the operator returns NULL as soon as an operand is NULL, whatever the other one.
+pub(crate) fn propagates_null(op: &BinaryOperator) -> bool {
+    !matches!(
+        op,
+        BinaryOperator::And
+            | BinaryOperator::Or
+            | BinaryOperator::Is
+            | BinaryOperator::IsNot
+            | BinaryOperator::In
+            | BinaryOperator::NotIn
+    )
+}
+
+/// Evaluates a binary operation on constants. IN lists are evaluated by [in_list].
+pub(crate) fn binary(left: &Value, op: &BinaryOperator, right: &Value) -> Option<Value> {
+    match op {
+        BinaryOperator::And => Some(from_truth(and(left.truth()?, right.truth()?))),
+        BinaryOperator::Or => Some(from_truth(or(left.truth()?, right.truth()?))),
+        BinaryOperator::Is | BinaryOperator::IsNot => {
+            let result = match right {
+                Value::Null => *left == Value::Null,
+                Value::Boolean(b) => left.truth()? == Some(*b),
+                _ => return None,
+            };
+            Some(Value::Boolean(result == (*op == BinaryOperator::Is)))
+        }
+        BinaryOperator::In | BinaryOperator::NotIn => None,
+        _ if *left == Value::Null || *right == Value::Null => Some(Value::Null),
+        _ => strict(left, op, right),
+    }
+}
+
+/// Evaluates an operator that returns NULL for NULL operands, on operands that are not NULL.
+fn strict(left: &Value, op: &BinaryOperator, right: &Value) -> Option<Value> {
+    use Value::{Boolean, Number, String};
+    let value = match (left, op, right) {
+        (Number(a), BinaryOperator::Plus, Number(b)) => Number(a + b),
+        (Number(a), BinaryOperator::Minus, Number(b)) => Number(a - b),
+        (Number(a), BinaryOperator::Multiply, Number(b)) => Number(a * b),
+        (Number(a), BinaryOperator::Divide, Number(b)) if *b != 0.0 => Number(a / b),
+        (Number(a), BinaryOperator::Modulo, Number(b)) if *b != 0.0 => Number(a % b),
+        (String(a), BinaryOperator::Concat, String(b)) => String(format!("{a}{b}")),
+        (String(value), op, String(pattern)) if is_pattern_match(op) => {
+            Boolean(pattern_match(value, op, pattern, None)?)
+        }
+        (a, BinaryOperator::Eq, b) => Boolean(equal(a, b)?),
+        (a, BinaryOperator::Neq, b) => Boolean(!equal(a, b)?),
+        (a, BinaryOperator::Lt, b) => Boolean(order(a, b)? == Ordering::Less),
+        (a, BinaryOperator::Gt, b) => Boolean(order(a, b)? == Ordering::Greater),
+        (a, BinaryOperator::Le, b) => Boolean(order(a, b)? != Ordering::Greater),
+        (a, BinaryOperator::Ge, b) => Boolean(order(a, b)? != Ordering::Less),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Evaluates a unary operation on a constant.
+pub(crate) fn unary(op: &UnaryOperator, value: &Value) -> Option<Value> {
+    match (op, value) {
+        (UnaryOperator::Not, value) => Some(from_truth(value.truth()?.map(|b| !b))),
+        (_, Value::Null) => Some(Value::Null),
+        (UnaryOperator::Minus, Value::Number(n)) => Some(Value::Number(-n)),
+        (UnaryOperator::Plus, Value::Number(n)) => Some(Value::Number(*n)),
+        _ => None,
+    }
+}
+
+/// Evaluates `value [NOT] BETWEEN low AND high`, which is `value >= low AND value <= high`.
+pub(crate) fn between(value: &Value, low: &Value, high: &Value, negated: bool) -> Option<Value> {
+    let above = binary(value, &BinaryOperator::Ge, low)?;
+    let below = binary(value, &BinaryOperator::Le, high)?;
+    let result = binary(&above, &BinaryOperator::And, &below)?;
+    if negated {
+        unary(&UnaryOperator::Not, &result)
+    } else {
+        Some(result)
+    }
+}
+
+/// Evaluates `value [NOT] IN (items)`. Items that are not constant are None, and the result is
+/// only known if the value equals a constant item, or if every item is constant.
+///
+/// The result is TRUE if the value equals an item, and otherwise NULL if the value or an item is
+/// NULL, as the NULL could stand for the value: `2 NOT IN (1, NULL)` is NULL, not TRUE.
+pub(crate) fn in_list(value: &Value, items: &[Option<Value>], negated: bool) -> Option<Value> {
+    let mut result = Some(false);
+    for item in items {
+        let Some(item) = item else {
+            // The item could be equal to the value, unless another item is.
+            result = result.filter(|found| *found);
+            continue;
+        };
+        match binary(value, &BinaryOperator::Eq, item)? {
+            Value::Boolean(true) => {
+                result = Some(true);
+                break;
+            }
+            Value::Null if result == Some(false) => result = None,
+            _ => {}
+        }
+    }
+    // Without a match, unknown items leave the result unknown.
+    if items.iter().any(Option::is_none) && result != Some(true) {
+        return None;
+    }
+    let result = from_truth(result);
+    if negated {
+        unary(&UnaryOperator::Not, &result)
+    } else {
+        Some(result)
+    }
+}
+
+fn and(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+fn or(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+/// Equality of two values that are not NULL, or None if they have different types.
+fn equal(a: &Value, b: &Value) -> Option<bool> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => Some(a == b),
+        (Value::String(a), Value::String(b)) => Some(a == b),
+        (Value::Boolean(a), Value::Boolean(b)) => Some(a == b),
+        _ => None,
+    }
+}
+
+/// Order of two values that are not NULL. Only numbers are ordered, as the order of strings
+/// depends on the collation.
+fn order(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+        _ => None,
+    }
+}
+
+/// Evaluates `value op pattern ESCAPE escape`, for the operators that accept an ESCAPE clause.
+pub(crate) fn escaped(
+    value: &Value,
+    op: &BinaryOperator,
+    pattern: &Value,
+    escape: &Value,
+) -> Option<Value> {
+    match (value, pattern, escape) {
+        (Value::Null, _, _) | (_, Value::Null, _) | (_, _, Value::Null) => Some(Value::Null),
+        (Value::String(value), Value::String(pattern), Value::String(escape)) => {
+            let escape = single_char(escape)?;
+            Some(Value::Boolean(pattern_match(
+                value,
+                op,
+                pattern,
+                Some(escape),
+            )?))
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if the operator matches a string against a pattern.
+fn is_pattern_match(op: &BinaryOperator) -> bool {
+    op.accepts_escape() || matches!(op, BinaryOperator::RegexMatch | BinaryOperator::RegexIMatch)
+}
+
+/// Matches a string against the pattern of a pattern matching operator. Returns None if the
+/// pattern is invalid, or if its meaning depends on the database: without an ESCAPE clause, a
+/// backslash escapes the next character of LIKE patterns in some databases but not in others.
+fn pattern_match(
+    value: &str,
+    op: &BinaryOperator,
+    pattern: &str,
+    escape: Option<char>,
+) -> Option<bool> {
+    if op.accepts_escape() && escape.is_none() && pattern.contains('\\') {
+        return None;
+    }
+    let matched = match op {
+        BinaryOperator::Like | BinaryOperator::NotLike => {
+            like(value, &like_pieces(pattern, escape)?, false)
+        }
+        BinaryOperator::ILike | BinaryOperator::NotILike => {
+            like(value, &like_pieces(pattern, escape)?, true)
+        }
+        BinaryOperator::SimilarTo | BinaryOperator::NotSimilarTo => {
+            regex::Regex::new(&similar_regex(pattern, escape)?)
+                .ok()?
+                .is_match(value)
+        }
+        BinaryOperator::RegexMatch => regex::Regex::new(pattern).ok()?.is_match(value),
+        BinaryOperator::RegexIMatch => regex::Regex::new(&format!("(?i){pattern}"))
+            .ok()?
+            .is_match(value),
+        _ => return None,
+    };
+    let negated = matches!(
+        op,
+        BinaryOperator::NotLike | BinaryOperator::NotILike | BinaryOperator::NotSimilarTo
+    );
+    Some(matched != negated)
+}
+
+/// An element of a LIKE pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum LikePiece {
+    /// `%`, any sequence of characters.
+    Any,
+    /// `_`, any single character.
+    One,
+    Char(char),
+}
+
+/// Splits a LIKE pattern into its elements. An escaped `%`, `_` or escape character stands for
+/// itself, and escaping any other character, or ending the pattern with the escape character, is
+/// an error.
+pub(crate) fn like_pieces(pattern: &str, escape: Option<char>) -> Option<Vec<LikePiece>> {
+    let mut pieces = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        pieces.push(match c {
+            c if Some(c) == escape => match chars.next()? {
+                c if c == '%' || c == '_' || Some(c) == escape => LikePiece::Char(c),
+                _ => return None,
+            },
+            '%' => LikePiece::Any,
+            '_' => LikePiece::One,
+            c => LikePiece::Char(c),
+        });
+    }
+    Some(pieces)
+}
+
+/// Matches a string against the elements of a LIKE pattern. Each `%` first matches as few
+/// characters as possible, and matches one more whenever the rest of the pattern fails, so no
+/// more than the last `%` has to be remembered.
+fn like(value: &str, pieces: &[LikePiece], ignore_case: bool) -> bool {
+    let value: Vec<char> = value.chars().collect();
+    let same = |a: char, b: char| a == b || (ignore_case && a.to_lowercase().eq(b.to_lowercase()));
+    let (mut v, mut p) = (0, 0);
+    // Position of the last `%` in the pattern, and of the value where it stops matching.
+    let mut last_any = None;
+    while v < value.len() {
+        match pieces.get(p) {
+            Some(LikePiece::Any) => {
+                last_any = Some((p, v));
+                p += 1;
+            }
+            Some(LikePiece::One) => {
+                v += 1;
+                p += 1;
+            }
+            Some(LikePiece::Char(c)) if same(*c, value[v]) => {
+                v += 1;
+                p += 1;
+            }
+            _ => match last_any {
+                Some((any, end)) => {
+                    last_any = Some((any, end + 1));
+                    p = any + 1;
+                    v = end + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pieces[p..].iter().all(|piece| *piece == LikePiece::Any)
+}
+
+/// Translates a SIMILAR TO pattern into an anchored regular expression. `%` and `_` are the
+/// wildcards of LIKE, `.` is an ordinary character, and the other operators of regular
+/// expressions keep their meaning.
+fn similar_regex(pattern: &str, escape: Option<char>) -> Option<String> {
+    let mut regex = String::from("(?s)^(?:");
+    let mut in_class = false;
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if Some(c) == escape => regex.push_str(&regex::escape(&chars.next()?.to_string())),
+            ']' if in_class => {
+                in_class = false;
+                regex.push(c);
+            }
+            c if in_class => regex.push(c),
+            '[' => {
+                in_class = true;
+                regex.push(c);
+            }
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            '|' | '*' | '+' | '?' | '(' | ')' | '{' | '}' => regex.push(c),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push_str(")$");
+    Some(regex)
+}
+
+/// The character of a single-character string.
+pub(crate) fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
@@ -0,0 +1,485 @@
+//! Registry of the functions known to the simplifier.
+//!
+//! Each function records its signature and whether calls can be evaluated ahead of time.
+//! Deterministic scalar functions are folded when all their arguments are literals, so that
+//! `UPPER('abc')` becomes `'ABC'`, while volatile functions such as `RANDOM()` and aggregates such
+//! as `SUM(x)` are left to the database.
+//!
+//! Calls are only folded when they are valid: with the right number of arguments, of the declared
+//! types, and with values the function accepts. Anything else, such as `ABS('a')` or
+//! `SQRT(-1)`, is an error or an implicit cast in the database, and is kept as written.
+//!
+//! The built-in functions can be extended from Rust with [register]:
+//!
+//! ```ignore
+//! functions::register(
+//!     Function::new("DOUBLE", &[Type::Number], Type::Number).with_eval(|args| match args {
+//!         [Value::Number(n)] => Some(Value::Number(n * 2.0)),
+//!         _ => None,
+//!     }),
+//! );
+//! ```
+//!
+//! The shell declares the functions of a database with `.function`, read by [parse_declaration].
+//! Declared functions are not evaluated, but the simplifier knows whether they are deterministic
+//! and the planner whether they are aggregates.
+use crate::ast::BinaryOperator;
+use crate::eval::{self, Value};
+use std::collections::HashMap;
+use std::mem;
+use std::sync::{LazyLock, PoisonError, RwLock, RwLockReadGuard};
+
+/// Type of an argument or of the result of a function.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Type {
+    /// Any type, such as the arguments of COALESCE.
+    Any,
+    /// No built-in function takes or returns booleans, but custom functions may.
+    Boolean,
+    Number,
+    Text,
+    Timestamp,
+}
+
+impl Type {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "any" => Some(Self::Any),
+            "boolean" => Some(Self::Boolean),
+            "number" => Some(Self::Number),
+            "text" => Some(Self::Text),
+            "timestamp" => Some(Self::Timestamp),
+            _ => None,
+        }
+    }
+
+    /// Returns true if a value can be passed as an argument of this type without a cast.
+    fn accepts(&self, value: &Value) -> bool {
+        matches!(
+            (self, value),
+            (Type::Any, _)
+                | (_, Value::Null)
+                | (Type::Boolean, Value::Boolean(_))
+                | (Type::Number, Value::Number(_))
+                | (Type::Text, Value::String(_))
+        )
+    }
+}
+
+/// Computes the result of a call from literal arguments, or None if the call fails.
+pub(crate) type Eval = fn(&[Value]) -> Option<Value>;
+
+/// A function and its signature.
+#[derive(Debug, Clone)]
+pub(crate) struct Function {
+    /// Name of the function, in upper case.
+    pub(crate) name: String,
+    /// Types of the arguments. The last one applies to every extra argument of variadic functions.
+    pub(crate) args: Vec<Type>,
+    /// Smallest number of arguments, the remaining ones being optional.
+    pub(crate) min_args: usize,
+    /// Largest number of arguments, or None if the function is variadic.
+    pub(crate) max_args: Option<usize>,
+    pub(crate) returns: Type,
+    /// Returns true if calls with the same arguments always return the same result.
+    pub(crate) deterministic: bool,
+    /// Returns true if the function computes a value over a group of rows.
+    pub(crate) aggregate: bool,
+    /// Returns true if the function returns NULL as soon as an argument is NULL, without being
+    /// called.
+    pub(crate) strict: bool,
+    pub(crate) eval: Option<Eval>,
+}
+
+impl Function {
+    /// A deterministic scalar function, which takes exactly the given arguments and returns NULL
+    /// when one of them is NULL.
+    pub(crate) fn new(name: &str, args: &[Type], returns: Type) -> Self {
+        Self {
+            name: name.to_uppercase(),
+            args: args.to_vec(),
+            min_args: args.len(),
+            max_args: Some(args.len()),
+            returns,
+            deterministic: true,
+            aggregate: false,
+            strict: true,
+            eval: None,
+        }
+    }
+
+    /// Sets how calls with literal arguments are evaluated.
+    pub(crate) fn with_eval(mut self, eval: Eval) -> Self {
+        self.eval = Some(eval);
+        self
+    }
+
+    /// Makes the last `count` arguments optional.
+    pub(crate) fn with_optional_args(mut self, count: usize) -> Self {
+        self.min_args = self.args.len() - count;
+        self
+    }
+
+    /// Accepts any number of extra arguments of the type of the last one.
+    pub(crate) fn variadic(mut self) -> Self {
+        self.max_args = None;
+        self
+    }
+
+    /// Marks the function as returning different results for the same arguments.
+    pub(crate) fn volatile(mut self) -> Self {
+        self.deterministic = false;
+        self
+    }
+
+    /// Marks the function as an aggregate.
+    pub(crate) fn aggregate(mut self) -> Self {
+        self.aggregate = true;
+        self
+    }
+
+    /// Passes NULL arguments to the function instead of returning NULL.
+    pub(crate) fn accepts_nulls(mut self) -> Self {
+        self.strict = false;
+        self
+    }
+
+    /// Type of the argument at the given position.
+    fn arg_type(&self, index: usize) -> Type {
+        let last = self.args.len().saturating_sub(1);
+        self.args.get(index.min(last)).copied().unwrap_or(Type::Any)
+    }
+
+    /// Returns true if the function can be called with that many arguments.
+    pub(crate) fn accepts_arg_count(&self, count: usize) -> bool {
+        count >= self.min_args && self.max_args.is_none_or(|max| count <= max)
+    }
+
+    /// Evaluates a call with literal arguments, or returns None if it cannot be evaluated ahead of
+    /// time: the function is volatile, an aggregate, has no implementation, or the call is invalid.
+    /// Results of another type than the declared one are discarded too.
+    pub(crate) fn evaluate(&self, args: &[Value]) -> Option<Value> {
+        if !self.deterministic || self.aggregate || !self.accepts_arg_count(args.len()) {
+            return None;
+        }
+        let eval = self.eval?;
+        let valid = args
+            .iter()
+            .enumerate()
+            .all(|(i, arg)| self.arg_type(i).accepts(arg));
+        if !valid {
+            return None;
+        }
+        if self.strict && args.contains(&Value::Null) {
+            return Some(Value::Null);
+        }
+        eval(args).filter(|result| self.returns.accepts(result))
+    }
+}
+
+/// Functions by name.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FunctionRegistry {
+    functions: HashMap<String, Function>,
+}
+
+impl FunctionRegistry {
+    /// A registry with the built-in functions.
+    pub(crate) fn builtin() -> Self {
+        let mut registry = Self::default();
+        for function in builtin_functions() {
+            registry.register(function);
+        }
+        registry
+    }
+
+    /// Adds a function, replacing any function of the same name.
+    pub(crate) fn register(&mut self, function: Function) {
+        self.functions.insert(function.name.clone(), function);
+    }
+
+    /// Looks up a function by name, ignoring case.
+    pub(crate) fn get(&self, name: &str) -> Option<&Function> {
+        self.functions.get(&name.to_uppercase())
+    }
+
+    /// Returns true if the function is a known aggregate.
+    pub(crate) fn is_aggregate(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|function| function.aggregate)
+    }
+
+    /// Evaluates a call with literal arguments. Unknown functions are never evaluated.
+    pub(crate) fn evaluate(&self, name: &str, args: &[Value]) -> Option<Value> {
+        self.get(name)?.evaluate(args)
+    }
+}
+
+/// The functions used by the simplifier and the planner: the built-in ones, and those added with
+/// [register].
+static REGISTRY: LazyLock<RwLock<FunctionRegistry>> =
+    LazyLock::new(|| RwLock::new(FunctionRegistry::builtin()));
+
+/// The functions known to the simplifier and the planner.
+pub(crate) fn registry() -> RwLockReadGuard<'static, FunctionRegistry> {
+    REGISTRY.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Adds a function to the registry used by the simplifier and the planner, replacing any
+/// function of the same name.
+pub(crate) fn register(function: Function) {
+    REGISTRY
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .register(function);
+}
+
+/// Reads the declaration of a function without implementation, as
+/// `NAME([TYPE, ...]) RETURNS TYPE [VOLATILE | AGGREGATE]`, where the types are ANY, BOOLEAN,
+/// NUMBER, TEXT or TIMESTAMP.
+pub(crate) fn parse_declaration(declaration: &str) -> Result<Function, String> {
+    let error = || {
+        format!(
+            "Invalid function declaration '{declaration}', expected \
+             NAME([TYPE, ...]) RETURNS TYPE [VOLATILE | AGGREGATE]"
+        )
+    };
+    let (name, rest) = declaration.split_once('(').ok_or_else(error)?;
+    let (args, rest) = rest.split_once(')').ok_or_else(error)?;
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(error());
+    }
+    let args = match args.trim() {
+        "" => Vec::new(),
+        args => args
+            .split(',')
+            .map(|arg| Type::from_name(arg.trim()).ok_or_else(error))
+            .collect::<Result<_, _>>()?,
+    };
+
+    let mut words = rest.split_whitespace();
+    let returns = match (words.next(), words.next()) {
+        (Some(keyword), Some(name)) if keyword.eq_ignore_ascii_case("returns") => {
+            Type::from_name(name).ok_or_else(error)?
+        }
+        _ => return Err(error()),
+    };
+    let function = Function::new(name, &args, returns);
+    let function = match words.next().map(str::to_uppercase).as_deref() {
+        None => function,
+        Some("VOLATILE") => function.volatile(),
+        Some("AGGREGATE") => function.aggregate(),
+        Some(_) => return Err(error()),
+    };
+    match words.next() {
+        None => Ok(function),
+        Some(_) => Err(error()),
+    }
+}
+
+fn builtin_functions() -> Vec<Function> {
+    use Type::{Any, Number, Text, Timestamp};
+    vec![
+        // Strings
+        Function::new("UPPER", &[Text], Text).with_eval(|args| text(args, str::to_uppercase)),
+        Function::new("LOWER", &[Text], Text).with_eval(|args| text(args, str::to_lowercase)),
+        Function::new("LENGTH", &[Text], Number).with_eval(length),
+        Function::new("CHAR_LENGTH", &[Text], Number).with_eval(length),
+        Function::new("TRIM", &[Text], Text)
+            .with_eval(|args| text(args, |s| s.trim_matches(' ').to_string())),
+        Function::new("LTRIM", &[Text], Text)
+            .with_eval(|args| text(args, |s| s.trim_start_matches(' ').to_string())),
+        Function::new("RTRIM", &[Text], Text)
+            .with_eval(|args| text(args, |s| s.trim_end_matches(' ').to_string())),
+        Function::new("SUBSTRING", &[Text, Number, Number], Text)
+            .with_optional_args(1)
+            .with_eval(substring),
+        Function::new("REPLACE", &[Text, Text, Text], Text).with_eval(replace),
+        // NULL arguments are ignored by some databases and make the result NULL in others, so
+        // calls with NULL arguments are not evaluated.
+        Function::new("CONCAT", &[Text], Text)
+            .variadic()
+            .accepts_nulls()
+            .with_eval(concat),
+        // Numbers
+        Function::new("ABS", &[Number], Number).with_eval(|args| number(args, f64::abs)),
+        Function::new("CEIL", &[Number], Number).with_eval(|args| number(args, f64::ceil)),
+        Function::new("CEILING", &[Number], Number).with_eval(|args| number(args, f64::ceil)),
+        Function::new("FLOOR", &[Number], Number).with_eval(|args| number(args, f64::floor)),
+        Function::new("SIGN", &[Number], Number).with_eval(|args| number(args, sign)),
+        Function::new("ROUND", &[Number, Number], Number)
+            .with_optional_args(1)
+            .with_eval(round),
+        Function::new("MOD", &[Number, Number], Number).with_eval(|args| match args {
+            [a, b] => eval::binary(a, &BinaryOperator::Modulo, b),
+            _ => None,
+        }),
+        Function::new("POWER", &[Number, Number], Number).with_eval(|args| match args {
+            [Value::Number(a), Value::Number(b)] => Some(a.powf(*b))
+                .filter(|n| n.is_finite())
+                .map(Value::Number),
+            _ => None,
+        }),
+        Function::new("SQRT", &[Number], Number).with_eval(|args| match args {
+            [Value::Number(n)] if *n >= 0.0 => Some(Value::Number(n.sqrt())),
+            _ => None,
+        }),
+        // NULL handling
+        Function::new("COALESCE", &[Any], Any)
+            .variadic()
+            .accepts_nulls()
+            .with_eval(coalesce),
+        Function::new("NULLIF", &[Any, Any], Any)
+            .accepts_nulls()
+            .with_eval(nullif),
+        // Volatile functions
+        Function::new("RANDOM", &[], Number).volatile(),
+        Function::new("RAND", &[], Number).volatile(),
+        Function::new("NOW", &[], Timestamp).volatile(),
+        Function::new("CLOCK_TIMESTAMP", &[], Timestamp).volatile(),
+        Function::new("GEN_RANDOM_UUID", &[], Text).volatile(),
+        Function::new("UUID", &[], Text).volatile(),
+        // Aggregates
+        Function::new("COUNT", &[Any], Number).aggregate(),
+        Function::new("SUM", &[Number], Number).aggregate(),
+        Function::new("AVG", &[Number], Number).aggregate(),
+        Function::new("MIN", &[Any], Any).aggregate(),
+        Function::new("MAX", &[Any], Any).aggregate(),
+    ]
+}
+
+/// Applies a string function to a single string argument.
+fn text(args: &[Value], f: impl Fn(&str) -> String) -> Option<Value> {
+    match args {
+        [Value::String(s)] => Some(Value::String(f(s))),
+        _ => None,
+    }
+}
+
+/// Applies a numeric function to a single numeric argument.
+fn number(args: &[Value], f: impl Fn(f64) -> f64) -> Option<Value> {
+    match args {
+        [Value::Number(n)] => Some(Value::Number(f(*n))),
+        _ => None,
+    }
+}
+
+/// Number of characters of a string.
+fn length(args: &[Value]) -> Option<Value> {
+    match args {
+        [Value::String(s)] => Some(Value::Number(s.chars().count() as f64)),
+        _ => None,
+    }
+}
+
+/// `SUBSTRING(s, start [, length])`: the characters of `s` from position `start`, counted from 1,
+/// up to `start + length`. Positions before the start of the string are allowed, and shorten the
+/// result.
+fn substring(args: &[Value]) -> Option<Value> {
+    let (s, start, length) = match args {
+        [Value::String(s), Value::Number(start)] => (s, *start, None),
+        [
+            Value::String(s),
+            Value::Number(start),
+            Value::Number(length),
+        ] => (s, *start, Some(*length)),
+        _ => return None,
+    };
+    if start.fract() != 0.0 || length.is_some_and(|length| length.fract() != 0.0 || length < 0.0) {
+        return None;
+    }
+    let end = length.map(|length| start + length);
+    let result = s
+        .chars()
+        .enumerate()
+        .filter(|(i, _)| {
+            let position = *i as f64 + 1.0;
+            position >= start && end.is_none_or(|end| position < end)
+        })
+        .map(|(_, c)| c)
+        .collect();
+    Some(Value::String(result))
+}
+
+/// `REPLACE(s, from, to)`: `s` with every occurrence of `from` replaced by `to`.
+fn replace(args: &[Value]) -> Option<Value> {
+    match args {
+        [Value::String(s), Value::String(from), _] if from.is_empty() => {
+            Some(Value::String(s.clone()))
+        }
+        [Value::String(s), Value::String(from), Value::String(to)] => {
+            Some(Value::String(s.replace(from.as_str(), to)))
+        }
+        _ => None,
+    }
+}
+
+fn concat(args: &[Value]) -> Option<Value> {
+    args.iter()
+        .map(|arg| match arg {
+            Value::String(s) => Some(s.as_str()),
+            _ => None,
+        })
+        .collect::<Option<String>>()
+        .map(Value::String)
+}
+
+fn sign(n: f64) -> f64 {
+    if n == 0.0 { 0.0 } else { n.signum() }
+}
+
+/// `ROUND(n [, digits])`: `n` rounded to `digits` decimal places, halves away from zero.
+///
+/// Numeric literals are exact decimals in SQL, so the digits of the literal are rounded rather
+/// than its binary approximation: `ROUND(1.005, 2)` is `1.01`.
+fn round(args: &[Value]) -> Option<Value> {
+    let (n, digits) = match args {
+        [Value::Number(n)] => (*n, 0.0),
+        [Value::Number(n), Value::Number(digits)] => (*n, *digits),
+        _ => return None,
+    };
+    if digits.fract() != 0.0 || !(0.0..=15.0).contains(&digits) || !n.is_finite() {
+        return None;
+    }
+    let digits = digits as usize;
+    let text = n.abs().to_string();
+    let (integer, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    if fraction.len() <= digits {
+        return Some(Value::Number(n));
+    }
+    // The kept digits as an integer, plus one if the first dropped digit is 5 or more.
+    let kept: f64 = format!("{integer}{}", &fraction[..digits]).parse().ok()?;
+    let round_up = fraction.as_bytes()[digits] >= b'5';
+    let rounded = (kept + if round_up { 1.0 } else { 0.0 }) / 10f64.powi(digits as i32);
+    let rounded: f64 = format!("{rounded:.digits$}").parse().ok()?;
+    // Negative numbers rounded to zero give zero, not -0.
+    Some(Value::Number(if rounded == 0.0 {
+        0.0
+    } else {
+        rounded.copysign(n)
+    }))
+}
+
+/// `COALESCE(a, b, ...)`: the first argument that is not NULL. Arguments of different types are
+/// an error or an implicit cast, depending on the database.
+fn coalesce(args: &[Value]) -> Option<Value> {
+    let mut values = args.iter().filter(|arg| **arg != Value::Null);
+    let Some(first) = values.next() else {
+        return Some(Value::Null);
+    };
+    if values.any(|value| mem::discriminant(value) != mem::discriminant(first)) {
+        return None;
+    }
+    Some(first.clone())
+}
+
+/// `NULLIF(a, b)`: NULL if `a` equals `b`, and `a` otherwise.
+fn nullif(args: &[Value]) -> Option<Value> {
+    let [a, b] = args else {
+        return None;
+    };
+    match eval::binary(a, &BinaryOperator::Eq, b)? {
+        Value::Boolean(true) => Some(Value::Null),
+        _ => Some(a.clone()),
+    }
+}
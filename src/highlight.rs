@@ -0,0 +1,141 @@
+//! Syntax highlighting of the REPL input, based on the tokens of the [Lexer].
+use crate::completion::SqlHelper;
+use crate::dialect::Dialect;
+use crate::lexer::Lexer;
+use crate::token::{Span, Token};
+use colored::*;
+use rustyline::highlight::Highlighter;
+use std::borrow::Cow;
+
+/// How a piece of the input is displayed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Style {
+    Keyword,
+    String,
+    Number,
+    Identifier,
+    Operator,
+    /// Comments and optimizer hints.
+    Comment,
+    /// Invalid input, such as an unterminated string.
+    Error,
+    /// A parenthesis next to the cursor, and the one it matches.
+    Bracket,
+}
+
+impl Style {
+    fn paint(self, text: &str) -> ColoredString {
+        match self {
+            Style::Keyword => text.blue().bold(),
+            Style::String => text.green(),
+            Style::Number => text.yellow(),
+            Style::Identifier => text.cyan(),
+            Style::Operator => text.magenta(),
+            Style::Comment => text.bright_black(),
+            Style::Error => text.red(),
+            Style::Bracket => text.bright_yellow().bold(),
+        }
+    }
+}
+
+/// Splits a line into styled pieces, in order. Whitespace is left out, and everything else the
+/// lexer skipped is a comment.
+pub(crate) fn styles(line: &str, pos: usize, dialect: Dialect) -> Vec<(Span, Style)> {
+    let mut lexer = Lexer::with_dialect(line, dialect);
+    let mut styles = Vec::new();
+    let mut brackets = Vec::new();
+    let mut end = 0;
+    loop {
+        let (token, span) = lexer.next_token_with_span();
+        let gap = &line[end..span.start];
+        if !gap.trim().is_empty() {
+            let start = end + (gap.len() - gap.trim_start().len());
+            let end = span.start - (gap.len() - gap.trim_end().len());
+            styles.push((Span { start, end }, Style::Comment));
+        }
+        end = span.end;
+
+        let style = match token {
+            Token::Eof => break,
+            Token::LParen | Token::RParen => {
+                brackets.push((styles.len(), token == Token::LParen));
+                Style::Operator
+            }
+            token if token.is_keyword() => Style::Keyword,
+            Token::Identifier(_) => Style::Identifier,
+            Token::NumberLiteral(_) => Style::Number,
+            Token::StringLiteral(_)
+            | Token::EscapeStringLiteral(_)
+            | Token::NationalStringLiteral(_)
+            | Token::HexStringLiteral(_)
+            | Token::BitStringLiteral(_)
+            | Token::UnicodeStringLiteral(_)
+            | Token::DollarQuotedString { .. } => Style::String,
+            Token::Hint(_) => Style::Comment,
+            Token::Error { .. } => Style::Error,
+            _ => Style::Operator,
+        };
+        styles.push((span, style));
+    }
+
+    if let Some((first, second)) = matching_brackets(&styles, &brackets, pos) {
+        styles[first].1 = Style::Bracket;
+        styles[second].1 = Style::Bracket;
+    }
+    styles
+}
+
+/// Finds the parenthesis under the cursor, or just before it, and the one it matches.
+/// `brackets` holds the index in `styles` of every parenthesis, and whether it is an opening one.
+/// Both parentheses are returned as indexes into `styles`.
+fn matching_brackets(
+    styles: &[(Span, Style)],
+    brackets: &[(usize, bool)],
+    pos: usize,
+) -> Option<(usize, usize)> {
+    let at = |pos: usize| brackets.iter().position(|&(i, _)| styles[i].0.start == pos);
+    let current = at(pos).or_else(|| pos.checked_sub(1).and_then(at))?;
+    let (index, open) = brackets[current];
+
+    // Walk towards the matching parenthesis, counting the nesting depth.
+    let mut depth = 0;
+    let candidates: Box<dyn Iterator<Item = &(usize, bool)>> = if open {
+        Box::new(brackets[current..].iter())
+    } else {
+        Box::new(brackets[..=current].iter().rev())
+    };
+    for &(i, is_open) in candidates {
+        if is_open == open {
+            depth += 1;
+        } else {
+            depth -= 1;
+            if depth == 0 {
+                return Some(if open { (index, i) } else { (i, index) });
+            }
+        }
+    }
+    None
+}
+
+impl Highlighter for SqlHelper {
+    fn highlight<'l>(&self, line: &'l str, pos: usize) -> Cow<'l, str> {
+        let styles = styles(line, pos, self.dialect);
+        if styles.is_empty() {
+            return Cow::Borrowed(line);
+        }
+        let mut highlighted = String::with_capacity(line.len() * 2);
+        let mut end = 0;
+        for (span, style) in styles {
+            highlighted.push_str(&line[end..span.start]);
+            highlighted.push_str(&style.paint(&line[span.start..span.end]).to_string());
+            end = span.end;
+        }
+        highlighted.push_str(&line[end..]);
+        Cow::Owned(highlighted)
+    }
+
+    /// The brackets to highlight depend on the cursor, so the line is redrawn whenever it moves.
+    fn highlight_char(&self, _line: &str, _pos: usize, _forced: bool) -> bool {
+        true
+    }
+}
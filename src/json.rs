@@ -0,0 +1,146 @@
+//! JSON representation of the AST, for tools written in other languages and for storing parsed
+//! queries.
+//!
+//! A document holds the statements of a script, with an identifier and a version of the format:
+//!
+//! ```json
+//! {
+//!   "format": "sql-ast",
+//!   "version": 3,
+//!   "file": "queries/users.sql",
+//!   "statements": [
+//!     {
+//!       "span": { "start": 0, "end": 21 },
+//!       "statement": {
+//!         "type": "Select",
+//!         "value": {
+//!           "distinct": false,
+//!           "columns": [
+//!             {
+//!               "type": "ExprWithAlias",
+//!               "value": { "expr": { "type": "Identifier", "value": { "value": "id" } }, "alias": null }
+//!             }
+//!           ],
+//!           "from": { "type": "Table", "value": { "name": [{ "value": "users" }], "alias": null } },
+//!           "where_clause": null,
+//!           "group_by": [],
+//!           "having": null,
+//!           "order_by": [],
+//!           "limit": null
+//!         }
+//!       }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! The types of [crate::ast] map to JSON as follows:
+//!
+//! - Structs are objects with a member per field, named like the field. Absent optional values are
+//!   `null`, except the `quote_style` of identifiers, which is left out for unquoted ones.
+//! - Enums with data are objects with the name of the variant under `type`, and its data under
+//!   `value`: an object for variants with named fields, an array for variants with several
+//!   unnamed ones. Variants without data only have a `type`: `{"type": "Null"}`.
+//! - Enums without data, such as operators and join types, are strings: `"Plus"`, `"Left"`.
+//! - Qualified names are arrays of identifiers.
+//! - `file`, and the `span` of each statement, are optional. Spans are byte offsets in the file.
+//!
+//! [VERSION] is incremented on every change to the AST that changes its JSON form, and documents
+//! of another version are rejected, so consumers can rely on the shape of the nodes they know.
+use crate::ast::Statement;
+use crate::token::Span;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Identifier of the format, stored in every document.
+pub(crate) const FORMAT: &str = "sql-ast";
+
+/// Version of the format, stored in every document.
+pub(crate) const VERSION: u32 = 3;
+
+/// The statements of a script, in the JSON format described in the [module](self) documentation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Document {
+    pub(crate) format: String,
+    pub(crate) version: u32,
+    /// Name of the script the statements were read from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) file: Option<String>,
+    pub(crate) statements: Vec<StatementNode>,
+}
+
+/// A statement, with where it was found in the script.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct StatementNode {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) span: Option<Span>,
+    pub(crate) statement: Statement,
+}
+
+/// Members of a document checked before the statements are read, so that documents of another
+/// version are reported as such rather than as malformed.
+#[derive(Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+}
+
+impl Document {
+    pub(crate) fn new(file: Option<String>, statements: Vec<StatementNode>) -> Self {
+        Self {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            file,
+            statements,
+        }
+    }
+
+    /// Serializes the document on a single line.
+    pub(crate) fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("the AST only holds JSON-compatible values")
+    }
+
+    /// Serializes the document with indentation.
+    pub(crate) fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(self).expect("the AST only holds JSON-compatible values")
+    }
+
+    /// Reads a document, checking its format and version first.
+    pub(crate) fn from_json(json: &str) -> Result<Self, String> {
+        let header: Header = read(json)?;
+        if header.format != FORMAT {
+            return Err(format!(
+                "Unknown document format '{}', expected '{FORMAT}'",
+                header.format
+            ));
+        }
+        if header.version != VERSION {
+            return Err(format!(
+                "Unsupported version {} of the AST format, expected {VERSION}",
+                header.version
+            ));
+        }
+        read(json)
+    }
+}
+
+/// Deserializes a JSON value without a limit on its nesting, so that the long chains of operators
+/// and joins the parser accepts can be read back. The stack grows on the heap as needed.
+fn read<T: DeserializeOwned>(json: &str) -> Result<T, String> {
+    let invalid = |error: serde_json::Error| format!("Invalid AST document: {error}");
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    deserializer.disable_recursion_limit();
+    let value =
+        T::deserialize(serde_stacker::Deserializer::new(&mut deserializer)).map_err(invalid)?;
+    deserializer.end().map_err(invalid)?;
+    Ok(value)
+}
+
+impl From<Statement> for StatementNode {
+    fn from(statement: Statement) -> Self {
+        Self {
+            span: None,
+            statement,
+        }
+    }
+}
@@ -1,288 +1,666 @@
-use crate::token::{Token, keyword_to_token};
+use crate::dialect::Dialect;
+use crate::token::{Location, Span, Token, Word, lookup_keyword};
+use std::borrow::Cow;
 
 /// ESCAPE QUOTE IS A CONSTANT FOR THE LEXER PROGRAM.
-const ESCAPE_QUOTE: char = '\'';
-const DOUBLE_QUOTE: char = '"';
-const DECIMAL_MARKER: char = '.';
-const UNDERSCORE: char = '_';
-const STAR: char = '*';
-const COMMA: char = ',';
-const DOT: char = '.';
-const SEMICOLON: char = ';';
-const NOT: char = '!';
-const EQ: char = '=';
-const LT: char = '<';
-const GT: char = '>';
-const PLUS: char = '+';
-const MINUS: char = '-';
-const DIVISOR: char = '/';
-const MODULO: char = '%';
-const PIPE: char = '|';
-const LEFT_PARENTHESES: char = '(';
-const RIGHT_PARENTHESES: char = ')';
+const ESCAPE_QUOTE: u8 = b'\'';
+const DECIMAL_MARKER: u8 = b'.';
+const UNDERSCORE: u8 = b'_';
+const STAR: u8 = b'*';
+const COMMA: u8 = b',';
+const DOT: u8 = b'.';
+const SEMICOLON: u8 = b';';
+const NOT: u8 = b'!';
+const EQ: u8 = b'=';
+const LT: u8 = b'<';
+const GT: u8 = b'>';
+const PLUS: u8 = b'+';
+const MINUS: u8 = b'-';
+const DIVISOR: u8 = b'/';
+const MODULO: u8 = b'%';
+const PIPE: u8 = b'|';
+const TILDE: u8 = b'~';
+const DOLLAR: u8 = b'$';
+const AMPERSAND: u8 = b'&';
+const BACKSLASH: u8 = b'\\';
+const NEWLINE: u8 = b'\n';
+const LEFT_PARENTHESES: u8 = b'(';
+const RIGHT_PARENTHESES: u8 = b')';
 
 /// SQL Lexer implementation.
-pub(crate) struct Lexer {
-    input: Vec<char>,
+///
+/// The lexer walks the bytes of the input without copying it. The cursor always sits on a
+/// character boundary, so identifiers and literals are returned as slices of the input and only
+/// allocate when escapes or doubled quotes have to be resolved.
+pub(crate) struct Lexer<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
     position: usize,
-    current_char: Option<char>,
+    dialect: Dialect,
 }
 
-impl Lexer {
+impl<'a> Lexer<'a> {
     // Creates a new lexer with an specific input.
-    // Positions the cursor of the lexer at the beginning of the chars array.
-    pub(crate) fn new(input: &str) -> Self {
-        let chars: Vec<char> = input.chars().collect();
-        let current_char = if chars.is_empty() {
-            None
-        } else {
-            Some(chars[0])
-        };
+    // Positions the cursor of the lexer at the beginning of the input.
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self::with_dialect(input, Dialect::default())
+    }
 
+    /// Creates a new lexer that follows the lexical rules of a dialect.
+    pub(crate) fn with_dialect(input: &'a str, dialect: Dialect) -> Self {
         Lexer {
-            input: chars,
+            input,
+            bytes: input.as_bytes(),
             position: 0,
-            current_char,
+            dialect,
         }
     }
 
-    /// Advances the cursor of the lexer to the next position.
-    pub(crate) fn advance(&mut self) {
-        self.position += 1;
-        if self.position >= self.input.len() {
-            self.current_char = None;
-        } else {
-            self.current_char = Some(self.input[self.position]);
-        }
+    /// Returns the byte under the cursor.
+    fn current(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
     }
 
-    /// Peek the char offseted at [offset] from  the lexer cursor position in the input string
-    fn peek(&self, offset: usize) -> Option<char> {
-        let pos = self.position + offset;
-        if pos < self.input.len() {
-            Some(self.input[pos])
-        } else {
-            None
+    /// Returns the character under the cursor, decoding it if it is not ASCII.
+    fn current_char(&self) -> Option<char> {
+        match self.current() {
+            Some(byte) if byte.is_ascii() => Some(byte as char),
+            Some(_) => self.input[self.position..].chars().next(),
+            None => None,
         }
     }
 
+    /// Advances the cursor past an ASCII character.
+    fn advance(&mut self) {
+        self.position += 1;
+    }
+
+    /// Advances the cursor past the character under it, whatever its length.
+    fn advance_char(&mut self) {
+        self.position += self.current_char().map_or(1, char::len_utf8);
+    }
+
+    /// Peek the byte offseted at [offset] from the lexer cursor position in the input string
+    fn peek(&self, offset: usize) -> Option<u8> {
+        self.bytes.get(self.position + offset).copied()
+    }
+
     /// Advances the cursor until the next non-whitespace position.
     fn skip_whitespace(&mut self) {
-        while let Some(ch) = self.current_char {
+        while let Some(ch) = self.current_char() {
             if ch.is_whitespace() {
-                self.advance();
+                self.advance_char();
             } else {
                 break;
             }
         }
     }
 
+    /// Returns the line and column of a byte position in the input.
+    /// Columns count characters, not bytes.
+    pub(crate) fn location(&self, position: usize) -> Location {
+        let before = &self.input[..position.min(self.input.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Location {
+            line: before.bytes().filter(|byte| *byte == NEWLINE).count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// Builds an error token located at the given position.
+    pub(crate) fn error(&self, message: String, position: usize) -> Token<'a> {
+        Token::Error {
+            message,
+            location: self.location(position),
+        }
+    }
+
+    /// Returns the text of the input covered by a span.
+    pub(crate) fn text(&self, span: Span) -> &'a str {
+        &self.input[span.start..span.end]
+    }
+
     /// Consume a string from the input buffer.
     /// In SQL, strings are formatted between quotes.
     /// Therefore we need to skip them to get to consume the actual information.
-    fn read_string(&mut self) -> String {
+    ///
+    /// Adjacent literals separated by whitespace that contains a newline are concatenated:
+    /// `'foo'\n'bar'` is the same as `'foobar'`.
+    fn read_string(&mut self) -> Result<Cow<'a, str>, String> {
+        let mut result = self
+            .read_quoted(ESCAPE_QUOTE)
+            .ok_or("Unterminated string literal".to_string())?;
+        while self.at_string_continuation() {
+            let part = self
+                .read_quoted(ESCAPE_QUOTE)
+                .ok_or("Unterminated string literal".to_string())?;
+            result.to_mut().push_str(&part);
+        }
+        Ok(result)
+    }
+
+    /// Checks if the cursor is followed by whitespace with at least one newline and then a quote,
+    /// which continues the previous string literal. If so, moves the cursor to that quote.
+    fn at_string_continuation(&mut self) -> bool {
+        let mut newline = false;
+        for (offset, ch) in self.input[self.position..].char_indices() {
+            if ch.is_whitespace() {
+                newline |= ch == '\n';
+                continue;
+            }
+            if newline && ch == ESCAPE_QUOTE as char {
+                self.position += offset;
+                return true;
+            }
+            break;
+        }
+        false
+    }
+
+    /// Consume an escape string (`E'...'`) with the cursor on its opening quote.
+    /// Backslash escapes are resolved: `\n`, `\t`, `\'`, `\\`, octal `\101`, hex `\x41`,
+    /// and Unicode `\u0041` or `\U00000041`.
+    fn read_escape_string(&mut self) -> Result<String, String> {
         let mut result = String::new();
+        loop {
+            self.advance(); // Skip opening quote
+            let mut chunk_start = self.position;
+            loop {
+                match self.current() {
+                    None => return Err("Unterminated string literal".to_string()),
+                    Some(BACKSLASH) => {
+                        result.push_str(&self.input[chunk_start..self.position]);
+                        self.advance();
+                        result.push(self.read_escape_sequence()?);
+                        chunk_start = self.position;
+                    }
+                    Some(ESCAPE_QUOTE) => {
+                        result.push_str(&self.input[chunk_start..self.position]);
+                        self.advance();
+                        if self.current() != Some(ESCAPE_QUOTE) {
+                            break; // Closing quote
+                        }
+                        result.push(ESCAPE_QUOTE as char);
+                        self.advance();
+                        chunk_start = self.position;
+                    }
+                    // Quotes and backslashes are ASCII, so they never appear inside a multi-byte character.
+                    Some(_) => self.advance(),
+                }
+            }
+            if !self.at_string_continuation() {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Reads the escape sequence that follows a backslash in an escape string.
+    fn read_escape_sequence(&mut self) -> Result<char, String> {
+        let ch = self
+            .current_char()
+            .ok_or("Unterminated string literal".to_string())?;
+        let start = self.position;
+        self.advance_char();
+        match ch {
+            'b' => Ok('\u{8}'),
+            'f' => Ok('\u{c}'),
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            '0'..='7' => self.read_code_point(8, start, 1, 3),
+            'x' => self.read_code_point(16, self.position, 1, 2),
+            'u' => self.read_code_point(16, self.position, 4, 4),
+            'U' => self.read_code_point(16, self.position, 8, 8),
+            // Any other escaped character stands for itself: \' and \\ included.
+            other => Ok(other),
+        }
+    }
+
+    /// Reads digits in the given radix until there are between `min` and `max` of them, counting
+    /// from `start`, and returns the character with that code point.
+    fn read_code_point(
+        &mut self,
+        radix: u32,
+        start: usize,
+        min: usize,
+        max: usize,
+    ) -> Result<char, String> {
+        while self.position - start < max
+            && let Some(byte) = self.current()
+            && (byte as char).is_digit(radix)
+        {
+            self.advance();
+        }
+
+        let digits = &self.input[start..self.position];
+        if digits.len() < min {
+            return Err("Invalid escape sequence in string literal".to_string());
+        }
+        u32::from_str_radix(digits, radix)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(format!("Invalid code point in escape sequence: {digits}"))
+    }
+
+    /// Consume a dollar-quoted string: `$$...$$` or `$tag$...$tag$`.
+    /// The body is taken verbatim, without any escape processing.
+    fn read_dollar_quoted(&mut self) -> Result<(&'a str, &'a str), String> {
+        let rest = &self.input[self.position + 1..];
+        // Tags follow the rules of unquoted identifiers, so `$1` is not a tag.
+        let tag_len = rest
+            .char_indices()
+            .find(|&(i, ch)| !(ch.is_alphabetic() || ch == '_' || (ch.is_ascii_digit() && i > 0)))
+            .map_or(rest.len(), |(i, _)| i);
+        if rest.as_bytes().get(tag_len) != Some(&DOLLAR) {
+            self.advance();
+            return Err(format!("Unexpected character '{}'", DOLLAR as char));
+        }
+
+        let tag = &rest[..tag_len];
+        let body_start = self.position + tag_len + 2;
+        let mut search = body_start;
+        while let Some(offset) = self.input[search..].find(DOLLAR as char) {
+            let candidate = search + offset;
+            let after = &self.input[candidate + 1..];
+            if after.starts_with(tag) && after.as_bytes().get(tag.len()) == Some(&DOLLAR) {
+                self.position = candidate + tag.len() + 2;
+                return Ok((tag, &self.input[body_start..candidate]));
+            }
+            search = candidate + 1;
+        }
+
+        self.position = self.bytes.len();
+        Err("Unterminated dollar-quoted string".to_string())
+    }
+
+    /// Consume a quoted identifier from the input buffer.
+    /// The closing quote can be escaped by doubling it: `"a ""quoted"" name"`.
+    fn read_quoted_identifier(&mut self, quote_end: u8) -> Result<Cow<'a, str>, String> {
+        self.read_quoted(quote_end)
+            .ok_or("Unterminated quoted identifier".to_string())
+    }
+
+    /// Reads the text up to the closing quote, resolving doubled quotes into a single one.
+    /// The text is borrowed from the input unless it contains doubled quotes.
+    /// Returns None if the input ends before the closing quote.
+    fn read_quoted(&mut self, quote_end: u8) -> Option<Cow<'a, str>> {
         self.advance(); // Skip opening quote
+        let mut owned: Option<String> = None;
+        let mut chunk_start = self.position;
 
-        while let Some(ch) = self.current_char {
-            if ch == ESCAPE_QUOTE {
-                // Check for escaped quote
-                if self.peek(1) == Some(ESCAPE_QUOTE) {
-                    result.push(ESCAPE_QUOTE);
-                    self.advance();
-                    self.advance();
-                } else {
-                    self.advance(); // Skip closing quote
-                    break;
-                }
+        loop {
+            let Some(offset) = self.bytes[self.position..]
+                .iter()
+                .position(|b| *b == quote_end)
+            else {
+                self.position = self.bytes.len();
+                return None;
+            };
+            let quote = self.position + offset;
+
+            // Check for escaped quote
+            if self.bytes.get(quote + 1) == Some(&quote_end) {
+                owned
+                    .get_or_insert_with(String::new)
+                    .push_str(&self.input[chunk_start..=quote]);
+                self.position = quote + 2;
+                chunk_start = self.position;
             } else {
-                result.push(ch);
-                self.advance();
+                self.position = quote + 1; // Skip closing quote
+                let last = &self.input[chunk_start..quote];
+                return Some(match owned {
+                    Some(mut text) => {
+                        text.push_str(last);
+                        Cow::Owned(text)
+                    }
+                    None => Cow::Borrowed(last),
+                });
             }
         }
-
-        result
     }
 
     // Read a number from the input buffer.
     // On my implementation, decimals are represented with dots.
-    fn read_number(&mut self) -> f64 {
-        let mut num_str = String::new();
-
-        while let Some(ch) = self.current_char {
-            if ch.is_ascii_digit() || ch == DECIMAL_MARKER {
-                num_str.push(ch);
+    fn read_number(&mut self) -> Result<f64, String> {
+        let start = self.position;
+        while let Some(byte) = self.current() {
+            if byte.is_ascii_digit() || byte == DECIMAL_MARKER {
                 self.advance();
             } else {
                 break;
             }
         }
 
-        num_str.parse().unwrap_or(0.0)
+        let num_str = &self.input[start..self.position];
+        num_str
+            .parse()
+            .map_err(|_| format!("Invalid number '{num_str}'"))
+    }
+
+    /// Reads a block comment, with the cursor on its opening `/*`, and returns the text between
+    /// its outermost markers. Block comments can be nested: `/* outer /* inner */ still a comment */`.
+    fn read_block_comment(&mut self) -> Result<&'a str, String> {
+        let start = self.position + 2;
+        let mut depth = 0;
+
+        while let Some(byte) = self.current() {
+            if byte == DIVISOR && self.peek(1) == Some(STAR) {
+                depth += 1;
+                self.position += 2;
+            } else if byte == STAR && self.peek(1) == Some(DIVISOR) {
+                depth -= 1;
+                self.position += 2;
+                if depth == 0 {
+                    return Ok(&self.input[start..self.position - 2]);
+                }
+            } else {
+                self.advance();
+            }
+        }
+
+        Err("Unterminated block comment".to_string())
     }
 
     /// Read an identifier from the input buffer.
     /// Identifiers are allowed to contain alphanumeric characters and underscores.
-    fn read_identifier(&mut self) -> String {
-        let mut ident = String::new();
-
-        while let Some(ch) = self.current_char {
-            if ch.is_alphanumeric() || ch == UNDERSCORE {
-                ident.push(ch);
+    fn read_identifier(&mut self) -> &'a str {
+        let start = self.position;
+        while let Some(byte) = self.current() {
+            if byte.is_ascii_alphanumeric() || byte == UNDERSCORE {
                 self.advance();
+            } else if !byte.is_ascii() && self.current_char().is_some_and(char::is_alphanumeric) {
+                self.advance_char();
             } else {
                 break;
             }
         }
-
-        ident
+        &self.input[start..self.position]
     }
 
     /// Read the next token.
     /// First, advances the cursor to the next token position (position where the whitespace ends).
     /// Reads the next token, dispatching to the corresponding reader functio based on the input.
     /// If the next char is an [ESCAPE_QUOTE], tries to interpret everything between it and the next [ESCAPE_QUOTE] as a string.
-    /// If the next char is a [DOUBLE_QUOTE] tries to interpret anything until the next  [DOUBLE_QUOTE] as an identifier.
+    /// If the next char opens a quoted identifier in the lexer dialect (double quotes, and also backticks or brackets
+    /// on some dialects), tries to interpret anything until the closing quote as an identifier.
     /// Single-char tokens are pretty easy to peek any other way.
-    pub fn next_token(&mut self) -> Token {
-        self.skip_whitespace();
+    ///
+    /// Comments are skipped, except for optimizer hints (`/*+ ... */`), which are returned as [Token::Hint].
+    /// Invalid input is never skipped: it produces a [Token::Error] with the location where it starts.
+    #[cfg(test)]
+    pub(crate) fn next_token(&mut self) -> Token<'a> {
+        self.next_token_with_span().0
+    }
 
-        match self.current_char {
-            None => Token::Eof,
-            Some(ESCAPE_QUOTE) => Token::StringLiteral(self.read_string()),
-            Some(DOUBLE_QUOTE) => {
-                self.advance();
-                let mut ident = String::new();
-                while let Some(ch) = self.current_char {
-                    if ch == DOUBLE_QUOTE {
-                        self.advance();
-                        break;
-                    }
-                    ident.push(ch);
-                    self.advance();
+    /// Reads the next token along with the span of input it was read from.
+    pub(crate) fn next_token_with_span(&mut self) -> (Token<'a>, Span) {
+        loop {
+            self.skip_whitespace();
+            let start = self.position;
+            if let Some(token) = self.read_token(start) {
+                let span = Span {
+                    start,
+                    end: self.position,
+                };
+                return (token, span);
+            }
+        }
+    }
+
+    /// Reads the token that starts at the cursor. Returns None for comments.
+    fn read_token(&mut self, start: usize) -> Option<Token<'a>> {
+        let Some(byte) = self.current() else {
+            return Some(Token::Eof);
+        };
+        let token = match byte {
+            ESCAPE_QUOTE => match self.read_string() {
+                Ok(string) => Token::StringLiteral(string),
+                Err(message) => self.error(message, start),
+            },
+            byte if byte.is_ascii()
+                && self.dialect.identifier_quote_end(byte as char).is_some() =>
+            {
+                let quote_end = self.dialect.identifier_quote_end(byte as char).unwrap();
+                match self.read_quoted_identifier(quote_end as u8) {
+                    Ok(ident) => Token::Identifier(Word::with_quote(ident, byte as char)),
+                    Err(message) => self.error(message, start),
                 }
-                Token::Identifier(ident)
             }
-            Some(ch) if ch.is_ascii_digit() => Token::NumberLiteral(self.read_number()),
-            Some(ch) if ch.is_alphabetic() || ch == '_' => {
-                let ident = self.read_identifier();
-                keyword_to_token(&ident)
+            // Prefixed string literals: E'...', N'...', X'...' and B'...'.
+            byte if matches!(byte.to_ascii_uppercase(), b'E' | b'N' | b'X' | b'B')
+                && self.peek(1) == Some(ESCAPE_QUOTE) =>
+            {
+                self.advance(); // Skip the prefix
+                let token = match byte.to_ascii_uppercase() {
+                    b'E' => self.read_escape_string().map(Token::EscapeStringLiteral),
+                    b'N' => self.read_string().map(Token::NationalStringLiteral),
+                    b'X' => self
+                        .read_string()
+                        .and_then(|s| decode_hex_string(&s))
+                        .map(Token::HexStringLiteral),
+                    _ => self
+                        .read_string()
+                        .and_then(decode_bit_string)
+                        .map(Token::BitStringLiteral),
+                };
+                token.unwrap_or_else(|message| self.error(message, start))
             }
-            Some(STAR) => {
+            byte if byte.eq_ignore_ascii_case(&b'u')
+                && self.peek(1) == Some(AMPERSAND)
+                && self.peek(2) == Some(ESCAPE_QUOTE) =>
+            {
+                self.advance(); // Skip U
+                self.advance(); // Skip &
+                self.read_string()
+                    .and_then(|s| decode_unicode_string(&s))
+                    .map(Token::UnicodeStringLiteral)
+                    .unwrap_or_else(|message| self.error(message, start))
+            }
+            DOLLAR => match self.read_dollar_quoted() {
+                Ok((tag, value)) => Token::DollarQuotedString { tag, value },
+                Err(message) => self.error(message, start),
+            },
+            byte if byte.is_ascii_digit() => match self.read_number() {
+                Ok(number) => Token::NumberLiteral(number),
+                Err(message) => self.error(message, start),
+            },
+            byte if byte.is_ascii_alphabetic()
+                || byte == UNDERSCORE
+                || (!byte.is_ascii() && self.current_char().is_some_and(char::is_alphabetic)) =>
+            {
+                let word = self.read_identifier();
+                match lookup_keyword(word) {
+                    Some(keyword) => keyword,
+                    None => Token::Identifier(Word::from(word)),
+                }
+            }
+            STAR => {
                 self.advance();
                 Token::Star
             }
-            Some(COMMA) => {
+            COMMA => {
                 self.advance();
                 Token::Comma
             }
-            Some(DOT) => {
+            DOT => {
                 self.advance();
                 Token::Dot
             }
-            Some(SEMICOLON) => {
+            SEMICOLON => {
                 self.advance();
                 Token::Semicolon
             }
-            Some(LEFT_PARENTHESES) => {
+            LEFT_PARENTHESES => {
                 self.advance();
                 Token::LParen
             }
-            Some(RIGHT_PARENTHESES) => {
+            RIGHT_PARENTHESES => {
                 self.advance();
                 Token::RParen
             }
-            Some(EQ) => {
+            EQ => {
                 self.advance();
                 Token::Eq
             }
-            Some(NOT) => {
+            NOT => {
                 self.advance();
-                if self.current_char == Some(EQ) {
+                if self.current() == Some(EQ) {
                     self.advance();
                     Token::Neq
                 } else {
                     Token::Not
                 }
             }
-            Some(LT) => {
+            LT => {
                 self.advance();
-                if self.current_char == Some(EQ) {
+                if self.current() == Some(EQ) {
                     self.advance();
                     Token::Le
 
                 // <> means not equal in ANSI-SQL.
-                } else if self.current_char == Some(GT) {
+                } else if self.current() == Some(GT) {
                     self.advance();
                     Token::Neq
                 } else {
                     Token::Lt
                 }
             }
-            Some(GT) => {
+            GT => {
                 self.advance();
-                if self.current_char == Some(EQ) {
+                if self.current() == Some(EQ) {
                     self.advance();
                     Token::Ge
                 } else {
                     Token::Gt
                 }
             }
-            Some(PLUS) => {
+            PLUS => {
                 self.advance();
                 Token::Plus
             }
-            Some(MINUS) => {
+            MINUS => {
                 self.advance();
                 // Check for comments
-                if self.current_char == Some('-') {
+                if self.current() == Some(MINUS) {
                     // Skip until end of line
-                    while let Some(ch) = self.current_char {
-                        if ch == '\n' {
-                            break;
-                        }
-                        self.advance();
-                    }
-                    self.next_token()
-                } else {
-                    Token::Minus
+                    let rest = &self.bytes[self.position..];
+                    self.position += rest
+                        .iter()
+                        .position(|b| *b == NEWLINE)
+                        .unwrap_or(rest.len());
+                    return None;
+                }
+                Token::Minus
+            }
+            DIVISOR if self.peek(1) == Some(STAR) => {
+                let is_hint = self.peek(2) == Some(PLUS);
+                match self.read_block_comment() {
+                    // Skip the '+' marker of the hint.
+                    Ok(text) if is_hint => Token::Hint(text[1..].trim()),
+                    Ok(_) => return None,
+                    Err(message) => self.error(message, start),
                 }
             }
-            Some(DIVISOR) => {
+            DIVISOR => {
                 self.advance();
                 Token::Slash
             }
-            Some(MODULO) => {
+            MODULO => {
                 self.advance();
                 Token::Percent
             }
-            Some(PIPE) => {
+            TILDE => {
+                self.advance();
+                if self.current() == Some(STAR) {
+                    self.advance();
+                    Token::TildeStar
+                } else {
+                    Token::Tilde
+                }
+            }
+            PIPE => {
                 self.advance();
-                if self.current_char == Some(PIPE) {
+                if self.current() == Some(PIPE) {
                     self.advance();
                     Token::Concat
                 } else {
-                    // Single | is not a valid SQL operator, treat as unknown
-                    self.next_token()
+                    // Single | is not a valid SQL operator.
+                    self.error(
+                        "Unexpected character '|', did you mean '||'?".to_string(),
+                        start,
+                    )
                 }
             }
             _ => {
-                self.advance();
-                self.next_token()
+                let ch = self.current_char().unwrap_or_default();
+                self.advance_char();
+                self.error(format!("Unexpected character '{ch}'"), start)
             }
-        }
+        };
+        Some(token)
     }
+}
 
-    /// Peek the next token, without advancing the cursor.
-    pub(crate) fn __peek_token(&mut self) -> Token {
-        let saved_position = self.position;
-        let saved_char = self.current_char;
+/// Decodes the body of a hex string literal (`X'DEADBEEF'`) into bytes.
+/// Spaces may separate groups of digits.
+fn decode_hex_string(body: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<u32> = body
+        .chars()
+        .filter(|ch| *ch != ' ')
+        .map(|ch| ch.to_digit(16))
+        .collect::<Option<_>>()
+        .ok_or(format!("Invalid hexadecimal string literal: X'{body}'"))?;
+    if !digits.len().is_multiple_of(2) {
+        return Err(format!(
+            "Hexadecimal string literal must have an even number of digits: X'{body}'"
+        ));
+    }
+    Ok(digits
+        .chunks(2)
+        .map(|pair| (pair[0] * 16 + pair[1]) as u8)
+        .collect())
+}
 
-        let token = self.next_token();
+/// Validates the body of a bit string literal (`B'0101'`).
+fn decode_bit_string(body: Cow<'_, str>) -> Result<Cow<'_, str>, String> {
+    if body.bytes().all(|byte| byte == b'0' || byte == b'1') {
+        Ok(body)
+    } else {
+        Err(format!("Invalid bit string literal: B'{body}'"))
+    }
+}
 
-        self.position = saved_position;
-        self.current_char = saved_char;
+/// Resolves the escapes of a Unicode string literal: `\XXXX` and `\+XXXXXX` code points, and `\\`.
+fn decode_unicode_string(body: &str) -> Result<String, String> {
+    let mut result = String::new();
+    let mut chars = body.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
 
-        token
+        let len = match chars.clone().next() {
+            Some('\\') => {
+                chars.next();
+                result.push('\\');
+                continue;
+            }
+            Some('+') => {
+                chars.next();
+                6
+            }
+            _ => 4,
+        };
+        let digits: String = chars.by_ref().take(len).collect();
+        let code_point = (digits.len() == len && digits.chars().all(|d| d.is_ascii_hexdigit()))
+            .then(|| u32::from_str_radix(&digits, 16).ok())
+            .flatten()
+            .and_then(char::from_u32)
+            .ok_or(format!(
+                "Invalid Unicode escape in string literal: \\{digits}"
+            ))?;
+        result.push(code_point);
     }
+    Ok(result)
 }
@@ -1,24 +1,62 @@
 mod ast;
+mod binder;
+mod boolean;
+mod catalog;
+mod cli;
+mod completion;
+mod cte;
+mod decorrelate;
+mod diagnostics;
+mod dialect;
+mod equivalence;
+mod eval;
+mod functions;
+mod highlight;
+mod json;
 mod lexer;
 mod parser;
+mod plan;
+mod printer;
+mod range;
+mod repl;
 #[cfg(test)]
 mod tests;
 mod token;
+mod tree;
+mod validation;
 //mod optimizer;
 mod simplify;
 mod visitor;
+mod walk;
+#[cfg(test)]
 use ast::*;
+#[cfg(test)]
 use lexer::Lexer;
+#[cfg(test)]
 use parser::Parser;
+#[cfg(test)]
 use visitor::Visitor;
-use simplify::Simplify;
+use completion::SqlHelper;
+#[cfg(test)]
+use diagnostics::Diagnostic;
 use colored::*;
-use rustyline::DefaultEditor;
+use rustyline::Editor;
+use rustyline::history::DefaultHistory;
 use rustyline::error::ReadlineError;
+use repl::{Flow, Session};
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use std::process;
 
+#[cfg(test)]
 use crate::simplify::Simplifyer;
 
+/// File in the home directory where the shell keeps its input history.
+const HISTORY_FILE: &str = ".sql_parser_history";
+
 /// Parse a SQL query string into an AST
+#[cfg(test)]
 pub(crate) fn parse_sql(sql: &str) -> Result<Statement, String> {
     let lexer = Lexer::new(sql);
     let mut parser = Parser::new(lexer);
@@ -27,7 +65,16 @@ pub(crate) fn parse_sql(sql: &str) -> Result<Statement, String> {
 }
 
 
+/// Parse every statement of a SQL script, collecting all the syntax errors instead of stopping at the first one
+#[cfg(test)]
+pub(crate) fn parse_script(sql: &str) -> (Vec<Statement>, Vec<Diagnostic>) {
+    let lexer = Lexer::new(sql);
+    let mut parser = Parser::new(lexer);
+    parser.parse_statements()
+}
+
 /// Simplify a SQL query string into an optimized AST
+#[cfg(test)]
 pub(crate) fn simplify_sql(sql: &str) -> Result<Statement, String> {
     let lexer = Lexer::new(sql);
     let parser = Parser::new(lexer);
@@ -37,53 +84,58 @@ pub(crate) fn simplify_sql(sql: &str) -> Result<Statement, String> {
 }
 
 fn main() {
-    let mut rl = DefaultEditor::new().unwrap();
+    // With arguments, run a command of the command-line interface instead of the shell.
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        let status = cli::run(
+            &args,
+            &mut io::stdin().lock(),
+            &mut io::stdout().lock(),
+            &mut io::stderr(),
+        );
+        process::exit(status.code());
+    }
+
+    let mut rl: Editor<SqlHelper, DefaultHistory> = Editor::new().unwrap();
     println!("{}", "ANSI-Compatible SQL Parser".bold().blue());
     println!("Type your SQL statements (end with ';'), or type .help or .exit.\n");
 
+    // Tables created during the session, used to resolve names in later queries, and settings.
+    let mut session = Session::new();
+    rl.set_helper(Some(SqlHelper::new(session.catalog.clone())));
+
+    // The input history is kept across sessions in the home directory.
+    let history = env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE));
+    if let Some(path) = &history {
+        rl.load_history(path).ok();
+    }
+
     loop {
         let readline = rl.readline("sql> ");
         match readline {
-            Ok(mut line) => {
-                line = line.trim().to_string();
-
-                if line == ".exit" || line == ".quit" {
-                    println!("{}", "Goodbye!".green());
-                    break;
-                }
-
-                if line == ".help" {
-                    println!(
-                        "{}",
-                        "Commands:\n  .help  - Show this message\n  .exit  - Quit the shell\n"
-                            .bright_black()
-                    );
-                    continue;
-                }
-
-                while !line.trim_end().ends_with(';') {
-                    let more = rl.readline("...> ");
-                    match more {
-                        Ok(next_line) => line.push_str(&format!(" {}", next_line)),
-                        Err(_) => break,
-                    }
-                }
-
+            Ok(line) => {
+                let line = line.trim();
                 if line.is_empty() {
                     continue;
                 }
 
-                rl.add_history_entry(line.clone()).ok();
+                rl.add_history_entry(line).ok();
 
-                match simplify_sql(&line) {
-                    Ok(ast) => {
-
-                        println!("{}\n{:#?}\n", "Successfully parsed:".green().bold(), ast);
+                match session.execute(line, &mut io::stdout(), &mut io::stderr()) {
+                    Ok(Flow::Continue) => {}
+                    Ok(Flow::Exit) => {
+                        println!("{}", "Goodbye!".green());
+                        break;
                     }
                     Err(err) => {
-                        eprintln!("{} {}", "Parse error:".red().bold(), err);
+                        eprintln!("Error: {:?}", err);
+                        break;
                     }
                 }
+                if let Some(helper) = rl.helper_mut() {
+                    helper.set_catalog(session.catalog.clone());
+                    helper.dialect = session.dialect;
+                }
             }
             Err(ReadlineError::Interrupted) => {
                 println!("CTRL-C pressed. Type .exit to quit.");
@@ -99,4 +151,8 @@ fn main() {
             }
         }
     }
+
+    if let Some(path) = &history {
+        rl.save_history(path).ok();
+    }
 }
@@ -1,34 +1,184 @@
 use crate::ast::*;
+use crate::diagnostics::{Diagnostic, did_you_mean, suggest_keyword};
 use crate::lexer::Lexer;
-use crate::token::Token;
+use crate::token::{Span, Token, Word};
 use crate::visitor::Visitor;
+use std::collections::VecDeque;
 use std::mem;
+use std::slice;
+
+/// Maximum number of parts in an object name: catalog, schema and object.
+const MAX_OBJECT_NAME_PARTS: usize = 3;
+
+/// Bounds on the size of the input the parser accepts, so hostile or machine-generated queries
+/// fail with an error instead of exhausting the stack or the memory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ParserLimits {
+    /// Maximum nesting of expressions and subqueries: parentheses, prefix operators, CASE...
+    pub(crate) max_depth: usize,
+    /// Maximum number of tokens in the input.
+    pub(crate) max_tokens: usize,
+    /// Maximum number of expression nodes in the tree.
+    pub(crate) max_nodes: usize,
+}
+
+impl Default for ParserLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 128,
+            max_tokens: 1_000_000,
+            max_nodes: 100_000,
+        }
+    }
+}
 
 /// Main parser implementation.
 /// Uses a pratt parsing approach to parse sql expressions into AST nodes.
-pub(crate) struct Parser {
-    lexer: Lexer,
-    current_token: Token,
+pub(crate) struct Parser<'a> {
+    lexer: Lexer<'a>,
+    current_token: Token<'a>,
+    /// Input text covered by the current token.
+    current_span: Span,
+    /// Input text covered by the token before the current one.
+    previous_span: Span,
+    /// Last alias written without AS, which may be a misspelled keyword: `SELECT a FORM t`.
+    implicit_alias: Option<Span>,
+    /// Ring buffer of tokens read ahead of the current one, filled by [Parser::peek_token].
+    lookahead: VecDeque<(Token<'a>, Span)>,
+    limits: ParserLimits,
+    /// Current nesting depth, number of tokens read and number of expression nodes built.
+    depth: usize,
+    tokens: usize,
+    nodes: usize,
+    /// Whether syntax errors are recorded in `diagnostics` and skipped instead of returned.
+    recovery: bool,
+    diagnostics: Vec<Diagnostic>,
+    /// Tokens the grammar accepted where the input ends, and whether a name was accepted there.
+    /// Completion uses them to find what may follow the text before the cursor.
+    expected: Vec<Token<'a>>,
+    expects_name: bool,
 }
 
-impl Parser {
-    pub(crate) fn new(mut lexer: Lexer) -> Self {
-        let current_token = lexer.next_token();
-        Parser {
+impl<'a> Parser<'a> {
+    pub(crate) fn new(lexer: Lexer<'a>) -> Self {
+        let mut parser = Parser {
             lexer,
-            current_token,
-        }
+            current_token: Token::Eof,
+            current_span: Span::default(),
+            previous_span: Span::default(),
+            implicit_alias: None,
+            lookahead: VecDeque::with_capacity(1),
+            limits: ParserLimits::default(),
+            depth: 0,
+            tokens: 0,
+            nodes: 0,
+            recovery: false,
+            diagnostics: Vec::new(),
+            expected: Vec::new(),
+            expects_name: false,
+        };
+        parser.next_token();
+        parser
     }
 
+    /// Replaces the default resource limits.
+    pub(crate) fn with_limits(mut self, limits: ParserLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Advances to the next token, taking it from the lookahead buffer if it was already read.
     fn next_token(&mut self) {
-        self.current_token = self.lexer.next_token();
+        self.previous_span = self.current_span;
+        (self.current_token, self.current_span) = match self.lookahead.pop_front() {
+            Some(spanned) => spanned,
+            None => self.lex_token(),
+        };
     }
 
-    fn __peek_token(&mut self) -> Token {
-        self.lexer.__peek_token()
+    /// Reads a token from the lexer. Optimizer hints are not interpreted yet, so they are skipped.
+    /// Past the token limit, the lexer is not read anymore: an error token is returned once, then
+    /// the input ends, so that error recovery stops there.
+    fn lex_token(&mut self) -> (Token<'a>, Span) {
+        loop {
+            if self.tokens >= self.limits.max_tokens {
+                let span = Span {
+                    start: self.current_span.end,
+                    end: self.current_span.end,
+                };
+                if self.tokens > self.limits.max_tokens {
+                    return (Token::Eof, span);
+                }
+                self.tokens += 1;
+                let message = format!(
+                    "Query exceeds the maximum of {} tokens",
+                    self.limits.max_tokens
+                );
+                return (self.lexer.error(message, span.start), span);
+            }
+            self.tokens += 1;
+
+            let (token, span) = self.lexer.next_token_with_span();
+            if !matches!(token, Token::Hint(_)) {
+                return (token, span);
+            }
+        }
     }
 
-    fn expect(&mut self, expected: Token) -> Result<(), String> {
+    /// Runs a parsing function one nesting level deeper, failing if that exceeds the depth limit.
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<T, String> {
+        if self.depth >= self.limits.max_depth {
+            return Err(format!(
+                "Query exceeds the maximum nesting depth of {}",
+                self.limits.max_depth
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Counts a new expression node, failing if there are too many.
+    fn add_node(&mut self) -> Result<(), String> {
+        self.nodes += 1;
+        if self.nodes > self.limits.max_nodes {
+            return Err(format!(
+                "Query exceeds the maximum of {} expression nodes",
+                self.limits.max_nodes
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the token after the current one without consuming anything.
+    /// The token is lexed once and kept in the lookahead buffer until the parser advances to it.
+    fn peek_token(&mut self) -> &Token<'a> {
+        if self.lookahead.is_empty() {
+            let spanned = self.lex_token();
+            self.lookahead.push_back(spanned);
+        }
+        &self.lookahead[0].0
+    }
+
+    /// Records that one of the given tokens may come next, if the input ends at the current token.
+    fn expecting(&mut self, tokens: &[Token<'a>]) {
+        if self.current_token == Token::Eof {
+            self.expected.extend_from_slice(tokens);
+        }
+    }
+
+    /// Returns true if the current token matches the given one, without consuming it.
+    fn at(&mut self, token: &Token<'a>) -> bool {
+        self.expecting(slice::from_ref(token));
+        mem::discriminant(&self.current_token) == mem::discriminant(token)
+    }
+
+    fn expect(&mut self, expected: Token<'a>) -> Result<(), String> {
+        self.expecting(slice::from_ref(&expected));
         // docs on std::mem::discriminant: https://doc.rust-lang.org/std/mem/fn.discriminant.html
         // Returns a value uniquely identifying the enum variant in the calling token.
         if mem::discriminant(&self.current_token) == mem::discriminant(&expected) {
@@ -43,8 +193,22 @@ impl Parser {
     }
 
     /// Consumes the next token if the current token matches the expected one.
-    fn consume_if(&mut self, token: &Token) -> bool {
-        if mem::discriminant(&self.current_token) == mem::discriminant(token) {
+    fn consume_if(&mut self, token: &Token<'a>) -> bool {
+        if self.at(token) {
+            self.next_token();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes the current token if it is an identifier matching the given word.
+    /// Used for contextual words that are not reserved keywords (IF, EXCLUDE, REPLACE...).
+    fn consume_identifier(&mut self, word: &str) -> bool {
+        if let Token::Identifier(ident) = &self.current_token
+            && ident.quote_style.is_none()
+            && ident.value.eq_ignore_ascii_case(word)
+        {
             self.next_token();
             true
         } else {
@@ -59,22 +223,40 @@ impl Parser {
 
     /// Obtains the expression binding power using a Pratt Parsing approach.
     /// I recommend this read on Pratt Parsing: https://matklad.github.io/2020/04/13/simple-but-powerful-pratt-parsing.html
+    /// Each call is one nesting level: operands are parsed recursively, while a chain of operators
+    /// of the same binding power (`a OR b OR c`) is consumed by the loop.
     fn parse_expr_bp(&mut self, min_bp: u8) -> Result<Expr, String> {
-        let mut lhs = self.parse_prefix()?;
+        self.nested(|p| {
+            let mut lhs = p.parse_prefix()?;
 
-        while let Some((l_bp, r_bp)) = self.infix_binding_power() {
-            if l_bp < min_bp {
-                break;
-            }
+            while let Some((l_bp, r_bp)) = p.infix_binding_power() {
+                if l_bp < min_bp {
+                    break;
+                }
 
-            lhs = self.parse_infix(lhs, r_bp)?;
-        }
+                lhs = p.parse_infix(lhs, r_bp)?;
+            }
 
-        Ok(lhs)
+            Ok(lhs)
+        })
     }
 
     /// Given the current token, obtains the parsed prefix of the expression.
     fn parse_prefix(&mut self) -> Result<Expr, String> {
+        self.add_node()?;
+        let is_name = self.current_identifier().is_some();
+        self.expecting(&[
+            Token::True,
+            Token::False,
+            Token::Null,
+            Token::Star,
+            Token::LParen,
+            Token::Plus,
+            Token::Minus,
+            Token::Not,
+            Token::Case,
+            Token::Exists,
+        ]);
         match &self.current_token {
             Token::NumberLiteral(n) => {
                 let num = *n;
@@ -82,10 +264,40 @@ impl Parser {
                 Ok(Expr::Number(num))
             }
             Token::StringLiteral(s) => {
-                let string = s.clone();
+                let string = s.to_string();
                 self.next_token();
                 Ok(Expr::String(string))
             }
+            Token::EscapeStringLiteral(s) => {
+                let string = s.clone();
+                self.next_token();
+                Ok(Expr::EscapeString(string))
+            }
+            Token::NationalStringLiteral(s) => {
+                let string = s.to_string();
+                self.next_token();
+                Ok(Expr::NationalString(string))
+            }
+            Token::HexStringLiteral(bytes) => {
+                let bytes = bytes.clone();
+                self.next_token();
+                Ok(Expr::HexString(bytes))
+            }
+            Token::BitStringLiteral(bits) => {
+                let bits = bits.to_string();
+                self.next_token();
+                Ok(Expr::BitString(bits))
+            }
+            Token::UnicodeStringLiteral(s) => {
+                let string = s.clone();
+                self.next_token();
+                Ok(Expr::UnicodeString(string))
+            }
+            Token::DollarQuotedString { tag, value } => {
+                let (tag, value) = (tag.to_string(), value.to_string());
+                self.next_token();
+                Ok(Expr::DollarString { tag, value })
+            }
             Token::True => {
                 self.next_token();
                 Ok(Expr::Boolean(true))
@@ -103,35 +315,54 @@ impl Parser {
                 Ok(Expr::Star)
             }
             // Consuming identifiers is tricky as we want to support qualified expressions like [TABLE].[COLUMN], or even [TABLE].* for selecting all columns in a table.
-            Token::Identifier(name) => {
-                let name = name.clone();
-                self.next_token();
+            // Column references can have up to four parts: [CATALOG].[SCHEMA].[TABLE].[COLUMN].
+            // Non-reserved keywords are parsed as identifiers too.
+            _ if is_name => {
+                let name = self.parse_identifier("identifier")?;
 
                 // Check for qualified identifier (table.column)
-                if self.consume_if(&Token::Dot) {
-                    if let Token::Identifier(col) = &self.current_token {
-                        let column = col.clone();
-                        self.next_token();
-                        Ok(Expr::QualifiedIdentifier {
-                            table: name,
-                            column,
-                        })
-                    } else if self.current_token == Token::Star {
-                        self.next_token();
-                        Ok(Expr::Star) // Actually should be QualifiedStar in SELECT
-                    } else {
-                        Err("Expected column name after '.'".to_string())
+                if self.at(&Token::Dot) {
+                    let mut parts = vec![name];
+                    while self.consume_if(&Token::Dot) {
+                        if self.current_token.is_keyword()
+                            || matches!(self.current_token, Token::Identifier(_))
+                        {
+                            parts.push(self.parse_label("column name after '.'")?);
+                        } else if self.at(&Token::Star) {
+                            self.next_token();
+                            if parts.len() > MAX_OBJECT_NAME_PARTS {
+                                return Err(format!(
+                                    "Too many parts in qualified wildcard: {}.*",
+                                    ObjectName(parts)
+                                ));
+                            }
+                            return Ok(Expr::QualifiedStar(ObjectName(parts)));
+                        } else {
+                            return Err("Expected column name after '.'".to_string());
+                        }
+                    }
+
+                    if parts.len() > MAX_OBJECT_NAME_PARTS + 1 {
+                        return Err(format!(
+                            "Too many parts in column reference: {}",
+                            ObjectName(parts)
+                        ));
                     }
+                    let column = parts.pop().expect("qualified names have two parts or more");
+                    Ok(Expr::QualifiedIdentifier {
+                        table: ObjectName(parts),
+                        column,
+                    })
                 }
                 // Check for function call
-                else if self.current_token == Token::LParen {
+                else if self.at(&Token::LParen) {
                     self.next_token();
 
                     // Check for DISTINCT in aggregate functions
                     let distinct = self.consume_if(&Token::Distinct);
 
                     let mut args = Vec::new();
-                    if self.current_token != Token::RParen {
+                    if !self.at(&Token::RParen) {
                         loop {
                             // We need to recursively parse the inner expression.
                             // Args in function calls are separated by commas.
@@ -144,7 +375,7 @@ impl Parser {
 
                     self.expect(Token::RParen)?;
                     Ok(Expr::FunctionCall {
-                        name,
+                        name: name.value,
                         args,
                         distinct,
                     })
@@ -155,6 +386,13 @@ impl Parser {
             Token::LParen => {
                 self.next_token();
 
+                // Scalar subquery
+                if self.at(&Token::Select) {
+                    let subquery = self.parse_select_statement()?;
+                    self.expect(Token::RParen)?;
+                    return Ok(Expr::Subquery(Box::new(subquery)));
+                }
+
                 let mut exprs = Vec::new();
                 loop {
                     exprs.push(self.parse_expression()?);
@@ -239,6 +477,7 @@ impl Parser {
     }
 
     fn parse_infix(&mut self, left: Expr, r_bp: u8) -> Result<Expr, String> {
+        self.add_node()?;
         let op = match &self.current_token {
             Token::Plus => {
                 self.next_token();
@@ -296,15 +535,40 @@ impl Parser {
                 self.next_token();
                 BinaryOperator::Like
             }
+            Token::ILike => {
+                self.next_token();
+                BinaryOperator::ILike
+            }
+            Token::Similar => {
+                self.next_token();
+                self.expect(Token::To)?;
+                BinaryOperator::SimilarTo
+            }
+            Token::Tilde => {
+                self.next_token();
+                BinaryOperator::RegexMatch
+            }
+            Token::TildeStar => {
+                self.next_token();
+                BinaryOperator::RegexIMatch
+            }
             Token::Not => {
-                // Handle combined infix forms: NOT IN / NOT BETWEEN / NOT LIKE
+                // Handle combined infix forms: NOT IN / NOT BETWEEN / NOT LIKE / NOT ILIKE /
+                // NOT SIMILAR TO
                 self.next_token(); // consume NOT
+                self.expecting(&[
+                    Token::In,
+                    Token::Between,
+                    Token::Like,
+                    Token::ILike,
+                    Token::Similar,
+                ]);
                 match &self.current_token {
                     Token::In => {
                         self.next_token();
                         self.expect(Token::LParen)?;
 
-                        if self.current_token == Token::Select {
+                        if self.at(&Token::Select) {
                             let subquery = self.parse_select_statement()?;
                             self.expect(Token::RParen)?;
                             return Ok(Expr::BinaryOp {
@@ -340,14 +604,17 @@ impl Parser {
                             high: Box::new(high),
                         });
                     }
-                    Token::Like => {
+                    Token::Like | Token::ILike | Token::Similar => {
+                        let op = match self.current_token {
+                            Token::Like => BinaryOperator::NotLike,
+                            Token::ILike => BinaryOperator::NotILike,
+                            _ => BinaryOperator::NotSimilarTo,
+                        };
                         self.next_token();
-                        let right = self.parse_expr_bp(r_bp)?;
-                        return Ok(Expr::BinaryOp {
-                            left: Box::new(left),
-                            op: BinaryOperator::NotLike,
-                            right: Box::new(right),
-                        });
+                        if op == BinaryOperator::NotSimilarTo {
+                            self.expect(Token::To)?;
+                        }
+                        return self.parse_pattern(left, op, r_bp);
                     }
                     _ => {
                         return Err(format!(
@@ -362,7 +629,7 @@ impl Parser {
                 self.expect(Token::LParen)?;
 
                 // Check if it's a subquery or a list
-                if self.current_token == Token::Select {
+                if self.at(&Token::Select) {
                     let subquery = self.parse_select_statement()?;
                     self.expect(Token::RParen)?;
                     return Ok(Expr::BinaryOp {
@@ -400,11 +667,20 @@ impl Parser {
             }
             Token::Is => {
                 self.next_token();
-                if self.consume_if(&Token::Not) {
+                let op = if self.consume_if(&Token::Not) {
                     BinaryOperator::IsNot
                 } else {
                     BinaryOperator::Is
+                };
+                // UNKNOWN is the null truth value, so IS UNKNOWN is IS NULL.
+                if self.consume_if(&Token::Unknown) {
+                    return Ok(Expr::BinaryOp {
+                        left: Box::new(left),
+                        op,
+                        right: Box::new(Expr::Null),
+                    });
                 }
+                op
             }
             Token::Concat => {
                 self.next_token();
@@ -418,6 +694,9 @@ impl Parser {
             }
         };
 
+        if op.accepts_escape() {
+            return self.parse_pattern(left, op, r_bp);
+        }
         let right = self.parse_expr_bp(r_bp)?;
 
         Ok(Expr::BinaryOp {
@@ -435,29 +714,78 @@ impl Parser {
     // + has lbp of 7 and * has lbp of 9. Therefore, the expression is interpreted as follows: (+ 1 (* 2 3)).
     // The operand 2 binds to the operator * as it has higher lbp than +'s rbp.
     fn infix_binding_power(&mut self) -> Option<(u8, u8)> {
+        self.expecting(&[
+            Token::Or,
+            Token::And,
+            Token::Eq,
+            Token::Neq,
+            Token::Lt,
+            Token::Gt,
+            Token::Le,
+            Token::Ge,
+            Token::Like,
+            Token::In,
+            Token::Between,
+            Token::ILike,
+            Token::Similar,
+            Token::Tilde,
+            Token::TildeStar,
+            Token::Is,
+            Token::Plus,
+            Token::Minus,
+            Token::Star,
+            Token::Slash,
+            Token::Percent,
+            Token::Concat,
+            Token::Not,
+        ]);
         match &self.current_token {
             Token::Or => Some((1, 2)),
             Token::And => Some((3, 4)),
             Token::Eq | Token::Neq | Token::Lt | Token::Gt | Token::Le | Token::Ge => Some((5, 6)),
             Token::Like | Token::In | Token::Between => Some((5, 6)),
+            Token::ILike | Token::Similar | Token::Tilde | Token::TildeStar => Some((5, 6)),
             Token::Is => Some((5, 6)),
             Token::Plus | Token::Minus => Some((7, 8)),
             Token::Star | Token::Slash | Token::Percent => Some((9, 10)),
             Token::Concat => Some((7, 8)),
-            Token::Not => {
-                let next = self.__peek_token();
-                match next {
-                    Token::In | Token::Between | Token::Like => Some((5, 6)),
-                    _ => None,
-                }
-            }
+            // NOT is taken as an operator at the end of the input too, to record what may follow it.
+            Token::Not => match self.peek_token() {
+                Token::In
+                | Token::Between
+                | Token::Like
+                | Token::ILike
+                | Token::Similar
+                | Token::Eof => Some((5, 6)),
+                _ => None,
+            },
             _ => None,
         }
     }
 }
 
 /// Utilities for parsing complex expressions.
-impl Parser {
+impl<'a> Parser<'a> {
+    /// Parses the pattern of LIKE, ILIKE or SIMILAR TO, with its optional ESCAPE character.
+    /// ```sql
+    /// name LIKE 'a!%%' ESCAPE '!'
+    /// ```
+    fn parse_pattern(&mut self, left: Expr, op: BinaryOperator, r_bp: u8) -> Result<Expr, String> {
+        let mut pattern = self.parse_expr_bp(r_bp)?;
+        if self.consume_if(&Token::Escape) {
+            let escape = self.parse_expr_bp(r_bp)?;
+            pattern = Expr::Escape {
+                pattern: Box::new(pattern),
+                escape: Box::new(escape),
+            };
+        }
+        Ok(Expr::BinaryOp {
+            left: Box::new(left),
+            op,
+            right: Box::new(pattern),
+        })
+    }
+
     /// Parses a CASE expression.
     /// ```sql
     /// CASE enum
@@ -473,7 +801,7 @@ impl Parser {
         let mut when_clauses = Vec::new();
 
         // Check if there's an operand before WHEN
-        if self.current_token != Token::When {
+        if !self.at(&Token::When) {
             operand = Some(Box::new(self.parse_expression()?));
         }
 
@@ -501,17 +829,65 @@ impl Parser {
         })
     }
 
+    /// Returns the current token as an identifier, if it is an identifier or a non-reserved keyword.
+    fn current_identifier(&mut self) -> Option<Ident> {
+        if self.current_token == Token::Eof {
+            self.expects_name = true;
+        }
+        match &self.current_token {
+            Token::Identifier(word) => Some(Ident::from(word)),
+            token if token.is_non_reserved_keyword() => {
+                Some(Ident::new(self.lexer.text(self.current_span)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses a single identifier, failing with "Expected [what]" if the current token is not one.
+    /// Non-reserved keywords are accepted as identifiers.
+    fn parse_identifier(&mut self, what: &str) -> Result<Ident, String> {
+        if let Some(ident) = self.current_identifier() {
+            self.next_token();
+            Ok(ident)
+        } else {
+            Err(format!("Expected {what}"))
+        }
+    }
+
+    /// Parses a name in a position where no keyword could appear, such as an alias after AS
+    /// or a name after a '.'. Reserved keywords are accepted here too.
+    fn parse_label(&mut self, what: &str) -> Result<Ident, String> {
+        if self.current_token.is_keyword() {
+            let ident = Ident::new(self.lexer.text(self.current_span));
+            self.next_token();
+            Ok(ident)
+        } else {
+            self.parse_identifier(what)
+        }
+    }
+
+    /// Parses a possibly qualified object name: [CATALOG].[SCHEMA].[TABLE].
+    fn parse_object_name(&mut self) -> Result<ObjectName, String> {
+        let mut parts = vec![self.parse_identifier("table name")?];
+        while self.consume_if(&Token::Dot) {
+            parts.push(self.parse_label("identifier after '.'")?);
+        }
+
+        if parts.len() > MAX_OBJECT_NAME_PARTS {
+            return Err(format!(
+                "Too many parts in object name: {}",
+                ObjectName(parts)
+            ));
+        }
+        Ok(ObjectName(parts))
+    }
+
     /// Parses a list of identifier tokens: (col1, col2, col3 ...).
-    fn parse_identifier_list(&mut self) -> Result<Vec<String>, String> {
+    fn parse_identifier_list(&mut self) -> Result<Vec<Ident>, String> {
         let mut identifiers = Vec::new();
 
         loop {
-            if let Token::Identifier(name) = &self.current_token {
-                identifiers.push(name.clone());
-                self.next_token();
-            } else {
-                return Err("Expected identifier".to_string());
-            }
+            identifiers.push(self.parse_identifier("identifier")?);
 
             if !self.consume_if(&Token::Comma) {
                 break;
@@ -522,41 +898,253 @@ impl Parser {
     }
 }
 
-impl Visitor for Parser {
+impl Visitor for Parser<'_> {
+    /// Parses a single statement, optionally terminated by a semicolon.
+    /// Lexical errors take precedence over the parse errors they cause, and any input left after
+    /// the statement is rejected.
     fn visit(&mut self) -> Result<Statement, String> {
+        let result = self.parse_statement();
+        if let Token::Error { .. } = &self.current_token {
+            return Err(self.current_token.to_string());
+        }
+        let stmt = result.map_err(|message| self.with_suggestion(message))?;
+
+        self.consume_if(&Token::Semicolon);
+        match &self.current_token {
+            Token::Eof => Ok(stmt),
+            Token::Error { .. } => Err(self.current_token.to_string()),
+            token => {
+                let message = format!("Unexpected token after end of statement: {token}");
+                Err(self.with_suggestion(message))
+            }
+        }
+    }
+}
+
+impl<'a> Parser<'a> {
+    /// Parses a single statement like [Visitor::visit], and returns the tokens the grammar accepts
+    /// at the end of the input, with whether a name is accepted there too. Nothing is returned if
+    /// parsing stopped before the end, as no token can fix the input there.
+    pub(crate) fn expected(mut self) -> (Vec<Token<'a>>, bool) {
+        if self.visit().is_err() && self.current_token != Token::Eof {
+            return (Vec::new(), false);
+        }
+        (self.expected, self.expects_name)
+    }
+
+    /// Returns true if parsing runs out of input inside a statement, before it is terminated by a
+    /// semicolon. A statement with a syntax error is skipped up to its semicolon, so that the error
+    /// is reported once the input is submitted.
+    pub(crate) fn ends_inside_statement(mut self) -> bool {
+        loop {
+            while self.consume_if(&Token::Semicolon) {}
+            if self.current_token == Token::Eof {
+                return false;
+            }
+            if self.parse_statement().is_err() || self.current_token != Token::Semicolon {
+                self.skip_statement();
+            }
+            if self.current_token == Token::Eof {
+                return true;
+            }
+        }
+    }
+}
+
+/// Panic-mode error recovery.
+///
+/// When recovery is enabled, a clause that fails to parse is replaced by an error placeholder, the
+/// error is recorded, and the tokens up to the next clause keyword are skipped. A statement that
+/// cannot be parsed at all is skipped up to the next semicolon.
+impl<'a> Parser<'a> {
+    /// Parses every statement in the input, separated by semicolons, reporting all the syntax
+    /// errors found instead of stopping at the first one. Statements with errors inside a clause are
+    /// returned with error placeholders, and statements that cannot be parsed are left out.
+    pub(crate) fn parse_statements(&mut self) -> (Vec<Statement>, Vec<Diagnostic>) {
+        self.recovery = true;
+        let mut statements = Vec::new();
+
+        loop {
+            while self.consume_if(&Token::Semicolon) {}
+            if self.current_token == Token::Eof {
+                break;
+            }
+
+            match self.parse_statement() {
+                Ok(stmt) => {
+                    statements.push(stmt);
+                    if !matches!(self.current_token, Token::Semicolon | Token::Eof) {
+                        let message = format!(
+                            "Unexpected token after end of statement: {}",
+                            self.current_token
+                        );
+                        self.report(message);
+                        self.skip_statement();
+                    }
+                }
+                Err(message) => {
+                    self.report(message);
+                    self.skip_statement();
+                }
+            }
+        }
+
+        self.recovery = false;
+        (statements, mem::take(&mut self.diagnostics))
+    }
+
+    /// Parses a clause, returning the placeholder instead of failing if recovery is enabled.
+    fn recover<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, String>,
+        placeholder: T,
+    ) -> Result<T, String> {
+        match parse(self) {
+            Err(message) if self.recovery => {
+                self.report(message);
+                self.synchronize();
+                Ok(placeholder)
+            }
+            result => result,
+        }
+    }
+
+    /// Records an error at the current token. A lexical error under the cursor is reported instead,
+    /// as it is the cause of the syntax error.
+    fn report(&mut self, message: String) {
+        let diagnostic = match &self.current_token {
+            Token::Error { message, location } => Diagnostic {
+                message: message.clone(),
+                location: *location,
+                span: self.current_span,
+            },
+            _ => Diagnostic {
+                message: self.with_suggestion(message),
+                location: self.lexer.location(self.current_span.start),
+                span: self.current_span,
+            },
+        };
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Skips tokens until a clause keyword outside of any parentheses opened while skipping.
+    /// Also stops before a closing parenthesis that belongs to an enclosing expression.
+    fn synchronize(&mut self) {
+        let mut depth = 0;
+        loop {
+            match &self.current_token {
+                Token::Eof => return,
+                Token::RParen if depth == 0 => return,
+                token if depth == 0 && is_clause_keyword(token) => return,
+                Token::LParen => depth += 1,
+                Token::RParen => depth -= 1,
+                _ => {}
+            }
+            self.skip_token();
+        }
+    }
+
+    /// Skips tokens up to the semicolon that ends the current statement, or the end of the input.
+    fn skip_statement(&mut self) {
+        while !matches!(self.current_token, Token::Semicolon | Token::Eof) {
+            self.skip_token();
+        }
+    }
+
+    /// Advances to the next token while recovering, reporting it if it is a lexical error.
+    fn skip_token(&mut self) {
+        self.next_token();
+        if let Token::Error { message, .. } = &self.current_token {
+            let message = message.clone();
+            self.report(message);
+        }
+    }
+}
+
+impl Parser<'_> {
+    /// Appends a keyword suggestion to a syntax error when a misspelled keyword is its likely
+    /// cause: either the token the error was found at, or an alias written without AS just before
+    /// it, since `SELECT a FORM t` takes FORM for an alias of `a` and fails at `t`.
+    fn with_suggestion(&self, message: String) -> String {
+        if let Some(span) = self.implicit_alias
+            && span == self.previous_span
+        {
+            let word = self.lexer.text(span);
+            if let Some(keyword) = suggest_keyword(word) {
+                return format!("{message}, did you mean '{keyword}' instead of '{word}'?");
+            }
+        }
+        match &self.current_token {
+            Token::Identifier(word) if word.quote_style.is_none() => {
+                message + &did_you_mean(suggest_keyword(&word.value).as_deref())
+            }
+            _ => message,
+        }
+    }
+}
+
+/// Returns true for the tokens that start a clause of a query, where recovery resumes parsing.
+fn is_clause_keyword(token: &Token<'_>) -> bool {
+    matches!(
+        token,
+        Token::From
+            | Token::Where
+            | Token::Group
+            | Token::Having
+            | Token::Order
+            | Token::Limit
+            | Token::Semicolon
+    )
+}
+
+impl<'a> Parser<'a> {
+    fn parse_statement(&mut self) -> Result<Statement, String> {
+        self.expecting(&[
+            Token::With,
+            Token::Select,
+            Token::Insert,
+            Token::Update,
+            Token::Delete,
+            Token::Create,
+            Token::Alter,
+            Token::Drop,
+            Token::Begin,
+            Token::Commit,
+            Token::Rollback,
+        ]);
         match &self.current_token {
             Token::With => Ok(Statement::With(self.parse_with_statement()?)),
             Token::Select => Ok(Statement::Select(self.parse_select_statement()?)),
             Token::Insert => Ok(Statement::Insert(self.parse_insert_statement()?)),
             Token::Update => Ok(Statement::Update(self.parse_update_statement()?)),
             Token::Delete => Ok(Statement::Delete(self.parse_delete_statement()?)),
-            Token::Create => {
-                let next_token = self.__peek_token();
-                match next_token {
-                    Token::Table => {
-                        Ok(Statement::CreateTable(self.parse_create_table_statement()?))
-                    }
-                    Token::Index | Token::Unique => {
-                        Ok(Statement::CreateIndex(self.parse_create_index_statement()?))
-                    }
+            Token::Create => match self.peek_token() {
+                Token::Table => Ok(Statement::CreateTable(self.parse_create_table_statement()?)),
+                Token::Index | Token::Unique => {
+                    Ok(Statement::CreateIndex(self.parse_create_index_statement()?))
+                }
 
-                    _ => Err(format!("Invalid token: {}", next_token).to_string()),
+                // The error is located at the token after CREATE, which is the one that is wrong.
+                _ => {
+                    self.next_token();
+                    self.expecting(&[Token::Table, Token::Index, Token::Unique]);
+                    Err(format!("Invalid token: {}", self.current_token))
                 }
-            }
+            },
             Token::Alter => Ok(Statement::AlterTable(self.parse_alter_statement()?)),
             Token::Drop => Ok(Statement::DropTable(self.parse_drop_statement()?)),
             Token::Begin | Token::Commit | Token::Rollback => {
                 Ok(Statement::Transaction(self.parse_transaction_statement()?))
             }
             _ => Err(format!(
-                "Unexpected statement type: {:?}",
+                "Unexpected statement type: {}",
                 self.current_token
             )),
         }
     }
 }
 
-impl Parser {
+impl<'a> Parser<'a> {
     /// Visits an ALTER TABLE statement.
     ///
     /// ```sql
@@ -572,18 +1160,11 @@ impl Parser {
         self.expect(Token::Table)?;
 
         // Parse the table name as an identifier.
-        let table = if let Token::Identifier(name) = &self.current_token {
-            let table_name = name.clone();
-            self.next_token();
-            table_name
-        } else {
-            return Err("Expected table name".to_string());
-        };
+        let table = self.parse_object_name()?;
 
         let action = if self.consume_if(&Token::Add) {
             // Allows both ADD COLUMN and ADD (column identifier) to generate ADD COLUMN statements.
-            if self.consume_if(&Token::Column) || matches!(self.current_token, Token::Identifier(_))
-            {
+            if self.consume_if(&Token::Column) || self.current_identifier().is_some() {
                 // Add column action requires a column definition
                 let column_def = self.parse_column_def()?;
                 AlterAction::AddColumn(column_def)
@@ -599,23 +1180,11 @@ impl Parser {
         } else if self.consume_if(&Token::Drop) {
             // Drop column
             if self.consume_if(&Token::Column) {
-                if let Token::Identifier(col_name) = &self.current_token {
-                    let name = col_name.clone();
-                    self.next_token();
-                    AlterAction::DropColumn(name)
-                } else {
-                    return Err("Expected column name".to_string());
-                }
+                AlterAction::DropColumn(self.parse_identifier("column name")?)
 
             // Drop constraint
             } else if self.consume_if(&Token::Constraint) {
-                if let Token::Identifier(constraint_name) = &self.current_token {
-                    let name = constraint_name.clone();
-                    self.next_token();
-                    AlterAction::DropConstraint(name)
-                } else {
-                    return Err("Expected constraint name".to_string());
-                }
+                AlterAction::DropConstraint(self.parse_identifier("constraint name")?)
             } else {
                 return Err("Expected COLUMN or CONSTRAINT after DROP".to_string());
             }
@@ -629,9 +1198,14 @@ impl Parser {
         // ALTER COLUMN DROP NOT NULL
         // ALTER COLUMN SET [DATATYPE]
         } else if self.consume_if(&Token::Alter) || self.consume_if(&Token::Modify) {
-            self.consume_if(&Token::Column);
-            if let Token::Identifier(col_name) = &self.current_token {
-                let name = col_name.clone();
+            // COLUMN is the keyword when a name follows it, and otherwise the name of the column.
+            if self.at(&Token::Column) {
+                let next = self.peek_token();
+                if matches!(next, Token::Identifier(_)) || next.is_non_reserved_keyword() {
+                    self.next_token();
+                }
+            }
+            if let Some(name) = self.current_identifier() {
                 self.next_token();
 
                 let action = if self.consume_if(&Token::Set) {
@@ -681,13 +1255,7 @@ impl Parser {
     fn parse_create_table_statement(&mut self) -> Result<CreateTableStatement, String> {
         self.expect(Token::Create)?;
         self.expect(Token::Table)?;
-        let table = if let Token::Identifier(name) = &self.current_token {
-            let table_name = name.clone();
-            self.next_token();
-            table_name
-        } else {
-            return Err("Expected table name".to_string());
-        };
+        let table = self.parse_object_name()?;
 
         self.expect(Token::LParen)?;
 
@@ -701,8 +1269,7 @@ impl Parser {
                 Token::Primary | Token::Unique | Token::Foreign | Token::Check | Token::Constraint
             ) {
                 constraints.push(self.parse_table_constraint()?);
-            } else if let Token::Identifier(name) = &self.current_token {
-                let col_name = name.clone();
+            } else if let Some(col_name) = self.current_identifier() {
                 self.next_token();
                 let data_type = self.parse_data_type()?;
                 let col_constraints = self.parse_column_constraints()?;
@@ -735,39 +1302,25 @@ impl Parser {
 
         let unique = self.consume_if(&Token::Unique);
         self.expect(Token::Index)?;
-        let if_not_exists = if self.current_token == Token::Identifier("if".to_string()) {
-            self.next_token();
+        let if_not_exists = if self.consume_identifier("if") {
             self.expect(Token::Not)?;
-            self.expect(Token::Identifier("exists".to_string()))?;
+            self.expect(Token::Exists)?;
             true
         } else {
             false
         };
 
-        let name = if let Token::Identifier(idx_name) = &self.current_token {
-            let index_name = idx_name.clone();
-            self.next_token();
-            index_name
-        } else {
-            return Err("Expected index name".to_string());
-        };
+        let name = self.parse_identifier("index name")?;
 
         self.expect(Token::On)?;
 
-        let table = if let Token::Identifier(tbl_name) = &self.current_token {
-            let table_name = tbl_name.clone();
-            self.next_token();
-            table_name
-        } else {
-            return Err("Expected table name".to_string());
-        };
+        let table = self.parse_object_name()?;
 
         self.expect(Token::LParen)?;
 
         let mut columns = Vec::new();
         loop {
-            if let Token::Identifier(col_name) = &self.current_token {
-                let name = col_name.clone();
+            if let Some(name) = self.current_identifier() {
                 self.next_token();
 
                 let order = if self.consume_if(&Token::Asc) {
@@ -810,16 +1363,10 @@ impl Parser {
         self.expect(Token::Delete)?;
         self.expect(Token::From)?;
 
-        let table = if let Token::Identifier(name) = &self.current_token {
-            let table_name = name.clone();
-            self.next_token();
-            table_name
-        } else {
-            return Err("Expected table name".to_string());
-        };
+        let table = self.parse_object_name()?;
 
         let where_clause = if self.consume_if(&Token::Where) {
-            Some(self.parse_expression()?)
+            Some(self.recover(Self::parse_expression, Expr::Error)?)
         } else {
             None
         };
@@ -840,47 +1387,18 @@ impl Parser {
         self.expect(Token::Table)?;
 
         // Check for IF EXISTS clause
-        let if_exists = if let Token::Identifier(s) = &self.current_token {
-            if s.to_lowercase() == "if" {
-                self.next_token();
-                if let Token::Identifier(s2) = &self.current_token {
-                    if s2.to_lowercase() == "exists" {
-                        self.next_token();
-                        true
-                    } else {
-                        return Err("Expected EXISTS after IF".to_string());
-                    }
-                } else if self.current_token == Token::Exists {
-                    self.next_token();
-                    true
-                } else {
-                    return Err("Expected EXISTS after IF".to_string());
-                }
-            } else {
-                false
+        let if_exists = if self.consume_identifier("if") {
+            if !self.consume_if(&Token::Exists) {
+                return Err("Expected EXISTS after IF".to_string());
             }
+            true
         } else {
             false
         };
 
-        let table = if let Token::Identifier(name) = &self.current_token {
-            let table_name = name.clone();
-            self.next_token();
-            table_name
-        } else {
-            return Err("Expected table name".to_string());
-        };
+        let table = self.parse_object_name()?;
 
-        let cascade = if let Token::Identifier(s) = &self.current_token {
-            if s.to_lowercase() == "cascade" {
-                self.next_token();
-                true
-            } else {
-                false
-            }
-        } else {
-            false
-        };
+        let cascade = self.consume_identifier("cascade");
 
         Ok(DropTableStatement {
             table,
@@ -905,25 +1423,14 @@ impl Parser {
         self.expect(Token::Insert)?;
         self.expect(Token::Into)?;
 
-        let table = if let Token::Identifier(name) = &self.current_token {
-            let table_name = name.clone();
-            self.next_token();
-            table_name
-        } else {
-            return Err("Expected table name".to_string());
-        };
+        let table = self.parse_object_name()?;
 
         // Parse optional column list
-        let columns = if self.current_token == Token::LParen {
+        let columns = if self.at(&Token::LParen) {
             self.next_token();
             let mut cols = Vec::new();
             loop {
-                if let Token::Identifier(col) = &self.current_token {
-                    cols.push(col.clone());
-                    self.next_token();
-                } else {
-                    return Err("Expected column name".to_string());
-                }
+                cols.push(self.parse_identifier("column name")?);
                 if !self.consume_if(&Token::Comma) {
                     break;
                 }
@@ -954,7 +1461,7 @@ impl Parser {
                 }
             }
             Values::Values(value_lists)
-        } else if self.current_token == Token::Select {
+        } else if self.at(&Token::Select) {
             Values::Query(Box::new(self.parse_select_statement()?))
         } else {
             return Err("Expected VALUES or SELECT".to_string());
@@ -1005,20 +1512,13 @@ impl Parser {
     fn parse_update_statement(&mut self) -> Result<UpdateStatement, String> {
         self.expect(Token::Update)?;
 
-        let table = if let Token::Identifier(name) = &self.current_token {
-            let table_name = name.clone();
-            self.next_token();
-            table_name
-        } else {
-            return Err("Expected table name".to_string());
-        };
+        let table = self.parse_object_name()?;
 
         self.expect(Token::Set)?;
 
         let mut set_clauses = Vec::new();
         loop {
-            if let Token::Identifier(col) = &self.current_token {
-                let column = col.clone();
+            if let Some(column) = self.current_identifier() {
                 self.next_token();
                 self.expect(Token::Eq)?;
                 let value = self.parse_expression()?;
@@ -1033,7 +1533,7 @@ impl Parser {
         }
 
         let where_clause = if self.consume_if(&Token::Where) {
-            Some(self.parse_expression()?)
+            Some(self.recover(Self::parse_expression, Expr::Error)?)
         } else {
             None
         };
@@ -1049,13 +1549,13 @@ impl Parser {
     /// ```sql
     /// WITH
     /// [RECURSIVE]
-    /// ALIAS AS
+    /// ALIAS AS [[NOT] MATERIALIZED]
     /// ([CTE])
     ///[...] (Supports up to N ctes)
     /// SELECT [...]
     fn parse_with_statement(&mut self) -> Result<WithStatement, String> {
         self.expect(Token::With)?;
-        let recursive = if matches!(self.current_token, Token::Recursive) {
+        let recursive = if self.at(&Token::Recursive) {
             self.next_token();
             true
         } else {
@@ -1065,20 +1565,26 @@ impl Parser {
         let mut ctes = Vec::new();
 
         loop {
-            let name = if let Token::Identifier(id) = &self.current_token {
-                let id = id.clone();
-                self.next_token();
-                id
-            } else {
-                return Err("Expected CTE name".to_string());
-            };
+            let name = self.parse_identifier("CTE name")?;
 
             self.expect(Token::As)?;
+            let materialized = if self.consume_if(&Token::Materialized) {
+                Some(true)
+            } else if self.consume_if(&Token::Not) {
+                self.expect(Token::Materialized)?;
+                Some(false)
+            } else {
+                None
+            };
             self.expect(Token::LParen)?;
-            let select_stmt = self.parse_select_statement()?;
+            let query = self.parse_select_statement()?;
             self.expect(Token::RParen)?;
 
-            ctes.push((name, select_stmt));
+            ctes.push(Cte {
+                name,
+                materialized,
+                query,
+            });
 
             if !self.consume_if(&Token::Comma) {
                 break;
@@ -1105,23 +1611,33 @@ impl Parser {
     /// LIMIT n;
     /// ```
     fn parse_select_statement(&mut self) -> Result<SelectStatement, String> {
+        self.nested(Self::parse_select_body)
+    }
+
+    fn parse_select_body(&mut self) -> Result<SelectStatement, String> {
         self.expect(Token::Select)?;
 
         let distinct = self.consume_if(&Token::Distinct);
 
         // Parse SELECT list
-        let columns = self.parse_select_list()?;
+        let columns = self.recover(
+            Self::parse_select_list,
+            vec![SelectItem::ExprWithAlias {
+                expr: Expr::Error,
+                alias: None,
+            }],
+        )?;
 
         // Parse FROM clause
         let from = if self.consume_if(&Token::From) {
-            Some(self.parse_table_ref()?)
+            Some(self.recover(Self::parse_table_ref, TableReference::Error)?)
         } else {
             None
         };
 
         // Parse WHERE clause
         let where_clause = if self.consume_if(&Token::Where) {
-            Some(self.parse_expression()?)
+            Some(self.recover(Self::parse_expression, Expr::Error)?)
         } else {
             None
         };
@@ -1129,18 +1645,25 @@ impl Parser {
         // Parse GROUP BY clause
         let mut group_by = Vec::new();
         if self.consume_if(&Token::Group) {
-            self.expect(Token::By)?;
-            loop {
-                group_by.push(self.parse_expression()?);
-                if !self.consume_if(&Token::Comma) {
-                    break;
-                }
-            }
+            group_by = self.recover(
+                |p| {
+                    p.expect(Token::By)?;
+                    let mut group_by = Vec::new();
+                    loop {
+                        group_by.push(p.parse_expression()?);
+                        if !p.consume_if(&Token::Comma) {
+                            break;
+                        }
+                    }
+                    Ok(group_by)
+                },
+                vec![Expr::Error],
+            )?;
         }
 
         // Parse HAVING clause
         let having = if self.consume_if(&Token::Having) {
-            Some(self.parse_expression()?)
+            Some(self.recover(Self::parse_expression, Expr::Error)?)
         } else {
             None
         };
@@ -1148,31 +1671,45 @@ impl Parser {
         // Parse ORDER BY clause
         let mut order_by = Vec::new();
         if self.consume_if(&Token::Order) {
-            self.expect(Token::By)?;
-            loop {
-                let expr = self.parse_expression()?;
-                let asc = if self.consume_if(&Token::Desc) {
-                    false
-                } else {
-                    self.consume_if(&Token::Asc);
-                    true
-                };
-                order_by.push(OrderByExpr { expr, asc });
-                if !self.consume_if(&Token::Comma) {
-                    break;
-                }
-            }
+            order_by = self.recover(
+                |p| {
+                    p.expect(Token::By)?;
+                    let mut order_by = Vec::new();
+                    loop {
+                        let expr = p.parse_expression()?;
+                        let asc = if p.consume_if(&Token::Desc) {
+                            false
+                        } else {
+                            p.consume_if(&Token::Asc);
+                            true
+                        };
+                        order_by.push(OrderByExpr { expr, asc });
+                        if !p.consume_if(&Token::Comma) {
+                            break;
+                        }
+                    }
+                    Ok(order_by)
+                },
+                vec![OrderByExpr {
+                    expr: Expr::Error,
+                    asc: true,
+                }],
+            )?;
         }
 
         // Parse LIMIT clause
         let limit = if self.consume_if(&Token::Limit) {
-            if let Token::NumberLiteral(n) = self.current_token {
-                let limit_val = n as usize;
-                self.next_token();
-                Some(limit_val)
-            } else {
-                return Err("Expected number after LIMIT".to_string());
-            }
+            self.recover(
+                |p| {
+                    if let Token::NumberLiteral(n) = p.current_token {
+                        p.next_token();
+                        Ok(Some(n as usize))
+                    } else {
+                        Err("Expected number after LIMIT".to_string())
+                    }
+                },
+                None,
+            )?
         } else {
             None
         };
@@ -1193,33 +1730,25 @@ impl Parser {
         let mut items = Vec::new();
 
         loop {
-            if self.current_token == Token::Star {
+            if self.at(&Token::Star) {
                 self.next_token();
-                items.push(SelectItem::Star);
+                items.push(SelectItem::Star(self.parse_wildcard_options()?));
             } else {
                 let expr = self.parse_expression()?;
 
-                // Check for alias
-                let alias = if self.consume_if(&Token::As) {
-                    if let Token::Identifier(alias) = &self.current_token {
-                        let alias_str = alias.clone();
-                        self.next_token();
-                        Some(alias_str)
-                    } else {
-                        return Err("Expected identifier after AS".to_string());
-                    }
-                } else if let Token::Identifier(_) = &self.current_token {
-                    // Implicit alias without AS
-                    if let Token::Identifier(alias) = &self.current_token {
-                        let alias_str = alias.clone();
-                        self.next_token();
-                        Some(alias_str)
-                    } else {
-                        None
+                // table.* is parsed as an expression, but it is a wildcard in a select list.
+                if let Expr::QualifiedStar(table) = &expr {
+                    items.push(SelectItem::QualifiedWildcard(
+                        table.clone(),
+                        self.parse_wildcard_options()?,
+                    ));
+                    if !self.consume_if(&Token::Comma) {
+                        break;
                     }
-                } else {
-                    None
-                };
+                    continue;
+                }
+
+                let alias = self.parse_optional_alias()?;
 
                 items.push(SelectItem::ExprWithAlias { expr, alias });
             }
@@ -1232,64 +1761,120 @@ impl Parser {
         Ok(items)
     }
 
+    /// Parses the modifiers that may follow a wildcard in the select list.
+    /// Each modifier accepts either a parenthesized list or a single item.
+    ///
+    /// ```sql
+    /// * EXCLUDE (col1, col2)
+    /// * EXCEPT (col1)
+    /// * REPLACE (expr AS col1)
+    /// * RENAME (col1 AS new_name)
+    /// ```
+    fn parse_wildcard_options(&mut self) -> Result<WildcardOptions, String> {
+        let mut options = WildcardOptions::default();
+
+        loop {
+            if self.consume_identifier("exclude") {
+                options.exclude = self.parse_modifier_list(|p| p.parse_column_name())?;
+            } else if self.at(&Token::Except) && *self.peek_token() == Token::LParen {
+                // EXCEPT always takes a parenthesized list, which keeps it apart from the set operator.
+                self.next_token();
+                options.except = self.parse_modifier_list(|p| p.parse_column_name())?;
+            } else if self.consume_identifier("replace") {
+                options.replace = self.parse_modifier_list(|p| {
+                    let expr = p.parse_expression()?;
+                    p.expect(Token::As)?;
+                    let column = p.parse_column_name()?;
+                    Ok(ReplaceItem { expr, column })
+                })?;
+            } else if self.consume_if(&Token::Rename) {
+                options.rename = self.parse_modifier_list(|p| {
+                    let column = p.parse_column_name()?;
+                    p.expect(Token::As)?;
+                    let alias = p.parse_column_name()?;
+                    Ok(RenameItem { column, alias })
+                })?;
+            } else {
+                break;
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Parses either a single item or a parenthesized, comma separated list of items.
+    fn parse_modifier_list<T>(
+        &mut self,
+        parse_item: impl Fn(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        if !self.consume_if(&Token::LParen) {
+            return Ok(vec![parse_item(self)?]);
+        }
+
+        let mut items = Vec::new();
+        loop {
+            items.push(parse_item(self)?);
+            if !self.consume_if(&Token::Comma) {
+                break;
+            }
+        }
+        self.expect(Token::RParen)?;
+        Ok(items)
+    }
+
+    /// Parses a single column name.
+    fn parse_column_name(&mut self) -> Result<Ident, String> {
+        if let Some(name) = self.current_identifier() {
+            self.next_token();
+            Ok(name)
+        } else {
+            Err(format!(
+                "Expected column name, got {:?}",
+                self.current_token
+            ))
+        }
+    }
+
     /// Parses a table reference in the FROM clause, including joins and subqueries.
     /// ```sql
     /// FROM
     /// table1
     /// JOIN table2 ON table1.col1 = table2.col2
     /// [...]
-    /// JOIN tablen ON .[..]
+    /// JOIN tablen USING (col1, col2)
+    /// NATURAL JOIN tablem
     /// ```
+    ///
+    /// Comma separated table lists (`FROM a, b`) are parsed as CROSS JOINs.
     fn parse_table_ref(&mut self) -> Result<TableReference, String> {
-        let mut table_ref = match &self.current_token {
-            Token::Identifier(name) => {
-                let table_name = name.clone();
-                self.next_token();
-                let alias = if let Token::Identifier(alias_name) = &self.current_token {
-                    let alias_name = alias_name.clone();
-                    self.next_token();
-                    Some(alias_name)
-                } else {
-                    None
+        let mut table_ref = self.parse_table_factor()?;
+
+        // Parse optional JOINs
+        loop {
+            // Comma separated FROM lists are implicit cross joins.
+            if self.consume_if(&Token::Comma) {
+                let right = self.parse_table_factor()?;
+                table_ref = TableReference::Join {
+                    left: Box::new(table_ref),
+                    join_type: JoinType::Cross,
+                    right: Box::new(right),
+                    constraint: JoinConstraint::None,
                 };
-                TableReference::Table {
-                    name: table_name,
-                    alias,
-                }
+                continue;
             }
-            Token::LParen => {
-                // Subquery in FROM
-                self.next_token();
-                let subquery = self.parse_select_statement()?;
-                self.expect(Token::RParen)?;
 
-                if let Token::As = &self.current_token {
-                    self.next_token();
-                };
+            let natural = self.consume_if(&Token::Natural);
 
-                // Optional alias required for subquery
-                let alias = if let Token::Identifier(alias_name) = &self.current_token {
-                    let alias_name = alias_name.clone();
-                    self.next_token();
-                    alias_name
-                } else {
-                    return Err("Expected alias for subquery".to_string());
-                };
-                TableReference::Subquery {
-                    query: Box::new(subquery),
-                    alias,
-                }
+            self.expecting(&[
+                Token::Join,
+                Token::Inner,
+                Token::Left,
+                Token::Right,
+                Token::Full,
+            ]);
+            if !natural {
+                self.expecting(&[Token::Cross]);
             }
-            _ => {
-                return Err(format!(
-                    "Unexpected token in table reference: {:?}",
-                    self.current_token
-                ));
-            }
-        };
-
-        // Parse optional JOINs
-        loop {
             let join_type = match &self.current_token {
                 Token::Join => {
                     self.next_token();
@@ -1302,54 +1887,137 @@ impl Parser {
                 }
                 Token::Left => {
                     self.next_token();
+                    self.consume_if(&Token::Outer);
                     self.expect(Token::Join)?;
                     JoinType::Left
                 }
                 Token::Right => {
                     self.next_token();
+                    self.consume_if(&Token::Outer);
                     self.expect(Token::Join)?;
                     JoinType::Right
                 }
                 Token::Full => {
                     self.next_token();
+                    self.consume_if(&Token::Outer);
                     self.expect(Token::Join)?;
                     JoinType::Full
                 }
-                Token::Cross => {
+                Token::Cross if !natural => {
                     self.next_token();
                     self.expect(Token::Join)?;
                     JoinType::Cross
                 }
+                _ if natural => {
+                    return Err(format!(
+                        "Expected JOIN after NATURAL, got {:?}",
+                        self.current_token
+                    ));
+                }
                 _ => break, // No more joins
             };
 
             // Parse the right-hand side of the join
-            let right = self.parse_table_ref()?;
-
-            // Optional ON clause
-            let on = if self.consume_if(&Token::On) {
-                Some(self.parse_expression()?)
+            let right = self.parse_table_factor()?;
+
+            // NATURAL joins carry their own condition, so ON and USING are not allowed after them.
+            let constraint = if natural {
+                JoinConstraint::Natural
+            } else if self.consume_if(&Token::On) {
+                JoinConstraint::On(self.parse_expression()?)
+            } else if self.consume_if(&Token::Using) {
+                self.expect(Token::LParen)?;
+                let columns = self.parse_identifier_list()?;
+                self.expect(Token::RParen)?;
+                JoinConstraint::Using(columns)
             } else {
-                None
+                JoinConstraint::None
             };
 
             table_ref = TableReference::Join {
                 left: Box::new(table_ref),
                 join_type,
                 right: Box::new(right),
-                on,
+                constraint,
             };
         }
 
         Ok(table_ref)
     }
 
+    /// Parses a single relation in a FROM clause: a table with an optional alias,
+    /// or a (possibly LATERAL) subquery with a mandatory alias.
+    ///
+    /// ```sql
+    /// table [AS] alias
+    /// [LATERAL] (SELECT ...) [AS] alias
+    /// ```
+    fn parse_table_factor(&mut self) -> Result<TableReference, String> {
+        let is_name = self.current_identifier().is_some();
+        self.expecting(&[Token::LParen, Token::Lateral]);
+        match &self.current_token {
+            _ if is_name => {
+                let table_name = self.parse_object_name()?;
+                let alias = self.parse_optional_alias()?;
+                Ok(TableReference::Table {
+                    name: table_name,
+                    alias,
+                })
+            }
+            Token::LParen | Token::Lateral => {
+                // Subquery in FROM
+                let lateral = self.consume_if(&Token::Lateral);
+                self.expect(Token::LParen)?;
+                let subquery = self.parse_select_statement()?;
+                self.expect(Token::RParen)?;
+
+                // Alias is required for subqueries
+                let alias = match self.parse_optional_alias()? {
+                    Some(alias) => alias,
+                    None => return Err("Expected alias for subquery".to_string()),
+                };
+                Ok(TableReference::Subquery {
+                    query: Box::new(subquery),
+                    alias,
+                    lateral,
+                })
+            }
+            _ => Err(format!(
+                "Unexpected token in table reference: {:?}",
+                self.current_token
+            )),
+        }
+    }
+
+    /// Parses an optional alias for a relation or a select item, with or without the AS keyword.
+    ///
+    /// Any keyword can follow AS. Without AS, only identifiers and non-reserved keywords that
+    /// can't start the next clause are taken as aliases, so `FROM t LIMIT 1` keeps working.
+    fn parse_optional_alias(&mut self) -> Result<Option<Ident>, String> {
+        if self.consume_if(&Token::As) {
+            Ok(Some(self.parse_label("identifier after AS")?))
+        } else if !self.at(&Token::Limit)
+            && let Some(alias) = self.current_identifier()
+        {
+            if let Token::Identifier(Word {
+                quote_style: None, ..
+            }) = &self.current_token
+            {
+                self.implicit_alias = Some(self.current_span);
+            }
+            self.next_token();
+            Ok(Some(alias))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Parses data types.
     ///
     /// Supports both SQL standard data types and RQLite specific types (VARINT, BLOB and TEXT).
     fn parse_data_type(&mut self) -> Result<DataType, String> {
         let data_type = if let Token::Identifier(type_name) = &self.current_token {
-            let name = type_name.to_uppercase();
+            let name = type_name.value.to_uppercase();
             self.next_token();
 
             match name.as_str() {
@@ -1357,7 +2025,7 @@ impl Parser {
                 "BIGINT" => DataType::BigInt,
                 "SMALLINT" => DataType::SmallInt,
                 "DECIMAL" | "NUMERIC" => {
-                    if self.current_token == Token::LParen {
+                    if self.at(&Token::LParen) {
                         self.next_token();
                         let precision = if let Token::NumberLiteral(n) = self.current_token {
                             let p = n as u8;
@@ -1395,7 +2063,7 @@ impl Parser {
                 "REAL" | "FLOAT" => DataType::Real,
                 "DOUBLE" => DataType::Double,
                 "VARCHAR" => {
-                    let size = if self.current_token == Token::LParen {
+                    let size = if self.at(&Token::LParen) {
                         self.next_token();
                         let s = if let Token::NumberLiteral(n) = self.current_token {
                             let size_val = n as usize;
@@ -1412,7 +2080,7 @@ impl Parser {
                     DataType::Varchar(size)
                 }
                 "CHAR" => {
-                    let size = if self.current_token == Token::LParen {
+                    let size = if self.at(&Token::LParen) {
                         self.next_token();
                         let s = if let Token::NumberLiteral(n) = self.current_token {
                             let size_val = n as usize;
@@ -1450,13 +2118,7 @@ impl Parser {
     /// Parses a column definition statement.
     /// [COL_NAME] [DATA TYPE] [CONSTRAINTS]
     fn parse_column_def(&mut self) -> Result<ColumnDef, String> {
-        let name = if let Token::Identifier(col_name) = &self.current_token {
-            let name = col_name.clone();
-            self.next_token();
-            name
-        } else {
-            return Err("Expected column name".to_string());
-        };
+        let name = self.parse_identifier("column name")?;
 
         let data_type = self.parse_data_type()?;
         let constraints = self.parse_column_constraints()?;
@@ -1481,6 +2143,14 @@ impl Parser {
         let mut constraints = Vec::new();
 
         loop {
+            self.expecting(&[
+                Token::Not,
+                Token::Unique,
+                Token::Primary,
+                Token::References,
+                Token::Check,
+                Token::Default,
+            ]);
             match &self.current_token {
                 Token::Not => {
                     self.next_token();
@@ -1498,27 +2168,15 @@ impl Parser {
                 }
                 Token::References => {
                     self.next_token();
-                    let ref_table = if let Token::Identifier(name) = &self.current_token {
-                        let table = name.clone();
-                        self.next_token();
-                        table
-                    } else {
-                        return Err("Expected referenced table name".to_string());
-                    };
+                    let ref_table = self.parse_object_name()?;
 
-                    let ref_column = if self.current_token == Token::LParen {
+                    let ref_column = if self.at(&Token::LParen) {
                         self.next_token();
-                        let col = if let Token::Identifier(name) = &self.current_token {
-                            let column = name.clone();
-                            self.next_token();
-                            column
-                        } else {
-                            return Err("Expected referenced column name".to_string());
-                        };
+                        let col = self.parse_identifier("referenced column name")?;
                         self.expect(Token::RParen)?;
                         col
                     } else {
-                        "id".to_string() // Default to 'id' if not specified
+                        Ident::new("id") // Default to 'id' if not specified
                     };
 
                     constraints.push(ColumnConstraint::ForeignKey {
@@ -1553,12 +2211,11 @@ impl Parser {
     /// ```
     fn parse_table_constraint(&mut self) -> Result<TableConstraint, String> {
         // Skip optional CONSTRAINT name
-        if self.consume_if(&Token::Constraint)
-            && let Token::Identifier(_) = self.current_token
-        {
+        if self.consume_if(&Token::Constraint) && self.current_identifier().is_some() {
             self.next_token();
         }
 
+        self.expecting(&[Token::Primary, Token::Unique, Token::Foreign, Token::Check]);
         match &self.current_token {
             Token::Primary => {
                 self.next_token();
@@ -1583,13 +2240,7 @@ impl Parser {
                 self.expect(Token::RParen)?;
 
                 self.expect(Token::References)?;
-                let ref_table = if let Token::Identifier(name) = &self.current_token {
-                    let table = name.clone();
-                    self.next_token();
-                    table
-                } else {
-                    return Err("Expected referenced table name".to_string());
-                };
+                let ref_table = self.parse_object_name()?;
 
                 self.expect(Token::LParen)?;
                 let ref_columns = self.parse_identifier_list()?;
@@ -0,0 +1,198 @@
+//! Logical query plans, shown by the REPL and by `explain`.
+//!
+//! The plan of a query lists the relational operators that evaluate it, from the last one applied
+//! down to the scans of its tables:
+//!
+//! ```text
+//! Limit 10
+//! └── Sort: total DESC
+//!     └── Project: name, total
+//!         └── Filter: total > 100 (selectivity 33.3%)
+//!             └── JOIN
+//!                 ├── Scan users AS u
+//!                 └── Scan orders AS o
+//! ```
+//!
+//! Plans describe the evaluation order that the SQL semantics prescribe. No cost-based choices are
+//! made, so joins keep the order they are written in. Filters that compare columns with numbers
+//! show the fraction of the rows they are estimated to keep, see [range::selectivity].
+use crate::ast::*;
+use crate::functions;
+use crate::range;
+use crate::tree::{Tree, join_label};
+
+/// Builds the logical plan of a statement. Statements that do not read any rows, such as DDL, are
+/// shown as a single node with their SQL text.
+pub(crate) fn plan(stmt: &Statement) -> Tree {
+    match stmt {
+        Statement::With(with) => {
+            let mut tree = Tree::new(if with.recursive {
+                "With Recursive"
+            } else {
+                "With"
+            });
+            for cte in &with.ctes {
+                tree = tree.child(Tree::with_children(
+                    format!("CTE {}", cte.name),
+                    vec![select_plan(&cte.query)],
+                ));
+            }
+            tree.child(select_plan(&with.body))
+        }
+        Statement::Select(select) => select_plan(select),
+        Statement::Insert(insert) => {
+            let source = match &insert.values {
+                Values::Values(rows) => Tree::new(format!("Values: {} rows", rows.len())),
+                Values::Query(query) => select_plan(query),
+            };
+            Tree::with_children(format!("Insert into {}", insert.table), vec![source])
+        }
+        Statement::Update(update) => {
+            let assignments = update
+                .set_clauses
+                .iter()
+                .map(|clause| format!("{} = {}", clause.column, clause.value))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Tree::with_children(
+                format!("Update {}: {assignments}", update.table),
+                vec![filter(&update.where_clause, scan(&update.table, None))],
+            )
+        }
+        Statement::Delete(delete) => Tree::with_children(
+            format!("Delete from {}", delete.table),
+            vec![filter(&delete.where_clause, scan(&delete.table, None))],
+        ),
+        stmt => Tree::new(stmt.to_string()),
+    }
+}
+
+/// Builds the plan of a query: its FROM clause, then WHERE, GROUP BY, HAVING, the select list,
+/// DISTINCT, ORDER BY and LIMIT, in that order.
+fn select_plan(select: &SelectStatement) -> Tree {
+    let mut plan = match &select.from {
+        Some(from) => table_plan(from),
+        None => Tree::new("Single row"),
+    };
+    plan = filter(&select.where_clause, plan);
+
+    let aggregates = select.columns.iter().any(|item| match item {
+        SelectItem::ExprWithAlias { expr, .. } => has_aggregate(expr),
+        _ => false,
+    }) || select.having.as_ref().is_some_and(has_aggregate);
+    if !select.group_by.is_empty() || aggregates {
+        let label = if select.group_by.is_empty() {
+            "Aggregate".to_string()
+        } else {
+            format!("Aggregate: group by {}", list(&select.group_by))
+        };
+        plan = Tree::with_children(label, vec![plan]);
+    }
+    plan = filter(&select.having, plan);
+
+    plan = Tree::with_children(format!("Project: {}", list(&select.columns)), vec![plan]);
+    if select.distinct {
+        plan = Tree::with_children("Distinct", vec![plan]);
+    }
+    if !select.order_by.is_empty() {
+        plan = Tree::with_children(format!("Sort: {}", list(&select.order_by)), vec![plan]);
+    }
+    if let Some(limit) = select.limit {
+        plan = Tree::with_children(format!("Limit {limit}"), vec![plan]);
+    }
+    plan
+}
+
+fn table_plan(table: &TableReference) -> Tree {
+    match table {
+        TableReference::Table { name, alias } => scan(name, alias.as_ref()),
+        TableReference::Subquery {
+            query,
+            alias,
+            lateral,
+        } => {
+            let lateral = if *lateral { "Lateral " } else { "" };
+            Tree::with_children(
+                format!("{lateral}Subquery AS {alias}"),
+                vec![select_plan(query)],
+            )
+        }
+        TableReference::Join { .. } => {
+            let (first, joins) = table.join_chain();
+            let mut plan = table_plan(first);
+            for (join_type, right, constraint) in joins {
+                let mut label = join_label(join_type, constraint);
+                match constraint {
+                    JoinConstraint::On(expr) => label.push_str(&format!(" ON {expr}")),
+                    JoinConstraint::Using(columns) => {
+                        label.push_str(&format!(" USING ({})", list(columns)))
+                    }
+                    JoinConstraint::Natural | JoinConstraint::None => {}
+                }
+                plan = Tree::with_children(label, vec![plan, table_plan(right)]);
+            }
+            plan
+        }
+        TableReference::Error => Tree::new("<error>"),
+    }
+}
+
+fn scan(name: &ObjectName, alias: Option<&Ident>) -> Tree {
+    match alias {
+        Some(alias) => Tree::new(format!("Scan {name} AS {alias}")),
+        None => Tree::new(format!("Scan {name}")),
+    }
+}
+
+/// Puts a filter on top of a plan, if there is a condition.
+fn filter(condition: &Option<Expr>, plan: Tree) -> Tree {
+    match condition {
+        Some(condition) => {
+            let label = match range::selectivity(condition) {
+                Some(selectivity) => format!(
+                    "Filter: {condition} (selectivity {}%)",
+                    (selectivity * 1000.0).round() / 10.0
+                ),
+                None => format!("Filter: {condition}"),
+            };
+            Tree::with_children(label, vec![plan])
+        }
+        None => plan,
+    }
+}
+
+/// Returns true if the expression calls an aggregate function outside of a subquery.
+pub(crate) fn has_aggregate(expr: &Expr) -> bool {
+    match expr {
+        Expr::FunctionCall { name, args, .. } => {
+            functions::registry().is_aggregate(name) || args.iter().any(has_aggregate)
+        }
+        Expr::BinaryOp { left, right, .. } => has_aggregate(left) || has_aggregate(right),
+        Expr::UnaryOp { expr, .. } => has_aggregate(expr),
+        Expr::Case {
+            operand,
+            when_clauses,
+            else_clause,
+        } => {
+            operand.as_deref().is_some_and(has_aggregate)
+                || when_clauses
+                    .iter()
+                    .any(|c| has_aggregate(&c.condition) || has_aggregate(&c.result))
+                || else_clause.as_deref().is_some_and(has_aggregate)
+        }
+        Expr::List(items) => items.iter().any(has_aggregate),
+        Expr::Between {
+            expr, low, high, ..
+        } => has_aggregate(expr) || has_aggregate(low) || has_aggregate(high),
+        Expr::Escape { pattern, escape } => has_aggregate(pattern) || has_aggregate(escape),
+        _ => false,
+    }
+}
+
+fn list<T: ToString>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
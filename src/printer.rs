@@ -0,0 +1,665 @@
+//! Prints the AST back as SQL text.
+//!
+//! The output is meant to be parsed again into the same tree, so parentheses are added wherever
+//! the parser would otherwise group the operands of an expression differently.
+use crate::ast::*;
+use std::fmt::{self, Display, Formatter, Write};
+
+/// Binding power of an expression when it appears as an operand, mirroring the binding powers
+/// of the parser. Higher values bind tighter.
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::BinaryOp { op, .. } => match op {
+            BinaryOperator::Or => 1,
+            BinaryOperator::And => 3,
+            BinaryOperator::Eq
+            | BinaryOperator::Neq
+            | BinaryOperator::Lt
+            | BinaryOperator::Gt
+            | BinaryOperator::Le
+            | BinaryOperator::Ge
+            | BinaryOperator::Like
+            | BinaryOperator::NotLike
+            | BinaryOperator::ILike
+            | BinaryOperator::NotILike
+            | BinaryOperator::SimilarTo
+            | BinaryOperator::NotSimilarTo
+            | BinaryOperator::RegexMatch
+            | BinaryOperator::RegexIMatch
+            | BinaryOperator::In
+            | BinaryOperator::NotIn
+            | BinaryOperator::Is
+            | BinaryOperator::IsNot => 5,
+            BinaryOperator::Plus | BinaryOperator::Minus | BinaryOperator::Concat => 7,
+            BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulo => 9,
+        },
+        Expr::Between { .. } => 5,
+        // Prefix operators take everything to their right that binds tighter than them,
+        // so they are always parenthesized when used as operands.
+        Expr::UnaryOp { .. } => 0,
+        _ => u8::MAX,
+    }
+}
+
+/// Writes an operand, wrapped in parentheses if it binds looser than `min`.
+fn write_operand(f: &mut Formatter<'_>, expr: &Expr, min: u8) -> fmt::Result {
+    if precedence(expr) < min {
+        write!(f, "({expr})")
+    } else {
+        write!(f, "{expr}")
+    }
+}
+
+/// Writes the items separated by commas.
+fn write_list<T: Display>(f: &mut Formatter<'_>, items: &[T]) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{item}")?;
+    }
+    Ok(())
+}
+
+/// Writes a quoted string, doubling the quotes inside it.
+fn write_quoted(f: &mut Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "'{}'", value.replace('\'', "''"))
+}
+
+impl Display for BinaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            BinaryOperator::Plus => "+",
+            BinaryOperator::Minus => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Modulo => "%",
+            BinaryOperator::Eq => "=",
+            BinaryOperator::Neq => "<>",
+            BinaryOperator::Lt => "<",
+            BinaryOperator::Gt => ">",
+            BinaryOperator::Le => "<=",
+            BinaryOperator::Ge => ">=",
+            BinaryOperator::And => "AND",
+            BinaryOperator::Or => "OR",
+            BinaryOperator::Like => "LIKE",
+            BinaryOperator::NotLike => "NOT LIKE",
+            BinaryOperator::ILike => "ILIKE",
+            BinaryOperator::NotILike => "NOT ILIKE",
+            BinaryOperator::SimilarTo => "SIMILAR TO",
+            BinaryOperator::NotSimilarTo => "NOT SIMILAR TO",
+            BinaryOperator::RegexMatch => "~",
+            BinaryOperator::RegexIMatch => "~*",
+            BinaryOperator::Concat => "||",
+            BinaryOperator::In => "IN",
+            BinaryOperator::NotIn => "NOT IN",
+            BinaryOperator::Is => "IS",
+            BinaryOperator::IsNot => "IS NOT",
+        };
+        write!(f, "{op}")
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{n}"),
+            Expr::String(s) => write_quoted(f, s),
+            Expr::EscapeString(s) => {
+                write!(f, "E'")?;
+                for ch in s.chars() {
+                    match ch {
+                        '\'' => write!(f, "\\'")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\r' => write!(f, "\\r")?,
+                        '\t' => write!(f, "\\t")?,
+                        '\u{8}' => write!(f, "\\b")?,
+                        '\u{c}' => write!(f, "\\f")?,
+                        ch if ch.is_control() => write!(f, "\\u{:04X}", ch as u32)?,
+                        ch => f.write_char(ch)?,
+                    }
+                }
+                write!(f, "'")
+            }
+            Expr::NationalString(s) => {
+                write!(f, "N")?;
+                write_quoted(f, s)
+            }
+            Expr::HexString(bytes) => {
+                write!(f, "X'")?;
+                for byte in bytes {
+                    write!(f, "{byte:02X}")?;
+                }
+                write!(f, "'")
+            }
+            Expr::BitString(bits) => write!(f, "B'{bits}'"),
+            Expr::UnicodeString(s) => {
+                write!(f, "U&'")?;
+                for ch in s.chars() {
+                    match ch {
+                        '\'' => write!(f, "''")?,
+                        '\\' => write!(f, "\\\\")?,
+                        ch if ch.is_ascii() && !ch.is_ascii_control() => f.write_char(ch)?,
+                        ch if (ch as u32) <= 0xFFFF => write!(f, "\\{:04X}", ch as u32)?,
+                        ch => write!(f, "\\+{:06X}", ch as u32)?,
+                    }
+                }
+                write!(f, "'")
+            }
+            Expr::DollarString { tag, value } => write!(f, "${tag}${value}${tag}$"),
+            Expr::Boolean(true) => write!(f, "TRUE"),
+            Expr::Boolean(false) => write!(f, "FALSE"),
+            Expr::Null => write!(f, "NULL"),
+            Expr::Identifier(ident) => write!(f, "{ident}"),
+            Expr::QualifiedIdentifier { table, column } => write!(f, "{table}.{column}"),
+            Expr::Star => write!(f, "*"),
+            Expr::QualifiedStar(table) => write!(f, "{table}.*"),
+            Expr::BinaryOp { .. } => {
+                // Left operands of the same precedence need no parentheses, so a chain like
+                // `a OR b OR c` is walked down its left side with a loop instead of recursion.
+                let min = precedence(self);
+                let mut rights = Vec::new();
+                let mut leftmost = self;
+                while let Expr::BinaryOp { left, op, right } = leftmost
+                    && precedence(leftmost) == min
+                {
+                    rights.push((op, right));
+                    leftmost = left;
+                }
+
+                write_operand(f, leftmost, min)?;
+                for (op, right) in rights.into_iter().rev() {
+                    write!(f, " {op} ")?;
+                    // Operators are left associative, so a right operand of the same precedence
+                    // must be parenthesized.
+                    write_operand(f, right, min + 1)?;
+                }
+                Ok(())
+            }
+            Expr::UnaryOp { op, expr } => {
+                match op {
+                    UnaryOperator::Plus => write!(f, "+")?,
+                    UnaryOperator::Minus => write!(f, "-")?,
+                    UnaryOperator::Not => write!(f, "NOT ")?,
+                }
+                write_operand(f, expr, u8::MAX)
+            }
+            Expr::FunctionCall {
+                name,
+                args,
+                distinct,
+            } => {
+                write!(f, "{name}(")?;
+                if *distinct {
+                    write!(f, "DISTINCT ")?;
+                }
+                write_list(f, args)?;
+                write!(f, ")")
+            }
+            Expr::Case {
+                operand,
+                when_clauses,
+                else_clause,
+            } => {
+                write!(f, "CASE")?;
+                if let Some(operand) = operand {
+                    write!(f, " {operand}")?;
+                }
+                for clause in when_clauses {
+                    write!(f, " WHEN {} THEN {}", clause.condition, clause.result)?;
+                }
+                if let Some(else_clause) = else_clause {
+                    write!(f, " ELSE {else_clause}")?;
+                }
+                write!(f, " END")
+            }
+            Expr::Subquery(query) => write!(f, "({query})"),
+            Expr::List(items) => {
+                write!(f, "(")?;
+                write_list(f, items)?;
+                write!(f, ")")
+            }
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                write_operand(f, expr, 5)?;
+                write!(f, " {}BETWEEN ", if *negated { "NOT " } else { "" })?;
+                write_operand(f, low, 6)?;
+                write!(f, " AND ")?;
+                write_operand(f, high, 6)
+            }
+            Expr::Exists(query) => write!(f, "EXISTS ({query})"),
+            Expr::Escape { pattern, escape } => {
+                write_operand(f, pattern, 6)?;
+                write!(f, " ESCAPE ")?;
+                write_operand(f, escape, 6)
+            }
+            // Trees with errors cannot be printed back as valid SQL, so the placeholder stands out.
+            Expr::Error => write!(f, "<error>"),
+        }
+    }
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::With(s) => write!(f, "{s}"),
+            Statement::Select(s) => write!(f, "{s}"),
+            Statement::Insert(s) => write!(f, "{s}"),
+            Statement::Update(s) => write!(f, "{s}"),
+            Statement::Delete(s) => write!(f, "{s}"),
+            Statement::CreateTable(s) => write!(f, "{s}"),
+            Statement::AlterTable(s) => write!(f, "{s}"),
+            Statement::DropTable(s) => write!(f, "{s}"),
+            Statement::CreateIndex(s) => write!(f, "{s}"),
+            Statement::Transaction(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl Display for WithStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "WITH ")?;
+        if self.recursive {
+            write!(f, "RECURSIVE ")?;
+        }
+        for (i, cte) in self.ctes.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{cte}")?;
+        }
+        write!(f, " {}", self.body)
+    }
+}
+
+impl Display for Cte {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} AS ", self.name)?;
+        match self.materialized {
+            Some(true) => write!(f, "MATERIALIZED ")?,
+            Some(false) => write!(f, "NOT MATERIALIZED ")?,
+            None => {}
+        }
+        write!(f, "({})", self.query)
+    }
+}
+
+impl Display for SelectStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "SELECT ")?;
+        if self.distinct {
+            write!(f, "DISTINCT ")?;
+        }
+        write_list(f, &self.columns)?;
+        if let Some(from) = &self.from {
+            write!(f, " FROM {from}")?;
+        }
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " WHERE {where_clause}")?;
+        }
+        if !self.group_by.is_empty() {
+            write!(f, " GROUP BY ")?;
+            write_list(f, &self.group_by)?;
+        }
+        if let Some(having) = &self.having {
+            write!(f, " HAVING {having}")?;
+        }
+        if !self.order_by.is_empty() {
+            write!(f, " ORDER BY ")?;
+            write_list(f, &self.order_by)?;
+        }
+        if let Some(limit) = self.limit {
+            write!(f, " LIMIT {limit}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for SelectItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectItem::Star(options) => write!(f, "*{options}"),
+            SelectItem::QualifiedWildcard(table, options) => write!(f, "{table}.*{options}"),
+            SelectItem::ExprWithAlias { expr, alias } => {
+                write!(f, "{expr}")?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {alias}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for WildcardOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.exclude.is_empty() {
+            write!(f, " EXCLUDE (")?;
+            write_list(f, &self.exclude)?;
+            write!(f, ")")?;
+        }
+        if !self.except.is_empty() {
+            write!(f, " EXCEPT (")?;
+            write_list(f, &self.except)?;
+            write!(f, ")")?;
+        }
+        if !self.replace.is_empty() {
+            write!(f, " REPLACE (")?;
+            for (i, item) in self.replace.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{} AS {}", item.expr, item.column)?;
+            }
+            write!(f, ")")?;
+        }
+        if !self.rename.is_empty() {
+            write!(f, " RENAME (")?;
+            for (i, item) in self.rename.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{} AS {}", item.column, item.alias)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for TableReference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TableReference::Table { name, alias } => {
+                write!(f, "{name}")?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {alias}")?;
+                }
+                Ok(())
+            }
+            TableReference::Subquery {
+                query,
+                alias,
+                lateral,
+            } => {
+                if *lateral {
+                    write!(f, "LATERAL ")?;
+                }
+                write!(f, "({query}) AS {alias}")
+            }
+            TableReference::Join { .. } => {
+                // Chains of joins are written with a loop, as they can be long.
+                let (first, joins) = self.join_chain();
+                write!(f, "{first}")?;
+                for (join_type, right, constraint) in joins {
+                    write!(f, " ")?;
+                    if let JoinConstraint::Natural = constraint {
+                        write!(f, "NATURAL ")?;
+                    }
+                    let join = match join_type {
+                        JoinType::Inner => "JOIN",
+                        JoinType::Left => "LEFT JOIN",
+                        JoinType::Right => "RIGHT JOIN",
+                        JoinType::Full => "FULL JOIN",
+                        JoinType::Cross => "CROSS JOIN",
+                    };
+                    write!(f, "{join} {right}")?;
+                    match constraint {
+                        JoinConstraint::On(expr) => write!(f, " ON {expr}")?,
+                        JoinConstraint::Using(columns) => {
+                            write!(f, " USING (")?;
+                            write_list(f, columns)?;
+                            write!(f, ")")?;
+                        }
+                        JoinConstraint::Natural | JoinConstraint::None => {}
+                    }
+                }
+                Ok(())
+            }
+            TableReference::Error => write!(f, "<error>"),
+        }
+    }
+}
+
+impl Display for OrderByExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)?;
+        if !self.asc {
+            write!(f, " DESC")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for InsertStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "INSERT INTO {}", self.table)?;
+        if let Some(columns) = &self.columns {
+            write!(f, " (")?;
+            write_list(f, columns)?;
+            write!(f, ")")?;
+        }
+        match &self.values {
+            Values::Values(rows) => {
+                write!(f, " VALUES ")?;
+                for (i, row) in rows.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "(")?;
+                    write_list(f, row)?;
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Values::Query(query) => write!(f, " {query}"),
+        }
+    }
+}
+
+impl Display for UpdateStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "UPDATE {} SET ", self.table)?;
+        for (i, clause) in self.set_clauses.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} = {}", clause.column, clause.value)?;
+        }
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " WHERE {where_clause}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for DeleteStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "DELETE FROM {}", self.table)?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " WHERE {where_clause}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CreateTableStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "CREATE TABLE {} (", self.table)?;
+        write_list(f, &self.columns)?;
+        if !self.columns.is_empty() && !self.constraints.is_empty() {
+            write!(f, ", ")?;
+        }
+        write_list(f, &self.constraints)?;
+        write!(f, ")")
+    }
+}
+
+impl Display for ColumnDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.data_type)?;
+        for constraint in &self.constraints {
+            write!(f, " {constraint}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for DataType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let with_precision = |f: &mut Formatter<'_>, name, p: &Option<u8>, s: &Option<u8>| {
+            match (p, s) {
+                (Some(p), Some(s)) => write!(f, "{name}({p}, {s})"),
+                (Some(p), None) => write!(f, "{name}({p})"),
+                _ => write!(f, "{name}"),
+            }
+        };
+        let with_size = |f: &mut Formatter<'_>, name, size: &Option<usize>| match size {
+            Some(size) => write!(f, "{name}({size})"),
+            None => write!(f, "{name}"),
+        };
+        match self {
+            DataType::Integer => write!(f, "INTEGER"),
+            DataType::BigInt => write!(f, "BIGINT"),
+            DataType::SmallInt => write!(f, "SMALLINT"),
+            DataType::VarInt => write!(f, "VARINT"),
+            DataType::Decimal(p, s) => with_precision(f, "DECIMAL", p, s),
+            DataType::Numeric(p, s) => with_precision(f, "NUMERIC", p, s),
+            DataType::Real => write!(f, "REAL"),
+            DataType::Double => write!(f, "DOUBLE"),
+            DataType::Varchar(size) => with_size(f, "VARCHAR", size),
+            DataType::Char(size) => with_size(f, "CHAR", size),
+            DataType::Text => write!(f, "TEXT"),
+            DataType::Date => write!(f, "DATE"),
+            DataType::Time => write!(f, "TIME"),
+            DataType::Timestamp => write!(f, "TIMESTAMP"),
+            DataType::Boolean => write!(f, "BOOLEAN"),
+            DataType::Json => write!(f, "JSON"),
+            DataType::Jsonb => write!(f, "JSONB"),
+            DataType::Uuid => write!(f, "UUID"),
+            DataType::Blob => write!(f, "BLOB"),
+            DataType::Custom(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl Display for ColumnConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnConstraint::NotNull => write!(f, "NOT NULL"),
+            ColumnConstraint::Unique => write!(f, "UNIQUE"),
+            ColumnConstraint::PrimaryKey => write!(f, "PRIMARY KEY"),
+            ColumnConstraint::ForeignKey { table, column } => {
+                write!(f, "REFERENCES {table}({column})")
+            }
+            ColumnConstraint::Check(expr) => write!(f, "CHECK ({expr})"),
+            ColumnConstraint::Default(expr) => write!(f, "DEFAULT {expr}"),
+        }
+    }
+}
+
+impl Display for TableConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TableConstraint::PrimaryKey(columns) => {
+                write!(f, "PRIMARY KEY (")?;
+                write_list(f, columns)?;
+                write!(f, ")")
+            }
+            TableConstraint::Unique(columns) => {
+                write!(f, "UNIQUE (")?;
+                write_list(f, columns)?;
+                write!(f, ")")
+            }
+            TableConstraint::ForeignKey {
+                columns,
+                ref_table,
+                ref_columns,
+            } => {
+                write!(f, "FOREIGN KEY (")?;
+                write_list(f, columns)?;
+                write!(f, ") REFERENCES {ref_table} (")?;
+                write_list(f, ref_columns)?;
+                write!(f, ")")
+            }
+            TableConstraint::Check(expr) => write!(f, "CHECK ({expr})"),
+        }
+    }
+}
+
+impl Display for AlterTableStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ALTER TABLE {} ", self.table)?;
+        match &self.action {
+            AlterAction::AddColumn(column) => write!(f, "ADD COLUMN {column}"),
+            AlterAction::DropColumn(name) => write!(f, "DROP COLUMN {name}"),
+            AlterAction::AddConstraint(constraint) => write!(f, "ADD CONSTRAINT {constraint}"),
+            AlterAction::DropConstraint(name) => write!(f, "DROP CONSTRAINT {name}"),
+            AlterAction::AlterColumn(AlterColumnStatement { name, action }) => {
+                write!(f, "ALTER COLUMN {name} ")?;
+                match action {
+                    AlterColumnAction::SetDataType(data_type) => write!(f, "{data_type}"),
+                    AlterColumnAction::SetDefault(expr) => write!(f, "SET DEFAULT {expr}"),
+                    AlterColumnAction::DropDefault => write!(f, "DROP DEFAULT"),
+                    AlterColumnAction::SetNotNull => write!(f, "SET NOT NULL"),
+                    AlterColumnAction::DropNotNull => write!(f, "DROP NOT NULL"),
+                }
+            }
+        }
+    }
+}
+
+impl Display for DropTableStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "DROP TABLE ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        write!(f, "{}", self.table)?;
+        if self.cascade {
+            write!(f, " CASCADE")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CreateIndexStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "CREATE ")?;
+        if self.unique {
+            write!(f, "UNIQUE ")?;
+        }
+        write!(f, "INDEX ")?;
+        if self.if_not_exists {
+            write!(f, "IF NOT EXISTS ")?;
+        }
+        write!(f, "{} ON {} (", self.name, self.table)?;
+        for (i, column) in self.columns.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", column.name)?;
+            match column.order {
+                Some(OrderDirection::Asc) => write!(f, " ASC")?,
+                Some(OrderDirection::Desc) => write!(f, " DESC")?,
+                None => {}
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+impl Display for TransactionStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionStatement::Begin => write!(f, "BEGIN"),
+            TransactionStatement::Commit => write!(f, "COMMIT"),
+            TransactionStatement::Rollback => write!(f, "ROLLBACK"),
+        }
+    }
+}
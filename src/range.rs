@@ -0,0 +1,639 @@
+//! Range analysis of the comparisons between a column and constants, used by simplification.
+//!
+//! The predicates of an AND on the same column are intersected, and those of an OR are united:
+//!
+//! ```sql
+//! x > 5 AND x > 3              -- x > 5
+//! x >= 5 AND x <= 5            -- x = 5
+//! x IN (1, 2, 3) AND x > 1     -- x IN (2, 3)
+//! x = 1 OR x = 2               -- x IN (1, 2)
+//! x < 5 OR x BETWEEN 3 AND 8   -- x <= 8
+//! ```
+//!
+//! Only numbers are ordered, as the order of strings and whether two of them are equal depend on
+//! the collation of the column.
+//!
+//! A condition that no value satisfies, such as `x > 5 AND x < 2`, is still NULL when the column
+//! is NULL. It is only replaced with FALSE where it is used as a filter, in which NULL rejects the
+//! row as FALSE does. A condition that every value satisfies becomes `x IS NOT NULL` in a filter,
+//! or TRUE anywhere if it also accepts NULL, as in `x > 5 OR x <= 5 OR x IS NULL`.
+use crate::ast::{BinaryOperator, Expr};
+use crate::boolean;
+use crate::eval::Value;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+/// Fraction of the rows estimated to hold a given value of a column, without statistics on it.
+const VALUE_SELECTIVITY: f64 = 0.1;
+
+/// Fraction of the rows estimated to be on the allowed side of a bound.
+const BOUND_SELECTIVITY: f64 = 1.0 / 3.0;
+
+/// Lower or upper bound of a range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Bound {
+    pub(crate) value: f64,
+    pub(crate) inclusive: bool,
+}
+
+/// Values of a column allowed by the predicates on it: those between the bounds, or only the
+/// listed ones, minus the excluded ones. NULL is never allowed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Range {
+    /// Lower bound, or None if the values are not bounded below.
+    pub(crate) low: Option<Bound>,
+    /// Upper bound, or None if the values are not bounded above.
+    pub(crate) high: Option<Bound>,
+    /// The only values allowed, from `=` and IN. The bounds are then empty.
+    pub(crate) values: Option<Vec<f64>>,
+    /// Values excluded by `<>`, within the bounds.
+    pub(crate) excluded: Vec<f64>,
+}
+
+impl Range {
+    fn new(low: Option<Bound>, high: Option<Bound>) -> Self {
+        Range {
+            low,
+            high,
+            ..Range::default()
+        }
+    }
+
+    fn values(values: Vec<f64>) -> Self {
+        Range {
+            values: Some(values),
+            ..Range::default()
+        }
+    }
+
+    fn within_bounds(&self, value: f64) -> bool {
+        let above = self
+            .low
+            .is_none_or(|low| value > low.value || (value == low.value && low.inclusive));
+        let below = self
+            .high
+            .is_none_or(|high| value < high.value || (value == high.value && high.inclusive));
+        above && below
+    }
+
+    /// Returns true if no value is in the range.
+    pub(crate) fn is_empty(&self) -> bool {
+        match (&self.values, self.low, self.high) {
+            (Some(values), _, _) => values.is_empty(),
+            (None, Some(low), Some(high)) => {
+                low.value > high.value
+                    || (low.value == high.value && !(low.inclusive && high.inclusive))
+            }
+            _ => false,
+        }
+    }
+
+    /// Fraction of the rows whose value is in the range, estimated without statistics: a tenth of
+    /// the rows per allowed value, a third per bound, and a tenth less per excluded value.
+    pub(crate) fn selectivity(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        if let Some(values) = &self.values {
+            return (values.len() as f64 * VALUE_SELECTIVITY).min(1.0);
+        }
+        let bounds = self.low.iter().chain(&self.high).count();
+        BOUND_SELECTIVITY.powi(bounds as i32)
+            * (1.0 - VALUE_SELECTIVITY).powi(self.excluded.len() as i32)
+    }
+
+    /// Values in both ranges.
+    fn intersect(self, other: Range) -> Range {
+        let values = match (self.values, other.values) {
+            (Some(a), Some(b)) => {
+                let b: HashSet<u64> = b.into_iter().map(key).collect();
+                Some(a.into_iter().filter(|&v| b.contains(&key(v))).collect())
+            }
+            (a, b) => a.or(b),
+        };
+        let range = Range {
+            low: tighter(self.low, other.low, Ordering::Greater),
+            high: tighter(self.high, other.high, Ordering::Less),
+            values,
+            excluded: [self.excluded, other.excluded].concat(),
+        };
+        range.normalized()
+    }
+
+    /// Resolves the listed values against the bounds and the excluded values, and turns a range
+    /// of a single value into a list, so equal ranges have the same form.
+    fn normalized(mut self) -> Range {
+        if let (None, Some(low), Some(high)) = (&self.values, self.low, self.high)
+            && low.value == high.value
+            && low.inclusive
+            && high.inclusive
+        {
+            self.values = Some(vec![low.value]);
+        }
+        let excluded = distinct(mem::take(&mut self.excluded), |_| true);
+        match self.values.take() {
+            Some(values) => {
+                let excluded: HashSet<u64> = excluded.iter().map(|&v| key(v)).collect();
+                Range::values(distinct(values, |value| {
+                    self.within_bounds(value) && !excluded.contains(&key(value))
+                }))
+            }
+            None => {
+                self.excluded = excluded
+                    .into_iter()
+                    .filter(|&value| self.within_bounds(value))
+                    .collect();
+                self
+            }
+        }
+    }
+
+    /// The range as a condition on the column: `x >= 1 AND x < 5 AND x <> 3`, or `x IN (1, 2)`.
+    fn to_expr(&self, column: &Expr) -> Expr {
+        let compare = |op, value: f64| Expr::BinaryOp {
+            left: Box::new(column.clone()),
+            op,
+            right: Box::new(Expr::Number(value)),
+        };
+        if let Some(values) = &self.values {
+            return match values.as_slice() {
+                [value] => compare(BinaryOperator::Eq, *value),
+                values => Expr::BinaryOp {
+                    left: Box::new(column.clone()),
+                    op: BinaryOperator::In,
+                    right: Box::new(Expr::List(
+                        values.iter().map(|&v| Expr::Number(v)).collect(),
+                    )),
+                },
+            };
+        }
+        let mut conjuncts = Vec::new();
+        if let Some(low) = self.low {
+            let op = if low.inclusive {
+                BinaryOperator::Ge
+            } else {
+                BinaryOperator::Gt
+            };
+            conjuncts.push(compare(op, low.value));
+        }
+        if let Some(high) = self.high {
+            let op = if high.inclusive {
+                BinaryOperator::Le
+            } else {
+                BinaryOperator::Lt
+            };
+            conjuncts.push(compare(op, high.value));
+        }
+        for &value in &self.excluded {
+            conjuncts.push(compare(BinaryOperator::Neq, value));
+        }
+        boolean::chain(conjuncts, &BinaryOperator::And)
+    }
+}
+
+/// Hashable form of a number, equal for equal numbers.
+fn key(value: f64) -> u64 {
+    // Adding zero turns -0 into 0.
+    (value + 0.0).to_bits()
+}
+
+/// The values that `keep` accepts, without repetitions, in their first order.
+fn distinct(values: Vec<f64>, keep: impl Fn(f64) -> bool) -> Vec<f64> {
+    let mut seen = HashSet::new();
+    let kept = values
+        .into_iter()
+        .filter(|&value| keep(value) && seen.insert(key(value)));
+    kept.collect()
+}
+
+/// Of two bounds, the one further in the given direction from the other: the greater lower bound
+/// or the smaller upper bound. At the same value, an exclusive bound is tighter.
+fn tighter(a: Option<Bound>, b: Option<Bound>, direction: Ordering) -> Option<Bound> {
+    match (a, b) {
+        (Some(a), Some(b)) => match a.value.partial_cmp(&b.value) {
+            Some(Ordering::Equal) => Some(Bound {
+                value: a.value,
+                inclusive: a.inclusive && b.inclusive,
+            }),
+            Some(order) if order == direction => Some(a),
+            _ => Some(b),
+        },
+        (a, b) => a.or(b),
+    }
+}
+
+fn number(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Number(n) => Some(*n),
+        _ => None,
+    }
+}
+
+/// The column and range of a comparison between a column and a number, or of a BETWEEN or IN on a
+/// column with numbers only.
+fn predicate(expr: &Expr) -> Option<(&Expr, Range)> {
+    let bound = |value, inclusive| Some(Bound { value, inclusive });
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::In,
+            right,
+        } if left.is_column() => {
+            let Expr::List(items) = &**right else {
+                return None;
+            };
+            let values = items.iter().map(number).collect::<Option<Vec<_>>>()?;
+            Some((left, Range::values(values).normalized()))
+        }
+        Expr::BinaryOp { left, op, right } => {
+            // 5 < x is x > 5.
+            let (column, op, value) = match (number(left), number(right)) {
+                (None, Some(value)) if left.is_column() => (&**left, op.clone(), value),
+                (Some(value), None) if right.is_column() => (&**right, flipped(op)?, value),
+                _ => return None,
+            };
+            let range = match op {
+                BinaryOperator::Eq => Range::values(vec![value]),
+                BinaryOperator::Neq => Range {
+                    excluded: vec![value],
+                    ..Range::default()
+                },
+                BinaryOperator::Lt => Range::new(None, bound(value, false)),
+                BinaryOperator::Le => Range::new(None, bound(value, true)),
+                BinaryOperator::Gt => Range::new(bound(value, false), None),
+                BinaryOperator::Ge => Range::new(bound(value, true), None),
+                _ => return None,
+            };
+            Some((column, range))
+        }
+        Expr::Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        } if expr.is_column() => {
+            let range = Range::new(bound(number(low)?, true), bound(number(high)?, true));
+            Some((expr, range.normalized()))
+        }
+        _ => None,
+    }
+}
+
+/// The comparison operator with its operands swapped: `>` for `<`.
+fn flipped(op: &BinaryOperator) -> Option<BinaryOperator> {
+    let flipped = match op {
+        BinaryOperator::Eq => BinaryOperator::Eq,
+        BinaryOperator::Neq => BinaryOperator::Neq,
+        BinaryOperator::Lt => BinaryOperator::Gt,
+        BinaryOperator::Le => BinaryOperator::Ge,
+        BinaryOperator::Gt => BinaryOperator::Lt,
+        BinaryOperator::Ge => BinaryOperator::Le,
+        _ => return None,
+    };
+    Some(flipped)
+}
+
+/// The column and range of a predicate, or of an AND of predicates on the same column.
+fn column_range(expr: &Expr) -> Option<(&Expr, Range)> {
+    let Expr::BinaryOp {
+        left,
+        op: BinaryOperator::And,
+        right,
+    } = expr
+    else {
+        return predicate(expr);
+    };
+    let (column, left) = column_range(left)?;
+    let (other, right) = predicate(right)?;
+    (column == other).then(|| (column, left.intersect(right)))
+}
+
+/// Ranges of the columns that a condition restricts with the predicates of its top-level AND, for
+/// estimating the number of rows it selects: `x > 1 AND x <= 10 AND y IN (1, 2)` gives
+/// `(1, 10]` for x and `{1, 2}` for y.
+pub(crate) fn column_ranges(condition: &Expr) -> Vec<(Expr, Range)> {
+    let mut ranges: Vec<(Expr, Range)> = Vec::new();
+    for conjunct in boolean::operand_refs(condition, &BinaryOperator::And) {
+        let Some((column, range)) = predicate(conjunct) else {
+            continue;
+        };
+        match ranges.iter_mut().find(|(other, _)| other == column) {
+            Some((_, merged)) => *merged = mem::take(merged).intersect(range),
+            None => ranges.push((column.clone(), range)),
+        }
+    }
+    ranges
+}
+
+/// Fraction of the rows that a condition selects, estimated from the [column_ranges] it restricts,
+/// the columns being taken as independent. Its other predicates are not estimated, and None is
+/// returned if it restricts no column.
+pub(crate) fn selectivity(condition: &Expr) -> Option<f64> {
+    let ranges = column_ranges(condition);
+    (!ranges.is_empty()).then(|| {
+        ranges
+            .iter()
+            .map(|(_, range)| range.selectivity())
+            .product()
+    })
+}
+
+/// Merges the predicates on the same column of an AND or OR chain whose operands are already
+/// simplified. `filter` is true where the chain is used as a filter, or inside an AND or OR
+/// used as a filter, so that NULL can be treated as FALSE.
+pub(crate) fn simplify(expr: &mut Expr, filter: bool) {
+    let Expr::BinaryOp {
+        op: op @ (BinaryOperator::And | BinaryOperator::Or),
+        ..
+    } = expr
+    else {
+        return;
+    };
+    let op = op.clone();
+    let operands = boolean::operands(mem::replace(expr, Expr::Null), &op);
+    let operands = match op {
+        BinaryOperator::And => intersect(operands, filter),
+        _ => unite(operands, filter),
+    };
+    let operands = operands
+        .into_iter()
+        .flat_map(|operand| boolean::operands(operand, &op))
+        .collect();
+    *expr = boolean::chain(operands, &op);
+}
+
+/// Simplifies a condition used as a filter, as in WHERE, HAVING or ON: the predicates of its ANDs
+/// and ORs are merged, and those that no value satisfies are replaced with FALSE.
+pub(crate) fn simplify_filter(condition: &mut Expr) {
+    let Expr::BinaryOp {
+        op: op @ (BinaryOperator::And | BinaryOperator::Or),
+        ..
+    } = condition
+    else {
+        if predicate(condition).is_some_and(|(_, range)| range.is_empty()) {
+            *condition = Expr::Boolean(false);
+        }
+        return;
+    };
+    let op = op.clone();
+    let mut operands = boolean::operands(mem::replace(condition, Expr::Null), &op);
+    for operand in &mut operands {
+        simplify_filter(operand);
+    }
+    // Operands that became TRUE or FALSE decide the chain, or can be dropped.
+    let absorbing = Expr::Boolean(op == BinaryOperator::Or);
+    if operands.contains(&absorbing) {
+        *condition = absorbing;
+        return;
+    }
+    operands.retain(|operand| *operand != Expr::Boolean(op == BinaryOperator::And));
+    *condition = boolean::chain(operands, &op);
+    simplify(condition, true);
+}
+
+/// Indices of the operands that are predicates on the same column, with the column, in the order
+/// the columns first appear. `piece` extracts what an operand says about its column.
+fn group<'a, T>(
+    operands: &'a [Expr],
+    piece: impl Fn(&'a Expr) -> Option<(&'a Expr, T)>,
+) -> Vec<(&'a Expr, Vec<(usize, T)>)> {
+    // Columns are looked up by their SQL text, so long chains are grouped in linear time.
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut groups: Vec<(&Expr, Vec<(usize, T)>)> = Vec::new();
+    for (i, operand) in operands.iter().enumerate() {
+        let Some((column, piece)) = piece(operand) else {
+            continue;
+        };
+        let position = *index.entry(column.to_string()).or_insert_with(|| {
+            groups.push((column, Vec::new()));
+            groups.len() - 1
+        });
+        groups[position].1.push((i, piece));
+    }
+    groups
+}
+
+/// Replaces the operands at the given indices with one, at the position of the first one.
+fn replace(operands: &mut [Option<Expr>], indices: &[usize], replacement: Expr) {
+    for &i in indices {
+        operands[i] = None;
+    }
+    operands[indices[0]] = Some(replacement);
+}
+
+/// Intersects the ranges of the conjuncts on the same column.
+fn intersect(conjuncts: Vec<Expr>, filter: bool) -> Vec<Expr> {
+    let groups = group(&conjuncts, predicate);
+    let mut merged: Vec<(Vec<usize>, Expr)> = Vec::new();
+    for (column, predicates) in groups {
+        let (indices, ranges): (Vec<usize>, Vec<Range>) = predicates.into_iter().unzip();
+        let range = ranges
+            .into_iter()
+            .reduce(Range::intersect)
+            .unwrap_or_default();
+        if range.is_empty() {
+            // In a filter the contradiction counts as FALSE, which makes the whole AND FALSE.
+            if filter {
+                return vec![Expr::Boolean(false)];
+            }
+            continue;
+        }
+        if indices.len() > 1 {
+            merged.push((indices, range.to_expr(column)));
+        }
+    }
+    let mut conjuncts: Vec<Option<Expr>> = conjuncts.into_iter().map(Some).collect();
+    for (indices, replacement) in merged {
+        replace(&mut conjuncts, &indices, replacement);
+    }
+    conjuncts.into_iter().flatten().collect()
+}
+
+/// What a disjunct of an OR allows for its column.
+enum Piece {
+    /// Numbers in a range without excluded values.
+    Range(Range),
+    /// Constants of an `=` or IN that are not all numbers.
+    Constants(Vec<Expr>),
+    /// `x IS NULL`.
+    Null,
+}
+
+fn piece(expr: &Expr) -> Option<(&Expr, Piece)> {
+    if let Some((column, range)) = column_range(expr) {
+        return range
+            .excluded
+            .is_empty()
+            .then_some((column, Piece::Range(range)));
+    }
+    let Expr::BinaryOp { left, op, right } = expr else {
+        return None;
+    };
+    let constant = |expr: &Expr| Value::from_expr(expr).is_some_and(|value| value != Value::Null);
+    match (op, &**right) {
+        (BinaryOperator::Is, Expr::Null) if left.is_column() => Some((left, Piece::Null)),
+        (BinaryOperator::Eq, right) if left.is_column() && constant(right) => {
+            Some((left, Piece::Constants(vec![right.clone()])))
+        }
+        (BinaryOperator::Eq, _) if right.is_column() && constant(left) => {
+            Some((right, Piece::Constants(vec![(**left).clone()])))
+        }
+        (BinaryOperator::In, Expr::List(items))
+            if left.is_column() && items.iter().all(constant) =>
+        {
+            Some((left, Piece::Constants(items.clone())))
+        }
+        _ => None,
+    }
+}
+
+/// A range between two bounds, each of which can be missing.
+type Span = (Option<Bound>, Option<Bound>);
+
+/// Unites the ranges and constants of the disjuncts on the same column.
+fn unite(disjuncts: Vec<Expr>, filter: bool) -> Vec<Expr> {
+    let groups = group(&disjuncts, piece);
+    let mut merged: Vec<(Vec<usize>, Expr)> = Vec::new();
+    for (column, pieces) in groups {
+        let mut null = false;
+        let mut indices = Vec::new();
+        let mut spans: Vec<Span> = Vec::new();
+        // Constants in the order they are written, with numbers as their range.
+        let mut constants: Vec<Expr> = Vec::new();
+        for (i, piece) in pieces {
+            match piece {
+                Piece::Null => {
+                    null = true;
+                    continue;
+                }
+                Piece::Constants(items) => constants.extend(items),
+                Piece::Range(range) => match range.values {
+                    Some(values) => {
+                        for value in values {
+                            let bound = Some(Bound {
+                                value,
+                                inclusive: true,
+                            });
+                            spans.push((bound, bound));
+                            constants.push(Expr::Number(value));
+                        }
+                    }
+                    None if range.is_empty() => {}
+                    None => spans.push((range.low, range.high)),
+                },
+            }
+            indices.push(i);
+        }
+
+        let spans = merge_spans(spans);
+        if let [(None, None)] = spans.as_slice() {
+            if null {
+                return vec![Expr::Boolean(true)];
+            }
+            if filter {
+                let not_null = Expr::BinaryOp {
+                    left: Box::new(column.clone()),
+                    op: BinaryOperator::IsNot,
+                    right: Box::new(Expr::Null),
+                };
+                merged.push((indices, not_null));
+            }
+            continue;
+        }
+        if indices.len() < 2 {
+            continue;
+        }
+
+        // Numbers inside a wider range are dropped, and repeated constants.
+        let mut seen = HashSet::new();
+        constants.retain(|constant| {
+            let covered = number(constant).is_some_and(|value| {
+                let after = spans.partition_point(|(low, _)| {
+                    low.is_none_or(|low| low.value < value || (low.value == value && low.inclusive))
+                });
+                let Some(&(low, high)) = after.checked_sub(1).map(|i| &spans[i]) else {
+                    return false;
+                };
+                low != high && Range::new(low, high).within_bounds(value)
+            });
+            !covered && seen.insert(constant.to_string())
+        });
+        let mut alternatives = Vec::new();
+        match constants.as_slice() {
+            [] => {}
+            [constant] => alternatives.push(Expr::BinaryOp {
+                left: Box::new(column.clone()),
+                op: BinaryOperator::Eq,
+                right: Box::new(constant.clone()),
+            }),
+            _ => alternatives.push(Expr::BinaryOp {
+                left: Box::new(column.clone()),
+                op: BinaryOperator::In,
+                right: Box::new(Expr::List(constants)),
+            }),
+        }
+        for (low, high) in spans {
+            if low != high {
+                alternatives.push(Range::new(low, high).to_expr(column));
+            }
+        }
+        merged.push((indices, boolean::chain(alternatives, &BinaryOperator::Or)));
+    }
+
+    let mut disjuncts: Vec<Option<Expr>> = disjuncts.into_iter().map(Some).collect();
+    for (indices, replacement) in merged {
+        replace(&mut disjuncts, &indices, replacement);
+    }
+    disjuncts.into_iter().flatten().collect()
+}
+
+/// Unites overlapping and adjacent spans, giving disjoint spans in increasing order.
+fn merge_spans(mut spans: Vec<Span>) -> Vec<Span> {
+    // Unbounded spans first, then by lower bound, with inclusive bounds first at the same value.
+    spans.sort_by(|(a, _), (b, _)| match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (Some(a), Some(b)) => a
+            .value
+            .total_cmp(&b.value)
+            .then(b.inclusive.cmp(&a.inclusive)),
+    });
+    let mut merged: Vec<Span> = Vec::new();
+    for (low, high) in spans {
+        if let Some((_, last_high)) = merged.last_mut()
+            && touches(*last_high, low)
+        {
+            *last_high = looser(*last_high, high);
+            continue;
+        }
+        merged.push((low, high));
+    }
+    merged
+}
+
+/// Returns true if a span ending at `high` overlaps or meets a span starting at `low`.
+fn touches(high: Option<Bound>, low: Option<Bound>) -> bool {
+    match (high, low) {
+        (Some(high), Some(low)) => {
+            low.value < high.value || (low.value == high.value && (low.inclusive || high.inclusive))
+        }
+        _ => true,
+    }
+}
+
+/// The greater of two upper bounds, with None standing for no bound.
+fn looser(a: Option<Bound>, b: Option<Bound>) -> Option<Bound> {
+    match (a, b) {
+        (Some(a), Some(b)) => match a.value.total_cmp(&b.value) {
+            Ordering::Equal => Some(Bound {
+                value: a.value,
+                inclusive: a.inclusive || b.inclusive,
+            }),
+            Ordering::Greater => Some(a),
+            Ordering::Less => Some(b),
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,409 @@
+//! State and meta-commands of the interactive shell.
+//!
+//! Input is either a meta-command, starting with a `.`, or SQL statements separated by semicolons.
+//! Each statement is parsed, simplified and bound against the catalog of the session, and the
+//! result is shown in the current output mode.
+use crate::ast::{CreateTableStatement, Statement};
+use crate::binder::Binder;
+use crate::catalog::{Catalog, parse_search_path};
+use crate::cte::Materialization;
+use crate::dialect::Dialect;
+use crate::functions::{self, parse_declaration};
+use crate::json::Document;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::plan::plan;
+use crate::simplify::{Rewrites, Simplify};
+use crate::token::{Span, Token};
+use crate::tree::statement_tree;
+use crate::visitor::Visitor;
+use colored::*;
+use std::fs;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+const HELP: &str = "\
+Commands:
+  .help                  Show this message
+  .exit                  Quit the shell
+  .mode [MODE]           Show or set the output mode: ast, sql, json, tree, tokens or plan
+  .simplify on|off       Simplify statements after parsing them
+  .ctes off|MODE         Optimize CTEs when simplifying, inlining those referenced once (auto),
+                         none (materialized) or all (inline)
+  .extract_ctes on|off   Move repeated derived tables to CTEs when simplifying
+  .decorrelate on|off    Rewrite correlated subqueries as joins when simplifying
+  .expand_null_functions on|off
+                         Rewrite COALESCE and NULLIF as CASE expressions when simplifying
+  .cnf on|off            Rewrite conditions as ANDs of ORs when simplifying
+  .timer on|off          Report how long each step takes
+  .dialect [NAME]        Show or set the dialect: ansi, postgres, mysql, mssql or sqlite
+  .read FILE             Run the statements of a SQL script
+  .load FILE             Load the tables created by a DDL script, without showing them
+  .schema                List the tables of the catalog
+  .search_path [PATH]    Show or set the schemas searched for unqualified table names,
+                         as [CATALOG.]SCHEMA[,SCHEMA...]
+  .function DECLARATION  Declare a function of the database, for the simplifier and the planner,
+                         as NAME([TYPE, ...]) RETURNS TYPE [VOLATILE | AGGREGATE]
+";
+
+/// How the shell shows the statements it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum OutputMode {
+    /// The syntax tree, as a Rust debug dump.
+    #[default]
+    Ast,
+    /// The statement printed back as SQL.
+    Sql,
+    /// The syntax tree, as a JSON document.
+    Json,
+    /// The syntax tree, drawn with one node per clause and operator.
+    Tree,
+    /// The tokens read by the lexer.
+    Tokens,
+    /// The logical plan.
+    Plan,
+}
+
+impl OutputMode {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "ast" => Some(Self::Ast),
+            "sql" => Some(Self::Sql),
+            "json" => Some(Self::Json),
+            "tree" => Some(Self::Tree),
+            "tokens" => Some(Self::Tokens),
+            "plan" => Some(Self::Plan),
+            _ => None,
+        }
+    }
+}
+
+/// What the shell does once a command has run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Flow {
+    Continue,
+    Exit,
+}
+
+/// A shell session: the catalog built from the DDL it ran, and the settings changed by
+/// meta-commands.
+pub(crate) struct Session {
+    pub(crate) catalog: Catalog,
+    mode: OutputMode,
+    simplify: bool,
+    /// Rewrites applied after simplification.
+    rewrites: Rewrites,
+    timer: bool,
+    pub(crate) dialect: Dialect,
+}
+
+impl Session {
+    pub(crate) fn new() -> Self {
+        Self {
+            catalog: Catalog::new(),
+            mode: OutputMode::default(),
+            simplify: true,
+            rewrites: Rewrites::default(),
+            timer: false,
+            dialect: Dialect::default(),
+        }
+    }
+
+    /// Runs a meta-command or SQL statements. Results go to `out`, and errors and warnings to `err`.
+    pub(crate) fn execute(
+        &mut self,
+        input: &str,
+        out: &mut dyn Write,
+        err: &mut dyn Write,
+    ) -> io::Result<Flow> {
+        let input = input.trim();
+        if input.starts_with('.') {
+            return match self.command(input, out, err) {
+                Ok(flow) => Ok(flow),
+                Err(message) => {
+                    writeln!(err, "{} {message}", "Error:".red().bold())?;
+                    Ok(Flow::Continue)
+                }
+            };
+        }
+        for statement in split_statements(input, self.dialect) {
+            self.run(statement, out, err)?;
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Runs a meta-command.
+    fn command(
+        &mut self,
+        input: &str,
+        out: &mut dyn Write,
+        err: &mut dyn Write,
+    ) -> Result<Flow, String> {
+        // Declarations are the only argument with spaces.
+        if let Some((".function", declaration)) = input.trim().split_once(char::is_whitespace) {
+            functions::register(parse_declaration(declaration)?);
+            return Ok(Flow::Continue);
+        }
+
+        let mut words = input.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let argument = words.next();
+        if words.next().is_some() {
+            return Err(format!("Too many arguments for {command}"));
+        }
+
+        match (command, argument) {
+            (".exit" | ".quit", None) => return Ok(Flow::Exit),
+            (".help", None) => write!(out, "{}", HELP.bright_black()).map_err(io_error)?,
+            (".mode", None) => writeln!(out, "{:?}", self.mode).map_err(io_error)?,
+            (".mode", Some(name)) => {
+                let mode = OutputMode::from_name(name).ok_or(format!(
+                    "Unknown output mode '{name}', expected ast, sql, json, tree, tokens or plan"
+                ))?;
+                self.mode = mode;
+            }
+            (".simplify", Some(value)) => self.simplify = switch(command, value)?,
+            (".ctes", Some(name)) => {
+                self.rewrites.ctes = match name.to_lowercase().as_str() {
+                    "off" => None,
+                    _ => Some(Materialization::from_name(name).ok_or(format!(
+                        "Unknown CTE mode '{name}', expected off, auto, materialized or inline"
+                    ))?),
+                };
+            }
+            (".extract_ctes", Some(value)) => {
+                self.rewrites.extract_ctes = switch(command, value)?;
+            }
+            (".decorrelate", Some(value)) => self.rewrites.decorrelate = switch(command, value)?,
+            (".expand_null_functions", Some(value)) => {
+                self.rewrites.expand_null_functions = switch(command, value)?;
+            }
+            (".cnf", Some(value)) => self.rewrites.cnf = switch(command, value)?,
+            (".timer", Some(value)) => self.timer = switch(command, value)?,
+            (".dialect", None) => writeln!(out, "{:?}", self.dialect).map_err(io_error)?,
+            (".dialect", Some(name)) => {
+                self.dialect =
+                    Dialect::from_name(name).ok_or(format!("Unknown dialect '{name}'"))?;
+                self.catalog.set_normalization(self.dialect.normalization());
+            }
+            (".read", Some(path)) => {
+                let script = read_file(path)?;
+                for statement in split_statements(&script, self.dialect) {
+                    self.run(statement, out, err).map_err(io_error)?;
+                }
+            }
+            (".load", Some(path)) => {
+                let script = read_file(path)?;
+                let mut loaded = 0;
+                for statement in split_statements(&script, self.dialect) {
+                    let stmt = self
+                        .parse(statement)
+                        .map_err(|message| format!("{path}: {message}"))?;
+                    self.catalog.apply(&stmt)?;
+                    loaded += 1;
+                }
+                writeln!(out, "Loaded {loaded} statements from {path}").map_err(io_error)?;
+            }
+            (".schema", None) => {
+                let mut tables: Vec<_> = self.catalog.tables().collect();
+                tables.sort_by_key(|table| table.name.to_string());
+                for table in tables {
+                    let create = CreateTableStatement {
+                        table: table.name.clone(),
+                        columns: table.columns.clone(),
+                        constraints: Vec::new(),
+                    };
+                    writeln!(out, "{create};").map_err(io_error)?;
+                }
+            }
+            (".search_path", None) => {
+                writeln!(out, "{}", self.catalog.search_path()).map_err(io_error)?;
+            }
+            (".search_path", Some(path)) => {
+                let (catalog, schemas) = parse_search_path(path)?;
+                self.catalog.set_search_path(&catalog, &schemas);
+            }
+            (
+                ".simplify"
+                | ".ctes"
+                | ".extract_ctes"
+                | ".decorrelate"
+                | ".expand_null_functions"
+                | ".cnf"
+                | ".timer"
+                | ".read"
+                | ".load"
+                | ".function",
+                None,
+            ) => {
+                return Err(format!("Missing argument for {command}, see .help"));
+            }
+            _ => return Err(format!("Unknown command {input}, see .help")),
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Parses a single statement in the dialect of the session.
+    fn parse(&self, sql: &str) -> Result<Statement, String> {
+        Parser::new(Lexer::with_dialect(sql, self.dialect)).visit()
+    }
+
+    /// Runs a single statement and shows the result.
+    fn run(&mut self, sql: &str, out: &mut dyn Write, err: &mut dyn Write) -> io::Result<()> {
+        if self.mode == OutputMode::Tokens {
+            let mut lexer = Lexer::with_dialect(sql, self.dialect);
+            loop {
+                match lexer.next_token_with_span() {
+                    (Token::Eof, _) => break,
+                    (token, span) => writeln!(out, "{}..{}\t{token:?}", span.start, span.end)?,
+                }
+            }
+        }
+
+        let mut timings = Vec::new();
+        let start = Instant::now();
+        let parsed = self.parse(sql);
+        timings.push(("parse", start.elapsed()));
+        let mut stmt = match parsed {
+            Ok(stmt) => stmt,
+            Err(message) => {
+                // Report every syntax error in the input, not only the first one.
+                let (_, diagnostics) =
+                    Parser::new(Lexer::with_dialect(sql, self.dialect)).parse_statements();
+                if diagnostics.is_empty() {
+                    writeln!(err, "{} {message}", "Parse error:".red().bold())?;
+                }
+                for diagnostic in diagnostics {
+                    writeln!(err, "{} {diagnostic}", "Parse error:".red().bold())?;
+                }
+                return Ok(());
+            }
+        };
+
+        let result = self.analyze(&mut stmt, &mut timings, err);
+        if let Err(message) = result {
+            writeln!(err, "{} {message}", "Error:".red().bold())?;
+            return Ok(());
+        }
+
+        match self.mode {
+            OutputMode::Ast => writeln!(
+                out,
+                "{}\n{stmt:#?}\n",
+                "Successfully parsed:".green().bold()
+            )?,
+            OutputMode::Sql => writeln!(out, "{stmt};")?,
+            OutputMode::Tree => write!(out, "{}", statement_tree(&stmt))?,
+            OutputMode::Plan => write!(out, "{}", plan(&stmt))?,
+            OutputMode::Json => {
+                let document = Document::new(None, vec![stmt.into()]);
+                writeln!(out, "{}", document.to_json_pretty())?
+            }
+            OutputMode::Tokens => {}
+        }
+        if self.timer {
+            let timings: Vec<String> = timings
+                .iter()
+                .map(|(step, time)| format!("{step} {}", format_duration(*time)))
+                .collect();
+            writeln!(
+                out,
+                "{}",
+                format!("Time: {}", timings.join(", ")).bright_black()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Simplifies and binds a parsed statement, then applies it to the catalog.
+    fn analyze(
+        &mut self,
+        stmt: &mut Statement,
+        timings: &mut Vec<(&'static str, Duration)>,
+        err: &mut dyn Write,
+    ) -> Result<(), String> {
+        if self.simplify {
+            let start = Instant::now();
+            stmt.simplify()?;
+            self.rewrites.apply(stmt);
+            timings.push(("simplify", start.elapsed()));
+        }
+
+        let start = Instant::now();
+        let mut binder = Binder::new(&self.catalog).with_wildcard_expansion();
+        let bound = binder.bind(stmt);
+        timings.push(("bind", start.elapsed()));
+        // Names that look misspelled are reported even when the statement is valid.
+        for warning in binder.warnings() {
+            writeln!(err, "{} {warning}", "Warning:".yellow().bold()).map_err(io_error)?;
+        }
+        bound?;
+        self.catalog.apply(stmt)
+    }
+}
+
+/// Splits a script into statements, each with its terminating semicolon. Semicolons inside
+/// strings, quoted identifiers and comments do not split anything.
+pub(crate) fn split_statements(script: &str, dialect: Dialect) -> Vec<&str> {
+    statement_spans(script, dialect)
+        .into_iter()
+        .map(|span| &script[span.start..span.end])
+        .collect()
+}
+
+/// Spans of the statements of a script, as split by [split_statements]. Leading whitespace is left
+/// out, but comments before a statement belong to it.
+pub(crate) fn statement_spans(script: &str, dialect: Dialect) -> Vec<Span> {
+    let mut lexer = Lexer::with_dialect(script, dialect);
+    let mut spans = Vec::new();
+    let mut start = 0;
+    loop {
+        match lexer.next_token_with_span() {
+            (Token::Eof, _) => break,
+            (Token::Semicolon, span) => {
+                let span = trimmed(script, start, span.end);
+                if &script[span.start..span.end] != ";" {
+                    spans.push(span);
+                }
+                start = span.end;
+            }
+            _ => {}
+        }
+    }
+    let rest = trimmed(script, start, script.len());
+    let text = &script[rest.start..rest.end];
+    if Lexer::with_dialect(text, dialect).next_token_with_span().0 != Token::Eof {
+        spans.push(rest);
+    }
+    spans
+}
+
+/// Span of `script[start..end]` without its leading and trailing whitespace.
+fn trimmed(script: &str, start: usize, end: usize) -> Span {
+    let text = &script[start..end];
+    let start = start + (text.len() - text.trim_start().len());
+    Span {
+        start,
+        end: start + text.trim().len(),
+    }
+}
+
+fn switch(command: &str, value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "on" => Ok(true),
+        "off" => Ok(false),
+        _ => Err(format!("Expected on or off after {command}")),
+    }
+}
+
+fn read_file(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|error| format!("Cannot read {path}: {error}"))
+}
+
+fn io_error(error: io::Error) -> String {
+    error.to_string()
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3} ms", duration.as_secs_f64() * 1000.0)
+}
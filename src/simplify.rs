@@ -1,5 +1,9 @@
 use crate::ast::Statement;
+use crate::boolean;
+use crate::cte::Materialization;
+#[cfg(test)]
 use crate::parser::Parser;
+#[cfg(test)]
 use crate::visitor::Visitor;
 
 pub(crate) trait Simplify {
@@ -8,23 +12,66 @@ pub(crate) trait Simplify {
     }
 }
 
-
-pub(crate) struct Simplifyer {
-    parser: Parser,
+/// Parses a statement and simplifies it.
+#[cfg(test)]
+pub(crate) struct Simplifyer<'a> {
+    parser: Parser<'a>,
 }
 
-impl Simplifyer {
-    pub(crate) fn new(parser: Parser) -> Self {
+#[cfg(test)]
+impl<'a> Simplifyer<'a> {
+    pub(crate) fn new(parser: Parser<'a>) -> Self {
         Self {
             parser
         }
     }
 }
 
-impl Visitor for Simplifyer {
+#[cfg(test)]
+impl Visitor for Simplifyer<'_> {
     fn visit(&mut self) -> Result<Statement, String> {
         let mut stmt = self.parser.visit()?;
         stmt.simplify()?;
         Ok(stmt)
     }
 }
+
+/// Rewrites that may follow simplification, each chosen on the command line and in the shell.
+/// They change the form of statements rather than make them simpler, so none is on by default.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct Rewrites {
+    /// Merge identical CTEs, remove unused ones, and inline those the materialization leaves out.
+    pub(crate) ctes: Option<Materialization>,
+    /// Rewrite the subqueries correlated through equalities as joins with derived tables.
+    pub(crate) decorrelate: bool,
+    /// Move repeated derived tables to shared CTEs.
+    pub(crate) extract_ctes: bool,
+    /// Rewrite COALESCE and NULLIF as CASE expressions, for engines that lack them.
+    pub(crate) expand_null_functions: bool,
+    /// Rewrite conditions in conjunctive normal form, an AND of ORs, within
+    /// [boolean::MAX_CNF_CLAUSES] clauses.
+    pub(crate) cnf: bool,
+}
+
+impl Rewrites {
+    /// Applies the chosen rewrites to a simplified statement.
+    pub(crate) fn apply(&self, stmt: &mut Statement) {
+        // Inlined CTEs may be decorrelated, and the derived tables of decorrelation extracted.
+        // Decorrelation also adds the COALESCE calls and join conditions of the later rewrites.
+        if let Some(materialization) = self.ctes {
+            stmt.optimize_ctes(materialization);
+        }
+        if self.decorrelate {
+            stmt.decorrelate();
+        }
+        if self.extract_ctes {
+            stmt.extract_ctes();
+        }
+        if self.expand_null_functions {
+            stmt.expand_null_functions();
+        }
+        if self.cnf {
+            boolean::conditions_to_cnf(stmt);
+        }
+    }
+}
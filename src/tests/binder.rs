@@ -0,0 +1,297 @@
+use crate::ast::*;
+use crate::binder::Binder;
+use crate::catalog::Catalog;
+use crate::parse_sql;
+
+fn catalog(ddl: &[&str]) -> Catalog {
+    let mut catalog = Catalog::new();
+    for sql in ddl {
+        catalog.apply(&parse_sql(sql).unwrap()).unwrap();
+    }
+    catalog
+}
+
+fn bind(catalog: &Catalog, sql: &str) -> Result<Statement, String> {
+    let mut stmt = parse_sql(sql)?;
+    Binder::new(catalog).bind(&mut stmt)?;
+    Ok(stmt)
+}
+
+fn column(table: &str, column: &str) -> Expr {
+    Expr::QualifiedIdentifier {
+        table: table.into(),
+        column: column.into(),
+    }
+}
+
+fn eq(left: Expr, right: Expr) -> Expr {
+    Expr::BinaryOp {
+        left: Box::new(left),
+        op: BinaryOperator::Eq,
+        right: Box::new(right),
+    }
+}
+
+fn shop() -> Catalog {
+    catalog(&[
+        "CREATE TABLE users (id INTEGER, tenant_id INTEGER, name TEXT);",
+        "CREATE TABLE orders (order_id INTEGER, id INTEGER, tenant_id INTEGER, total REAL);",
+    ])
+}
+
+fn select(stmt: Statement) -> SelectStatement {
+    match stmt {
+        Statement::Select(select) => select,
+        other => panic!("Expected a SELECT statement, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_bind_using_expands_to_on() {
+    let stmt = select(
+        bind(
+            &shop(),
+            "SELECT name FROM users u JOIN orders o USING (id, tenant_id);",
+        )
+        .unwrap(),
+    );
+
+    let Some(TableReference::Join { constraint, .. }) = &stmt.from else {
+        panic!("Expected a join");
+    };
+    assert_eq!(
+        *constraint,
+        JoinConstraint::On(Expr::BinaryOp {
+            left: Box::new(eq(column("u", "id"), column("o", "id"))),
+            op: BinaryOperator::And,
+            right: Box::new(eq(column("u", "tenant_id"), column("o", "tenant_id"))),
+        })
+    );
+}
+
+#[test]
+fn test_bind_natural_join_merges_star_columns() {
+    let stmt = select(bind(&shop(), "SELECT * FROM users NATURAL JOIN orders;").unwrap());
+
+    let names: Vec<Expr> = stmt
+        .columns
+        .into_iter()
+        .map(|item| match item {
+            SelectItem::ExprWithAlias { expr, .. } => expr,
+            _ => panic!("Star should have been expanded"),
+        })
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            column("users", "id"),
+            column("users", "tenant_id"),
+            column("users", "name"),
+            column("orders", "order_id"),
+            column("orders", "total"),
+        ]
+    );
+}
+
+#[test]
+fn test_bind_full_join_using_coalesces_columns() {
+    let stmt = select(
+        bind(
+            &shop(),
+            "SELECT * FROM users u FULL JOIN orders o USING (id);",
+        )
+        .unwrap(),
+    );
+
+    assert_eq!(
+        stmt.columns[0],
+        SelectItem::ExprWithAlias {
+            expr: Expr::FunctionCall {
+                name: "COALESCE".into(),
+                args: vec![column("u", "id"), column("o", "id")],
+                distinct: false,
+            },
+            alias: Some("id".into()),
+        }
+    );
+}
+
+#[test]
+fn test_bind_using_unknown_column() {
+    let result = bind(&shop(), "SELECT * FROM users JOIN orders USING (total);");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_bind_natural_join_with_subquery_and_cte() {
+    let stmt = bind(
+        &shop(),
+        "WITH big AS (SELECT id, total FROM orders WHERE total > 100) \
+         SELECT * FROM (SELECT id AS id, name FROM users) AS u NATURAL JOIN big;",
+    )
+    .unwrap();
+
+    let Statement::With(with) = stmt else {
+        panic!("Expected a WITH statement");
+    };
+    let Some(TableReference::Join { constraint, .. }) = &with.body.from else {
+        panic!("Expected a join");
+    };
+    assert_eq!(
+        *constraint,
+        JoinConstraint::On(eq(column("u", "id"), column("big", "id")))
+    );
+}
+
+#[test]
+fn test_bind_unknown_tables_are_left_untouched() {
+    let sql = "SELECT * FROM a NATURAL JOIN b;";
+    assert_eq!(bind(&shop(), sql).unwrap(), parse_sql(sql).unwrap());
+}
+
+fn expand(catalog: &Catalog, sql: &str) -> Result<Vec<SelectItem>, String> {
+    let mut stmt = parse_sql(sql)?;
+    Binder::new(catalog)
+        .with_wildcard_expansion()
+        .bind(&mut stmt)?;
+    Ok(select(stmt).columns)
+}
+
+fn item(expr: Expr, alias: Option<&str>) -> SelectItem {
+    SelectItem::ExprWithAlias {
+        expr,
+        alias: alias.map(Ident::from),
+    }
+}
+
+#[test]
+fn test_expand_star() {
+    assert_eq!(
+        expand(&shop(), "SELECT * FROM users;").unwrap(),
+        vec![
+            item(column("users", "id"), None),
+            item(column("users", "tenant_id"), None),
+            item(column("users", "name"), None),
+        ]
+    );
+}
+
+#[test]
+fn test_expand_qualified_wildcard() {
+    assert_eq!(
+        expand(
+            &shop(),
+            "SELECT o.*, u.name FROM users u JOIN orders o ON u.id = o.id;"
+        )
+        .unwrap(),
+        vec![
+            item(column("o", "order_id"), None),
+            item(column("o", "id"), None),
+            item(column("o", "tenant_id"), None),
+            item(column("o", "total"), None),
+            item(column("u", "name"), None),
+        ]
+    );
+}
+
+#[test]
+fn test_expand_star_modifiers() {
+    assert_eq!(
+        expand(
+            &shop(),
+            "SELECT * EXCLUDE (tenant_id) REPLACE (total * 2 AS total) RENAME (order_id AS oid) FROM orders;"
+        )
+        .unwrap(),
+        vec![
+            item(column("orders", "order_id"), Some("oid")),
+            item(column("orders", "id"), None),
+            item(
+                Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier("total".into())),
+                    op: BinaryOperator::Multiply,
+                    right: Box::new(Expr::Number(2.0)),
+                },
+                Some("total")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_expand_wildcard_errors() {
+    assert!(expand(&shop(), "SELECT * EXCLUDE (missing) FROM users;").is_err());
+    assert!(expand(&shop(), "SELECT x.* FROM users;").is_err());
+}
+
+#[test]
+fn test_expand_subquery_wildcard() {
+    assert_eq!(
+        expand(
+            &shop(),
+            "SELECT * FROM (SELECT u.* EXCEPT (tenant_id) FROM users u) AS s;"
+        )
+        .unwrap(),
+        vec![
+            item(column("s", "id"), None),
+            item(column("s", "name"), None)
+        ]
+    );
+}
+
+#[test]
+fn test_bind_using_resolves_unqualified_columns() {
+    let bound = |sql| bind(&shop(), sql).unwrap().to_string();
+    assert_eq!(
+        bound(
+            "SELECT id, name FROM users u JOIN orders o USING (id) \
+             WHERE id > 1 GROUP BY id, name HAVING id < 5 ORDER BY id;"
+        ),
+        "SELECT u.id, name FROM users AS u JOIN orders AS o ON u.id = o.id \
+         WHERE u.id > 1 GROUP BY u.id, name HAVING u.id < 5 ORDER BY u.id"
+    );
+    assert_eq!(
+        bound("SELECT id FROM users u LEFT JOIN orders o USING (id);"),
+        "SELECT u.id FROM users AS u LEFT JOIN orders AS o ON u.id = o.id"
+    );
+    assert_eq!(
+        bound("SELECT id FROM users u RIGHT JOIN orders o USING (id);"),
+        "SELECT o.id FROM users AS u RIGHT JOIN orders AS o ON u.id = o.id"
+    );
+    assert_eq!(
+        bound("SELECT tenant_id FROM users NATURAL JOIN orders;"),
+        "SELECT users.tenant_id FROM users JOIN orders \
+         ON users.id = orders.id AND users.tenant_id = orders.tenant_id"
+    );
+}
+
+#[test]
+fn test_bind_full_join_using_resolves_to_coalesce() {
+    let stmt = bind(
+        &shop(),
+        "SELECT id, total FROM users u FULL JOIN orders o USING (id) WHERE id > 1 ORDER BY id;",
+    )
+    .unwrap();
+    assert_eq!(
+        stmt.to_string(),
+        "SELECT COALESCE(u.id, o.id) AS id, total FROM users AS u FULL JOIN orders AS o \
+         ON u.id = o.id WHERE COALESCE(u.id, o.id) > 1 ORDER BY COALESCE(u.id, o.id)"
+    );
+}
+
+#[test]
+fn test_bind_using_kept_when_merged_columns_are_ambiguous() {
+    for sql in [
+        // Referenced from a subquery.
+        "SELECT name FROM users u JOIN orders o USING (id) \
+         WHERE EXISTS (SELECT 1 FROM (SELECT name FROM users) AS x WHERE x.name = name AND id > 0);",
+        // Referenced from a relation that is not in the catalog.
+        "SELECT name FROM users u JOIN orders o USING (id) WHERE total > (SELECT MAX(id) FROM c);",
+        // Referenced from the condition of another join.
+        "SELECT name FROM users u JOIN orders o USING (id) JOIN users v ON v.id = id;",
+        // Shadowed by a select alias in ORDER BY.
+        "SELECT total AS id FROM users u JOIN orders o USING (id) ORDER BY id;",
+    ] {
+        let stmt = bind(&shop(), sql).unwrap();
+        assert_eq!(stmt, parse_sql(sql).unwrap(), "{sql}");
+    }
+}
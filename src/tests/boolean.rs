@@ -0,0 +1,158 @@
+use crate::ast::*;
+use crate::boolean::{self, MAX_CNF_CLAUSES};
+use crate::parse_sql;
+use crate::tests::utils::{assert_simplified_condition, parse_expr};
+
+#[test]
+fn test_not_is_pushed_down() {
+    assert_simplified_condition("NOT (a AND b)", "(NOT a) OR (NOT b)");
+    assert_simplified_condition("NOT (a OR b OR c)", "(NOT a) AND (NOT b) AND (NOT c)");
+    assert_simplified_condition("NOT (a AND (b OR NOT c))", "(NOT a) OR (NOT b) AND c");
+    assert_simplified_condition("NOT NOT a", "a");
+    assert_simplified_condition("NOT (NOT (NOT a))", "NOT a");
+    assert_simplified_condition(
+        "NOT (x IS NULL OR y IS NOT NULL)",
+        "x IS NOT NULL AND y IS NULL",
+    );
+    assert_simplified_condition("NOT x BETWEEN 1 AND 2", "x NOT BETWEEN 1 AND 2");
+    assert_simplified_condition("NOT x IN (1, 2)", "x NOT IN (1, 2)");
+    assert_simplified_condition("NOT x LIKE '%a'", "x NOT LIKE '%a'");
+    // Operators without an opposite keep their NOT.
+    assert_simplified_condition("NOT (x ~ 'a' AND y)", "(NOT x ~ 'a') OR (NOT y)");
+    assert_simplified_condition("NOT EXISTS (SELECT 1)", "NOT EXISTS (SELECT 1)");
+}
+
+#[test]
+fn test_negated_comparisons_are_inverted() {
+    assert_simplified_condition("NOT a < b", "a >= b");
+    assert_simplified_condition("NOT a <= b", "a > b");
+    assert_simplified_condition("NOT a > b", "a <= b");
+    assert_simplified_condition("NOT a >= b", "a < b");
+    assert_simplified_condition("NOT a = b", "a <> b");
+    assert_simplified_condition("NOT a <> b", "a = b");
+    // Both forms are NULL for NULL operands, so constants still fold the same way.
+    assert_simplified_condition("NOT NULL < 1", "NULL");
+    assert_simplified_condition("NOT (x < 1 AND FALSE)", "TRUE");
+}
+
+#[test]
+fn test_chains_are_flattened_and_deduplicated() {
+    assert_simplified_condition("a AND (b AND c)", "a AND b AND c");
+    assert_simplified_condition("(a OR b) OR (c OR d)", "a OR b OR c OR d");
+    assert_simplified_condition("a AND b AND a", "a AND b");
+    assert_simplified_condition("x = 1 OR (y = 2 OR x = 1)", "x = 1 OR y = 2");
+    assert_simplified_condition("NOT (a OR a)", "NOT a");
+    // Volatile functions are called once per occurrence.
+    assert_simplified_condition(
+        "RANDOM() < 0.5 AND RANDOM() < 0.5",
+        "RANDOM() < 0.5 AND RANDOM() < 0.5",
+    );
+    assert_simplified_condition("f(x) AND f(x)", "f(x) AND f(x)");
+}
+
+#[test]
+fn test_absorption() {
+    assert_simplified_condition("a OR (a AND b)", "a");
+    assert_simplified_condition("a AND (a OR b)", "a");
+    assert_simplified_condition("(a OR b) AND a", "a");
+    assert_simplified_condition("(a OR b) AND (b OR c OR a) AND d", "(a OR b) AND d");
+    assert_simplified_condition("(a OR b) AND (b OR a)", "a OR b");
+    assert_simplified_condition("(a AND b AND c) OR (b AND a)", "b AND a");
+    // `a OR NOT a` is NULL when a is NULL.
+    assert_simplified_condition("a OR NOT a", "a OR NOT a");
+}
+
+#[test]
+fn test_common_terms_are_factored() {
+    assert_simplified_condition("(a AND b) OR (a AND c)", "a AND (b OR c)");
+    assert_simplified_condition(
+        "(x = 1 AND y = 2 AND z) OR (z AND x = 1 AND y = 3) OR (x = 1 AND z)",
+        "x = 1 AND z",
+    );
+    assert_simplified_condition(
+        "(x = 1 AND y = 2 AND z) OR (z AND x = 1 AND y = 3)",
+        "x = 1 AND z AND y IN (2, 3)",
+    );
+    assert_simplified_condition("(a AND b) OR (c AND d)", "a AND b OR c AND d");
+    assert_simplified_condition(
+        "(RANDOM() < 0.5 AND b) OR (RANDOM() < 0.5 AND c)",
+        "(RANDOM() < 0.5 AND b) OR (RANDOM() < 0.5 AND c)",
+    );
+}
+
+#[test]
+fn test_conjunctive_normal_form() {
+    let cnf = |expr: &str| boolean::to_cnf(&parse_expr(expr));
+    assert_eq!(
+        cnf("(a AND b) OR c"),
+        Some(parse_expr("(a OR c) AND (b OR c)"))
+    );
+    assert_eq!(
+        cnf("(a AND b) OR (c AND d)"),
+        Some(parse_expr(
+            "(a OR c) AND (a OR d) AND (b OR c) AND (b OR d)"
+        ))
+    );
+    assert_eq!(
+        cnf("(a AND b) OR (a AND c)"),
+        Some(parse_expr("a AND (b OR c)"))
+    );
+    assert_eq!(
+        cnf("NOT (a OR b AND c)"),
+        Some(parse_expr("(NOT a) AND ((NOT b) OR (NOT c))"))
+    );
+    assert_eq!(cnf("a AND (b OR c)"), Some(parse_expr("a AND (b OR c)")));
+
+    // Distributing would repeat a volatile call.
+    assert_eq!(cnf("(a AND b) OR RANDOM() < 0.5"), None);
+    assert_eq!(
+        cnf("RANDOM() < 0.5 AND (a OR b)"),
+        Some(parse_expr("RANDOM() < 0.5 AND (a OR b)"))
+    );
+}
+
+#[test]
+fn test_conjunctive_normal_form_size_is_bounded() {
+    // Each branch of the OR doubles the number of clauses.
+    let branches = |count: usize| {
+        (0..count)
+            .map(|i| format!("(a{i} AND b{i})"))
+            .collect::<Vec<_>>()
+            .join(" OR ")
+    };
+    let fits = MAX_CNF_CLAUSES.ilog2() as usize;
+    let Some(cnf) = boolean::to_cnf(&parse_expr(&branches(fits))) else {
+        panic!("Expected {} clauses", 1 << fits);
+    };
+    let clauses = boolean::operands(cnf, &BinaryOperator::And);
+    assert_eq!(clauses.len(), 1 << fits);
+    assert_eq!(boolean::to_cnf(&parse_expr(&branches(fits + 1))), None);
+}
+
+#[test]
+fn test_conditions_to_cnf() {
+    let cnf = |sql: &str| {
+        let mut stmt = parse_sql(sql).unwrap();
+        boolean::conditions_to_cnf(&mut stmt);
+        stmt
+    };
+    assert_eq!(
+        cnf("SELECT * FROM t JOIN u ON (a AND b) OR c \
+             WHERE x IN (SELECT y FROM v WHERE (d AND e) OR f) \
+             GROUP BY g HAVING (h AND i) OR j;"),
+        parse_sql(
+            "SELECT * FROM t JOIN u ON (a OR c) AND (b OR c) \
+             WHERE x IN (SELECT y FROM v WHERE (d OR f) AND (e OR f)) \
+             GROUP BY g HAVING (h OR j) AND (i OR j);"
+        )
+        .unwrap()
+    );
+    assert_eq!(
+        cnf("UPDATE t SET a = 1 WHERE (b AND c) OR d;"),
+        parse_sql("UPDATE t SET a = 1 WHERE (b OR d) AND (c OR d);").unwrap()
+    );
+    // Conditions past the bound are left as they are.
+    let sql = "DELETE FROM t WHERE (a0 AND b0) OR (a1 AND b1) OR (a2 AND b2) OR (a3 AND b3) \
+               OR (a4 AND b4) OR (a5 AND b5) OR (a6 AND b6);";
+    assert_eq!(cnf(sql), parse_sql(sql).unwrap());
+}
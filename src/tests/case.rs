@@ -0,0 +1,116 @@
+use crate::tests::utils::assert_simplified;
+use crate::{parse_sql, simplify_sql};
+
+#[test]
+fn test_dead_branches_are_removed() {
+    assert_simplified(
+        "CASE WHEN 1 = 2 THEN 'a' WHEN x > 0 THEN 'b' WHEN NULL THEN 'c' ELSE 'd' END",
+        "CASE WHEN x > 0 THEN 'b' ELSE 'd' END",
+    );
+    assert_simplified(
+        "CASE WHEN 1 = NULL THEN 'a' WHEN x > 0 THEN 'b' END",
+        "CASE WHEN x > 0 THEN 'b' END",
+    );
+    assert_simplified("CASE WHEN FALSE THEN 1 ELSE 2 END", "2");
+    assert_simplified("CASE WHEN FALSE THEN 1 END", "NULL");
+}
+
+#[test]
+fn test_branches_that_are_always_taken() {
+    assert_simplified("CASE WHEN 1 = 1 THEN 'a' ELSE 'b' END", "'a'");
+    assert_simplified("CASE WHEN FALSE THEN 1 WHEN TRUE THEN x END", "x");
+    // Later branches are unreachable, and the branch becomes the ELSE value.
+    assert_simplified(
+        "CASE WHEN x > 0 THEN 1 WHEN TRUE THEN 2 WHEN y > 0 THEN 3 ELSE 4 END",
+        "CASE WHEN x > 0 THEN 1 ELSE 2 END",
+    );
+}
+
+#[test]
+fn test_simple_case_with_literal_operand() {
+    assert_simplified("CASE 2 WHEN 1 THEN 'a' WHEN 2 THEN 'b' ELSE 'c' END", "'b'");
+    assert_simplified("CASE 3 WHEN 1 THEN 'a' WHEN 2 THEN 'b' ELSE 'c' END", "'c'");
+    assert_simplified("CASE 'x' WHEN 'y' THEN 1 END", "NULL");
+    // NULL never equals a WHEN value.
+    assert_simplified("CASE NULL WHEN NULL THEN 1 ELSE 2 END", "2");
+    assert_simplified(
+        "CASE x WHEN NULL THEN 1 WHEN 2 THEN 3 END",
+        "CASE x WHEN 2 THEN 3 END",
+    );
+    // A WHEN value that is not a literal could equal the operand.
+    assert_simplified(
+        "CASE 2 WHEN 1 THEN 'a' WHEN y THEN 'b' WHEN 2 THEN 'c' END",
+        "CASE 2 WHEN y THEN 'b' ELSE 'c' END",
+    );
+}
+
+#[test]
+fn test_branches_with_the_same_result_are_merged() {
+    assert_simplified(
+        "CASE WHEN x = 1 THEN 'a' WHEN x = 2 THEN 'a' WHEN x = 3 THEN 'b' END",
+        "CASE WHEN x = 1 OR x = 2 THEN 'a' WHEN x = 3 THEN 'b' END",
+    );
+    assert_simplified(
+        "CASE WHEN x = 1 THEN 'a' WHEN x = 2 THEN 'b' ELSE 'b' END",
+        "CASE WHEN x = 1 THEN 'a' ELSE 'b' END",
+    );
+    assert_simplified("CASE WHEN x = 1 THEN 'a' ELSE 'a' END", "'a'");
+    assert_simplified(
+        "CASE WHEN x = 1 THEN 'a' WHEN x = 2 THEN NULL END",
+        "CASE WHEN x = 1 THEN 'a' END",
+    );
+    // Branches that are not adjacent give different results in between.
+    assert_simplified(
+        "CASE WHEN x = 1 THEN 'a' WHEN x = 2 THEN 'b' WHEN x = 3 THEN 'a' END",
+        "CASE WHEN x = 1 THEN 'a' WHEN x = 2 THEN 'b' WHEN x = 3 THEN 'a' END",
+    );
+}
+
+#[test]
+fn test_case_as_coalesce_and_nullif() {
+    assert_simplified("CASE WHEN x IS NULL THEN 0 ELSE x END", "COALESCE(x, 0)");
+    assert_simplified(
+        "CASE WHEN a IS NOT NULL THEN a WHEN b IS NOT NULL THEN b ELSE 0 END",
+        "COALESCE(a, b, 0)",
+    );
+    assert_simplified(
+        "CASE WHEN a IS NOT NULL THEN a WHEN b IS NOT NULL THEN b END",
+        "COALESCE(a, b)",
+    );
+    assert_simplified("CASE WHEN a IS NOT NULL THEN a END", "a");
+    assert_simplified("CASE WHEN x = 0 THEN NULL ELSE x END", "NULLIF(x, 0)");
+    assert_simplified(
+        "CASE WHEN x IS NULL THEN 0 ELSE y END",
+        "CASE WHEN x IS NULL THEN 0 ELSE y END",
+    );
+}
+
+#[test]
+fn test_null_functions_are_expanded() {
+    let mut stmt = parse_sql(
+        "SELECT COALESCE(a, b, 0), NULLIF(x, 0) FROM t \
+         WHERE EXISTS (SELECT 1 FROM u WHERE coalesce(u.a, 1) = 1);",
+    )
+    .unwrap();
+    stmt.expand_null_functions();
+    assert_eq!(
+        stmt,
+        parse_sql(
+            "SELECT CASE WHEN a IS NOT NULL THEN a WHEN b IS NOT NULL THEN b ELSE 0 END, \
+             CASE WHEN x = 0 THEN NULL ELSE x END FROM t \
+             WHERE EXISTS (SELECT 1 FROM u WHERE \
+             CASE WHEN u.a IS NOT NULL THEN u.a ELSE 1 END = 1);"
+        )
+        .unwrap()
+    );
+
+    // Simplifying the expansion gives the calls back.
+    let expanded = stmt.to_string();
+    assert_eq!(
+        simplify_sql(&expanded),
+        parse_sql(
+            "SELECT COALESCE(a, b, 0), NULLIF(x, 0) FROM t \
+             WHERE EXISTS (SELECT 1 FROM u WHERE COALESCE(u.a, 1) = 1);"
+        )
+    );
+}
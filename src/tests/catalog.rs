@@ -0,0 +1,51 @@
+use crate::catalog::Catalog;
+use crate::parse_sql;
+
+fn apply(catalog: &mut Catalog, sql: &str) -> Result<(), String> {
+    catalog.apply(&parse_sql(sql)?)
+}
+
+#[test]
+fn test_catalog_resolves_default_schema() {
+    let mut catalog = Catalog::new();
+    apply(&mut catalog, "CREATE TABLE users (id INTEGER);").unwrap();
+
+    let table = catalog.table(&"users".into()).unwrap();
+    assert_eq!(table.name, "default.public.users".into());
+    assert!(catalog.table(&"public.users".into()).is_some());
+    assert!(catalog.table(&"default.public.users".into()).is_some());
+    assert!(catalog.table(&"other.users".into()).is_none());
+}
+
+#[test]
+fn test_catalog_search_path_order() {
+    let mut catalog = Catalog::new();
+    catalog.set_search_path("analytics", &["staging", "public"]);
+    apply(&mut catalog, "CREATE TABLE public.events (id INTEGER);").unwrap();
+    apply(&mut catalog, "CREATE TABLE events (id INTEGER, kind TEXT);").unwrap();
+
+    // Unqualified names resolve to the first schema of the search path that has the table.
+    let table = catalog.table(&"events".into()).unwrap();
+    assert_eq!(table.name, "analytics.staging.events".into());
+    assert_eq!(table.columns.len(), 2);
+
+    apply(&mut catalog, "DROP TABLE staging.events;").unwrap();
+    let table = catalog.table(&"EVENTS".into()).unwrap();
+    assert_eq!(table.name, "analytics.public.events".into());
+}
+
+#[test]
+fn test_catalog_alter_and_drop() {
+    let mut catalog = Catalog::new();
+    apply(&mut catalog, "CREATE TABLE s.t (a INTEGER);").unwrap();
+    apply(&mut catalog, "ALTER TABLE s.t ADD COLUMN b TEXT;").unwrap();
+    assert_eq!(
+        catalog.table(&"s.t".into()).unwrap().columns[1].name,
+        "b".into()
+    );
+
+    assert!(apply(&mut catalog, "DROP TABLE t;").is_err());
+    assert!(apply(&mut catalog, "DROP TABLE IF EXISTS t;").is_ok());
+    assert!(apply(&mut catalog, "DROP TABLE s.t;").is_ok());
+    assert!(catalog.table(&"s.t".into()).is_none());
+}
@@ -0,0 +1,429 @@
+use crate::cli::{Status, run};
+use crate::json::Document;
+use crate::parse_sql;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Runs a command with a script on the standard input, and returns its outcome and what it wrote
+/// to its output and error streams.
+fn cli(args: &[&str], stdin: &str) -> (Status, String, String) {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    let status = run(&args, &mut stdin.as_bytes(), &mut out, &mut err);
+    (
+        status,
+        String::from_utf8(out).unwrap(),
+        String::from_utf8(err).unwrap(),
+    )
+}
+
+/// Creates a temporary directory holding the given files, and returns its path.
+fn directory(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = env::temp_dir().join(format!("cli-{}-{name}", std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    for (path, content) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn test_parse() {
+    let (status, out, err) = cli(&["parse", "-o", "sql"], "select a from t; SELECT 1");
+    assert_eq!(status, Status::Success);
+    assert_eq!(out, "SELECT a FROM t;\nSELECT 1;\n");
+    assert_eq!(err, "");
+
+    let (status, out, _) = cli(&["parse"], "SELECT a FROM t");
+    assert_eq!(status, Status::Success);
+    assert_eq!(
+        out,
+        "SELECT\n├── Columns\n│   └── a\n└── From\n    └── Table t\n"
+    );
+}
+
+#[test]
+fn test_parse_json() {
+    let (status, out, _) = cli(&["parse", "-o", "json"], "SELECT 1;\nDELETE FROM t;");
+    assert_eq!(status, Status::Success);
+    // One document per script, on a single line.
+    assert_eq!(out.lines().count(), 1);
+    let document = Document::from_json(&out).unwrap();
+    assert_eq!(document.file.as_deref(), Some("<stdin>"));
+    let spans: Vec<_> = document
+        .statements
+        .iter()
+        .map(|node| node.span.map(|span| (span.start, span.end)))
+        .collect();
+    assert_eq!(spans, vec![Some((0, 9)), Some((10, 24))]);
+    assert_eq!(
+        document.statements[1].statement,
+        parse_sql("DELETE FROM t").unwrap()
+    );
+
+    let (status, out, _) = cli(&["simplify", "-o", "json"], "SELECT 1 + 1");
+    assert_eq!(status, Status::Success);
+    let document = Document::from_json(&out).unwrap();
+    assert_eq!(
+        document.statements[0].statement,
+        parse_sql("SELECT 2").unwrap()
+    );
+}
+
+#[test]
+fn test_format_from_json() {
+    let script = "select a from t where b;\ndelete from t;";
+    let (_, parsed, _) = cli(&["parse", "-o", "json"], script);
+    let (_, simplified, _) = cli(&["simplify", "-o", "json"], "SELECT 1 + 1");
+    let (status, out, _) = cli(&["format", "--from-json"], &(parsed + &simplified));
+    assert_eq!(status, Status::Success);
+    assert_eq!(out, "SELECT a FROM t WHERE b;\nDELETE FROM t;\nSELECT 2;\n");
+
+    // Documents that cannot be read are reported on the line they start on.
+    let (_, parsed, _) = cli(&["parse", "-o", "json"], "SELECT 1");
+    let (status, out, err) = cli(&["format", "--from-json"], &format!("{parsed}\n[]\n"));
+    assert_eq!(status, Status::SyntaxError);
+    assert_eq!(out, "SELECT 1;\n");
+    assert!(
+        err.starts_with("<stdin>:3:1: error: Invalid AST document: "),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_syntax_errors() {
+    let (status, out, err) = cli(
+        &["parse", "--output=sql"],
+        "SELECT 1;\nSELECT 1 +;\nSELECT 2;",
+    );
+    assert_eq!(status, Status::SyntaxError);
+    assert_eq!(out, "SELECT 1;\nSELECT 2;\n");
+    assert_eq!(
+        err,
+        "<stdin>:2:11: error: Unexpected token in expression: Semicolon\n"
+    );
+
+    // Problems are reported as JSON objects, one per line.
+    let (status, out, _) = cli(&["lint", "-o", "json"], "SELECT 'a\"b' FROM;");
+    assert_eq!(status, Status::SyntaxError);
+    assert_eq!(
+        out,
+        r#"{"file":"<stdin>","line":1,"column":18,"severity":"error","kind":"syntax","message":"Unexpected token in table reference: Semicolon"}"#
+            .to_string()
+            + "\n"
+    );
+}
+
+#[test]
+fn test_format() {
+    let script = "-- Active users\nselect id,name from users where id=1; -- by id\n\nSELECT a /* kept */ FROM t\n-- end\n";
+    let (status, out, _) = cli(&["format"], script);
+    assert_eq!(status, Status::Success);
+    assert_eq!(
+        out,
+        "-- Active users\nSELECT id, name FROM users WHERE id = 1; -- by id\nSELECT a /* kept */ FROM t;\n-- end\n"
+    );
+
+    // Formatting is idempotent, so formatted scripts pass the check.
+    let (status, out, _) = cli(&["format", "--check"], &out);
+    assert_eq!(status, Status::Success);
+    assert_eq!(out, "");
+
+    let (status, out, _) = cli(&["format", "--check"], script);
+    assert_eq!(status, Status::Unformatted);
+    assert_eq!(out, "<stdin>:2:1: error: File is not formatted\n");
+
+    // Optimizer hints are not part of the syntax tree, so statements with hints are kept too.
+    let (status, out, _) = cli(&["format"], "select /*+ index(t i) */ a from t");
+    assert_eq!(status, Status::Success);
+    assert_eq!(out, "select /*+ index(t i) */ a from t;\n");
+
+    // Scripts with syntax errors are not formatted.
+    let (status, out, _) = cli(&["format"], "SELECT 1; SELECT FROM;");
+    assert_eq!(status, Status::SyntaxError);
+    assert_eq!(out, "");
+}
+
+#[test]
+fn test_simplify_and_explain() {
+    let (status, out, _) = cli(&["simplify"], "SELECT a FROM t WHERE 1 = 1 AND b");
+    assert_eq!(status, Status::Success);
+    assert_eq!(out, "SELECT a FROM t WHERE b;\n");
+
+    let (status, out, _) = cli(&["explain"], "SELECT a FROM t WHERE b; DELETE FROM t");
+    assert_eq!(status, Status::Success);
+    assert_eq!(
+        out,
+        "Project: a\n└── Filter: b\n    └── Scan t\n\nDelete from t\n└── Scan t\n"
+    );
+}
+
+#[test]
+fn test_rewrites() {
+    let sql = "SELECT COALESCE(a, 0), NULLIF(a, 1) FROM t";
+    let (status, out, _) = cli(&["simplify"], sql);
+    assert_eq!(status, Status::Success);
+    assert_eq!(out, format!("{sql};\n"));
+    let (status, out, _) = cli(&["simplify", "--expand-null-functions"], sql);
+    assert_eq!(status, Status::Success);
+    assert_eq!(
+        out,
+        "SELECT CASE WHEN a IS NOT NULL THEN a ELSE 0 END, CASE WHEN a = 1 THEN NULL ELSE a END FROM t;\n"
+    );
+
+    let (status, out, _) = cli(&["simplify", "--cnf"], "DELETE FROM t WHERE (a AND b) OR c");
+    assert_eq!(status, Status::Success);
+    assert_eq!(out, "DELETE FROM t WHERE (a OR c) AND (b OR c);\n");
+
+    // Decorrelation adds COALESCE calls, which are then expanded.
+    let (status, out, _) = cli(
+        &["simplify", "--decorrelate", "--expand-null-functions"],
+        "SELECT t.x, (SELECT COUNT(*) FROM u WHERE u.a = t.a) FROM t",
+    );
+    assert_eq!(status, Status::Success);
+    assert_eq!(
+        out,
+        "SELECT t.x, CASE WHEN subquery1.value IS NOT NULL THEN subquery1.value ELSE 0 END FROM t \
+         LEFT JOIN (SELECT COUNT(*) AS value, u.a AS key1 FROM u GROUP BY u.a) AS subquery1 \
+         ON t.a = subquery1.key1;\n"
+    );
+
+    let sql = "WITH a AS (SELECT * FROM t), b AS (SELECT 1) SELECT * FROM a, a AS c";
+    let (status, out, _) = cli(&["simplify", "--ctes", "auto"], sql);
+    assert_eq!(status, Status::Success);
+    assert_eq!(
+        out,
+        "WITH a AS (SELECT * FROM t) SELECT * FROM a CROSS JOIN a AS c;\n"
+    );
+    let (status, out, _) = cli(&["simplify", "--ctes=inline"], sql);
+    assert_eq!(status, Status::Success);
+    assert_eq!(
+        out,
+        "SELECT * FROM (SELECT * FROM t) AS a CROSS JOIN (SELECT * FROM t) AS c;\n"
+    );
+    let (status, out, _) = cli(
+        &["simplify", "--extract-ctes"],
+        "SELECT * FROM (SELECT x FROM t) AS p, (SELECT x FROM t) AS q",
+    );
+    assert_eq!(status, Status::Success);
+    assert_eq!(
+        out,
+        "WITH cte1 AS (SELECT x FROM t) SELECT * FROM cte1 AS p CROSS JOIN cte1 AS q;\n"
+    );
+}
+
+#[test]
+fn test_tokens() {
+    let (status, out, _) = cli(&["tokens"], "SELECT\n  1");
+    assert_eq!(status, Status::Success);
+    assert_eq!(
+        out,
+        "<stdin>:1:1\tSelect\n<stdin>:2:3\tNumberLiteral(1.0)\n"
+    );
+
+    let (status, out, err) = cli(&["tokens", "-o", "json"], "x 'abc");
+    assert_eq!(status, Status::SyntaxError);
+    assert_eq!(
+        out,
+        r#"{"file":"<stdin>","line":1,"column":1,"start":0,"end":1,"kind":"Identifier","text":"x"}"#
+            .to_string() + "\n"
+    );
+    assert!(err.contains(r#""kind":"syntax""#), "{err}");
+}
+
+#[test]
+fn test_lint_and_validate() {
+    let dir = directory(
+        "lint",
+        &[
+            (
+                "schema.sql",
+                "CREATE TABLE users (id INT, name TEXT);\nCREATE TABLE orders (id INT, user_id INT);",
+            ),
+            (
+                "queries/a.sql",
+                "SELECT nme FROM users;\nDELETE FROM orders;",
+            ),
+            (
+                "queries/sub/b.sql",
+                "SELECT * FROM usres;\nSELECT x FROM logs;",
+            ),
+            ("queries/notes.txt", "not SQL"),
+        ],
+    );
+    let schema = dir.join("schema.sql");
+    let queries = dir.join("queries");
+    let (schema, queries) = (schema.to_str().unwrap(), queries.to_str().unwrap());
+    let (a, b) = (format!("{queries}/a.sql"), format!("{queries}/sub/b.sql"));
+
+    let (status, out, err) = cli(&["lint", "--schema", schema, queries], "");
+    assert_eq!(err, "");
+    assert_eq!(status, Status::SemanticError);
+    assert_eq!(
+        out,
+        format!(
+            "{a}:1:1: warning: Unknown column 'nme', did you mean 'name'?\n\
+             {a}:2:1: warning: DELETE without WHERE removes every row of orders\n\
+             {b}:1:1: warning: Unknown table 'usres', did you mean 'users'?\n"
+        )
+    );
+
+    // Validation reports every relation missing from the schema, as an error.
+    let (status, out, _) = cli(&["validate", "--schema", schema, &b], "");
+    assert_eq!(status, Status::SemanticError);
+    assert_eq!(
+        out,
+        format!(
+            "{b}:1:1: error: Unknown table 'usres', did you mean 'users'?\n\
+             {b}:2:1: error: Unknown table 'logs'\n"
+        )
+    );
+
+    // Tables created by the checked script are known to the statements that follow.
+    let (status, out, _) = cli(
+        &["validate", "--schema", schema],
+        "CREATE TABLE logs (at INT);\nSELECT at FROM logs;\nUPDATE users SET nme = 1;",
+    );
+    assert_eq!(status, Status::SemanticError);
+    assert_eq!(
+        out,
+        "<stdin>:3:1: error: Unknown column 'nme', did you mean 'name'?\n"
+    );
+}
+
+#[test]
+fn test_search_path() {
+    let dir = directory(
+        "search-path",
+        &[(
+            "schema.sql",
+            "CREATE TABLE shop.sales.orders (id INT);\nCREATE TABLE warehouse.sales.stock (id INT);",
+        )],
+    );
+    let schema = dir.join("schema.sql");
+    let schema = schema.to_str().unwrap();
+
+    let (status, out, _) = cli(&["validate", "--schema", schema], "SELECT id FROM orders;");
+    assert_eq!(status, Status::SemanticError);
+    assert_eq!(out, "<stdin>:1:1: error: Unknown table 'orders'\n");
+
+    let (status, out, _) = cli(
+        &[
+            "validate",
+            "--schema",
+            schema,
+            "--search-path",
+            "shop.public,sales",
+        ],
+        "SELECT id FROM orders;",
+    );
+    assert_eq!(status, Status::Success);
+    assert_eq!(out, "");
+
+    let (status, out, _) = cli(
+        &[
+            "validate",
+            "--schema",
+            schema,
+            "--search-path=warehouse.sales",
+        ],
+        "SELECT id FROM stock;\nSELECT id FROM orders;",
+    );
+    assert_eq!(status, Status::SemanticError);
+    assert_eq!(out, "<stdin>:2:1: error: Unknown table 'orders'\n");
+}
+
+#[test]
+fn test_parser_limits() {
+    let sql = format!("SELECT * FROM t WHERE {};", vec!["a"; 1000].join(" OR "));
+    let (status, out, _) = cli(&["parse", "-o", "sql"], &sql);
+    assert_eq!(status, Status::Success);
+    assert_eq!(out, format!("{sql}\n"));
+
+    let (status, out, err) = cli(&["parse", "-o", "sql", "--max-nodes", "500"], &sql);
+    assert_eq!(status, Status::SyntaxError);
+    assert_eq!(out, "");
+    assert!(
+        err.ends_with("error: Query exceeds the maximum of 500 expression nodes\n"),
+        "{err}"
+    );
+
+    let (status, out, _) = cli(&["lint", "--max-depth=2"], "SELECT (((1)));");
+    assert_eq!(status, Status::SyntaxError);
+    assert!(
+        out.starts_with("<stdin>:1:9: error: Query exceeds the maximum nesting depth of 2\n"),
+        "{out}"
+    );
+
+    let (status, _, err) = cli(&["format", "--max-tokens", "3"], "SELECT a, b FROM t;");
+    assert_eq!(status, Status::SyntaxError);
+    assert_eq!(
+        err,
+        "<stdin>:1:10: error: Query exceeds the maximum of 3 tokens\n"
+    );
+}
+
+#[test]
+fn test_usage_errors() {
+    let (status, out, _) = cli(&["--help"], "");
+    assert_eq!(status, Status::Success);
+    assert!(out.starts_with("Usage: parser"));
+
+    for (args, message) in [
+        (&["compile"][..], "Unknown command 'compile'"),
+        (&["parse", "--verbose"], "Unknown option '--verbose'"),
+        (
+            &["explain", "-o", "json"],
+            "Output format 'json' is not available for explain",
+        ),
+        (&["lint", "--check"], "--check is only available for format"),
+        (
+            &["format", "--from-json", "--check"],
+            "--from-json is only available for format, without --check",
+        ),
+        (
+            &["validate"],
+            "validate needs a schema, given with --schema",
+        ),
+        (&["parse", "--dialect"], "Missing value for --dialect"),
+        (
+            &["parse", "--search-path", "public"],
+            "--search-path is only available for lint and validate",
+        ),
+        (
+            &["lint", "--search-path", "a.b.c"],
+            "Invalid search path 'a.b.c', expected [CATALOG.]SCHEMA[,SCHEMA...]",
+        ),
+        (
+            &["format", "--expand-null-functions"],
+            "Rewrite options are only available for simplify",
+        ),
+        (
+            &["simplify", "--ctes", "lazy"],
+            "Unknown CTE mode 'lazy', expected auto, materialized or inline",
+        ),
+        (
+            &["parse", "--max-nodes", "0"],
+            "Invalid value '0' for --max-nodes, expected a positive number",
+        ),
+        (
+            &["parse", "--max-depth=deep"],
+            "Invalid value 'deep' for --max-depth, expected a positive number",
+        ),
+    ] {
+        let (status, out, err) = cli(args, "");
+        assert_eq!(status, Status::Failure);
+        assert_eq!(out, "");
+        assert_eq!(
+            err,
+            format!("error: {message}\nRun 'parser --help' for usage.\n")
+        );
+    }
+
+    let (status, _, err) = cli(&["parse", "missing.sql"], "");
+    assert_eq!(status, Status::Failure);
+    assert!(err.starts_with("error: Cannot read missing.sql"), "{err}");
+}
@@ -0,0 +1,144 @@
+use crate::catalog::Catalog;
+use crate::completion::SqlHelper;
+use crate::dialect::Dialect;
+use crate::parse_sql;
+
+fn helper(ddl: &[&str]) -> SqlHelper {
+    let mut catalog = Catalog::new();
+    for sql in ddl {
+        catalog.apply(&parse_sql(sql).unwrap()).unwrap();
+    }
+    SqlHelper::new(catalog)
+}
+
+fn shop() -> SqlHelper {
+    helper(&[
+        "CREATE TABLE users (id INTEGER, name TEXT, email TEXT);",
+        "CREATE TABLE orders (id INTEGER, user_id INTEGER, total REAL);",
+    ])
+}
+
+/// Completes the word at the end of the line.
+fn complete(helper: &SqlHelper, line: &str) -> Vec<String> {
+    helper.candidates(line, line.len()).1
+}
+
+#[test]
+fn test_complete_statement_keywords() {
+    let helper = helper(&[]);
+    assert_eq!(
+        complete(&helper, ""),
+        vec![
+            "ALTER", "BEGIN", "COMMIT", "CREATE", "DELETE", "DROP", "INSERT", "ROLLBACK", "SELECT",
+            "UPDATE", "WITH"
+        ]
+    );
+    assert_eq!(helper.candidates("SEL", 3), (0, vec!["SELECT".to_string()]));
+    // Keywords follow the case of the word being typed.
+    assert_eq!(complete(&helper, "sel"), vec!["select"]);
+    assert_eq!(
+        complete(&helper, "CREATE "),
+        vec!["INDEX", "TABLE", "UNIQUE"]
+    );
+}
+
+#[test]
+fn test_complete_keywords_accepted_by_the_parser() {
+    let helper = helper(&[]);
+    assert_eq!(complete(&helper, "SELECT * FROM users WH"), vec!["WHERE"]);
+    assert_eq!(
+        complete(&helper, "SELECT * FROM users "),
+        vec![
+            "AS", "CROSS", "FULL", "GROUP", "HAVING", "INNER", "JOIN", "LEFT", "LIMIT", "NATURAL",
+            "ORDER", "RIGHT", "WHERE"
+        ]
+    );
+    // Non-reserved keywords are only offered where they mean more than a name.
+    assert_eq!(
+        complete(&helper, "SELECT a FROM t ORDER BY a "),
+        vec![
+            "AND", "ASC", "BETWEEN", "DESC", "ILIKE", "IN", "IS", "LIKE", "LIMIT", "NOT", "OR",
+            "SIMILAR"
+        ]
+    );
+    assert_eq!(
+        complete(&helper, "SELECT a FROM t WHERE a NOT "),
+        vec!["BETWEEN", "ILIKE", "IN", "LIKE", "SIMILAR"]
+    );
+    assert!(!complete(&helper, "SELECT ").contains(&"KEY".to_string()));
+}
+
+#[test]
+fn test_complete_current_statement_only() {
+    let helper = helper(&[]);
+    assert_eq!(complete(&helper, "SELECT 1; SELECT * FR"), vec!["FROM"]);
+    assert_eq!(complete(&helper, "SELECT 'a;b', * FR"), vec!["FROM"]);
+}
+
+#[test]
+fn test_complete_in_the_session_dialect() {
+    let mut helper = helper(&[]);
+    assert_eq!(complete(&helper, "SELECT `a` FR"), Vec::<String>::new());
+    helper.dialect = Dialect::MySql;
+    assert_eq!(complete(&helper, "SELECT `a` FR"), vec!["FROM"]);
+}
+
+#[test]
+fn test_complete_table_names() {
+    let helper = shop();
+    assert_eq!(
+        complete(&helper, "SELECT * FROM "),
+        vec!["orders", "users", "LATERAL"]
+    );
+    assert_eq!(
+        complete(&helper, "SELECT * FROM users JOIN o"),
+        vec!["orders"]
+    );
+    assert_eq!(complete(&helper, "INSERT INTO "), vec!["orders", "users"]);
+}
+
+#[test]
+fn test_complete_column_names() {
+    let helper = shop();
+    assert_eq!(
+        complete(&helper, "SELECT * FROM users WHERE "),
+        vec![
+            "email", "id", "name", "CASE", "EXISTS", "FALSE", "NOT", "NULL", "TRUE"
+        ]
+    );
+    assert_eq!(
+        complete(
+            &helper,
+            "SELECT * FROM users u JOIN orders o ON u.id = o.user_id WHERE t"
+        ),
+        vec!["total", "true"]
+    );
+    // Tables written after the cursor are in scope too.
+    assert_eq!(
+        helper.candidates("SELECT na FROM users", 9),
+        (7, vec!["name".to_string()])
+    );
+    // Before the FROM clause, the columns of every table are offered.
+    assert_eq!(complete(&helper, "SELECT e"), vec!["email", "exists"]);
+}
+
+#[test]
+fn test_complete_qualified_column_names() {
+    let helper = shop();
+    assert_eq!(
+        complete(&helper, "SELECT * FROM users u WHERE u."),
+        vec!["email", "id", "name"]
+    );
+    assert_eq!(
+        complete(
+            &helper,
+            "SELECT * FROM users u JOIN orders AS o ON u.id = o."
+        ),
+        vec!["id", "total", "user_id"]
+    );
+    assert_eq!(complete(&helper, "SELECT users.n"), Vec::<String>::new());
+    assert_eq!(
+        complete(&helper, "SELECT * FROM users WHERE users.n"),
+        vec!["name"]
+    );
+}
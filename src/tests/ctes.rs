@@ -0,0 +1,149 @@
+use crate::cte::Materialization;
+use crate::parse_sql;
+
+/// Asserts that the CTEs of the statement optimize to the expected statement.
+fn assert_optimized(sql: &str, default: Materialization, expected: &str) {
+    let mut stmt = parse_sql(sql).unwrap();
+    stmt.optimize_ctes(default);
+    assert_eq!(Ok(&stmt), parse_sql(expected).as_ref(), "{sql}\n{stmt}");
+}
+
+/// Asserts that the repeated derived tables of the statement are extracted as expected.
+fn assert_extracted(sql: &str, expected: &str) {
+    let mut stmt = parse_sql(sql).unwrap();
+    stmt.extract_ctes();
+    assert_eq!(Ok(&stmt), parse_sql(expected).as_ref(), "{sql}\n{stmt}");
+}
+
+#[test]
+fn test_materialized_grammar() {
+    for sql in [
+        "WITH a AS MATERIALIZED (SELECT 1) SELECT * FROM a",
+        "WITH a AS NOT MATERIALIZED (SELECT 1), b AS (SELECT 2) SELECT * FROM a CROSS JOIN b",
+    ] {
+        assert_eq!(parse_sql(sql).unwrap().to_string(), sql);
+    }
+    assert!(parse_sql("WITH a AS NOT (SELECT 1) SELECT * FROM a;").is_err());
+    // MATERIALIZED is not reserved.
+    assert!(parse_sql("SELECT materialized FROM t;").is_ok());
+}
+
+#[test]
+fn test_single_references_are_inlined() {
+    assert_optimized(
+        "WITH a AS (SELECT * FROM t WHERE x > 1) SELECT a.y FROM a WHERE a.x = 2;",
+        Materialization::Auto,
+        "SELECT a.y FROM (SELECT * FROM t WHERE x > 1) AS a WHERE a.x = 2;",
+    );
+    // Into the CTEs that refer to them too, keeping the alias of the reference.
+    assert_optimized(
+        "WITH a AS (SELECT * FROM t), b AS (SELECT * FROM a AS s) SELECT * FROM b JOIN u ON b.id = u.id;",
+        Materialization::Auto,
+        "SELECT * FROM (SELECT * FROM (SELECT * FROM t) AS s) AS b JOIN u ON b.id = u.id;",
+    );
+    // Referenced twice, or volatile.
+    for sql in [
+        "WITH a AS (SELECT * FROM t) SELECT * FROM a, a AS b;",
+        "WITH a AS (SELECT RANDOM() AS r) SELECT * FROM a;",
+    ] {
+        assert_optimized(sql, Materialization::Auto, sql);
+    }
+}
+
+#[test]
+fn test_materialization_is_configurable() {
+    let sql = "WITH a AS (SELECT * FROM t) SELECT * FROM a, a AS b;";
+    assert_optimized(sql, Materialization::Always, sql);
+    assert_optimized(
+        sql,
+        Materialization::Never,
+        "SELECT * FROM (SELECT * FROM t) AS a, (SELECT * FROM t) AS b;",
+    );
+    assert_optimized(
+        "WITH a AS (SELECT * FROM t) SELECT * FROM a;",
+        Materialization::Always,
+        "WITH a AS (SELECT * FROM t) SELECT * FROM a;",
+    );
+    // The query decides over the configuration.
+    assert_optimized(
+        "WITH a AS MATERIALIZED (SELECT * FROM t) SELECT * FROM a;",
+        Materialization::Never,
+        "WITH a AS MATERIALIZED (SELECT * FROM t) SELECT * FROM a;",
+    );
+    assert_optimized(
+        "WITH a AS NOT MATERIALIZED (SELECT * FROM t) SELECT * FROM a JOIN a AS b ON a.id = b.id;",
+        Materialization::Always,
+        "SELECT * FROM (SELECT * FROM t) AS a JOIN (SELECT * FROM t) AS b ON a.id = b.id;",
+    );
+}
+
+#[test]
+fn test_unused_ctes_are_removed() {
+    assert_optimized(
+        "WITH a AS (SELECT 1), b AS (SELECT * FROM a), c AS (SELECT 2) SELECT * FROM c, c AS d;",
+        Materialization::Auto,
+        "WITH c AS (SELECT 2) SELECT * FROM c, c AS d;",
+    );
+    assert_optimized(
+        "WITH a AS MATERIALIZED (SELECT 1) SELECT 2;",
+        Materialization::Auto,
+        "SELECT 2;",
+    );
+    // A recursive CTE that only refers to itself is unused.
+    assert_optimized(
+        "WITH RECURSIVE r AS (SELECT 1 AS n FROM r), s AS (SELECT 2) SELECT * FROM s;",
+        Materialization::Auto,
+        "WITH RECURSIVE s AS (SELECT 2) SELECT * FROM s;",
+    );
+}
+
+#[test]
+fn test_identical_ctes_are_merged() {
+    assert_optimized(
+        "WITH a AS (SELECT * FROM t WHERE x = 1), b AS (SELECT * FROM t WHERE x = 1) \
+         SELECT * FROM a JOIN b ON a.id = b.id;",
+        Materialization::Auto,
+        "WITH a AS (SELECT * FROM t WHERE x = 1) SELECT * FROM a JOIN a AS b ON a.id = b.id;",
+    );
+    // Each copy of a volatile query has its own values.
+    let sql = "WITH a AS (SELECT RANDOM() AS r), b AS (SELECT RANDOM() AS r) \
+               SELECT * FROM a, a AS c, b, b AS d;";
+    assert_optimized(sql, Materialization::Auto, sql);
+}
+
+#[test]
+fn test_repeated_subqueries_are_extracted() {
+    assert_extracted(
+        "SELECT * FROM (SELECT id FROM t WHERE x > 1) AS a \
+         JOIN (SELECT id FROM t WHERE x > 1) AS b ON a.id = b.id;",
+        "WITH cte1 AS (SELECT id FROM t WHERE x > 1) \
+         SELECT * FROM cte1 AS a JOIN cte1 AS b ON a.id = b.id;",
+    );
+    // The largest first, before the first CTE that uses it, and in subqueries too.
+    assert_extracted(
+        "WITH c AS (SELECT * FROM (SELECT * FROM (SELECT 1 AS x) AS i) AS o) \
+         SELECT * FROM c WHERE EXISTS (SELECT 1 FROM (SELECT * FROM (SELECT 1 AS x) AS i) AS p);",
+        "WITH cte1 AS (SELECT * FROM (SELECT 1 AS x) AS i), c AS (SELECT * FROM cte1 AS o) \
+         SELECT * FROM c WHERE EXISTS (SELECT 1 FROM cte1 AS p);",
+    );
+    // Extracting and inlining again gives back the statement.
+    let sql = "SELECT * FROM (SELECT 1 AS x) AS a, (SELECT 1 AS x) AS b;";
+    let mut stmt = parse_sql(sql).unwrap();
+    stmt.extract_ctes();
+    stmt.optimize_ctes(Materialization::Never);
+    assert_eq!(Ok(stmt), parse_sql(sql));
+}
+
+#[test]
+fn test_other_subqueries_are_not_extracted() {
+    for sql in [
+        "SELECT * FROM (SELECT 1 AS x) AS a;",
+        "SELECT * FROM (SELECT RANDOM() AS r) AS a, (SELECT RANDOM() AS r) AS b;",
+        "SELECT * FROM t, LATERAL (SELECT t.x) AS a, LATERAL (SELECT t.x) AS b;",
+        // Correlated with the outer query.
+        "SELECT * FROM t WHERE EXISTS (SELECT 1 FROM (SELECT t.x) AS a) \
+         AND EXISTS (SELECT 1 FROM (SELECT t.x) AS b);",
+    ] {
+        assert_extracted(sql, sql);
+    }
+}
@@ -5,15 +5,15 @@ sql_test!(
     test_create_table_basic,
     "CREATE TABLE users (id INTEGER PRIMARY KEY, name VARCHAR(255) NOT NULL);",
     Statement::CreateTable(CreateTableStatement {
-        table: "users".to_string(),
+        table: "users".into(),
         columns: vec![
             ColumnDef {
-                name: "id".to_string(),
+                name: "id".into(),
                 data_type: DataType::Integer,
                 constraints: vec![ColumnConstraint::PrimaryKey]
             },
             ColumnDef {
-                name: "name".to_string(),
+                name: "name".into(),
                 data_type: DataType::Varchar(Some(255)),
                 constraints: vec![ColumnConstraint::NotNull]
             }
@@ -26,19 +26,19 @@ sql_test!(
     test_create_table_with_foreign_key,
     "CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INTEGER REFERENCES users(id));",
     Statement::CreateTable(CreateTableStatement {
-        table: "orders".to_string(),
+        table: "orders".into(),
         columns: vec![
             ColumnDef {
-                name: "id".to_string(),
+                name: "id".into(),
                 data_type: DataType::Integer,
                 constraints: vec![ColumnConstraint::PrimaryKey]
             },
             ColumnDef {
-                name: "user_id".to_string(),
+                name: "user_id".into(),
                 data_type: DataType::Integer,
                 constraints: vec![ColumnConstraint::ForeignKey {
-                    table: "users".to_string(),
-                    column: "id".to_string()
+                    table: "users".into(),
+                    column: "id".into()
                 }]
             }
         ],
@@ -50,18 +50,18 @@ sql_test!(
     test_create_table_with_check_constraint,
     "CREATE TABLE products (id INTEGER, price DECIMAL(10, 2) CHECK (price > 0));",
     Statement::CreateTable(CreateTableStatement {
-        table: "products".to_string(),
+        table: "products".into(),
         columns: vec![
             ColumnDef {
-                name: "id".to_string(),
+                name: "id".into(),
                 data_type: DataType::Integer,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "price".to_string(),
+                name: "price".into(),
                 data_type: DataType::Decimal(Some(10), Some(2)),
                 constraints: vec![ColumnConstraint::Check(Expr::BinaryOp {
-                    left: Box::new(Expr::Identifier("price".to_string())),
+                    left: Box::new(Expr::Identifier("price".into())),
                     op: BinaryOperator::Gt,
                     right: Box::new(Expr::Number(0.0))
                 })]
@@ -75,17 +75,17 @@ sql_test!(
     test_create_table_with_default,
     "CREATE TABLE posts (id INTEGER, status VARCHAR(20) DEFAULT 'draft');",
     Statement::CreateTable(CreateTableStatement {
-        table: "posts".to_string(),
+        table: "posts".into(),
         columns: vec![
             ColumnDef {
-                name: "id".to_string(),
+                name: "id".into(),
                 data_type: DataType::Integer,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "status".to_string(),
+                name: "status".into(),
                 data_type: DataType::Varchar(Some(20)),
-                constraints: vec![ColumnConstraint::Default(Expr::String("draft".to_string()))]
+                constraints: vec![ColumnConstraint::Default(Expr::String("draft".into()))]
             }
         ],
         constraints: vec![]
@@ -96,22 +96,22 @@ sql_test!(
     test_create_table_with_table_constraints,
     "CREATE TABLE order_items (order_id INTEGER, product_id INTEGER, PRIMARY KEY (order_id, product_id));",
     Statement::CreateTable(CreateTableStatement {
-        table: "order_items".to_string(),
+        table: "order_items".into(),
         columns: vec![
             ColumnDef {
-                name: "order_id".to_string(),
+                name: "order_id".into(),
                 data_type: DataType::Integer,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "product_id".to_string(),
+                name: "product_id".into(),
                 data_type: DataType::Integer,
                 constraints: vec![]
             }
         ],
         constraints: vec![TableConstraint::PrimaryKey(vec![
-            "order_id".to_string(),
-            "product_id".to_string()
+            "order_id".into(),
+            "product_id".into()
         ])]
     })
 );
@@ -120,9 +120,9 @@ sql_test!(
     test_alter_table_add_column,
     "ALTER TABLE users ADD COLUMN email VARCHAR(255);",
     Statement::AlterTable(AlterTableStatement {
-        table: "users".to_string(),
+        table: "users".into(),
         action: AlterAction::AddColumn(ColumnDef {
-            name: "email".to_string(),
+            name: "email".into(),
             data_type: DataType::Varchar(Some(255)),
             constraints: vec![]
         })
@@ -133,8 +133,8 @@ sql_test!(
     test_alter_table_drop_column,
     "ALTER TABLE users DROP COLUMN email;",
     Statement::AlterTable(AlterTableStatement {
-        table: "users".to_string(),
-        action: AlterAction::DropColumn("email".to_string())
+        table: "users".into(),
+        action: AlterAction::DropColumn("email".into())
     })
 );
 
@@ -142,9 +142,9 @@ sql_test!(
     test_alter_table_alter_column_type,
     "ALTER TABLE products ALTER COLUMN price DECIMAL(12, 2);",
     Statement::AlterTable(AlterTableStatement {
-        table: "products".to_string(),
+        table: "products".into(),
         action: AlterAction::AlterColumn(AlterColumnStatement {
-            name: "price".to_string(),
+            name: "price".into(),
             action: AlterColumnAction::SetDataType(DataType::Decimal(Some(12), Some(2)))
         })
     })
@@ -154,10 +154,10 @@ sql_test!(
     test_alter_table_set_default,
     "ALTER TABLE orders ALTER COLUMN status SET DEFAULT 'pending';",
     Statement::AlterTable(AlterTableStatement {
-        table: "orders".to_string(),
+        table: "orders".into(),
         action: AlterAction::AlterColumn(AlterColumnStatement {
-            name: "status".to_string(),
-            action: AlterColumnAction::SetDefault(Expr::String("pending".to_string()))
+            name: "status".into(),
+            action: AlterColumnAction::SetDefault(Expr::String("pending".into()))
         })
     })
 );
@@ -166,9 +166,9 @@ sql_test!(
     test_alter_table_drop_default,
     "ALTER TABLE orders ALTER COLUMN status DROP DEFAULT;",
     Statement::AlterTable(AlterTableStatement {
-        table: "orders".to_string(),
+        table: "orders".into(),
         action: AlterAction::AlterColumn(AlterColumnStatement {
-            name: "status".to_string(),
+            name: "status".into(),
             action: AlterColumnAction::DropDefault
         })
     })
@@ -178,7 +178,7 @@ sql_test!(
     test_drop_table_simple,
     "DROP TABLE users;",
     Statement::DropTable(DropTableStatement {
-        table: "users".to_string(),
+        table: "users".into(),
         if_exists: false,
         cascade: false
     })
@@ -188,7 +188,7 @@ sql_test!(
     test_drop_table_if_exists,
     "DROP TABLE IF EXISTS temp_data;",
     Statement::DropTable(DropTableStatement {
-        table: "temp_data".to_string(),
+        table: "temp_data".into(),
         if_exists: true,
         cascade: false
     })
@@ -198,7 +198,7 @@ sql_test!(
     test_drop_table_cascade,
     "DROP TABLE orders CASCADE;",
     Statement::DropTable(DropTableStatement {
-        table: "orders".to_string(),
+        table: "orders".into(),
         if_exists: false,
         cascade: true
     })
@@ -208,10 +208,10 @@ sql_test!(
     test_create_index_simple,
     "CREATE INDEX idx_user_email ON users (email);",
     Statement::CreateIndex(CreateIndexStatement {
-        name: "idx_user_email".to_string(),
-        table: "users".to_string(),
+        name: "idx_user_email".into(),
+        table: "users".into(),
         columns: vec![IndexColumn {
-            name: "email".to_string(),
+            name: "email".into(),
             order: None
         }],
         unique: false,
@@ -223,10 +223,10 @@ sql_test!(
     test_create_unique_index,
     "CREATE UNIQUE INDEX idx_user_username ON users (username);",
     Statement::CreateIndex(CreateIndexStatement {
-        name: "idx_user_username".to_string(),
-        table: "users".to_string(),
+        name: "idx_user_username".into(),
+        table: "users".into(),
         columns: vec![IndexColumn {
-            name: "username".to_string(),
+            name: "username".into(),
             order: None
         }],
         unique: true,
@@ -238,15 +238,15 @@ sql_test!(
     test_create_index_multi_column,
     "CREATE INDEX idx_orders_composite ON orders (user_id ASC, created_at DESC);",
     Statement::CreateIndex(CreateIndexStatement {
-        name: "idx_orders_composite".to_string(),
-        table: "orders".to_string(),
+        name: "idx_orders_composite".into(),
+        table: "orders".into(),
         columns: vec![
             IndexColumn {
-                name: "user_id".to_string(),
+                name: "user_id".into(),
                 order: Some(OrderDirection::Asc)
             },
             IndexColumn {
-                name: "created_at".to_string(),
+                name: "created_at".into(),
                 order: Some(OrderDirection::Desc)
             }
         ],
@@ -301,90 +301,90 @@ sql_test!(
         col_uuid UUID
     );",
     Statement::CreateTable(CreateTableStatement {
-        table: "test_types".to_string(),
+        table: "test_types".into(),
         columns: vec![
             ColumnDef {
-                name: "col_int".to_string(),
+                name: "col_int".into(),
                 data_type: DataType::Integer,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_bigint".to_string(),
+                name: "col_bigint".into(),
                 data_type: DataType::BigInt,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_smallint".to_string(),
+                name: "col_smallint".into(),
                 data_type: DataType::SmallInt,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_decimal".to_string(),
+                name: "col_decimal".into(),
                 data_type: DataType::Decimal(Some(10), Some(2)),
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_numeric".to_string(),
+                name: "col_numeric".into(),
                 data_type: DataType::Numeric(Some(8), Some(4)),
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_real".to_string(),
+                name: "col_real".into(),
                 data_type: DataType::Real,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_double".to_string(),
+                name: "col_double".into(),
                 data_type: DataType::Double,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_varchar".to_string(),
+                name: "col_varchar".into(),
                 data_type: DataType::Varchar(Some(100)),
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_char".to_string(),
+                name: "col_char".into(),
                 data_type: DataType::Char(Some(10)),
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_text".to_string(),
+                name: "col_text".into(),
                 data_type: DataType::Text,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_date".to_string(),
+                name: "col_date".into(),
                 data_type: DataType::Date,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_time".to_string(),
+                name: "col_time".into(),
                 data_type: DataType::Time,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_timestamp".to_string(),
+                name: "col_timestamp".into(),
                 data_type: DataType::Timestamp,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_boolean".to_string(),
+                name: "col_boolean".into(),
                 data_type: DataType::Boolean,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_json".to_string(),
+                name: "col_json".into(),
                 data_type: DataType::Json,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_jsonb".to_string(),
+                name: "col_jsonb".into(),
                 data_type: DataType::Jsonb,
                 constraints: vec![]
             },
             ColumnDef {
-                name: "col_uuid".to_string(),
+                name: "col_uuid".into(),
                 data_type: DataType::Uuid,
                 constraints: vec![]
             }
@@ -0,0 +1,158 @@
+use crate::parse_sql;
+
+/// Asserts that the statement decorrelates to the expected one, which the printer writes back as
+/// SQL that parses to the same statement.
+fn assert_decorrelated(sql: &str, expected: &str) {
+    let mut stmt = parse_sql(sql).unwrap();
+    stmt.decorrelate();
+    assert_eq!(Ok(&stmt), parse_sql(expected).as_ref(), "{sql}\n{stmt}");
+    assert_eq!(parse_sql(&stmt.to_string()), Ok(stmt));
+}
+
+#[test]
+fn test_exists_becomes_semi_join() {
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.a = t.a AND u.b > 0);",
+        "SELECT t.x FROM t JOIN (SELECT DISTINCT u.a AS key1 FROM u WHERE u.b > 0) AS subquery1 \
+         ON t.a = subquery1.key1;",
+    );
+    // The derived table does not add columns to `*`.
+    assert_decorrelated(
+        "SELECT * FROM t, v WHERE t.y = 1 AND EXISTS (SELECT * FROM u WHERE t.a = u.a AND v.b = u.b);",
+        "SELECT t.*, v.* FROM t, v \
+         JOIN (SELECT DISTINCT u.a AS key1, u.b AS key2 FROM u) AS subquery1 \
+         ON t.a = subquery1.key1 AND v.b = subquery1.key2 WHERE t.y = 1;",
+    );
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.b > 0);",
+        "SELECT t.x FROM t CROSS JOIN (SELECT DISTINCT 1 AS present FROM u WHERE u.b > 0) AS subquery1;",
+    );
+    // The table of the subquery shadows the outer one, so it is not correlated.
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE EXISTS (SELECT 1 FROM u AS t WHERE t.a = t.b);",
+        "SELECT t.x FROM t \
+         CROSS JOIN (SELECT DISTINCT 1 AS present FROM u AS t WHERE t.a = t.b) AS subquery1;",
+    );
+}
+
+#[test]
+fn test_in_becomes_semi_join() {
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE t.y IN (SELECT u.y FROM u WHERE u.a = t.a);",
+        "SELECT t.x FROM t \
+         JOIN (SELECT DISTINCT u.y AS value, u.a AS key1 FROM u) AS subquery1 \
+         ON t.y = subquery1.value AND t.a = subquery1.key1;",
+    );
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE t.y IN (SELECT y FROM u) AND t.x > 1;",
+        "SELECT t.x FROM t JOIN (SELECT DISTINCT y AS value FROM u) AS subquery1 \
+         ON t.y = subquery1.value WHERE t.x > 1;",
+    );
+}
+
+#[test]
+fn test_not_exists_becomes_anti_join() {
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE NOT EXISTS (SELECT 1 FROM u WHERE u.a = t.a);",
+        "SELECT t.x FROM t LEFT JOIN (SELECT DISTINCT u.a AS key1 FROM u) AS subquery1 \
+         ON t.a = subquery1.key1 WHERE subquery1.key1 IS NULL;",
+    );
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE NOT EXISTS (SELECT 1 FROM u);",
+        "SELECT t.x FROM t LEFT JOIN (SELECT DISTINCT 1 AS present FROM u) AS subquery1 ON TRUE \
+         WHERE subquery1.present IS NULL;",
+    );
+}
+
+#[test]
+fn test_not_in_is_null_aware() {
+    // No row matches the value, and either the subquery is empty or it has no NULL and the value
+    // is not NULL.
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE t.y NOT IN (SELECT u.y FROM u);",
+        "SELECT t.x FROM t \
+         LEFT JOIN (SELECT DISTINCT u.y AS value FROM u) AS subquery1 ON t.y = subquery1.value \
+         CROSS JOIN (SELECT COUNT(*) AS total, COUNT(u.y) AS non_null FROM u) AS subquery2 \
+         WHERE subquery1.value IS NULL AND (subquery2.total = 0 \
+         OR t.y IS NOT NULL AND subquery2.total = subquery2.non_null);",
+    );
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE t.y NOT IN (SELECT u.y FROM u WHERE u.a = t.a);",
+        "SELECT t.x FROM t \
+         LEFT JOIN (SELECT DISTINCT u.y AS value, u.a AS key1 FROM u) AS subquery1 \
+         ON t.y = subquery1.value AND t.a = subquery1.key1 \
+         LEFT JOIN (SELECT COUNT(*) AS total, COUNT(u.y) AS non_null, u.a AS key1 FROM u \
+         GROUP BY u.a) AS subquery2 ON t.a = subquery2.key1 \
+         WHERE subquery1.value IS NULL AND (subquery2.total IS NULL \
+         OR t.y IS NOT NULL AND subquery2.total = subquery2.non_null);",
+    );
+    // The value would be evaluated twice.
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE RANDOM() NOT IN (SELECT u.y FROM u);",
+        "SELECT t.x FROM t WHERE RANDOM() NOT IN (SELECT u.y FROM u);",
+    );
+}
+
+#[test]
+fn test_scalar_aggregates_become_grouped_joins() {
+    assert_decorrelated(
+        "SELECT t.x, (SELECT MAX(u.y) FROM u WHERE u.a = t.a) AS m FROM t;",
+        "SELECT t.x, subquery1.value AS m FROM t \
+         LEFT JOIN (SELECT MAX(u.y) AS value, u.a AS key1 FROM u GROUP BY u.a) AS subquery1 \
+         ON t.a = subquery1.key1;",
+    );
+    // COUNT is 0, not NULL, for the keys without rows.
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE (SELECT COUNT(*) FROM u WHERE t.a = u.a AND u.b = 1) > 2;",
+        "SELECT t.x FROM t \
+         LEFT JOIN (SELECT COUNT(*) AS value, u.a AS key1 FROM u WHERE u.b = 1 GROUP BY u.a) \
+         AS subquery1 ON t.a = subquery1.key1 WHERE COALESCE(subquery1.value, 0) > 2;",
+    );
+}
+
+#[test]
+fn test_other_subqueries_are_kept() {
+    for sql in [
+        // Correlated through something else than an equality.
+        "SELECT t.x FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.a > t.a);",
+        "SELECT t.x FROM t WHERE EXISTS (SELECT t.y FROM u WHERE u.a = t.a);",
+        // Limited or grouped subqueries.
+        "SELECT t.x FROM t WHERE t.y IN (SELECT u.y FROM u WHERE u.a = t.a LIMIT 1);",
+        "SELECT t.x FROM t WHERE EXISTS (SELECT u.a FROM u WHERE u.a = t.a GROUP BY u.a);",
+        // Not a filter of the query.
+        "SELECT t.x FROM t WHERE t.y = 1 OR EXISTS (SELECT 1 FROM u WHERE u.a = t.a);",
+        // The outer query is grouped, or the subquery is not a single aggregate.
+        "SELECT COUNT(*), (SELECT MAX(u.y) FROM u WHERE u.a = t.a) FROM t;",
+        "SELECT (SELECT MAX(u.y) + 1 FROM u WHERE u.a = t.a) FROM t;",
+        "SELECT (SELECT MAX(u.y) FROM u) FROM t;",
+        // `*` would select the columns of the derived table.
+        "SELECT * FROM t JOIN v USING (id) WHERE EXISTS (SELECT 1 FROM u WHERE u.a = t.a);",
+    ] {
+        assert_decorrelated(sql, sql);
+    }
+}
+
+#[test]
+fn test_nested_subqueries() {
+    // The inner subquery is unnested first, inside the outer one.
+    assert_decorrelated(
+        "SELECT t.x FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.a = t.a \
+         AND EXISTS (SELECT 1 FROM v WHERE v.b = u.b));",
+        "SELECT t.x FROM t JOIN (SELECT DISTINCT u.a AS key1 FROM u \
+         JOIN (SELECT DISTINCT v.b AS key1 FROM v) AS subquery1 ON u.b = subquery1.key1) \
+         AS subquery2 ON t.a = subquery2.key1;",
+    );
+    // The inner subquery refers to the outermost query, so it cannot be a derived table of the
+    // one around it, which is then correlated in other ways than equalities.
+    let sql = "SELECT t.x FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.a = t.a \
+               AND EXISTS (SELECT 1 FROM v WHERE v.b = t.b));";
+    assert_decorrelated(sql, sql);
+    let sql = "SELECT t.x FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.a = t.a \
+               AND u.b IN (SELECT v.b FROM v WHERE EXISTS (SELECT 1 FROM w WHERE w.c = t.c)));";
+    assert_decorrelated(sql, sql);
+    assert_decorrelated(
+        "DELETE FROM t WHERE id IN (SELECT w.id FROM w WHERE EXISTS (SELECT 1 FROM u WHERE u.a = w.a));",
+        "DELETE FROM t WHERE id IN (SELECT w.id FROM w \
+         JOIN (SELECT DISTINCT u.a AS key1 FROM u) AS subquery1 ON w.a = subquery1.key1);",
+    );
+}
@@ -5,9 +5,9 @@ sql_test!(
     test_delete_basic,
     "DELETE FROM users WHERE id = 1;",
     Statement::Delete(DeleteStatement {
-        table: "users".to_string(),
+        table: "users".into(),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("id".to_string())),
+            left: Box::new(Expr::Identifier("id".into())),
             op: BinaryOperator::Eq,
             right: Box::new(Expr::Number(1.0))
         })
@@ -18,7 +18,7 @@ sql_test!(
     test_delete_no_where,
     "DELETE FROM logs;",
     Statement::Delete(DeleteStatement {
-        table: "logs".to_string(),
+        table: "logs".into(),
         where_clause: None
     })
 );
@@ -27,18 +27,18 @@ sql_test!(
     test_delete_where,
     "DELETE FROM orders WHERE status = 'cancelled' AND created_at < '2024-01-01';",
     Statement::Delete(DeleteStatement {
-        table: "orders".to_string(),
+        table: "orders".into(),
         where_clause: Some(Expr::BinaryOp {
             left: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Identifier("status".to_string())),
+                left: Box::new(Expr::Identifier("status".into())),
                 op: BinaryOperator::Eq,
-                right: Box::new(Expr::String("cancelled".to_string()))
+                right: Box::new(Expr::String("cancelled".into()))
             }),
             op: BinaryOperator::And,
             right: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Identifier("created_at".to_string())),
+                left: Box::new(Expr::Identifier("created_at".into())),
                 op: BinaryOperator::Lt,
-                right: Box::new(Expr::String("2024-01-01".to_string()))
+                right: Box::new(Expr::String("2024-01-01".into()))
             })
         })
     })
@@ -0,0 +1,111 @@
+use crate::equivalence::MAX_PAIRED_COLUMNS;
+use crate::simplify_sql;
+use crate::tests::utils::assert_simplified_sql;
+
+#[test]
+fn test_constants_are_propagated() {
+    assert_simplified_sql(
+        "SELECT * FROM a, b WHERE a.id = b.id AND a.id = 42;",
+        "SELECT * FROM a, b WHERE a.id = b.id AND a.id = 42 AND b.id = 42;",
+    );
+    assert_simplified_sql(
+        "SELECT * FROM t WHERE x = y AND y = z AND 'a' = z;",
+        "SELECT * FROM t WHERE x = y AND y = z AND 'a' = z AND x = 'a' AND y = 'a';",
+    );
+    // The derived predicates are merged with the ranges of their columns.
+    assert_simplified_sql(
+        "SELECT * FROM a, b WHERE a.id = b.id AND a.id = 42 AND b.id > 50;",
+        "SELECT * FROM a, b WHERE FALSE;",
+    );
+    assert_simplified_sql(
+        "SELECT * FROM t WHERE x = y AND x = 1 AND y = 2;",
+        "SELECT * FROM t WHERE FALSE;",
+    );
+}
+
+#[test]
+fn test_transitive_equalities() {
+    assert_simplified_sql(
+        "SELECT * FROM t WHERE a = b AND b = c;",
+        "SELECT * FROM t WHERE a = b AND b = c AND a = c;",
+    );
+    assert_simplified_sql(
+        "SELECT * FROM t WHERE a = b AND c = a AND b = c;",
+        "SELECT * FROM t WHERE a = b AND c = a AND b = c;",
+    );
+    // Equalities under OR do not hold for every row.
+    assert_simplified_sql(
+        "SELECT * FROM t WHERE a = b AND (b = c OR d);",
+        "SELECT * FROM t WHERE a = b AND (b = c OR d);",
+    );
+    // Comparisons with NULL are never TRUE, so they say nothing about the column.
+    assert_simplified_sql(
+        "SELECT * FROM t WHERE a = b AND a = NULL;",
+        "SELECT * FROM t WHERE a = b AND NULL;",
+    );
+}
+
+#[test]
+fn test_inner_join_conditions() {
+    assert_simplified_sql(
+        "SELECT * FROM a JOIN b ON a.id = b.id JOIN c ON b.id = c.id;",
+        "SELECT * FROM a JOIN b ON a.id = b.id JOIN c ON b.id = c.id WHERE a.id = c.id;",
+    );
+    assert_simplified_sql(
+        "SELECT * FROM a JOIN b ON a.id = b.id WHERE b.id = 7;",
+        "SELECT * FROM a JOIN b ON a.id = b.id WHERE b.id = 7 AND a.id = 7;",
+    );
+    // Inner joins on the preserved side of an outer join.
+    assert_simplified_sql(
+        "SELECT * FROM a JOIN b ON a.id = b.id LEFT JOIN c ON c.x = 1 WHERE a.id = 2;",
+        "SELECT * FROM a JOIN b ON a.id = b.id LEFT JOIN c ON c.x = 1 \
+         WHERE a.id = 2 AND b.id = 2;",
+    );
+}
+
+#[test]
+fn test_outer_joins_are_not_crossed() {
+    // b.id is NULL in the rows that the LEFT JOIN fills, whatever a.id is.
+    assert_simplified_sql(
+        "SELECT * FROM a LEFT JOIN b ON a.id = b.id WHERE a.id = 42;",
+        "SELECT * FROM a LEFT JOIN b ON a.id = b.id WHERE a.id = 42;",
+    );
+    assert_simplified_sql(
+        "SELECT * FROM a RIGHT JOIN b ON a.id = b.id WHERE b.id = 42;",
+        "SELECT * FROM a RIGHT JOIN b ON a.id = b.id WHERE b.id = 42;",
+    );
+    assert_simplified_sql(
+        "SELECT * FROM a FULL JOIN b ON a.id = b.id AND a.id = 1;",
+        "SELECT * FROM a FULL JOIN b ON a.id = b.id AND a.id = 1;",
+    );
+    // Nor the joins on the nullable side.
+    assert_simplified_sql(
+        "SELECT * FROM a LEFT JOIN (b JOIN c ON b.id = c.id) ON a.id = b.id WHERE a.x = c.id;",
+        "SELECT * FROM a LEFT JOIN (b JOIN c ON b.id = c.id) ON a.id = b.id WHERE a.x = c.id;",
+    );
+    // Conditions of the WHERE clause hold for every row, even on the nullable side.
+    assert_simplified_sql(
+        "SELECT * FROM a LEFT JOIN b ON a.id = b.id WHERE a.x = b.x AND b.x = 3;",
+        "SELECT * FROM a LEFT JOIN b ON a.id = b.id WHERE a.x = b.x AND b.x = 3 AND a.x = 3;",
+    );
+}
+
+#[test]
+fn test_subqueries_and_large_classes() {
+    assert_simplified_sql(
+        "SELECT * FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.a = t.a AND t.a = 1);",
+        "SELECT * FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.a = t.a AND t.a = 1 AND u.a = 1);",
+    );
+
+    // A chain of equalities on many columns is not paired column by column.
+    let chain = |count: usize| {
+        (1..count)
+            .map(|i| format!("c{} = c{i}", i - 1))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    };
+    let sql = format!("SELECT * FROM t WHERE {};", chain(MAX_PAIRED_COLUMNS + 1));
+    assert_simplified_sql(&sql, &sql);
+    let simplified = simplify_sql(&format!("SELECT * FROM t WHERE {};", chain(3))).unwrap();
+    assert!(simplified.to_string().ends_with("AND c0 = c2"));
+}
@@ -0,0 +1,155 @@
+use crate::eval::Value;
+use crate::functions::{self, Function, FunctionRegistry, Type, parse_declaration, registry};
+use crate::parse_sql;
+use crate::plan::plan;
+use crate::tests::utils::assert_simplified;
+
+#[test]
+fn test_string_functions_are_folded() {
+    assert_simplified("UPPER('abc')", "'ABC'");
+    assert_simplified("lower('ÀBC')", "'àbc'");
+    assert_simplified("LENGTH('xyz')", "3");
+    assert_simplified("CHAR_LENGTH('héllo')", "5");
+    assert_simplified("TRIM('  a b  ')", "'a b'");
+    assert_simplified("LTRIM('  a ')", "'a '");
+    assert_simplified("RTRIM('  a ')", "'  a'");
+    assert_simplified("CONCAT('a', 'b', 'c')", "'abc'");
+    assert_simplified("REPLACE('a-b-c', '-', '+')", "'a+b+c'");
+    assert_simplified("REPLACE('abc', '', 'x')", "'abc'");
+    assert_simplified("UPPER(CONCAT('a', 'b')) || 'c'", "'ABc'");
+}
+
+#[test]
+fn test_substring() {
+    assert_simplified("SUBSTRING('hello', 2, 3)", "'ell'");
+    assert_simplified("SUBSTRING('hello', 3)", "'llo'");
+    assert_simplified("SUBSTRING('hello', 0, 2)", "'h'");
+    assert_simplified("SUBSTRING('hello', 4, 10)", "'lo'");
+    assert_simplified("SUBSTRING('hello', 2, -1)", "SUBSTRING('hello', 2, -1)");
+}
+
+#[test]
+fn test_numeric_functions_are_folded() {
+    assert_simplified("ABS(-3)", "3");
+    assert_simplified("ROUND(2.567, 2)", "2.57");
+    assert_simplified("ROUND(1.005, 2)", "1.01");
+    assert_simplified("ROUND(-2.5)", "-3");
+    assert_simplified("ROUND(-0.4)", "0");
+    assert_simplified("ROUND(2.5, 3)", "2.5");
+    assert_simplified("CEIL(1.2) + FLOOR(1.8)", "3");
+    assert_simplified("MOD(7, 3)", "1");
+    assert_simplified("POWER(2, 10)", "1024");
+    assert_simplified("SQRT(16)", "4");
+    assert_simplified("SIGN(-7)", "-1");
+}
+
+#[test]
+fn test_null_handling_functions() {
+    assert_simplified("COALESCE(NULL, 5)", "5");
+    assert_simplified("COALESCE(NULL, NULL)", "NULL");
+    assert_simplified("NULLIF(1, 1)", "NULL");
+    assert_simplified("NULLIF(1, 2)", "1");
+    assert_simplified("NULLIF(NULL, 2)", "NULL");
+    assert_simplified("NULLIF(1, NULL)", "1");
+    assert_simplified("UPPER(NULL)", "NULL");
+    assert_simplified("ROUND(2.5, NULL)", "NULL");
+}
+
+#[test]
+fn test_calls_that_cannot_be_folded() {
+    // Volatile functions and aggregates.
+    assert_simplified("RANDOM()", "RANDOM()");
+    assert_simplified("NOW()", "NOW()");
+    assert_simplified("MAX(1)", "MAX(1)");
+    assert_simplified("COUNT(DISTINCT 1)", "COUNT(DISTINCT 1)");
+    // Arguments that are not literals.
+    assert_simplified("UPPER(name)", "UPPER(name)");
+    assert_simplified("ABS(1 + 2)", "3");
+    assert_simplified("ABS(a + 2)", "ABS(a + 2)");
+    // Unknown functions, wrong arities and types, and errors are left to the database.
+    assert_simplified("MY_FUNCTION(1)", "MY_FUNCTION(1)");
+    assert_simplified("UPPER('a', 'b')", "UPPER('a', 'b')");
+    assert_simplified("ABS('a')", "ABS('a')");
+    assert_simplified("SQRT(-1)", "SQRT(-1)");
+    assert_simplified("MOD(1, 0)", "MOD(1, 0)");
+    assert_simplified("COALESCE(NULL, 1, 'a')", "COALESCE(NULL, 1, 'a')");
+    assert_simplified("CONCAT('a', NULL)", "CONCAT('a', NULL)");
+}
+
+#[test]
+fn test_registry_signatures() {
+    let registry = FunctionRegistry::builtin();
+    let round = registry.get("round").unwrap();
+    assert_eq!(round.args, vec![Type::Number, Type::Number]);
+    assert_eq!(round.returns, Type::Number);
+    assert!(round.accepts_arg_count(1) && round.accepts_arg_count(2));
+    assert!(!round.accepts_arg_count(3));
+    assert!(registry.get("COALESCE").unwrap().accepts_arg_count(10));
+    assert!(!registry.get("random").unwrap().deterministic);
+    assert!(registry.is_aggregate("sum"));
+    assert!(!registry.is_aggregate("abs"));
+    assert!(registry.get("unknown").is_none());
+}
+
+#[test]
+fn test_custom_functions() {
+    functions::register(
+        Function::new("TEST_DOUBLE", &[Type::Number], Type::Number).with_eval(|args| match args {
+            [Value::Number(n)] => Some(Value::Number(n * 2.0)),
+            _ => None,
+        }),
+    );
+    functions::register(
+        Function::new("TEST_IS_EMPTY", &[Type::Text], Type::Boolean).with_eval(|args| match args {
+            [Value::String(s)] => Some(Value::Boolean(s.is_empty())),
+            _ => None,
+        }),
+    );
+    functions::register(Function::new("TEST_MEDIAN", &[Type::Number], Type::Number).aggregate());
+    assert_simplified("test_double(21)", "42");
+    assert_simplified("TEST_DOUBLE(NULL)", "NULL");
+    assert_simplified("TEST_IS_EMPTY('') AND a", "a");
+    assert!(registry().is_aggregate("test_median"));
+
+    // Custom aggregates are planned like the built-in ones.
+    let stmt = parse_sql("SELECT TEST_MEDIAN(x) FROM t;").unwrap();
+    assert!(plan(&stmt).to_string().contains("Aggregate"));
+}
+
+#[test]
+fn test_parse_declaration() {
+    let function = parse_declaration("is_valid(text, Number) RETURNS boolean").unwrap();
+    assert_eq!(function.name, "IS_VALID");
+    assert_eq!(function.args, vec![Type::Text, Type::Number]);
+    assert_eq!(function.returns, Type::Boolean);
+    assert!(function.deterministic && !function.aggregate);
+    assert!(function.eval.is_none());
+
+    let function = parse_declaration(" now_utc() returns TIMESTAMP volatile").unwrap();
+    assert!(function.args.is_empty() && !function.deterministic);
+    assert!(
+        parse_declaration("median(number) RETURNS number AGGREGATE")
+            .unwrap()
+            .aggregate
+    );
+
+    for declaration in [
+        "f",
+        "f(number)",
+        "(number) RETURNS number",
+        "f(number,) RETURNS number",
+        "f(integer) RETURNS number",
+        "f(number) RETURNS",
+        "f(number) RETURNS number STABLE",
+        "f(number) RETURNS number VOLATILE AGGREGATE",
+        "f g(number) RETURNS number",
+    ] {
+        assert_eq!(
+            parse_declaration(declaration).map(|function| function.name),
+            Err(format!(
+                "Invalid function declaration '{declaration}', expected \
+                 NAME([TYPE, ...]) RETURNS TYPE [VOLATILE | AGGREGATE]"
+            ))
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use crate::dialect::Dialect;
+use crate::highlight::{Style, styles};
+
+/// Styles of a line, with the text of each piece.
+fn styled(line: &str, pos: usize) -> Vec<(&str, Style)> {
+    styles(line, pos, Dialect::default())
+        .into_iter()
+        .map(|(span, style)| (&line[span.start..span.end], style))
+        .collect()
+}
+
+#[test]
+fn test_highlight_tokens() {
+    assert_eq!(
+        styled("SELECT name, 42 FROM users WHERE note = 'a;b' || E'\\n'", 0),
+        vec![
+            ("SELECT", Style::Keyword),
+            ("name", Style::Identifier),
+            (",", Style::Operator),
+            ("42", Style::Number),
+            ("FROM", Style::Keyword),
+            ("users", Style::Identifier),
+            ("WHERE", Style::Keyword),
+            ("note", Style::Identifier),
+            ("=", Style::Operator),
+            ("'a;b'", Style::String),
+            ("||", Style::Operator),
+            ("E'\\n'", Style::String),
+        ]
+    );
+}
+
+#[test]
+fn test_highlight_in_the_session_dialect() {
+    let quoted = |dialect| {
+        styles("SELECT `a b`", 0, dialect)
+            .into_iter()
+            .map(|(_, style)| style)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        quoted(Dialect::MySql),
+        vec![Style::Keyword, Style::Identifier]
+    );
+    assert_ne!(
+        quoted(Dialect::Ansi),
+        vec![Style::Keyword, Style::Identifier]
+    );
+}
+
+#[test]
+fn test_highlight_comments_and_errors() {
+    assert_eq!(
+        styled("SELECT /*+ index(t) */ 1 -- one\n, /* two */ 'open", 0),
+        vec![
+            ("SELECT", Style::Keyword),
+            ("/*+ index(t) */", Style::Comment),
+            ("1", Style::Number),
+            ("-- one", Style::Comment),
+            (",", Style::Operator),
+            ("/* two */", Style::Comment),
+            ("'open", Style::Error),
+        ]
+    );
+}
+
+#[test]
+fn test_highlight_matching_brackets() {
+    let line = "SELECT f((a), b)";
+    let brackets = |pos| {
+        styles(line, pos, Dialect::default())
+            .into_iter()
+            .filter(|(_, style)| *style == Style::Bracket)
+            .map(|(span, _)| span.start)
+            .collect::<Vec<_>>()
+    };
+    // Under the cursor, or just before it.
+    assert_eq!(brackets(8), vec![8, 15]);
+    assert_eq!(brackets(16), vec![8, 15]);
+    assert_eq!(brackets(9), vec![9, 11]);
+    assert_eq!(brackets(11), vec![9, 11]);
+    assert_eq!(brackets(3), Vec::<usize>::new());
+    // Unbalanced parentheses are not highlighted.
+    assert_eq!(
+        styles("SELECT (1", 7, Dialect::default())
+            .iter()
+            .filter(|(_, style)| *style == Style::Bracket)
+            .count(),
+        0
+    );
+}
@@ -0,0 +1,142 @@
+use crate::ast::*;
+use crate::catalog::Catalog;
+use crate::dialect::{Dialect, IdentNormalization};
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::token::{Token, Word};
+use crate::visitor::Visitor;
+use crate::{parse_sql, sql_test};
+
+fn parse_with_dialect(sql: &str, dialect: Dialect) -> Result<Statement, String> {
+    let mut parser = Parser::new(Lexer::with_dialect(sql, dialect));
+    parser.visit()
+}
+
+fn select_column(expr: Expr) -> Statement {
+    Statement::Select(SelectStatement {
+        distinct: false,
+        columns: vec![SelectItem::ExprWithAlias { expr, alias: None }],
+        from: Some(TableReference::Table {
+            name: "t".into(),
+            alias: None,
+        }),
+        where_clause: None,
+        group_by: vec![],
+        having: None,
+        order_by: vec![],
+        limit: None,
+    })
+}
+
+sql_test!(
+    test_quoted_identifier_keeps_quote_style,
+    r#"SELECT "MyCol" FROM t;"#,
+    select_column(Expr::Identifier(Ident::with_quote("MyCol", '"')))
+);
+
+sql_test!(
+    test_quoted_identifier_escaped_quotes,
+    r#"SELECT "a ""quoted"" name" FROM t;"#,
+    select_column(Expr::Identifier(Ident::with_quote(
+        r#"a "quoted" name"#,
+        '"'
+    )))
+);
+
+sql_test!(
+    test_quoted_keyword_is_identifier,
+    r#"SELECT "select" FROM t;"#,
+    select_column(Expr::Identifier(Ident::with_quote("select", '"')))
+);
+
+#[test]
+fn test_unquoted_identifier_keeps_case() {
+    let mut lexer = Lexer::new("MyCol");
+    assert_eq!(lexer.next_token(), Token::Identifier("MyCol".into()));
+}
+
+#[test]
+fn test_backtick_identifiers() {
+    let stmt = parse_with_dialect("SELECT `my col` FROM t;", Dialect::MySql).unwrap();
+    assert_eq!(
+        stmt,
+        select_column(Expr::Identifier(Ident::with_quote("my col", '`')))
+    );
+
+    let mut lexer = Lexer::new("`a```");
+    assert_ne!(lexer.next_token(), Token::Identifier(Word::with_quote("a`", '`')));
+    let mut lexer = Lexer::with_dialect("`a```", Dialect::MySql);
+    assert_eq!(lexer.next_token(), Token::Identifier(Word::with_quote("a`", '`')));
+}
+
+#[test]
+fn test_bracket_identifiers() {
+    let stmt = parse_with_dialect("SELECT [order id]]s] FROM t;", Dialect::MsSql).unwrap();
+    assert_eq!(
+        stmt,
+        select_column(Expr::Identifier(Ident::with_quote("order id]s", '[')))
+    );
+}
+
+#[test]
+fn test_ident_display_round_trips() {
+    for (sql, dialect) in [
+        (r#""a ""b"" c""#, Dialect::Ansi),
+        ("`a``b`", Dialect::MySql),
+        ("[a]]b]", Dialect::MsSql),
+        ("MyCol", Dialect::Ansi),
+    ] {
+        let mut lexer = Lexer::with_dialect(sql, dialect);
+        assert_eq!(lexer.next_token().to_string(), sql);
+    }
+}
+
+#[test]
+fn test_ident_normalization() {
+    let unquoted = Ident::new("MyCol");
+    let quoted = Ident::with_quote("MyCol", '"');
+
+    assert_eq!(unquoted.normalized(IdentNormalization::Upper), "MYCOL");
+    assert_eq!(unquoted.normalized(IdentNormalization::Lower), "mycol");
+    assert_eq!(unquoted.normalized(IdentNormalization::Preserve), "MyCol");
+    for policy in [
+        IdentNormalization::Upper,
+        IdentNormalization::Lower,
+        IdentNormalization::Preserve,
+    ] {
+        assert_eq!(quoted.normalized(policy), "MyCol");
+    }
+}
+
+#[test]
+fn test_catalog_ansi_case_folding() {
+    let mut catalog = Catalog::new();
+    catalog
+        .apply(&parse_sql("CREATE TABLE users (id INTEGER);").unwrap())
+        .unwrap();
+
+    assert!(catalog.table(&"USERS".into()).is_some());
+    assert!(catalog.table(&ObjectName(vec![Ident::with_quote("USERS", '"')])).is_some());
+    assert!(catalog.table(&ObjectName(vec![Ident::with_quote("users", '"')])).is_none());
+}
+
+#[test]
+fn test_catalog_normalization_policies() {
+    let quoted_lower = ObjectName(vec![Ident::with_quote("users", '"')]);
+
+    let mut catalog = Catalog::new();
+    catalog.set_normalization(IdentNormalization::Lower);
+    catalog
+        .apply(&parse_sql("CREATE TABLE Users (id INTEGER);").unwrap())
+        .unwrap();
+    assert!(catalog.table(&quoted_lower).is_some());
+
+    let mut catalog = Catalog::new();
+    catalog.set_normalization(IdentNormalization::Preserve);
+    catalog
+        .apply(&parse_sql("CREATE TABLE Users (id INTEGER);").unwrap())
+        .unwrap();
+    assert!(catalog.table(&"Users".into()).is_some());
+    assert!(catalog.table(&"users".into()).is_none());
+    assert!(catalog.table(&quoted_lower).is_none());
+}
@@ -5,8 +5,8 @@ sql_test!(
     test_insert_basic,
     "INSERT INTO my_table(col1, col2) VALUES (1, 2);",
     Statement::Insert(InsertStatement {
-        table: "my_table".to_string(),
-        columns: Some(vec!["col1".to_string(), "col2".to_string()]),
+        table: "my_table".into(),
+        columns: Some(vec!["col1".into(), "col2".into()]),
         values: Values::Values(vec![vec![Expr::Number(1.0), Expr::Number(2.0)]])
     })
 );
@@ -15,19 +15,19 @@ sql_test!(
     test_insert_with_select,
     "INSERT INTO my_table(col1, col2) SELECT (col1, col2) FROM my_other_table;",
     Statement::Insert(InsertStatement {
-        table: "my_table".to_string(),
-        columns: Some(vec!["col1".to_string(), "col2".to_string()]),
+        table: "my_table".into(),
+        columns: Some(vec!["col1".into(), "col2".into()]),
         values: Values::Query(Box::new(SelectStatement {
             distinct: false,
             columns: vec![SelectItem::ExprWithAlias {
                 expr: Expr::List(vec![
-                    Expr::Identifier("col1".to_string()),
-                    Expr::Identifier("col2".to_string())
+                    Expr::Identifier("col1".into()),
+                    Expr::Identifier("col2".into())
                 ]),
                 alias: None
             }],
             from: Some(TableReference::Table {
-                name: "my_other_table".to_string(),
+                name: "my_other_table".into(),
                 alias: None
             }),
             where_clause: None,
@@ -43,16 +43,16 @@ sql_test!(
     test_insert_multiple_rows,
     "INSERT INTO users (name, email) VALUES ('John', 'john@example.com'), ('Jane', 'jane@example.com');",
     Statement::Insert(InsertStatement {
-        table: "users".to_string(),
-        columns: Some(vec!["name".to_string(), "email".to_string()]),
+        table: "users".into(),
+        columns: Some(vec!["name".into(), "email".into()]),
         values: Values::Values(vec![
             vec![
-                Expr::String("John".to_string()),
-                Expr::String("john@example.com".to_string())
+                Expr::String("John".into()),
+                Expr::String("john@example.com".into())
             ],
             vec![
-                Expr::String("Jane".to_string()),
-                Expr::String("jane@example.com".to_string())
+                Expr::String("Jane".into()),
+                Expr::String("jane@example.com".into())
             ]
         ])
     })
@@ -62,12 +62,22 @@ sql_test!(
     test_insert_without_columns,
     "INSERT INTO users VALUES (1, 'John', 'john@example.com');",
     Statement::Insert(InsertStatement {
-        table: "users".to_string(),
+        table: "users".into(),
         columns: None,
         values: Values::Values(vec![vec![
             Expr::Number(1.0),
-            Expr::String("John".to_string()),
-            Expr::String("john@example.com".to_string())
+            Expr::String("John".into()),
+            Expr::String("john@example.com".into())
         ]])
     })
 );
+
+sql_test!(
+    test_insert_qualified_table,
+    "INSERT INTO sales.orders (id) VALUES (1);",
+    Statement::Insert(InsertStatement {
+        table: "sales.orders".into(),
+        columns: Some(vec!["id".into()]),
+        values: Values::Values(vec![vec![Expr::Number(1.0)]])
+    })
+);
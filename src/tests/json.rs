@@ -0,0 +1,147 @@
+use crate::json::{Document, FORMAT, StatementNode, VERSION};
+use crate::parse_sql;
+use crate::token::Span;
+use serde_json::{Value, json};
+
+fn document(sql: &str) -> Document {
+    Document::new(None, vec![parse_sql(sql).unwrap().into()])
+}
+
+#[test]
+fn test_round_trip() {
+    for sql in [
+        "SELECT DISTINCT u.name AS n, count(DISTINCT o.id) FROM users AS u LEFT JOIN orders AS o ON u.id = o.user_id WHERE u.age BETWEEN 18 AND 65 GROUP BY u.name HAVING count(*) > 1 ORDER BY n DESC LIMIT 10;",
+        "SELECT * EXCLUDE (a) REPLACE (b * 2 AS b) RENAME (c AS d), t.* EXCEPT (e) FROM t NATURAL JOIN s, LATERAL (SELECT 1) AS x;",
+        "SELECT a FROM t JOIN s USING (id) CROSS JOIN r WHERE a IN (1, 2) AND NOT EXISTS (SELECT 1 FROM q) OR b IS NOT NULL;",
+        "SELECT CASE x WHEN 1 THEN 'one' ELSE E'a\\nb' END, N'n', X'ff00', B'101', U&'\\0041', $tag$ $ $tag$, -1.5, TRUE, NULL;",
+        "SELECT \"Quoted \"\" name\", 2.5e3 FROM cat.sch.tbl;",
+        "WITH RECURSIVE r AS (SELECT 1 AS n) SELECT n FROM r;",
+        "WITH a AS MATERIALIZED (SELECT 1), b AS NOT MATERIALIZED (SELECT 2) SELECT * FROM a, b;",
+        "INSERT INTO t (a, b) VALUES (1, 'x'), (2, 'y');",
+        "INSERT INTO t SELECT * FROM s;",
+        "UPDATE t SET a = a + 1 WHERE id = 2;",
+        "DELETE FROM t WHERE a LIKE 'x%';",
+        "SELECT a LIKE 'x!%' ESCAPE '!', a ILIKE 'x%', a SIMILAR TO 'x|y', a ~* '^x' FROM t;",
+        "CREATE TABLE t (id INTEGER PRIMARY KEY, name VARCHAR(20) NOT NULL DEFAULT 'x', price DECIMAL(10, 2) CHECK (price > 0), owner INTEGER REFERENCES users(id), data JSONB, kind my_type, UNIQUE (name), FOREIGN KEY (owner) REFERENCES users (id));",
+        "ALTER TABLE t ALTER COLUMN a DECIMAL(12, 2);",
+        "ALTER TABLE t ALTER COLUMN a SET DEFAULT 'x';",
+        "ALTER TABLE t ADD CONSTRAINT PRIMARY KEY (id);",
+        "DROP TABLE IF EXISTS t CASCADE;",
+        "CREATE UNIQUE INDEX IF NOT EXISTS i ON t (a DESC, b);",
+        "COMMIT;",
+    ] {
+        let Ok(stmt) = parse_sql(sql) else {
+            panic!("{sql} does not parse");
+        };
+        let json = Document::new(None, vec![stmt.clone().into()]).to_json();
+        let document = Document::from_json(&json).unwrap();
+        assert_eq!(document.statements[0].statement, stmt, "{json}");
+    }
+}
+
+#[test]
+fn test_documented_format() {
+    // Changing this document means changing the format, and incrementing its version.
+    let json = document("SELECT a.id, NOT b AS n FROM a WHERE id IS NULL;").to_json();
+    let value: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "format": "sql-ast",
+            "version": 3,
+            "statements": [{
+                "statement": {
+                    "type": "Select",
+                    "value": {
+                        "distinct": false,
+                        "columns": [
+                            {
+                                "type": "ExprWithAlias",
+                                "value": {
+                                    "expr": {
+                                        "type": "QualifiedIdentifier",
+                                        "value": { "table": [{ "value": "a" }], "column": { "value": "id" } }
+                                    },
+                                    "alias": null
+                                }
+                            },
+                            {
+                                "type": "ExprWithAlias",
+                                "value": {
+                                    "expr": {
+                                        "type": "UnaryOp",
+                                        "value": { "op": "Not", "expr": { "type": "Identifier", "value": { "value": "b" } } }
+                                    },
+                                    "alias": { "value": "n" }
+                                }
+                            }
+                        ],
+                        "from": { "type": "Table", "value": { "name": [{ "value": "a" }], "alias": null } },
+                        "where_clause": {
+                            "type": "BinaryOp",
+                            "value": {
+                                "left": { "type": "Identifier", "value": { "value": "id" } },
+                                "op": "Is",
+                                "right": { "type": "Null" }
+                            }
+                        },
+                        "group_by": [],
+                        "having": null,
+                        "order_by": [],
+                        "limit": null
+                    }
+                }
+            }]
+        })
+    );
+}
+
+#[test]
+fn test_spans_and_file_are_optional() {
+    let mut document = document("SELECT 1;");
+    assert!(!document.to_json().contains("span"));
+
+    document.file = Some("q.sql".to_string());
+    document.statements[0].span = Some(Span { start: 0, end: 9 });
+    let json = document.to_json();
+    assert!(json.contains(r#""file":"q.sql""#), "{json}");
+    assert!(json.contains(r#""span":{"start":0,"end":9}"#), "{json}");
+    assert_eq!(Document::from_json(&json).unwrap(), document);
+}
+
+#[test]
+fn test_format_and_version_are_checked() {
+    let json = document("SELECT 1;").to_json();
+    assert!(json.starts_with(&format!(r#"{{"format":"{FORMAT}","version":{VERSION},"#)));
+
+    let newer = json.replace(r#""version":3"#, r#""version":4"#);
+    assert_eq!(
+        Document::from_json(&newer),
+        Err("Unsupported version 4 of the AST format, expected 3".to_string())
+    );
+    let other = json.replace("sql-ast", "sql-plan");
+    assert_eq!(
+        Document::from_json(&other),
+        Err("Unknown document format 'sql-plan', expected 'sql-ast'".to_string())
+    );
+    let error = Document::from_json(
+        r#"{"format":"sql-ast","version":3,"statements":[{"statement":{"type":"Merge"}}]}"#,
+    )
+    .unwrap_err();
+    assert!(
+        error.starts_with("Invalid AST document: unknown variant `Merge`"),
+        "{error}"
+    );
+    assert!(Document::from_json("[]").is_err());
+}
+
+#[test]
+fn test_statement_node_from_statement() {
+    let stmt = parse_sql("BEGIN;").unwrap();
+    let node = StatementNode::from(stmt.clone());
+    assert_eq!(node.span, None);
+    assert_eq!(
+        serde_json::to_value(&node).unwrap(),
+        json!({ "statement": { "type": "Transaction", "value": "Begin" } })
+    );
+}
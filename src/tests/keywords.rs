@@ -0,0 +1,264 @@
+use crate::ast::*;
+use crate::parse_sql;
+use crate::token::{KEYWORDS, Token, lookup_keyword};
+
+fn keywords(reserved: bool) -> Vec<String> {
+    let mut words: Vec<String> = KEYWORDS
+        .iter()
+        .filter(|(_, token)| token.is_non_reserved_keyword() != reserved)
+        .map(|(word, _)| word.to_lowercase())
+        .collect();
+    words.sort();
+    words
+}
+
+fn select_item(stmt: &Statement) -> &SelectItem {
+    match stmt {
+        Statement::Select(select) => &select.columns[0],
+        other => panic!("Expected a SELECT statement, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_keyword_classification() {
+    for word in [
+        "key", "index", "database", "schema", "lock", "limit", "asc", "desc", "column", "default",
+        "end",
+    ] {
+        assert!(
+            lookup_keyword(word).unwrap().is_non_reserved_keyword(),
+            "{word}"
+        );
+    }
+    for word in ["select", "from", "case", "table", "natural", "add", "check"] {
+        assert!(
+            !lookup_keyword(word).unwrap().is_non_reserved_keyword(),
+            "{word}"
+        );
+        assert!(lookup_keyword(word).unwrap().is_keyword(), "{word}");
+    }
+    assert!(!Token::Identifier("value".into()).is_keyword());
+}
+
+#[test]
+fn test_non_reserved_keywords_as_identifiers() {
+    for word in keywords(false).iter().map(String::as_str) {
+        let stmt = parse_sql(&format!("SELECT {word} FROM {word};"))
+            .unwrap_or_else(|e| panic!("{word}: {e}"));
+        assert_eq!(
+            stmt,
+            Statement::Select(SelectStatement {
+                distinct: false,
+                columns: vec![SelectItem::ExprWithAlias {
+                    expr: Expr::Identifier(word.into()),
+                    alias: None,
+                }],
+                from: Some(TableReference::Table {
+                    name: word.into(),
+                    alias: None,
+                }),
+                where_clause: None,
+                group_by: vec![],
+                having: None,
+                order_by: vec![],
+                limit: None,
+            }),
+            "{word}"
+        );
+
+        let stmt = parse_sql(&format!("CREATE TABLE {word} ({word} INTEGER);"))
+            .unwrap_or_else(|e| panic!("{word}: {e}"));
+        let Statement::CreateTable(create) = stmt else {
+            panic!("{word}: expected CREATE TABLE");
+        };
+        assert_eq!(create.table, word.into());
+        assert_eq!(create.columns[0].name, Ident::new(word));
+
+        let stmt = parse_sql(&format!("SELECT {word}(x) AS {word} FROM t AS {word};"))
+            .unwrap_or_else(|e| panic!("{word}: {e}"));
+        assert_eq!(
+            select_item(&stmt),
+            &SelectItem::ExprWithAlias {
+                expr: Expr::FunctionCall {
+                    name: word.into(),
+                    args: vec![Expr::Identifier("x".into())],
+                    distinct: false,
+                },
+                alias: Some(word.into()),
+            },
+            "{word}"
+        );
+
+        // LIMIT may start the next clause and ILIKE may be an operator, so they are never taken
+        // as aliases without AS.
+        if word != "limit" && word != "ilike" {
+            parse_sql(&format!("SELECT x {word} FROM t {word};"))
+                .unwrap_or_else(|e| panic!("{word}: {e}"));
+        }
+
+        parse_sql(&format!("UPDATE t SET {word} = 1 WHERE {word} > 0;"))
+            .unwrap_or_else(|e| panic!("{word}: {e}"));
+        parse_sql(&format!("INSERT INTO t ({word}) VALUES (1);"))
+            .unwrap_or_else(|e| panic!("{word}: {e}"));
+    }
+}
+
+#[test]
+fn test_column_default_and_end_as_identifiers() {
+    let stmt =
+        parse_sql("SELECT CASE end WHEN 1 THEN column ELSE default END end FROM t;").unwrap();
+    assert_eq!(
+        select_item(&stmt),
+        &SelectItem::ExprWithAlias {
+            expr: Expr::Case {
+                operand: Some(Box::new(Expr::Identifier("end".into()))),
+                when_clauses: vec![WhenClause {
+                    condition: Expr::Number(1.0),
+                    result: Expr::Identifier("column".into()),
+                }],
+                else_clause: Some(Box::new(Expr::Identifier("default".into()))),
+            },
+            alias: Some("end".into()),
+        }
+    );
+
+    let stmt =
+        parse_sql("CREATE TABLE t (default INTEGER DEFAULT 0, end INTEGER DEFAULT end);").unwrap();
+    let Statement::CreateTable(create) = stmt else {
+        panic!("Expected CREATE TABLE");
+    };
+    assert_eq!(create.columns[0].name, "default".into());
+    assert_eq!(
+        create.columns[0].constraints,
+        vec![ColumnConstraint::Default(Expr::Number(0.0))]
+    );
+    assert_eq!(
+        create.columns[1].constraints,
+        vec![ColumnConstraint::Default(Expr::Identifier("end".into()))]
+    );
+
+    for (sql, name) in [
+        ("ALTER TABLE t ALTER COLUMN column SET DEFAULT 1;", "column"),
+        ("ALTER TABLE t ALTER column SET DEFAULT 1;", "column"),
+        (
+            "ALTER TABLE t ALTER COLUMN default DROP DEFAULT;",
+            "default",
+        ),
+        ("ALTER TABLE t MODIFY end DROP DEFAULT;", "end"),
+    ] {
+        let Statement::AlterTable(alter) = parse_sql(sql).unwrap_or_else(|e| panic!("{sql}: {e}"))
+        else {
+            panic!("{sql}: expected ALTER TABLE");
+        };
+        let AlterAction::AlterColumn(column) = alter.action else {
+            panic!("{sql}: expected ALTER COLUMN");
+        };
+        assert_eq!(column.name, name.into(), "{sql}");
+    }
+    for sql in [
+        "ALTER TABLE t ADD COLUMN column TEXT;",
+        "ALTER TABLE t DROP COLUMN end;",
+    ] {
+        parse_sql(sql).unwrap_or_else(|e| panic!("{sql}: {e}"));
+    }
+
+    // END still closes a CASE expression where an operand is expected.
+    assert!(parse_sql("SELECT CASE WHEN a THEN END;").is_err());
+}
+
+#[test]
+fn test_reserved_keywords_are_not_identifiers() {
+    for word in keywords(true).iter().map(String::as_str) {
+        assert!(
+            parse_sql(&format!("CREATE TABLE {word} (id INTEGER);")).is_err(),
+            "{word}"
+        );
+        assert!(
+            parse_sql(&format!("CREATE TABLE t ({word} INTEGER);")).is_err(),
+            "{word}"
+        );
+        assert!(
+            parse_sql(&format!("SELECT x FROM {word};")).is_err(),
+            "{word}"
+        );
+    }
+}
+
+#[test]
+fn test_any_keyword_as_label_or_quoted() {
+    for &(word, _) in KEYWORDS {
+        let stmt = parse_sql(&format!("SELECT t.{word} AS {word} FROM t;"))
+            .unwrap_or_else(|e| panic!("{word}: {e}"));
+        assert_eq!(
+            select_item(&stmt),
+            &SelectItem::ExprWithAlias {
+                expr: Expr::QualifiedIdentifier {
+                    table: "t".into(),
+                    column: word.into(),
+                },
+                alias: Some(word.into()),
+            },
+            "{word}"
+        );
+
+        let stmt = parse_sql(&format!("SELECT \"{word}\" FROM t;"))
+            .unwrap_or_else(|e| panic!("{word}: {e}"));
+        assert_eq!(
+            select_item(&stmt),
+            &SelectItem::ExprWithAlias {
+                expr: Expr::Identifier(Ident::with_quote(word, '"')),
+                alias: None,
+            },
+            "{word}"
+        );
+    }
+}
+
+#[test]
+fn test_keyword_identifier_keeps_case() {
+    let stmt = parse_sql("SELECT Key, value FROM kv LIMIT 1;").unwrap();
+    let Statement::Select(select) = stmt else {
+        panic!("Expected a SELECT statement");
+    };
+    assert_eq!(
+        select.columns[0],
+        SelectItem::ExprWithAlias {
+            expr: Expr::Identifier("Key".into()),
+            alias: None,
+        }
+    );
+    assert_eq!(
+        select.from,
+        Some(TableReference::Table {
+            name: "kv".into(),
+            alias: None,
+        })
+    );
+    assert_eq!(select.limit, Some(1));
+}
+
+#[test]
+fn test_keyword_lookup() {
+    assert_eq!(lookup_keyword("select"), Some(Token::Select));
+    assert_eq!(lookup_keyword("SeLeCt"), Some(Token::Select));
+    assert_eq!(lookup_keyword("TRANSACTION"), Some(Token::Transaction));
+    for word in [
+        "",
+        "selects",
+        "sel",
+        "value",
+        "transactions",
+        "séléct",
+        "a_very_long_identifier_name",
+    ] {
+        assert_eq!(lookup_keyword(word), None, "{word}");
+    }
+    for &(word, ref token) in KEYWORDS {
+        assert_eq!(lookup_keyword(word).as_ref(), Some(token), "{word}");
+        assert_eq!(
+            lookup_keyword(&word.to_uppercase()).as_ref(),
+            Some(token),
+            "{word}"
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use crate::lexer::Lexer;
+use crate::parse_sql;
+use crate::token::{Location, Token, Word};
+use std::borrow::Cow;
+
+fn tokens(sql: &str) -> Vec<Token<'_>> {
+    let mut lexer = Lexer::new(sql);
+    let mut tokens = Vec::new();
+    loop {
+        match lexer.next_token() {
+            Token::Eof => return tokens,
+            token => tokens.push(token),
+        }
+    }
+}
+
+fn error(message: &str, line: usize, column: usize) -> Token<'static> {
+    Token::Error {
+        message: message.to_string(),
+        location: Location { line, column },
+    }
+}
+
+#[test]
+fn test_block_comments_are_skipped() {
+    assert_eq!(
+        tokens("SELECT /* a comment */ 1 /* another\n one */;"),
+        vec![Token::Select, Token::NumberLiteral(1.0), Token::Semicolon]
+    );
+}
+
+#[test]
+fn test_nested_block_comments() {
+    assert_eq!(
+        tokens("1 /* outer /* inner */ still outer */ + 2"),
+        vec![
+            Token::NumberLiteral(1.0),
+            Token::Plus,
+            Token::NumberLiteral(2.0)
+        ]
+    );
+}
+
+#[test]
+fn test_division_and_star_are_not_comments() {
+    assert_eq!(
+        tokens("a / b * c"),
+        vec![
+            Token::Identifier("a".into()),
+            Token::Slash,
+            Token::Identifier("b".into()),
+            Token::Star,
+            Token::Identifier("c".into()),
+        ]
+    );
+}
+
+#[test]
+fn test_hint_tokens() {
+    assert_eq!(
+        tokens("SELECT /*+ INDEX(t idx) */ a"),
+        vec![
+            Token::Select,
+            Token::Hint("INDEX(t idx)"),
+            Token::Identifier("a".into()),
+        ]
+    );
+    assert_eq!(Token::Hint("NO_MERGE").to_string(), "/*+ NO_MERGE */");
+}
+
+#[test]
+fn test_hints_are_ignored_by_the_parser() {
+    assert_eq!(
+        parse_sql("SELECT /*+ PARALLEL(4) */ a FROM t;"),
+        parse_sql("SELECT a FROM t;")
+    );
+}
+
+#[test]
+fn test_unterminated_string() {
+    assert_eq!(
+        tokens("SELECT 'abc"),
+        vec![Token::Select, error("Unterminated string literal", 1, 8)]
+    );
+}
+
+#[test]
+fn test_unterminated_quoted_identifier() {
+    assert_eq!(
+        tokens("SELECT a,\n  \"b"),
+        vec![
+            Token::Select,
+            Token::Identifier("a".into()),
+            Token::Comma,
+            error("Unterminated quoted identifier", 2, 3),
+        ]
+    );
+}
+
+#[test]
+fn test_unterminated_block_comment() {
+    assert_eq!(
+        tokens("a /* /* */"),
+        vec![
+            Token::Identifier("a".into()),
+            error("Unterminated block comment", 1, 3)
+        ]
+    );
+}
+
+#[test]
+fn test_stray_characters() {
+    assert_eq!(
+        tokens("a @ b"),
+        vec![
+            Token::Identifier("a".into()),
+            error("Unexpected character '@'", 1, 3),
+            Token::Identifier("b".into()),
+        ]
+    );
+    assert_eq!(
+        tokens("a | b"),
+        vec![
+            Token::Identifier("a".into()),
+            error("Unexpected character '|', did you mean '||'?", 1, 3),
+            Token::Identifier("b".into()),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_number() {
+    assert_eq!(tokens("1.2.3"), vec![error("Invalid number '1.2.3'", 1, 1)]);
+}
+
+#[test]
+fn test_lexical_errors_fail_parsing() {
+    assert_eq!(
+        parse_sql("SELECT a FROM t\nWHERE b = 'open;"),
+        Err("Unterminated string literal at line 2, column 11".to_string())
+    );
+    assert_eq!(
+        parse_sql("SELECT a FROM t WHERE b = 1 # 2;"),
+        Err("Unexpected character '#' at line 1, column 29".to_string())
+    );
+    assert_eq!(
+        parse_sql("SELECT a | b FROM t;"),
+        Err("Unexpected character '|', did you mean '||'? at line 1, column 10".to_string())
+    );
+}
+
+#[test]
+fn test_trailing_input_is_rejected() {
+    assert!(parse_sql("SELECT a FROM t;").is_ok());
+    assert!(parse_sql("SELECT a FROM t").is_ok());
+    assert!(parse_sql("SELECT a FROM t WHERE b = 1 c d;").is_err());
+    assert!(parse_sql("SELECT a FROM t; @").is_err());
+}
+
+#[test]
+fn test_tokens_borrow_the_input() {
+    let mut lexer = Lexer::new(r#"name 'plain' 'it''s' "col" $$body$$"#);
+    assert!(matches!(
+        lexer.next_token(),
+        Token::Identifier(Word {
+            value: Cow::Borrowed("name"),
+            ..
+        })
+    ));
+    assert!(matches!(
+        lexer.next_token(),
+        Token::StringLiteral(Cow::Borrowed("plain"))
+    ));
+    // Doubled quotes have to be resolved, so only then is the text copied.
+    assert!(matches!(lexer.next_token(), Token::StringLiteral(Cow::Owned(s)) if s == "it's"));
+    assert!(matches!(
+        lexer.next_token(),
+        Token::Identifier(Word {
+            value: Cow::Borrowed("col"),
+            quote_style: Some('"')
+        })
+    ));
+    assert_eq!(
+        lexer.next_token(),
+        Token::DollarQuotedString {
+            tag: "",
+            value: "body"
+        }
+    );
+}
+
+#[test]
+fn test_token_spans() {
+    let sql = "SELECT  Key, 'x'";
+    let mut lexer = Lexer::new(sql);
+    let mut texts = Vec::new();
+    loop {
+        let (token, span) = lexer.next_token_with_span();
+        if token == Token::Eof {
+            break;
+        }
+        texts.push(lexer.text(span));
+    }
+    assert_eq!(texts, vec!["SELECT", "Key", ",", "'x'"]);
+}
+
+#[test]
+fn test_non_ascii_input() {
+    assert_eq!(
+        tokens("SELECT café, 'naïve' FROM «t»"),
+        vec![
+            Token::Select,
+            Token::Identifier("café".into()),
+            Token::Comma,
+            Token::StringLiteral("naïve".into()),
+            Token::From,
+            error("Unexpected character '«'", 1, 27),
+            Token::Identifier("t".into()),
+            error("Unexpected character '»'", 1, 29),
+        ]
+    );
+    assert_eq!(
+        parse_sql("SELECT 'é' FROM t WHERE ü = 1 @"),
+        Err("Unexpected character '@' at line 1, column 31".to_string())
+    );
+}
+
+#[test]
+fn test_lookahead_after_not() {
+    assert!(parse_sql("SELECT * FROM t WHERE a NOT IN (1) AND NOT b AND c NOT LIKE 'x';").is_ok());
+    assert!(parse_sql("SELECT * FROM t WHERE a NOT /*+ hint */ BETWEEN 1 AND 2;").is_ok());
+    assert!(parse_sql("SELECT * FROM t WHERE a NOT b;").is_err());
+}
@@ -0,0 +1,221 @@
+use crate::ast::*;
+use crate::binder::Binder;
+use crate::catalog::Catalog;
+use crate::json::Document;
+use crate::lexer::Lexer;
+use crate::parser::{Parser, ParserLimits};
+use crate::plan::plan;
+use crate::simplify::Simplify;
+use crate::tree::statement_tree;
+use crate::visitor::Visitor;
+use crate::{parse_sql, simplify_sql};
+
+fn parse_with_limits(sql: &str, limits: ParserLimits) -> Result<Statement, String> {
+    Parser::new(Lexer::new(sql)).with_limits(limits).visit()
+}
+
+/// Builds `SELECT * FROM t WHERE <operand> <op> <operand> ...` with `count` operands.
+fn chain(operand: &str, op: &str, count: usize) -> String {
+    let operands = vec![operand; count].join(&format!(" {op} "));
+    format!("SELECT * FROM t WHERE {operands};")
+}
+
+#[test]
+fn test_deeply_nested_parentheses_fail_cleanly() {
+    let depth = 100_000;
+    let sql = format!("SELECT {}1{} FROM t;", "(".repeat(depth), ")".repeat(depth));
+    assert_eq!(
+        parse_sql(&sql),
+        Err("Query exceeds the maximum nesting depth of 128".to_string())
+    );
+
+    let sql = format!("SELECT {}1{} FROM t;", "(".repeat(100), ")".repeat(100));
+    assert!(parse_sql(&sql).is_ok());
+}
+
+#[test]
+fn test_deeply_nested_prefix_operators_fail_cleanly() {
+    let sql = format!("SELECT * FROM t WHERE {}a;", "NOT ".repeat(10_000));
+    assert!(
+        parse_sql(&sql)
+            .unwrap_err()
+            .contains("maximum nesting depth")
+    );
+    let sql = format!("SELECT {}1 FROM t;", "- ".repeat(10_000));
+    assert!(
+        parse_sql(&sql)
+            .unwrap_err()
+            .contains("maximum nesting depth")
+    );
+}
+
+#[test]
+fn test_deeply_nested_subqueries_fail_cleanly() {
+    let depth = 1_000;
+    let sql = format!(
+        "{}SELECT * FROM t{};",
+        "SELECT * FROM (".repeat(depth),
+        ") AS s".repeat(depth)
+    );
+    assert!(
+        parse_sql(&sql)
+            .unwrap_err()
+            .contains("maximum nesting depth")
+    );
+
+    let sql = format!(
+        "SELECT * FROM t WHERE a IN {}(SELECT 1){};",
+        "(SELECT a FROM t WHERE a IN ".repeat(depth),
+        ")".repeat(depth)
+    );
+    assert!(
+        parse_sql(&sql)
+            .unwrap_err()
+            .contains("maximum nesting depth")
+    );
+}
+
+#[test]
+fn test_configurable_depth_limit() {
+    // The statement, each operand and each parenthesized expression is one level deeper.
+    let limits = ParserLimits {
+        max_depth: 4,
+        ..ParserLimits::default()
+    };
+    assert!(parse_with_limits("SELECT (1 + 2) FROM t;", limits).is_ok());
+    assert_eq!(
+        parse_with_limits("SELECT ((1 + 2)) FROM t;", limits),
+        Err("Query exceeds the maximum nesting depth of 4".to_string())
+    );
+}
+
+#[test]
+fn test_token_limit() {
+    let limits = ParserLimits {
+        max_tokens: 6,
+        ..ParserLimits::default()
+    };
+    assert!(parse_with_limits("SELECT a FROM t;", limits).is_ok());
+    assert_eq!(
+        parse_with_limits("SELECT a, b FROM t;", limits),
+        Err("Query exceeds the maximum of 6 tokens at line 1, column 19".to_string())
+    );
+
+    // Recovery stops at the limit instead of skipping error tokens forever.
+    let (_, diagnostics) = Parser::new(Lexer::new("SELECT a, b FROM t; SELECT 1;"))
+        .with_limits(limits)
+        .parse_statements();
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(messages, ["Query exceeds the maximum of 6 tokens"]);
+}
+
+#[test]
+fn test_node_limit() {
+    let limits = ParserLimits {
+        max_nodes: 5,
+        ..ParserLimits::default()
+    };
+    assert!(parse_with_limits("SELECT a + b + c FROM t;", limits).is_ok());
+    assert_eq!(
+        parse_with_limits("SELECT a + b + c + d FROM t;", limits),
+        Err("Query exceeds the maximum of 5 expression nodes".to_string())
+    );
+}
+
+#[test]
+fn test_long_chains_parse() {
+    // Chains of operators and joins do not count towards the nesting depth.
+    assert!(parse_sql(&chain("a", "OR", 1_000)).is_ok());
+    let tables: Vec<String> = (0..1_000).map(|i| format!("t{i}")).collect();
+    assert!(parse_sql(&format!("SELECT * FROM {};", tables.join(" JOIN "))).is_ok());
+    assert!(parse_sql(&format!("SELECT * FROM {};", tables.join(", "))).is_ok());
+}
+
+#[test]
+fn test_long_chains_go_through_every_pass() {
+    // The longest chain the default limits allow, and a long chain of joins.
+    let longest = (1..)
+        .map(|n| n * 1_000)
+        .take_while(|&n| parse_sql(&chain("a", "OR", n)).is_ok())
+        .last()
+        .unwrap();
+    assert!(longest >= 30_000, "{longest}");
+    let tables: Vec<String> = (0..1_000).map(|i| format!("t{i} ON a = b")).collect();
+    let joins = format!("SELECT * FROM t JOIN {};", tables.join(" JOIN "));
+
+    // Every pass runs on the stack of a test thread.
+    for sql in [chain("a", "OR", longest), joins] {
+        let stmt = parse_sql(&sql).unwrap();
+        let copy = stmt.clone();
+        assert_eq!(copy, stmt);
+        assert!(!stmt.to_string().is_empty());
+        assert!(!statement_tree(&stmt).to_string().is_empty());
+        assert!(!plan(&stmt).to_string().is_empty());
+        let mut bound = stmt.clone();
+        Binder::new(&Catalog::new()).bind(&mut bound).unwrap();
+        let mut simplified = stmt.clone();
+        simplified.simplify().unwrap();
+        let document = Document::new(None, vec![stmt.into()]);
+        let json = document.to_json();
+        assert!(Document::from_json(&json).unwrap() == document);
+    }
+}
+
+#[test]
+fn test_long_or_chain() {
+    let count = 10_000;
+    let stmt = parse_sql(&chain("a = 1", "OR", count)).unwrap();
+    let printed = stmt.to_string();
+    assert_eq!(printed, chain("a = 1", "OR", count).trim_end_matches(';'));
+
+    let Statement::Select(mut select) = stmt else {
+        panic!("Expected a SELECT statement");
+    };
+    select.where_clause.as_mut().unwrap().simplify().unwrap();
+    assert_eq!(
+        Statement::Select(select).to_string(),
+        "SELECT * FROM t WHERE a = 1"
+    );
+
+    // Operands on different columns are all kept.
+    let operands: Vec<String> = (0..count).map(|i| format!("a{i} = 1")).collect();
+    let sql = format!("SELECT * FROM t WHERE {}", operands.join(" OR "));
+    assert_eq!(simplify_sql(&format!("{sql};")).unwrap().to_string(), sql);
+}
+
+#[test]
+fn test_long_chains_are_simplified() {
+    assert_eq!(
+        simplify_sql(&chain("FALSE", "OR", 10_000)),
+        parse_sql("SELECT * FROM t WHERE FALSE;")
+    );
+    let sql = chain("TRUE", "AND", 10_000).replace(";", " AND a = 1;");
+    assert_eq!(
+        simplify_sql(&sql),
+        parse_sql("SELECT * FROM t WHERE a = 1;")
+    );
+}
+
+#[test]
+fn test_chain_simplification_matches_nested_rules() {
+    for (sql, expected) in [
+        (
+            "SELECT * FROM t WHERE a OR TRUE OR b;",
+            "SELECT * FROM t WHERE TRUE;",
+        ),
+        (
+            "SELECT * FROM t WHERE FALSE OR a OR FALSE;",
+            "SELECT * FROM t WHERE a;",
+        ),
+        (
+            "SELECT * FROM t WHERE a AND (FALSE OR b) AND TRUE;",
+            "SELECT * FROM t WHERE a AND b;",
+        ),
+        (
+            "SELECT * FROM t WHERE 1 = 1 AND a OR 2 > 3;",
+            "SELECT * FROM t WHERE a;",
+        ),
+    ] {
+        assert_eq!(simplify_sql(sql), parse_sql(expected), "{sql}");
+    }
+}
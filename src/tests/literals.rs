@@ -0,0 +1,132 @@
+use crate::ast::*;
+use crate::lexer::Lexer;
+use crate::parse_sql;
+use crate::token::Token;
+
+/// Parses `SELECT <literal>;` and returns the literal expression.
+fn literal(sql: &str) -> Result<Expr, String> {
+    match parse_sql(&format!("SELECT {sql};"))? {
+        Statement::Select(mut select) => match select.columns.remove(0) {
+            SelectItem::ExprWithAlias { expr, .. } => Ok(expr),
+            item => Err(format!("Unexpected select item {item:?}")),
+        },
+        stmt => Err(format!("Unexpected statement {stmt:?}")),
+    }
+}
+
+#[test]
+fn test_escape_strings() {
+    assert_eq!(
+        literal(r"E'a\nb\tc\\d\'e''f'"),
+        Ok(Expr::EscapeString("a\nb\tc\\d'e'f".to_string()))
+    );
+    assert_eq!(
+        literal(r"e'\101\x42C\U0001F600\q'"),
+        Ok(Expr::EscapeString("ABC\u{1F600}q".to_string()))
+    );
+    assert!(literal(r"E'\u12'").is_err());
+    assert!(literal(r"E'abc\'").is_err());
+}
+
+#[test]
+fn test_national_strings() {
+    assert_eq!(
+        literal("N'caf\u{e9} ''au lait'''"),
+        Ok(Expr::NationalString("caf\u{e9} 'au lait'".to_string()))
+    );
+}
+
+#[test]
+fn test_hex_strings() {
+    assert_eq!(
+        literal("X'DEADbeef'"),
+        Ok(Expr::HexString(vec![0xDE, 0xAD, 0xBE, 0xEF]))
+    );
+    assert_eq!(literal("x''"), Ok(Expr::HexString(vec![])));
+    assert_eq!(
+        literal("X'0G'"),
+        Err("Invalid hexadecimal string literal: X'0G' at line 1, column 8".to_string())
+    );
+    assert!(literal("X'ABC'").is_err());
+    assert_eq!(
+        Expr::HexString(vec![1]).literal_type(),
+        Some(DataType::Blob)
+    );
+}
+
+#[test]
+fn test_bit_strings() {
+    assert_eq!(literal("B'0101'"), Ok(Expr::BitString("0101".to_string())));
+    assert!(literal("B'012'").is_err());
+}
+
+#[test]
+fn test_unicode_strings() {
+    assert_eq!(
+        literal(r"U&'d\0061t\+000061 \\ ok'"),
+        Ok(Expr::UnicodeString("data \\ ok".to_string()))
+    );
+    assert!(literal(r"U&'\00G1'").is_err());
+    assert!(literal(r"U&'\+0061'").is_err());
+}
+
+#[test]
+fn test_dollar_quoted_strings() {
+    assert_eq!(
+        literal("$$it's a 'body'$$"),
+        Ok(Expr::DollarString {
+            tag: String::new(),
+            value: "it's a 'body'".to_string()
+        })
+    );
+    assert_eq!(
+        literal("$fn$BEGIN RETURN $$x$$; END;$fn$"),
+        Ok(Expr::DollarString {
+            tag: "fn".to_string(),
+            value: "BEGIN RETURN $$x$$; END;".to_string()
+        })
+    );
+    assert!(literal("$fn$ never closed $fm$").is_err());
+    assert!(literal("$1").is_err());
+}
+
+#[test]
+fn test_dollar_quoted_function_body() {
+    let sql = "INSERT INTO migrations (body) VALUES ($body$\nCREATE FUNCTION f() RETURNS int AS 'select 1';\n$body$);";
+    let Statement::Insert(insert) = parse_sql(sql).unwrap() else {
+        panic!("Expected an INSERT statement");
+    };
+    assert_eq!(
+        insert.values,
+        Values::Values(vec![vec![Expr::DollarString {
+            tag: "body".to_string(),
+            value: "\nCREATE FUNCTION f() RETURNS int AS 'select 1';\n".to_string()
+        }]])
+    );
+}
+
+#[test]
+fn test_adjacent_string_concatenation() {
+    assert_eq!(
+        literal("'foo'\n  'bar'\n'baz'"),
+        Ok(Expr::String("foobarbaz".to_string()))
+    );
+    assert_eq!(
+        literal("E'a\\n'\n'\\t'"),
+        Ok(Expr::EscapeString("a\n\t".to_string()))
+    );
+    assert_eq!(
+        literal("X'AB'\n'CD'"),
+        Ok(Expr::HexString(vec![0xAB, 0xCD]))
+    );
+    // Without a newline in between, adjacent literals are a syntax error.
+    assert!(literal("'foo' 'bar'").is_err());
+}
+
+#[test]
+fn test_prefixes_are_identifiers_without_quote() {
+    let mut lexer = Lexer::new("e n x b u");
+    for name in ["e", "n", "x", "b", "u"] {
+        assert_eq!(lexer.next_token(), Token::Identifier(name.into()));
+    }
+}
@@ -1,6 +1,33 @@
+mod binder;
+mod boolean;
+mod case;
+mod catalog;
+mod cli;
+mod completion;
+mod ctes;
+mod decorrelation;
 mod ddl;
 mod delete;
+mod equivalence;
+mod functions;
+mod highlight;
+mod identifiers;
 mod insert;
+mod json;
+mod keywords;
+mod lexer;
+mod limits;
+mod literals;
+mod nulls;
+mod patterns;
+mod plan;
+mod printer;
+mod ranges;
+mod recovery;
+mod repl;
 mod select;
+mod suggestions;
+mod tree;
 mod update;
 mod utils;
+mod validation;
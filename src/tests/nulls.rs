@@ -0,0 +1,92 @@
+use crate::ast::*;
+use crate::parse_sql;
+use crate::tests::utils::assert_simplified;
+
+#[test]
+fn test_null_propagates() {
+    assert_simplified("1 + NULL", "NULL");
+    assert_simplified("NULL * 0", "NULL");
+    assert_simplified("'a' || NULL", "NULL");
+    assert_simplified("-NULL", "NULL");
+    assert_simplified("a + NULL", "NULL");
+    assert_simplified("NULL || name", "NULL");
+    assert_simplified("'a' LIKE NULL", "NULL");
+}
+
+#[test]
+fn test_comparisons_with_null_are_unknown() {
+    assert_simplified("NULL = NULL", "NULL");
+    assert_simplified("1 <> NULL", "NULL");
+    assert_simplified("NULL < 2", "NULL");
+    assert_simplified("a = NULL", "NULL");
+    assert_simplified("NULL BETWEEN 1 AND 3", "NULL");
+}
+
+#[test]
+fn test_three_valued_logic() {
+    assert_simplified("NULL AND FALSE", "FALSE");
+    assert_simplified("NULL AND TRUE", "NULL");
+    assert_simplified("NULL OR TRUE", "TRUE");
+    assert_simplified("NULL OR FALSE", "NULL");
+    assert_simplified("NOT NULL", "NULL");
+    assert_simplified("NOT (1 = NULL)", "NULL");
+    // The other operand could be TRUE or FALSE.
+    assert_simplified("NULL AND a", "NULL AND a");
+    assert_simplified("NULL OR a", "NULL OR a");
+}
+
+#[test]
+fn test_in_lists_with_null() {
+    assert_simplified("1 IN (1, NULL)", "TRUE");
+    assert_simplified("2 IN (1, NULL)", "NULL");
+    assert_simplified("2 NOT IN (1, NULL)", "NULL");
+    assert_simplified("1 NOT IN (1, NULL)", "FALSE");
+    assert_simplified("NULL IN (1, 2)", "NULL");
+    assert_simplified("3 NOT IN (1, 2)", "TRUE");
+    assert_simplified("'b' IN ('a', 'b')", "TRUE");
+    // An item that is not constant could be equal to the value.
+    assert_simplified("1 IN (1, a)", "TRUE");
+    assert_simplified("2 IN (1, a)", "2 IN (1, a)");
+    assert_simplified("2 NOT IN (1, a)", "2 NOT IN (1, a)");
+}
+
+#[test]
+fn test_is_null_and_truth_values() {
+    assert_simplified("NULL IS NULL", "TRUE");
+    assert_simplified("1 IS NULL", "FALSE");
+    assert_simplified("1 + NULL IS NOT NULL", "FALSE");
+    assert_simplified("NULL IS TRUE", "FALSE");
+    assert_simplified("NULL IS NOT FALSE", "TRUE");
+    assert_simplified("(1 < 2) IS TRUE", "TRUE");
+    assert_simplified("(1 = NULL) IS UNKNOWN", "TRUE");
+    assert_simplified("FALSE IS NOT UNKNOWN", "TRUE");
+    assert_simplified("a IS NULL", "a IS NULL");
+}
+
+#[test]
+fn test_is_unknown_is_is_null() {
+    assert_eq!(
+        parse_sql("SELECT * FROM t WHERE a IS NOT UNKNOWN;"),
+        parse_sql("SELECT * FROM t WHERE a IS NOT NULL;")
+    );
+    let Ok(Statement::Select(select)) = parse_sql("SELECT a IS UNKNOWN AND b FROM t;") else {
+        panic!("Expected a SELECT statement");
+    };
+    assert_eq!(select.columns[0].to_string(), "a IS NULL AND b");
+}
+
+#[test]
+fn test_constants_are_evaluated() {
+    assert_simplified("0 - 5", "-5");
+    assert_simplified("7 % 4", "3");
+    assert_simplified("1 <> 2", "TRUE");
+    assert_simplified("'a' || 'b' = 'ab'", "TRUE");
+    assert_simplified("3 BETWEEN 1 AND 5", "TRUE");
+    assert_simplified("3 NOT BETWEEN 1 AND 5", "FALSE");
+    assert_simplified("'a.c' LIKE 'a._'", "TRUE");
+    assert_simplified("'abc' LIKE 'a.c'", "FALSE");
+    // Errors and implicit casts are left to the database.
+    assert_simplified("1 / 0", "1 / 0");
+    assert_simplified("5 % 0", "5 % 0");
+    assert_simplified("1 = '1'", "1 = '1'");
+}
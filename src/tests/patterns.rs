@@ -0,0 +1,123 @@
+use crate::ast::*;
+use crate::parse_sql;
+use crate::tests::utils::{assert_simplified, parse_expr};
+
+#[test]
+fn test_pattern_operators() {
+    let cases = [
+        ("a ILIKE 'x%'", BinaryOperator::ILike),
+        ("a NOT ILIKE 'x%'", BinaryOperator::NotILike),
+        ("a SIMILAR TO 'x|y'", BinaryOperator::SimilarTo),
+        ("a NOT SIMILAR TO 'x|y'", BinaryOperator::NotSimilarTo),
+        ("a ~ '^x'", BinaryOperator::RegexMatch),
+        ("a ~* '^x'", BinaryOperator::RegexIMatch),
+    ];
+    for (sql, op) in cases {
+        let Expr::BinaryOp { op: parsed, .. } = &parse_expr(sql) else {
+            panic!("Expected a binary operation: {sql}");
+        };
+        assert_eq!(*parsed, op, "{sql}");
+        assert_eq!(parse_expr(sql).to_string(), sql);
+    }
+}
+
+#[test]
+fn test_escape_clause() {
+    assert_eq!(
+        parse_expr("a NOT LIKE 'x!%' ESCAPE '!' AND b"),
+        Expr::BinaryOp {
+            left: Box::new(Expr::BinaryOp {
+                left: Box::new(Expr::Identifier("a".into())),
+                op: BinaryOperator::NotLike,
+                right: Box::new(Expr::Escape {
+                    pattern: Box::new(Expr::String("x!%".to_string())),
+                    escape: Box::new(Expr::String("!".to_string())),
+                }),
+            }),
+            op: BinaryOperator::And,
+            right: Box::new(Expr::Identifier("b".into())),
+        }
+    );
+    for sql in [
+        "a LIKE 'x' || b ESCAPE '#'",
+        "a ILIKE b ESCAPE c",
+        "a SIMILAR TO '%#_' ESCAPE '#'",
+    ] {
+        assert_eq!(parse_expr(sql).to_string(), sql);
+    }
+    assert!(parse_sql("SELECT a ~ 'x' ESCAPE '!';").is_err());
+}
+
+#[test]
+fn test_like_folding_escapes_regex_characters() {
+    assert_simplified("'a.b' LIKE 'a+b'", "FALSE");
+    assert_simplified("'a+b' LIKE 'a+b'", "TRUE");
+    assert_simplified("'(x' LIKE '(%'", "TRUE");
+    assert_simplified("'a[b' LIKE '_[_'", "TRUE");
+    assert_simplified("'a\nb' LIKE 'a_b'", "TRUE");
+    assert_simplified("'abcabd' LIKE '%ab_'", "TRUE");
+    assert_simplified("'abc' LIKE 'a%c%d'", "FALSE");
+    assert_simplified("'abc' NOT LIKE '%'", "FALSE");
+}
+
+#[test]
+fn test_like_folding_with_escape() {
+    assert_simplified("'50%' LIKE '50!%' ESCAPE '!'", "TRUE");
+    assert_simplified("'500' LIKE '50!%' ESCAPE '!'", "FALSE");
+    assert_simplified("'a!b' LIKE 'a!!b' ESCAPE '!'", "TRUE");
+    assert_simplified("'a_b' NOT LIKE 'a#_b' ESCAPE '#'", "FALSE");
+    assert_simplified("'a' LIKE 'a' ESCAPE NULL", "NULL");
+    // Invalid escapes are errors, reported by the database.
+    assert_simplified("'a' LIKE 'a!' ESCAPE '!'", "'a' LIKE 'a!' ESCAPE '!'");
+    assert_simplified("'ab' LIKE 'a!b' ESCAPE '!'", "'ab' LIKE 'a!b' ESCAPE '!'");
+    assert_simplified("'a' LIKE 'a' ESCAPE '!!'", "'a' LIKE 'a' ESCAPE '!!'");
+    // Without ESCAPE, a backslash is an escape in some databases only.
+    assert_simplified("'a_' LIKE 'a\\_'", "'a_' LIKE 'a\\_'");
+}
+
+#[test]
+fn test_ilike_similar_and_regex_folding() {
+    assert_simplified("'ABC' ILIKE 'a%'", "TRUE");
+    assert_simplified("'ABC' LIKE 'a%'", "FALSE");
+    assert_simplified("'ABC' NOT ILIKE '_b_'", "FALSE");
+    assert_simplified("'abc' SIMILAR TO '%(b|d)%'", "TRUE");
+    assert_simplified("'abc' SIMILAR TO 'a'", "FALSE");
+    assert_simplified("'a.c' SIMILAR TO 'a.c'", "TRUE");
+    assert_simplified("'abc' SIMILAR TO 'a.c'", "FALSE");
+    assert_simplified("'a-' SIMILAR TO 'a[-x]'", "TRUE");
+    assert_simplified("'a*' NOT SIMILAR TO 'a#*' ESCAPE '#'", "FALSE");
+    assert_simplified("'abc' ~ 'b'", "TRUE");
+    assert_simplified("'abc' ~ '^b'", "FALSE");
+    assert_simplified("'ABC' ~* 'b'", "TRUE");
+    assert_simplified("NULL ~ 'b'", "NULL");
+    // Invalid patterns are left to the database.
+    assert_simplified("'abc' ~ '('", "'abc' ~ '('");
+}
+
+#[test]
+fn test_like_without_wildcards_is_equality() {
+    assert_simplified("name LIKE 'abc'", "name = 'abc'");
+    assert_simplified("name NOT LIKE 'abc'", "name <> 'abc'");
+    assert_simplified("name LIKE 'a!%' ESCAPE '!'", "name = 'a%'");
+    assert_simplified("lower(name) LIKE 'abc'", "lower(name) = 'abc'");
+    // Trailing spaces are ignored by some comparisons, but never by LIKE.
+    assert_simplified("name LIKE 'abc '", "name LIKE 'abc '");
+    assert_simplified("name LIKE 'a_c'", "name LIKE 'a_c'");
+    assert_simplified("name ILIKE 'abc'", "name ILIKE 'abc'");
+}
+
+#[test]
+fn test_like_prefix_is_range() {
+    assert_simplified("name LIKE 'abc%'", "name >= 'abc' AND name < 'abd'");
+    assert_simplified("t.name LIKE 'az%'", "t.name >= 'az' AND t.name < 'a{'");
+    assert_simplified(
+        "name LIKE '10!%%' ESCAPE '!'",
+        "name >= '10%' AND name < '10&'",
+    );
+    assert_simplified("name LIKE '%abc'", "name LIKE '%abc'");
+    assert_simplified("name LIKE 'a%c%'", "name LIKE 'a%c%'");
+    assert_simplified("name LIKE '%'", "name LIKE '%'");
+    assert_simplified("name NOT LIKE 'abc%'", "name NOT LIKE 'abc%'");
+    // Only columns can use an index.
+    assert_simplified("lower(name) LIKE 'abc%'", "lower(name) LIKE 'abc%'");
+}
@@ -0,0 +1,84 @@
+use crate::parse_sql;
+use crate::plan::plan;
+
+fn explain(sql: &str) -> String {
+    plan(&parse_sql(sql).unwrap()).to_string()
+}
+
+#[test]
+fn test_plan_select() {
+    assert_eq!(
+        explain(
+            "SELECT name, total FROM users u JOIN orders o ON u.id = o.user_id WHERE total > 100 ORDER BY total DESC LIMIT 10;"
+        ),
+        "\
+Limit 10
+└── Sort: total DESC
+    └── Project: name, total
+        └── Filter: total > 100 (selectivity 33.3%)
+            └── JOIN ON u.id = o.user_id
+                ├── Scan users AS u
+                └── Scan orders AS o
+"
+    );
+}
+
+#[test]
+fn test_plan_aggregate() {
+    assert_eq!(
+        explain("SELECT DISTINCT city, count(*) FROM users GROUP BY city HAVING count(*) > 1;"),
+        "\
+Distinct
+└── Project: city, count(*)
+    └── Filter: count(*) > 1
+        └── Aggregate: group by city
+            └── Scan users
+"
+    );
+    // Aggregates without GROUP BY reduce all the rows to one.
+    assert_eq!(
+        explain("SELECT max(total) + 1 FROM orders;"),
+        "Project: max(total) + 1\n└── Aggregate\n    └── Scan orders\n"
+    );
+    assert_eq!(explain("SELECT 1;"), "Project: 1\n└── Single row\n");
+}
+
+#[test]
+fn test_plan_subqueries_and_ctes() {
+    assert_eq!(
+        explain(
+            "WITH big AS (SELECT * FROM orders WHERE total > 100) SELECT * FROM big, LATERAL (SELECT 1) AS one;"
+        ),
+        "\
+With
+├── CTE big
+│   └── Project: *
+│       └── Filter: total > 100 (selectivity 33.3%)
+│           └── Scan orders
+└── Project: *
+    └── CROSS JOIN
+        ├── Scan big
+        └── Lateral Subquery AS one
+            └── Project: 1
+                └── Single row
+"
+    );
+}
+
+#[test]
+fn test_plan_dml() {
+    assert_eq!(
+        explain("UPDATE t SET a = 1, b = b + 1 WHERE id = 2;"),
+        "Update t: a = 1, b = b + 1\n└── Filter: id = 2 (selectivity 10%)\n    └── Scan t\n"
+    );
+    assert_eq!(explain("DELETE FROM t;"), "Delete from t\n└── Scan t\n");
+    assert_eq!(
+        explain("INSERT INTO t VALUES (1), (2);"),
+        "Insert into t\n└── Values: 2 rows\n"
+    );
+    assert_eq!(
+        explain("INSERT INTO t SELECT * FROM u;"),
+        "Insert into t\n└── Project: *\n    └── Scan u\n"
+    );
+    assert_eq!(explain("BEGIN;"), "BEGIN\n");
+}
@@ -0,0 +1,90 @@
+use crate::parse_sql;
+
+/// Parses the statement, prints it and parses the output again.
+/// Returns the printed SQL after checking that both trees are equal.
+fn round_trip(sql: &str) -> String {
+    let stmt = parse_sql(sql).unwrap_or_else(|e| panic!("{sql}: {e}"));
+    let printed = stmt.to_string();
+    let reparsed = parse_sql(&printed).unwrap_or_else(|e| panic!("{printed}: {e}"));
+    assert_eq!(stmt, reparsed, "{printed}");
+    printed
+}
+
+#[test]
+fn test_print_select() {
+    assert_eq!(
+        round_trip("select distinct a, t.b as x, count(*) from t where a > 1 group by a having count(*) > 2 order by a desc, b limit 10;"),
+        "SELECT DISTINCT a, t.b AS x, count(*) FROM t WHERE a > 1 GROUP BY a HAVING count(*) > 2 ORDER BY a DESC, b LIMIT 10"
+    );
+}
+
+#[test]
+fn test_print_literals() {
+    assert_eq!(
+        round_trip(r"SELECT 'it''s', E'a\nb\'c', N'nat', X'00ff', B'101', U&'caf\00E9\+01F600', $q$it's$q$, 1.5, -2, TRUE, NULL;"),
+        r"SELECT 'it''s', E'a\nb\'c', N'nat', X'00FF', B'101', U&'caf\00E9\+01F600', $q$it's$q$, 1.5, -2, TRUE, NULL"
+    );
+}
+
+#[test]
+fn test_print_quoted_identifiers() {
+    assert_eq!(
+        round_trip(r#"SELECT "My Col", "a""b" FROM "Schema".t;"#),
+        r#"SELECT "My Col", "a""b" FROM "Schema".t"#
+    );
+}
+
+#[test]
+fn test_print_operator_precedence() {
+    assert_eq!(
+        round_trip("SELECT (a + b) * c, a + b * c, a - (b - c), (a - b) - c FROM t;"),
+        "SELECT (a + b) * c, a + b * c, a - (b - c), a - b - c FROM t"
+    );
+    assert_eq!(
+        round_trip("SELECT * FROM t WHERE (a OR b) AND NOT (c AND d) OR -(x * y) > 1;"),
+        "SELECT * FROM t WHERE (a OR b) AND (NOT (c AND d)) OR (-(x * y)) > 1"
+    );
+    assert_eq!(
+        round_trip("SELECT * FROM t WHERE a NOT BETWEEN 1 AND 2 AND b IN (1, 2) AND c NOT LIKE 'x%' AND d IS NOT NULL;"),
+        "SELECT * FROM t WHERE a NOT BETWEEN 1 AND 2 AND b IN (1, 2) AND c NOT LIKE 'x%' AND d IS NOT NULL"
+    );
+}
+
+#[test]
+fn test_print_expressions() {
+    round_trip("SELECT CASE WHEN a = 1 THEN 'one' WHEN a = 2 THEN 'two' ELSE 'many' END FROM t;");
+    round_trip("SELECT CASE a WHEN 1 THEN 'one' END, name || '!' FROM t;");
+    round_trip("SELECT * FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.id = t.id) AND a IN (SELECT b FROM u);");
+    round_trip("SELECT COUNT(DISTINCT a), (a, b) FROM t;");
+}
+
+#[test]
+fn test_print_from_clause() {
+    round_trip("SELECT * FROM a JOIN b ON a.id = b.id LEFT JOIN c USING (id) NATURAL FULL JOIN d CROSS JOIN e, f;");
+    round_trip("SELECT * FROM t AS x, LATERAL (SELECT * FROM u WHERE u.id = x.id) AS y;");
+    round_trip("SELECT * EXCLUDE (a) REPLACE (b + 1 AS b) RENAME (c AS d), t.* EXCEPT (e) FROM t;");
+    round_trip("WITH RECURSIVE a AS (SELECT 1), b AS (SELECT * FROM a) SELECT * FROM b;");
+}
+
+#[test]
+fn test_print_dml() {
+    round_trip("INSERT INTO s.t (a, b) VALUES (1, 'x'), (2, 'y');");
+    round_trip("INSERT INTO t SELECT * FROM u;");
+    round_trip("UPDATE t SET a = a + 1, b = 'x' WHERE id = 1;");
+    round_trip("DELETE FROM t WHERE id IN (1, 2);");
+}
+
+#[test]
+fn test_print_ddl() {
+    round_trip("CREATE TABLE t (id INTEGER PRIMARY KEY, name VARCHAR(20) NOT NULL UNIQUE, price DECIMAL(10, 2) DEFAULT 0 CHECK (price >= 0), owner INTEGER REFERENCES users(id), PRIMARY KEY (id), UNIQUE (name), FOREIGN KEY (owner) REFERENCES users (id), CHECK (id > 0));");
+    round_trip("ALTER TABLE t ADD COLUMN c TEXT;");
+    round_trip("ALTER TABLE t DROP COLUMN c;");
+    round_trip("ALTER TABLE t ALTER COLUMN c SET DEFAULT 1;");
+    round_trip("ALTER TABLE t ALTER COLUMN c DROP NOT NULL;");
+    round_trip("ALTER TABLE t ALTER COLUMN c BIGINT;");
+    round_trip("ALTER TABLE t ADD CONSTRAINT UNIQUE (a, b);");
+    round_trip("DROP TABLE IF EXISTS t CASCADE;");
+    round_trip("CREATE UNIQUE INDEX IF NOT EXISTS idx ON t (a ASC, b DESC, c);");
+    round_trip("BEGIN;");
+    round_trip("COMMIT;");
+}
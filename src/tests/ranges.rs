@@ -0,0 +1,160 @@
+use crate::range::{Bound, Range, column_ranges, selectivity};
+use crate::tests::utils::{assert_simplified, assert_simplified_condition, parse_expr};
+use crate::{parse_sql, simplify_sql};
+
+#[test]
+fn test_overlapping_ranges_are_merged() {
+    assert_simplified_condition("x > 5 AND x > 3", "x > 5");
+    assert_simplified_condition(
+        "x > 3 AND y = 1 AND x >= 5 AND x < 10",
+        "x >= 5 AND x < 10 AND y = 1",
+    );
+    assert_simplified_condition("x >= 5 AND x > 5", "x > 5");
+    assert_simplified_condition("x BETWEEN 1 AND 10 AND x < 5", "x >= 1 AND x < 5");
+    assert_simplified_condition("5 < x AND x <= 8", "x > 5 AND x <= 8");
+    assert_simplified_condition("x > 5 AND x <> 3", "x > 5");
+    assert_simplified_condition("t.x > 1 AND x > 2", "t.x > 1 AND x > 2");
+    // Strings are ordered by the collation of the column.
+    assert_simplified_condition("s > 'b' AND s > 'a'", "s > 'b' AND s > 'a'");
+}
+
+#[test]
+fn test_single_values() {
+    assert_simplified_condition("x >= 5 AND x <= 5", "x = 5");
+    assert_simplified_condition("x BETWEEN 5 AND 5", "x BETWEEN 5 AND 5");
+    assert_simplified_condition("x IN (1, 2, 3) AND x = 2", "x = 2");
+    assert_simplified_condition("x IN (1, 2, 3) AND x > 1", "x IN (2, 3)");
+    assert_simplified_condition("x IN (1, 2, 3) AND x IN (2, 3, 4)", "x IN (2, 3)");
+    assert_simplified_condition("x IN (1, 2) AND x <> 1", "x = 2");
+}
+
+#[test]
+fn test_contradictions_in_filters() {
+    assert_simplified_condition("x > 5 AND x > 3 AND x < 2", "FALSE");
+    assert_simplified_condition("x = 1 AND x = 2", "FALSE");
+    assert_simplified_condition("x > 5 AND x <= 5", "FALSE");
+    assert_simplified_condition("x IN (1, 2) AND x > 2", "FALSE");
+    assert_simplified_condition("x = 5 AND x <> 5", "FALSE");
+    assert_simplified_condition("x BETWEEN 5 AND 1", "FALSE");
+    assert_simplified_condition("a OR x < 1 AND x > 2", "a");
+    assert_simplified_condition("a AND (x < 1 AND x > 2 OR b)", "a AND b");
+    assert_eq!(
+        simplify_sql("SELECT * FROM t JOIN u ON t.id = u.id AND u.x < 0 AND u.x > 0;"),
+        parse_sql("SELECT * FROM t JOIN u ON FALSE;")
+    );
+    assert_eq!(
+        simplify_sql("DELETE FROM t WHERE x > 1 AND x < 0;"),
+        parse_sql("DELETE FROM t WHERE FALSE;")
+    );
+    assert_simplified("CASE WHEN x = 1 AND x = 2 THEN 'a' ELSE 'b' END", "'b'");
+
+    // Outside of a filter, the condition is NULL when x is NULL.
+    assert_simplified("x = 1 AND x = 2", "x = 1 AND x = 2");
+    assert_simplified("x > 5 AND x > 3", "x > 5");
+    // Under NOT, NULL is not the same as FALSE.
+    assert_simplified_condition("NOT (x = 1 AND x = 2)", "x <> 1 OR x <> 2");
+}
+
+#[test]
+fn test_equalities_are_merged_into_in() {
+    assert_simplified_condition("x = 1 OR x = 2", "x IN (1, 2)");
+    assert_simplified_condition("x = 1 OR y = 1 OR x = 3", "x IN (1, 3) OR y = 1");
+    assert_simplified_condition("x IN (1, 2) OR x = 3 OR x = 2", "x IN (1, 2, 3)");
+    assert_simplified_condition("s = 'a' OR s = 'b'", "s IN ('a', 'b')");
+    assert_simplified_condition("x = 1 OR x = 'a'", "x IN (1, 'a')");
+    assert_simplified_condition("x IN (1, NULL) OR x = 2", "x IN (1, NULL) OR x = 2");
+    assert_simplified("x = 1 OR x = 2", "x IN (1, 2)");
+}
+
+#[test]
+fn test_ranges_are_united() {
+    assert_simplified_condition("x > 3 OR x > 5", "x > 3");
+    assert_simplified_condition("x < 5 OR x BETWEEN 3 AND 8", "x <= 8");
+    assert_simplified_condition("x = 5 OR x > 5", "x >= 5");
+    assert_simplified_condition("x = 7 OR x > 5", "x > 5");
+    assert_simplified_condition("x < 1 OR x > 2", "x < 1 OR x > 2");
+    assert_simplified_condition("x = 0 OR x > 5 OR x = 1", "x IN (0, 1) OR x > 5");
+    assert_simplified_condition(
+        "x >= 1 AND x <= 3 OR x BETWEEN 2 AND 6",
+        "x >= 1 AND x <= 6",
+    );
+}
+
+#[test]
+fn test_tautologies() {
+    assert_simplified_condition("x > 5 OR x <= 5", "x IS NOT NULL");
+    assert_simplified_condition("x < 1 OR x >= 1 OR a", "x IS NOT NULL OR a");
+    assert_simplified_condition("x > 5 OR x IS NULL OR x <= 5", "TRUE");
+    assert_simplified("x > 5 OR x <= 5 OR x IS NULL", "TRUE");
+    // Outside of a filter, the condition is NULL when x is NULL.
+    assert_simplified("x > 5 OR x <= 5", "x > 5 OR x <= 5");
+    assert_simplified_condition("x < 5 OR x > 5", "x < 5 OR x > 5");
+}
+
+#[test]
+fn test_long_equality_chain_becomes_a_list() {
+    let count = 10_000;
+    let values: Vec<String> = (0..count).map(|i| i.to_string()).collect();
+    let operands: Vec<String> = values.iter().map(|v| format!("a = {v}")).collect();
+    let simplified =
+        simplify_sql(&format!("SELECT * FROM t WHERE {};", operands.join(" OR "))).unwrap();
+    assert_eq!(
+        simplified.to_string(),
+        format!("SELECT * FROM t WHERE a IN ({})", values.join(", "))
+    );
+}
+
+#[test]
+fn test_column_ranges() {
+    let bound = |value, inclusive| Some(Bound { value, inclusive });
+    assert_eq!(
+        column_ranges(&parse_expr(
+            "x > 1 AND y IN (2, 1) AND x <= 10 AND z AND 3 > w"
+        )),
+        vec![
+            (
+                parse_expr("x"),
+                Range {
+                    low: bound(1.0, false),
+                    high: bound(10.0, true),
+                    ..Range::default()
+                }
+            ),
+            (
+                parse_expr("y"),
+                Range {
+                    values: Some(vec![2.0, 1.0]),
+                    ..Range::default()
+                }
+            ),
+            (
+                parse_expr("w"),
+                Range {
+                    high: bound(3.0, false),
+                    ..Range::default()
+                }
+            ),
+        ]
+    );
+    let ranges = column_ranges(&parse_expr("x > 1 AND x < 0"));
+    assert!(ranges[0].1.is_empty());
+    assert!(column_ranges(&parse_expr("x > 1 OR x < 0")).is_empty());
+}
+
+#[test]
+fn test_selectivity() {
+    let selectivity = |condition: &str| selectivity(&parse_expr(condition));
+    assert_eq!(selectivity("x = 1"), Some(0.1));
+    assert_eq!(selectivity("x IN (1, 2, 3) AND z"), Some(0.1 * 3.0));
+    assert_eq!(selectivity("x > 1"), Some(1.0 / 3.0));
+    assert_eq!(selectivity("x > 1 AND x <= 10"), Some(1.0 / 9.0));
+    assert_eq!(selectivity("x > 1 AND x <> 5"), Some(1.0 / 3.0 * 0.9));
+    assert_eq!(selectivity("x = 1 AND y < 2"), Some(0.1 / 3.0));
+    assert_eq!(selectivity("x > 1 AND x < 0"), Some(0.0));
+    assert_eq!(
+        selectivity("x IN (1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11)"),
+        Some(1.0)
+    );
+    assert_eq!(selectivity("x > 1 OR y > 1"), None);
+    assert_eq!(selectivity("z"), None);
+}
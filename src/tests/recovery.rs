@@ -0,0 +1,156 @@
+use crate::ast::*;
+use crate::diagnostics::Diagnostic;
+use crate::token::{Location, Span};
+use crate::{parse_script, parse_sql};
+
+/// Parses a script and returns the printed statements and the diagnostics as text.
+fn recover(sql: &str) -> (Vec<String>, Vec<String>) {
+    let (statements, diagnostics) = parse_script(sql);
+    (
+        statements.iter().map(|stmt| stmt.to_string()).collect(),
+        diagnostics.iter().map(|d| d.to_string()).collect(),
+    )
+}
+
+#[test]
+fn test_multiple_errors_in_one_statement() {
+    let sql = "SELECT a, FROM t WHERE b = = 1 ORDER BY c;";
+    let (statements, diagnostics) = parse_script(sql);
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic {
+                message: "Unexpected token in expression: From".to_string(),
+                location: Location {
+                    line: 1,
+                    column: 11
+                },
+                span: Span { start: 10, end: 14 },
+            },
+            Diagnostic {
+                message: "Unexpected token in expression: Eq".to_string(),
+                location: Location {
+                    line: 1,
+                    column: 28
+                },
+                span: Span { start: 27, end: 28 },
+            },
+        ]
+    );
+
+    // The clauses that failed are replaced by placeholders, the others are kept.
+    let [Statement::Select(select)] = statements.as_slice() else {
+        panic!("Expected a single SELECT statement, got {statements:?}");
+    };
+    assert_eq!(
+        select.columns,
+        vec![SelectItem::ExprWithAlias {
+            expr: Expr::Error,
+            alias: None
+        }]
+    );
+    assert_eq!(
+        select.from,
+        Some(TableReference::Table {
+            name: "t".into(),
+            alias: None
+        })
+    );
+    assert_eq!(select.where_clause, Some(Expr::Error));
+    assert_eq!(select.order_by.len(), 1);
+
+    // Without recovery, parsing still stops at the first error.
+    assert_eq!(
+        parse_sql(sql),
+        Err("Unexpected token in expression: From".to_string())
+    );
+}
+
+#[test]
+fn test_broken_statements_are_skipped() {
+    assert_eq!(
+        recover("SELECT 1; SELEC 2; SELECT 3;"),
+        (
+            vec!["SELECT 1".to_string(), "SELECT 3".to_string()],
+            vec![
+                "Unexpected statement type: SELEC, did you mean 'SELECT'? at line 1, column 11"
+                    .to_string()
+            ]
+        )
+    );
+    assert_eq!(
+        recover("SELECT a) FROM t; SELECT 1;"),
+        (
+            vec!["SELECT a".to_string(), "SELECT 1".to_string()],
+            vec!["Unexpected token after end of statement: ) at line 1, column 9".to_string()]
+        )
+    );
+}
+
+#[test]
+fn test_recovery_inside_subqueries() {
+    assert_eq!(
+        recover("SELECT * FROM t WHERE a IN (SELECT b FROM WHERE) AND c = 1;"),
+        (
+            vec![
+                "SELECT * FROM t WHERE a IN (SELECT b FROM <error> WHERE <error>) AND c = 1"
+                    .to_string()
+            ],
+            vec![
+                "Unexpected token in table reference: Where at line 1, column 43".to_string(),
+                "Unexpected token in expression: RParen at line 1, column 48".to_string(),
+            ]
+        )
+    );
+}
+
+#[test]
+fn test_lexical_errors_are_reported() {
+    assert_eq!(
+        recover("SELECT a FROM t @ WHERE # 1;\nSELECT b FROM u;"),
+        (
+            vec!["SELECT a FROM t".to_string(), "SELECT b FROM u".to_string()],
+            vec![
+                "Unexpected character '@' at line 1, column 17".to_string(),
+                "Unexpected character '#' at line 1, column 25".to_string(),
+            ]
+        )
+    );
+}
+
+#[test]
+fn test_recovery_in_dml_and_limit() {
+    assert_eq!(
+        recover("SELECT a FROM t LIMIT x; DELETE FROM t WHERE; UPDATE t SET a = 1;"),
+        (
+            vec![
+                "SELECT a FROM t".to_string(),
+                "DELETE FROM t WHERE <error>".to_string(),
+                "UPDATE t SET a = 1".to_string(),
+            ],
+            vec![
+                "Expected number after LIMIT at line 1, column 23".to_string(),
+                "Unexpected token in expression: Semicolon at line 1, column 45".to_string(),
+            ]
+        )
+    );
+}
+
+#[test]
+fn test_one_typo_in_a_long_script() {
+    let mut script: Vec<String> = (0..500)
+        .map(|i| format!("INSERT INTO migrations (id) VALUES ({i});"))
+        .collect();
+    script[123] = "INSERT INTO migrations (id) VALUES (123;".to_string();
+    script[321] = "CREATE TABEL t (id INTEGER);".to_string();
+
+    let (statements, diagnostics) = parse_script(&script.join("\n"));
+    assert_eq!(statements.len(), 498);
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|d| d.location.line)
+            .collect::<Vec<_>>(),
+        vec![124, 322]
+    );
+}
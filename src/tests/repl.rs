@@ -0,0 +1,336 @@
+use crate::dialect::Dialect;
+use crate::json::Document;
+use crate::parse_sql;
+use crate::repl::{Flow, Session, split_statements};
+use std::env;
+use std::fs;
+
+/// Runs inputs in a new session and returns what it wrote to its output and error streams.
+fn run(session: &mut Session, inputs: &[&str]) -> (String, String) {
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    for input in inputs {
+        session.execute(input, &mut out, &mut err).unwrap();
+    }
+    (
+        String::from_utf8(out).unwrap(),
+        String::from_utf8(err).unwrap(),
+    )
+}
+
+/// Writes a script to a temporary file and returns its path.
+fn script(name: &str, sql: &str) -> String {
+    let path = env::temp_dir().join(format!("repl-{}-{name}", std::process::id()));
+    fs::write(&path, sql).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_split_statements() {
+    assert_eq!(
+        split_statements(
+            "SELECT 1; SELECT 'a;b' /* ; */;\n-- ;\nSELECT 2",
+            Dialect::Ansi
+        ),
+        vec!["SELECT 1;", "SELECT 'a;b' /* ; */;", "-- ;\nSELECT 2"]
+    );
+    assert_eq!(
+        split_statements("SELECT 1;; -- done", Dialect::Ansi),
+        vec!["SELECT 1;"]
+    );
+    assert_eq!(
+        split_statements("SELECT `a;b`;", Dialect::MySql),
+        vec!["SELECT `a;b`;"]
+    );
+}
+
+#[test]
+fn test_mode_sql_and_simplify() {
+    let mut session = Session::new();
+    let (out, err) = run(
+        &mut session,
+        &[".mode sql", "SELECT a FROM t WHERE 1 = 1 AND b; SELECT 2;"],
+    );
+    assert_eq!(out, "SELECT a FROM t WHERE b;\nSELECT 2;\n");
+    assert_eq!(err, "");
+
+    let (out, _) = run(
+        &mut session,
+        &[".simplify off", "SELECT a FROM t WHERE 1 = 1 AND b;"],
+    );
+    assert_eq!(out, "SELECT a FROM t WHERE 1 = 1 AND b;\n");
+}
+
+#[test]
+fn test_rewrites() {
+    let mut session = Session::new();
+    let (out, err) = run(
+        &mut session,
+        &[
+            ".mode sql",
+            ".expand_null_functions on",
+            "SELECT NULLIF(a, 1) FROM t;",
+            ".simplify off",
+            "SELECT NULLIF(a, 1) FROM t;",
+            ".simplify on",
+            ".expand_null_functions off",
+            "SELECT NULLIF(a, 1) FROM t;",
+            ".cnf on",
+            "SELECT * FROM t WHERE (a AND b) OR c;",
+            ".cnf off",
+            ".decorrelate on",
+            "SELECT t.x FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.a = t.a);",
+            ".decorrelate off",
+            ".ctes inline",
+            "WITH a AS (SELECT x FROM t) SELECT * FROM a;",
+            ".ctes off",
+            ".extract_ctes on",
+            "SELECT * FROM (SELECT x FROM t) AS p, (SELECT x FROM t) AS q;",
+        ],
+    );
+    assert_eq!(err, "");
+    assert_eq!(
+        out,
+        "SELECT CASE WHEN a = 1 THEN NULL ELSE a END FROM t;\n\
+         SELECT NULLIF(a, 1) FROM t;\n\
+         SELECT NULLIF(a, 1) FROM t;\n\
+         SELECT * FROM t WHERE (a OR c) AND (b OR c);\n\
+         SELECT t.x FROM t JOIN (SELECT DISTINCT u.a AS key1 FROM u) AS subquery1 \
+         ON t.a = subquery1.key1;\n\
+         SELECT a.x FROM (SELECT x FROM t) AS a;\n\
+         WITH cte1 AS (SELECT x FROM t) SELECT p.x, q.x FROM cte1 AS p CROSS JOIN cte1 AS q;\n"
+    );
+
+    let (_, err) = run(&mut session, &[".ctes lazy"]);
+    assert!(err.contains("Unknown CTE mode 'lazy'"), "{err}");
+}
+
+#[test]
+fn test_mode_tree_plan_and_tokens() {
+    let mut session = Session::new();
+    let (out, _) = run(
+        &mut session,
+        &[".mode tree", "SELECT a FROM t WHERE a > 1;"],
+    );
+    assert_eq!(
+        out,
+        "SELECT\n├── Columns\n│   └── a\n├── From\n│   └── Table t\n└── Where\n    └── >\n        ├── a\n        └── 1\n"
+    );
+
+    let (out, _) = run(
+        &mut session,
+        &[".mode plan", "SELECT a FROM t WHERE a > 1;"],
+    );
+    assert_eq!(
+        out,
+        "Project: a\n└── Filter: a > 1 (selectivity 33.3%)\n    └── Scan t\n"
+    );
+
+    let (out, _) = run(&mut session, &[".mode tokens", "SELECT a;"]);
+    assert_eq!(
+        out,
+        "0..6\tSelect\n7..8\tIdentifier(Word { value: \"a\", quote_style: None })\n8..9\tSemicolon\n"
+    );
+}
+
+#[test]
+fn test_mode_json() {
+    let mut session = Session::new();
+    let (out, err) = run(
+        &mut session,
+        &[".mode json", "SELECT a FROM t WHERE 1 + 1 > b;"],
+    );
+    assert_eq!(err, "");
+    let document = Document::from_json(&out).unwrap();
+    // Statements are shown after simplification.
+    assert_eq!(
+        document.statements[0].statement,
+        parse_sql("SELECT a FROM t WHERE 2 > b").unwrap()
+    );
+    assert!(out.starts_with("{\n  \"format\": \"sql-ast\""), "{out}");
+}
+
+#[test]
+fn test_mode_errors() {
+    let mut session = Session::new();
+    let (out, err) = run(&mut session, &[".mode", ".mode xml", ".mode"]);
+    assert_eq!(out, "Ast\nAst\n");
+    assert!(err.contains("Unknown output mode 'xml'"), "{err}");
+    let (_, err) = run(&mut session, &[".timer maybe", ".read", ".frobnicate"]);
+    assert!(err.contains("Expected on or off after .timer"), "{err}");
+    assert!(err.contains("Missing argument for .read"), "{err}");
+    assert!(err.contains("Unknown command .frobnicate"), "{err}");
+}
+
+#[test]
+fn test_exit() {
+    let mut session = Session::new();
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+    assert_eq!(
+        session.execute(".help", &mut out, &mut err).unwrap(),
+        Flow::Continue
+    );
+    assert_eq!(
+        session.execute(".exit", &mut out, &mut err).unwrap(),
+        Flow::Exit
+    );
+    assert_eq!(
+        session.execute(".quit", &mut out, &mut err).unwrap(),
+        Flow::Exit
+    );
+}
+
+#[test]
+fn test_load_and_schema() {
+    let mut session = Session::new();
+    let ddl = script(
+        "schema.sql",
+        "CREATE TABLE users (id INTEGER, name TEXT);\nCREATE TABLE orders (id INTEGER, total REAL);\n",
+    );
+    let (out, err) = run(&mut session, &[&format!(".load {ddl}"), ".schema"]);
+    assert_eq!(err, "");
+    assert_eq!(
+        out,
+        format!(
+            "Loaded 2 statements from {ddl}\nCREATE TABLE default.public.orders (id INTEGER, total REAL);\nCREATE TABLE default.public.users (id INTEGER, name TEXT);\n"
+        )
+    );
+    fs::remove_file(ddl).unwrap();
+
+    let (_, err) = run(&mut session, &[".load /nonexistent/ddl.sql"]);
+    assert!(err.contains("Cannot read /nonexistent/ddl.sql"), "{err}");
+}
+
+#[test]
+fn test_using_columns_are_resolved() {
+    let mut session = Session::new();
+    let (out, err) = run(
+        &mut session,
+        &[
+            ".mode sql",
+            "CREATE TABLE a (id INTEGER); CREATE TABLE b (id INTEGER);",
+            "SELECT id FROM a JOIN b USING (id);",
+            "SELECT id FROM a FULL JOIN b USING (id);",
+        ],
+    );
+    assert_eq!(err, "");
+    assert!(
+        out.ends_with(
+            "SELECT a.id FROM a JOIN b ON a.id = b.id;\n\
+             SELECT COALESCE(a.id, b.id) AS id FROM a FULL JOIN b ON a.id = b.id;\n"
+        ),
+        "{out}"
+    );
+}
+
+#[test]
+fn test_search_path() {
+    let mut session = Session::new();
+    let (out, err) = run(
+        &mut session,
+        &[
+            ".search_path",
+            ".search_path shop.sales,public",
+            ".search_path",
+            "CREATE TABLE orders (id INTEGER);",
+            ".schema",
+        ],
+    );
+    assert_eq!(err, "");
+    assert!(
+        out.starts_with("default.public\nshop.sales,public\n"),
+        "{out}"
+    );
+    assert!(
+        out.ends_with("CREATE TABLE shop.sales.orders (id INTEGER);\n"),
+        "{out}"
+    );
+
+    let (_, err) = run(&mut session, &[".search_path shop."]);
+    assert!(err.contains("Invalid search path 'shop.'"), "{err}");
+}
+
+#[test]
+fn test_declared_functions() {
+    let mut session = Session::new();
+    let (out, err) = run(
+        &mut session,
+        &[
+            ".mode sql",
+            "SELECT * FROM t WHERE repl_hash(a) = 1 AND repl_hash(a) = 1;",
+            ".function repl_hash(text) RETURNS number",
+            "SELECT * FROM t WHERE repl_hash(a) = 1 AND repl_hash(a) = 1;",
+            ".function repl_median(NUMBER) RETURNS NUMBER AGGREGATE",
+            ".mode plan",
+            "SELECT repl_median(x) FROM t;",
+        ],
+    );
+    assert_eq!(err, "");
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(
+        lines[..2],
+        [
+            "SELECT * FROM t WHERE repl_hash(a) = 1 AND repl_hash(a) = 1;",
+            "SELECT * FROM t WHERE repl_hash(a) = 1;",
+        ]
+    );
+    assert!(out.contains("Aggregate"), "{out}");
+
+    let (_, err) = run(&mut session, &[".function repl_f(number)"]);
+    assert!(
+        err.contains("Invalid function declaration 'repl_f(number)'"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_read_script() {
+    let mut session = Session::new();
+    let queries = script(
+        "queries.sql",
+        "CREATE TABLE users (id INTEGER, name TEXT);\nSELECT * FROM users;\nSELEC 1;\n",
+    );
+    let (out, err) = run(&mut session, &[".mode sql", &format!(".read {queries}")]);
+    assert_eq!(
+        out,
+        "CREATE TABLE users (id INTEGER, name TEXT);\nSELECT users.id, users.name FROM users;\n"
+    );
+    assert!(
+        err.contains("Unexpected statement type: SELEC, did you mean 'SELECT'?"),
+        "{err}"
+    );
+    fs::remove_file(queries).unwrap();
+}
+
+#[test]
+fn test_timer() {
+    let mut session = Session::new();
+    let (out, _) = run(&mut session, &[".mode sql", ".timer on", "SELECT 1;"]);
+    assert!(out.starts_with("SELECT 1;\n"), "{out}");
+    assert!(out.contains("parse"), "{out}");
+    assert!(out.contains("simplify"), "{out}");
+    assert!(out.contains("bind"), "{out}");
+}
+
+#[test]
+fn test_dialect() {
+    let mut session = Session::new();
+    let (out, err) = run(
+        &mut session,
+        &[
+            ".mode sql",
+            "CREATE TABLE Users (id INTEGER);",
+            ".dialect postgres",
+            ".dialect",
+            "SELECT `id` FROM users;",
+            ".dialect mysql",
+            "SELECT * FROM `Users`;",
+            ".dialect klingon",
+        ],
+    );
+    assert!(out.contains("Postgres\n"), "{out}");
+    // Backquotes only quote identifiers in MySQL.
+    assert!(err.contains("Unexpected character '`'"), "{err}");
+    // With MySQL, names are compared as written, and the catalog follows.
+    assert!(out.contains("SELECT `Users`.id FROM `Users`;\n"), "{out}");
+    assert!(err.contains("Unknown dialect 'klingon'"), "{err}");
+}
@@ -6,9 +6,9 @@ sql_test!(
     "SELECT * FROM my_table;",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "my_table".to_string(),
+            name: "my_table".into(),
             alias: None
         }),
         where_clause: None,
@@ -24,13 +24,13 @@ sql_test!(
     "SELECT * FROM my_table WHERE COL1 > 2;",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "my_table".to_string(),
+            name: "my_table".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("COL1".to_string())),
+            left: Box::new(Expr::Identifier("COL1".into())),
             op: BinaryOperator::Gt,
             right: Box::new(Expr::Number(2.0))
         }),
@@ -46,20 +46,20 @@ sql_test!(
     "SELECT * FROM my_table WHERE COL1 > 2 AND COL2 < 3;",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "my_table".to_string(),
+            name: "my_table".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
             left: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Identifier("COL1".to_string())),
+                left: Box::new(Expr::Identifier("COL1".into())),
                 op: BinaryOperator::Gt,
                 right: Box::new(Expr::Number(2.0))
             }),
             op: BinaryOperator::And,
             right: Box::new(Expr::BinaryOp {
-                left: Box::new(Expr::Identifier("COL2".to_string())),
+                left: Box::new(Expr::Identifier("COL2".into())),
                 op: BinaryOperator::Lt,
                 right: Box::new(Expr::Number(3.0))
             })
@@ -77,11 +77,11 @@ sql_test!(
     Statement::Select(SelectStatement {
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
-            expr: Expr::Identifier("col1".to_string()),
+            expr: Expr::Identifier("col1".into()),
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "my_table".to_string(),
+            name: "my_table".into(),
             alias: None
         }),
         where_clause: None,
@@ -98,11 +98,11 @@ sql_test!(
     Statement::Select(SelectStatement {
         distinct: true,
         columns: vec![SelectItem::ExprWithAlias {
-            expr: Expr::Identifier("col1".to_string()),
+            expr: Expr::Identifier("col1".into()),
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "my_table".to_string(),
+            name: "my_table".into(),
             alias: None
         }),
         where_clause: None,
@@ -120,14 +120,14 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
-                Expr::Identifier("col1".to_string()),
-                Expr::Identifier("col2".to_string()),
-                Expr::Identifier("col3".to_string())
+                Expr::Identifier("col1".into()),
+                Expr::Identifier("col2".into()),
+                Expr::Identifier("col3".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "my_table".to_string(),
+            name: "my_table".into(),
             alias: None
         }),
         where_clause: None,
@@ -146,20 +146,20 @@ sql_test!(
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
                 Expr::FunctionCall {
-                    name: "MAX".to_string(),
-                    args: vec![Expr::Identifier("col1".to_string())],
+                    name: "MAX".into(),
+                    args: vec![Expr::Identifier("col1".into())],
                     distinct: false
                 },
-                Expr::Identifier("col2".to_string())
+                Expr::Identifier("col2".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "my_table".to_string(),
+            name: "my_table".into(),
             alias: None
         }),
         where_clause: None,
-        group_by: vec![Expr::Identifier("col2".to_string())],
+        group_by: vec![Expr::Identifier("col2".into())],
         having: None,
         order_by: vec![],
         limit: None
@@ -174,23 +174,23 @@ sql_test!(
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
                 Expr::FunctionCall {
-                    name: "MAX".to_string(),
-                    args: vec![Expr::Identifier("col1".to_string())],
+                    name: "MAX".into(),
+                    args: vec![Expr::Identifier("col1".into())],
                     distinct: false
                 },
-                Expr::Identifier("col2".to_string())
+                Expr::Identifier("col2".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "my_table".to_string(),
+            name: "my_table".into(),
             alias: None
         }),
         where_clause: None,
-        group_by: vec![Expr::Identifier("col2".to_string())],
+        group_by: vec![Expr::Identifier("col2".into())],
         having: Some(Expr::BinaryOp {
             left: Box::new(Expr::FunctionCall {
-                name: "COUNT".to_string(),
+                name: "COUNT".into(),
                 args: vec![Expr::Star],
                 distinct: false
             }),
@@ -209,8 +209,8 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::FunctionCall {
-                name: "CAST".to_string(),
-                args: vec![Expr::Number(3.0), Expr::Identifier("STRING".to_string())],
+                name: "CAST".into(),
+                args: vec![Expr::Number(3.0), Expr::Identifier("STRING".into())],
                 distinct: false
             },
             alias: None
@@ -231,22 +231,22 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
-                Expr::Identifier("col1".to_string()),
-                Expr::Identifier("col2".to_string()),
-                Expr::Identifier("col3".to_string()),
-                Expr::Identifier("col4".to_string())
+                Expr::Identifier("col1".into()),
+                Expr::Identifier("col2".into()),
+                Expr::Identifier("col3".into()),
+                Expr::Identifier("col4".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "table1".to_string(),
+            name: "table1".into(),
             alias: None
         }),
         where_clause: None,
         group_by: vec![],
         having: None,
         order_by: vec![OrderByExpr {
-            expr: Expr::Identifier("col1".to_string()),
+            expr: Expr::Identifier("col1".into()),
             asc: true
         }],
         limit: Some(100)
@@ -260,22 +260,22 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
-                Expr::Identifier("col1".to_string()),
-                Expr::Identifier("col2".to_string()),
-                Expr::Identifier("col3".to_string()),
-                Expr::Identifier("col4".to_string())
+                Expr::Identifier("col1".into()),
+                Expr::Identifier("col2".into()),
+                Expr::Identifier("col3".into()),
+                Expr::Identifier("col4".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "table1".to_string(),
+            name: "table1".into(),
             alias: None
         }),
         where_clause: None,
         group_by: vec![],
         having: None,
         order_by: vec![OrderByExpr {
-            expr: Expr::Identifier("col1".to_string()),
+            expr: Expr::Identifier("col1".into()),
             asc: false
         }],
         limit: Some(100)
@@ -289,32 +289,32 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
-                Expr::Identifier("col1".to_string()),
-                Expr::Identifier("col2".to_string()),
-                Expr::Identifier("col3".to_string()),
-                Expr::Identifier("col4".to_string())
+                Expr::Identifier("col1".into()),
+                Expr::Identifier("col2".into()),
+                Expr::Identifier("col3".into()),
+                Expr::Identifier("col4".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Join {
             left: Box::new(TableReference::Table {
-                name: "table1".to_string(),
-                alias: Some("a".to_string())
+                name: "table1".into(),
+                alias: Some("a".into())
             }),
             join_type: JoinType::Inner,
             right: Box::new(TableReference::Table {
-                name: "table2".to_string(),
-                alias: Some("b".to_string())
+                name: "table2".into(),
+                alias: Some("b".into())
             }),
-            on: Some(Expr::BinaryOp {
+            constraint: JoinConstraint::On(Expr::BinaryOp {
                 left: Box::new(Expr::QualifiedIdentifier {
-                    table: "a".to_string(),
-                    column: "col1".to_string()
+                    table: "a".into(),
+                    column: "col1".into()
                 }),
                 op: BinaryOperator::Eq,
                 right: Box::new(Expr::QualifiedIdentifier {
-                    table: "b".to_string(),
-                    column: "col2".to_string()
+                    table: "b".into(),
+                    column: "col2".into()
                 })
             })
         }),
@@ -333,32 +333,32 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
-                Expr::Identifier("col1".to_string()),
-                Expr::Identifier("col2".to_string()),
-                Expr::Identifier("col3".to_string()),
-                Expr::Identifier("col4".to_string())
+                Expr::Identifier("col1".into()),
+                Expr::Identifier("col2".into()),
+                Expr::Identifier("col3".into()),
+                Expr::Identifier("col4".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Join {
             left: Box::new(TableReference::Table {
-                name: "table1".to_string(),
-                alias: Some("a".to_string())
+                name: "table1".into(),
+                alias: Some("a".into())
             }),
             join_type: JoinType::Inner,
             right: Box::new(TableReference::Table {
-                name: "table2".to_string(),
-                alias: Some("b".to_string())
+                name: "table2".into(),
+                alias: Some("b".into())
             }),
-            on: Some(Expr::BinaryOp {
+            constraint: JoinConstraint::On(Expr::BinaryOp {
                 left: Box::new(Expr::QualifiedIdentifier {
-                    table: "a".to_string(),
-                    column: "col1".to_string()
+                    table: "a".into(),
+                    column: "col1".into()
                 }),
                 op: BinaryOperator::Eq,
                 right: Box::new(Expr::QualifiedIdentifier {
-                    table: "b".to_string(),
-                    column: "col2".to_string()
+                    table: "b".into(),
+                    column: "col2".into()
                 })
             })
         }),
@@ -377,32 +377,32 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
-                Expr::Identifier("col1".to_string()),
-                Expr::Identifier("col2".to_string()),
-                Expr::Identifier("col3".to_string()),
-                Expr::Identifier("col4".to_string())
+                Expr::Identifier("col1".into()),
+                Expr::Identifier("col2".into()),
+                Expr::Identifier("col3".into()),
+                Expr::Identifier("col4".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Join {
             left: Box::new(TableReference::Table {
-                name: "table1".to_string(),
-                alias: Some("a".to_string())
+                name: "table1".into(),
+                alias: Some("a".into())
             }),
             join_type: JoinType::Left,
             right: Box::new(TableReference::Table {
-                name: "table2".to_string(),
-                alias: Some("b".to_string())
+                name: "table2".into(),
+                alias: Some("b".into())
             }),
-            on: Some(Expr::BinaryOp {
+            constraint: JoinConstraint::On(Expr::BinaryOp {
                 left: Box::new(Expr::QualifiedIdentifier {
-                    table: "a".to_string(),
-                    column: "col1".to_string()
+                    table: "a".into(),
+                    column: "col1".into()
                 }),
                 op: BinaryOperator::Eq,
                 right: Box::new(Expr::QualifiedIdentifier {
-                    table: "b".to_string(),
-                    column: "col2".to_string()
+                    table: "b".into(),
+                    column: "col2".into()
                 })
             })
         }),
@@ -421,32 +421,32 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
-                Expr::Identifier("col1".to_string()),
-                Expr::Identifier("col2".to_string()),
-                Expr::Identifier("col3".to_string()),
-                Expr::Identifier("col4".to_string())
+                Expr::Identifier("col1".into()),
+                Expr::Identifier("col2".into()),
+                Expr::Identifier("col3".into()),
+                Expr::Identifier("col4".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Join {
             left: Box::new(TableReference::Table {
-                name: "table1".to_string(),
-                alias: Some("a".to_string())
+                name: "table1".into(),
+                alias: Some("a".into())
             }),
             join_type: JoinType::Right,
             right: Box::new(TableReference::Table {
-                name: "table2".to_string(),
-                alias: Some("b".to_string())
+                name: "table2".into(),
+                alias: Some("b".into())
             }),
-            on: Some(Expr::BinaryOp {
+            constraint: JoinConstraint::On(Expr::BinaryOp {
                 left: Box::new(Expr::QualifiedIdentifier {
-                    table: "a".to_string(),
-                    column: "col1".to_string()
+                    table: "a".into(),
+                    column: "col1".into()
                 }),
                 op: BinaryOperator::Eq,
                 right: Box::new(Expr::QualifiedIdentifier {
-                    table: "b".to_string(),
-                    column: "col2".to_string()
+                    table: "b".into(),
+                    column: "col2".into()
                 })
             })
         }),
@@ -465,32 +465,32 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
-                Expr::Identifier("col1".to_string()),
-                Expr::Identifier("col2".to_string()),
-                Expr::Identifier("col3".to_string()),
-                Expr::Identifier("col4".to_string())
+                Expr::Identifier("col1".into()),
+                Expr::Identifier("col2".into()),
+                Expr::Identifier("col3".into()),
+                Expr::Identifier("col4".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Join {
             left: Box::new(TableReference::Table {
-                name: "table1".to_string(),
-                alias: Some("a".to_string())
+                name: "table1".into(),
+                alias: Some("a".into())
             }),
             join_type: JoinType::Full,
             right: Box::new(TableReference::Table {
-                name: "table2".to_string(),
-                alias: Some("b".to_string())
+                name: "table2".into(),
+                alias: Some("b".into())
             }),
-            on: Some(Expr::BinaryOp {
+            constraint: JoinConstraint::On(Expr::BinaryOp {
                 left: Box::new(Expr::QualifiedIdentifier {
-                    table: "a".to_string(),
-                    column: "col1".to_string()
+                    table: "a".into(),
+                    column: "col1".into()
                 }),
                 op: BinaryOperator::Eq,
                 right: Box::new(Expr::QualifiedIdentifier {
-                    table: "b".to_string(),
-                    column: "col2".to_string()
+                    table: "b".into(),
+                    column: "col2".into()
                 })
             })
         }),
@@ -509,32 +509,32 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
-                Expr::Identifier("col1".to_string()),
-                Expr::Identifier("col2".to_string()),
-                Expr::Identifier("col3".to_string()),
-                Expr::Identifier("col4".to_string())
+                Expr::Identifier("col1".into()),
+                Expr::Identifier("col2".into()),
+                Expr::Identifier("col3".into()),
+                Expr::Identifier("col4".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Join {
             left: Box::new(TableReference::Table {
-                name: "table1".to_string(),
-                alias: Some("a".to_string())
+                name: "table1".into(),
+                alias: Some("a".into())
             }),
             join_type: JoinType::Cross,
             right: Box::new(TableReference::Table {
-                name: "table2".to_string(),
-                alias: Some("b".to_string())
+                name: "table2".into(),
+                alias: Some("b".into())
             }),
-            on: Some(Expr::BinaryOp {
+            constraint: JoinConstraint::On(Expr::BinaryOp {
                 left: Box::new(Expr::QualifiedIdentifier {
-                    table: "a".to_string(),
-                    column: "col1".to_string()
+                    table: "a".into(),
+                    column: "col1".into()
                 }),
                 op: BinaryOperator::Eq,
                 right: Box::new(Expr::QualifiedIdentifier {
-                    table: "b".to_string(),
-                    column: "col2".to_string()
+                    table: "b".into(),
+                    column: "col2".into()
                 })
             })
         }),
@@ -553,50 +553,50 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::List(vec![
-                Expr::Identifier("col1".to_string()),
-                Expr::Identifier("col2".to_string()),
-                Expr::Identifier("col3".to_string()),
-                Expr::Identifier("col4".to_string())
+                Expr::Identifier("col1".into()),
+                Expr::Identifier("col2".into()),
+                Expr::Identifier("col3".into()),
+                Expr::Identifier("col4".into())
             ]),
             alias: None
         }],
         from: Some(TableReference::Join {
             left: Box::new(TableReference::Join {
                 left: Box::new(TableReference::Table {
-                    name: "table1".to_string(),
-                    alias: Some("a".to_string())
+                    name: "table1".into(),
+                    alias: Some("a".into())
                 }),
                 join_type: JoinType::Inner,
                 right: Box::new(TableReference::Table {
-                    name: "table2".to_string(),
-                    alias: Some("b".to_string())
+                    name: "table2".into(),
+                    alias: Some("b".into())
                 }),
-                on: Some(Expr::BinaryOp {
+                constraint: JoinConstraint::On(Expr::BinaryOp {
                     left: Box::new(Expr::QualifiedIdentifier {
-                        table: "a".to_string(),
-                        column: "col1".to_string()
+                        table: "a".into(),
+                        column: "col1".into()
                     }),
                     op: BinaryOperator::Eq,
                     right: Box::new(Expr::QualifiedIdentifier {
-                        table: "b".to_string(),
-                        column: "col2".to_string()
+                        table: "b".into(),
+                        column: "col2".into()
                     })
                 })
             }),
             join_type: JoinType::Inner,
             right: Box::new(TableReference::Table {
-                name: "table3".to_string(),
-                alias: Some("c".to_string())
+                name: "table3".into(),
+                alias: Some("c".into())
             }),
-            on: Some(Expr::BinaryOp {
+            constraint: JoinConstraint::On(Expr::BinaryOp {
                 left: Box::new(Expr::QualifiedIdentifier {
-                    table: "c".to_string(),
-                    column: "col3".to_string()
+                    table: "c".into(),
+                    column: "col3".into()
                 }),
                 op: BinaryOperator::Eq,
                 right: Box::new(Expr::QualifiedIdentifier {
-                    table: "a".to_string(),
-                    column: "col3".to_string()
+                    table: "a".into(),
+                    column: "col3".into()
                 })
             })
         }),
@@ -613,13 +613,14 @@ sql_test!(
     "WITH t1 AS (SELECT * FROM my_table) SELECT * FROM t1;",
     Statement::With(WithStatement {
         recursive: false,
-        ctes: vec![(
-            "t1".to_string(),
-            SelectStatement {
+        ctes: vec![Cte {
+            name: "t1".into(),
+            materialized: None,
+            query: SelectStatement {
                 distinct: false,
-                columns: vec![SelectItem::Star],
+                columns: vec![SelectItem::Star(WildcardOptions::default())],
                 from: Some(TableReference::Table {
-                    name: "my_table".to_string(),
+                    name: "my_table".into(),
                     alias: None
                 }),
                 where_clause: None,
@@ -628,12 +629,12 @@ sql_test!(
                 order_by: vec![],
                 limit: None
             }
-        )],
+        }],
         body: Box::new(SelectStatement {
             distinct: false,
-            columns: vec![SelectItem::Star],
+            columns: vec![SelectItem::Star(WildcardOptions::default())],
             from: Some(TableReference::Table {
-                name: "t1".to_string(),
+                name: "t1".into(),
                 alias: None
             }),
             where_clause: None,
@@ -651,13 +652,14 @@ sql_test!(
     Statement::With(WithStatement {
         recursive: false,
         ctes: vec![
-            (
-                "t1".to_string(),
-                SelectStatement {
+            Cte {
+                name: "t1".into(),
+                materialized: None,
+                query: SelectStatement {
                     distinct: false,
-                    columns: vec![SelectItem::Star],
+                    columns: vec![SelectItem::Star(WildcardOptions::default())],
                     from: Some(TableReference::Table {
-                        name: "my_table".to_string(),
+                        name: "my_table".into(),
                         alias: None
                     }),
                     where_clause: None,
@@ -666,14 +668,15 @@ sql_test!(
                     order_by: vec![],
                     limit: None
                 }
-            ),
-            (
-                "t2".to_string(),
-                SelectStatement {
+            },
+            Cte {
+                name: "t2".into(),
+                materialized: None,
+                query: SelectStatement {
                     distinct: false,
-                    columns: vec![SelectItem::Star],
+                    columns: vec![SelectItem::Star(WildcardOptions::default())],
                     from: Some(TableReference::Table {
-                        name: "my_other_table".to_string(),
+                        name: "my_other_table".into(),
                         alias: None
                     }),
                     where_clause: None,
@@ -682,25 +685,25 @@ sql_test!(
                     order_by: vec![],
                     limit: None
                 }
-            )
+            }
         ],
         body: Box::new(SelectStatement {
             distinct: false,
-            columns: vec![SelectItem::Star],
+            columns: vec![SelectItem::Star(WildcardOptions::default())],
             from: Some(TableReference::Join {
                 left: Box::new(TableReference::Table {
-                    name: "t1".to_string(),
+                    name: "t1".into(),
                     alias: None
                 }),
                 join_type: JoinType::Inner,
                 right: Box::new(TableReference::Table {
-                    name: "t2".to_string(),
+                    name: "t2".into(),
                     alias: None
                 }),
-                on: Some(Expr::BinaryOp {
-                    left: Box::new(Expr::Identifier("t1".to_string())),
+                constraint: JoinConstraint::On(Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier("t1".into())),
                     op: BinaryOperator::Eq,
-                    right: Box::new(Expr::Identifier("t2".to_string()))
+                    right: Box::new(Expr::Identifier("t2".into()))
                 })
             }),
             where_clause: None,
@@ -719,14 +722,14 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::Case {
-                operand: Some(Box::new(Expr::Identifier("status".to_string()))),
+                operand: Some(Box::new(Expr::Identifier("status".into()))),
                 when_clauses: vec![
                     WhenClause {
-                        condition: Expr::String("active".to_string()),
+                        condition: Expr::String("active".into()),
                         result: Expr::Number(1.0)
                     },
                     WhenClause {
-                        condition: Expr::String("inactive".to_string()),
+                        condition: Expr::String("inactive".into()),
                         result: Expr::Number(0.0)
                     }
                 ],
@@ -735,7 +738,7 @@ sql_test!(
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "users".to_string(),
+            name: "users".into(),
             alias: None
         }),
         where_clause: None,
@@ -757,27 +760,27 @@ sql_test!(
                 when_clauses: vec![
                     WhenClause {
                         condition: Expr::BinaryOp {
-                            left: Box::new(Expr::Identifier("age".to_string())),
+                            left: Box::new(Expr::Identifier("age".into())),
                             op: BinaryOperator::Lt,
                             right: Box::new(Expr::Number(18.0))
                         },
-                        result: Expr::String("minor".to_string())
+                        result: Expr::String("minor".into())
                     },
                     WhenClause {
                         condition: Expr::BinaryOp {
-                            left: Box::new(Expr::Identifier("age".to_string())),
+                            left: Box::new(Expr::Identifier("age".into())),
                             op: BinaryOperator::Ge,
                             right: Box::new(Expr::Number(65.0))
                         },
-                        result: Expr::String("senior".to_string())
+                        result: Expr::String("senior".into())
                     }
                 ],
-                else_clause: Some(Box::new(Expr::String("adult".to_string())))
+                else_clause: Some(Box::new(Expr::String("adult".into())))
             },
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "people".to_string(),
+            name: "people".into(),
             alias: None
         }),
         where_clause: None,
@@ -793,13 +796,13 @@ sql_test!(
     "SELECT * FROM products WHERE price BETWEEN 10 AND 100;",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "products".to_string(),
+            name: "products".into(),
             alias: None
         }),
         where_clause: Some(Expr::Between {
-            expr: Box::new(Expr::Identifier("price".to_string())),
+            expr: Box::new(Expr::Identifier("price".into())),
             negated: false,
             low: Box::new(Expr::Number(10.0)),
             high: Box::new(Expr::Number(100.0))
@@ -816,13 +819,13 @@ sql_test!(
     "SELECT * FROM users WHERE id IN (1, 2, 3);",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "users".to_string(),
+            name: "users".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("id".to_string())),
+            left: Box::new(Expr::Identifier("id".into())),
             op: BinaryOperator::In,
             right: Box::new(Expr::List(vec![
                 Expr::Number(1.0),
@@ -842,26 +845,26 @@ sql_test!(
     "SELECT * FROM orders WHERE user_id IN (SELECT id FROM users WHERE active = true);",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "orders".to_string(),
+            name: "orders".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("user_id".to_string())),
+            left: Box::new(Expr::Identifier("user_id".into())),
             op: BinaryOperator::In,
             right: Box::new(Expr::Subquery(Box::new(SelectStatement {
                 distinct: false,
                 columns: vec![SelectItem::ExprWithAlias {
-                    expr: Expr::Identifier("id".to_string()),
+                    expr: Expr::Identifier("id".into()),
                     alias: None
                 }],
                 from: Some(TableReference::Table {
-                    name: "users".to_string(),
+                    name: "users".into(),
                     alias: None
                 }),
                 where_clause: Some(Expr::BinaryOp {
-                    left: Box::new(Expr::Identifier("active".to_string())),
+                    left: Box::new(Expr::Identifier("active".into())),
                     op: BinaryOperator::Eq,
                     right: Box::new(Expr::Boolean(true))
                 }),
@@ -883,9 +886,9 @@ sql_test!(
     "SELECT * FROM users WHERE EXISTS (SELECT 1 FROM orders WHERE orders.user_id = users.id);",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "users".to_string(),
+            name: "users".into(),
             alias: None
         }),
         where_clause: Some(Expr::Exists(Box::new(SelectStatement {
@@ -895,18 +898,18 @@ sql_test!(
                 alias: None
             }],
             from: Some(TableReference::Table {
-                name: "orders".to_string(),
+                name: "orders".into(),
                 alias: None
             }),
             where_clause: Some(Expr::BinaryOp {
                 left: Box::new(Expr::QualifiedIdentifier {
-                    table: "orders".to_string(),
-                    column: "user_id".to_string()
+                    table: "orders".into(),
+                    column: "user_id".into()
                 }),
                 op: BinaryOperator::Eq,
                 right: Box::new(Expr::QualifiedIdentifier {
-                    table: "users".to_string(),
-                    column: "id".to_string()
+                    table: "users".into(),
+                    column: "id".into()
                 })
             }),
             group_by: vec![],
@@ -926,13 +929,13 @@ sql_test!(
     "SELECT * FROM users WHERE email IS NULL;",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "users".to_string(),
+            name: "users".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("email".to_string())),
+            left: Box::new(Expr::Identifier("email".into())),
             op: BinaryOperator::Is,
             right: Box::new(Expr::Null)
         }),
@@ -948,13 +951,13 @@ sql_test!(
     "SELECT * FROM users WHERE email IS NOT NULL;",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "users".to_string(),
+            name: "users".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("email".to_string())),
+            left: Box::new(Expr::Identifier("email".into())),
             op: BinaryOperator::IsNot,
             right: Box::new(Expr::Null)
         }),
@@ -970,15 +973,15 @@ sql_test!(
     "SELECT * FROM users WHERE name LIKE 'John%';",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "users".to_string(),
+            name: "users".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("name".to_string())),
+            left: Box::new(Expr::Identifier("name".into())),
             op: BinaryOperator::Like,
-            right: Box::new(Expr::String("John%".to_string()))
+            right: Box::new(Expr::String("John%".into()))
         }),
         group_by: vec![],
         having: None,
@@ -995,17 +998,17 @@ sql_test!(
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::BinaryOp {
                 left: Box::new(Expr::BinaryOp {
-                    left: Box::new(Expr::Identifier("first_name".to_string())),
+                    left: Box::new(Expr::Identifier("first_name".into())),
                     op: BinaryOperator::Concat,
-                    right: Box::new(Expr::String(" ".to_string()))
+                    right: Box::new(Expr::String(" ".into()))
                 }),
                 op: BinaryOperator::Concat,
-                right: Box::new(Expr::Identifier("last_name".to_string()))
+                right: Box::new(Expr::Identifier("last_name".into()))
             },
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "users".to_string(),
+            name: "users".into(),
             alias: None
         }),
         where_clause: None,
@@ -1024,7 +1027,7 @@ sql_test!(
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::BinaryOp {
                 left: Box::new(Expr::BinaryOp {
-                    left: Box::new(Expr::Identifier("price".to_string())),
+                    left: Box::new(Expr::Identifier("price".into())),
                     op: BinaryOperator::Multiply,
                     right: Box::new(Expr::Number(1.1))
                 }),
@@ -1034,7 +1037,7 @@ sql_test!(
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "products".to_string(),
+            name: "products".into(),
             alias: None
         }),
         where_clause: None,
@@ -1052,14 +1055,14 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::BinaryOp {
-                left: Box::new(Expr::Identifier("id".to_string())),
+                left: Box::new(Expr::Identifier("id".into())),
                 op: BinaryOperator::Modulo,
                 right: Box::new(Expr::Number(2.0))
             },
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "users".to_string(),
+            name: "users".into(),
             alias: None
         }),
         where_clause: None,
@@ -1078,12 +1081,12 @@ sql_test!(
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::UnaryOp {
                 op: UnaryOperator::Minus,
-                expr: Box::new(Expr::Identifier("price".to_string()))
+                expr: Box::new(Expr::Identifier("price".into()))
             },
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "products".to_string(),
+            name: "products".into(),
             alias: None
         }),
         where_clause: None,
@@ -1099,14 +1102,14 @@ sql_test!(
     "SELECT * FROM users WHERE NOT active;",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "users".to_string(),
+            name: "users".into(),
             alias: None
         }),
         where_clause: Some(Expr::UnaryOp {
             op: UnaryOperator::Not,
-            expr: Box::new(Expr::Identifier("active".to_string()))
+            expr: Box::new(Expr::Identifier("active".into()))
         }),
         group_by: vec![],
         having: None,
@@ -1121,11 +1124,11 @@ sql_test!(
     Statement::Select(SelectStatement {
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
-            expr: Expr::Identifier("user_id".to_string()),
-            alias: Some("id".to_string())
+            expr: Expr::Identifier("user_id".into()),
+            alias: Some("id".into())
         }],
         from: Some(TableReference::Table {
-            name: "orders".to_string(),
+            name: "orders".into(),
             alias: None
         }),
         where_clause: None,
@@ -1142,11 +1145,11 @@ sql_test!(
     Statement::Select(SelectStatement {
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
-            expr: Expr::Identifier("user_id".to_string()),
-            alias: Some("id".to_string())
+            expr: Expr::Identifier("user_id".into()),
+            alias: Some("id".into())
         }],
         from: Some(TableReference::Table {
-            name: "orders".to_string(),
+            name: "orders".into(),
             alias: None
         }),
         where_clause: None,
@@ -1162,16 +1165,16 @@ sql_test!(
     "SELECT * FROM (SELECT id FROM users) AS u;",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Subquery {
             query: Box::new(SelectStatement {
                 distinct: false,
                 columns: vec![SelectItem::ExprWithAlias {
-                    expr: Expr::Identifier("id".to_string()),
+                    expr: Expr::Identifier("id".into()),
                     alias: None
                 }],
                 from: Some(TableReference::Table {
-                    name: "users".to_string(),
+                    name: "users".into(),
                     alias: None
                 }),
                 where_clause: None,
@@ -1180,7 +1183,8 @@ sql_test!(
                 order_by: vec![],
                 limit: None
             }),
-            alias: "u".to_string()
+            alias: "u".into(),
+            lateral: false
         }),
         where_clause: None,
         group_by: vec![],
@@ -1195,13 +1199,13 @@ sql_test!(
     "SELECT * FROM table1 WHERE Value IN (1,2);",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "table1".to_string(),
+            name: "table1".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("Value".to_string())),
+            left: Box::new(Expr::Identifier("Value".into())),
             op: BinaryOperator::In,
             right: Box::new(Expr::List(vec![Expr::Number(1.0), Expr::Number(2.0)]))
         }),
@@ -1217,13 +1221,13 @@ sql_test!(
     "SELECT * FROM table1 WHERE Value NOT IN (1,2);",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "table1".to_string(),
+            name: "table1".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("Value".to_string())),
+            left: Box::new(Expr::Identifier("Value".into())),
             op: BinaryOperator::NotIn,
             right: Box::new(Expr::List(vec![Expr::Number(1.0), Expr::Number(2.0)]))
         }),
@@ -1239,15 +1243,15 @@ sql_test!(
     "SELECT * FROM table1 WHERE Value LIKE '%hello%';",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "table1".to_string(),
+            name: "table1".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("Value".to_string())),
+            left: Box::new(Expr::Identifier("Value".into())),
             op: BinaryOperator::Like,
-            right: Box::new(Expr::String("%hello%".to_string()))
+            right: Box::new(Expr::String("%hello%".into()))
         }),
         group_by: vec![],
         having: None,
@@ -1261,15 +1265,15 @@ sql_test!(
     "SELECT * FROM table1 WHERE Value NOT LIKE '%hello%';",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "table1".to_string(),
+            name: "table1".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("Value".to_string())),
+            left: Box::new(Expr::Identifier("Value".into())),
             op: BinaryOperator::NotLike,
-            right: Box::new(Expr::String("%hello%".to_string()))
+            right: Box::new(Expr::String("%hello%".into()))
         }),
         group_by: vec![],
         having: None,
@@ -1284,14 +1288,14 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::FunctionCall {
-                name: "COUNT".to_string(),
-                args: vec![Expr::Identifier("user_id".to_string())],
+                name: "COUNT".into(),
+                args: vec![Expr::Identifier("user_id".into())],
                 distinct: true
             },
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "orders".to_string(),
+            name: "orders".into(),
             alias: None
         }),
         where_clause: None,
@@ -1309,14 +1313,14 @@ sql_test!(
         distinct: false,
         columns: vec![SelectItem::ExprWithAlias {
             expr: Expr::FunctionCall {
-                name: "SUM".to_string(),
-                args: vec![Expr::Identifier("amount".to_string())],
+                name: "SUM".into(),
+                args: vec![Expr::Identifier("amount".into())],
                 distinct: true
             },
             alias: None
         }],
         from: Some(TableReference::Table {
-            name: "payments".to_string(),
+            name: "payments".into(),
             alias: None
         }),
         where_clause: None,
@@ -1335,7 +1339,7 @@ sql_test!(
         columns: vec![
             SelectItem::ExprWithAlias {
                 expr: Expr::FunctionCall {
-                    name: "COUNT".to_string(),
+                    name: "COUNT".into(),
                     args: vec![Expr::Star],
                     distinct: false
                 },
@@ -1343,39 +1347,39 @@ sql_test!(
             },
             SelectItem::ExprWithAlias {
                 expr: Expr::FunctionCall {
-                    name: "SUM".to_string(),
-                    args: vec![Expr::Identifier("amount".to_string())],
+                    name: "SUM".into(),
+                    args: vec![Expr::Identifier("amount".into())],
                     distinct: false
                 },
                 alias: None
             },
             SelectItem::ExprWithAlias {
                 expr: Expr::FunctionCall {
-                    name: "AVG".to_string(),
-                    args: vec![Expr::Identifier("amount".to_string())],
+                    name: "AVG".into(),
+                    args: vec![Expr::Identifier("amount".into())],
                     distinct: false
                 },
                 alias: None
             },
             SelectItem::ExprWithAlias {
                 expr: Expr::FunctionCall {
-                    name: "MIN".to_string(),
-                    args: vec![Expr::Identifier("amount".to_string())],
+                    name: "MIN".into(),
+                    args: vec![Expr::Identifier("amount".into())],
                     distinct: false
                 },
                 alias: None
             },
             SelectItem::ExprWithAlias {
                 expr: Expr::FunctionCall {
-                    name: "MAX".to_string(),
-                    args: vec![Expr::Identifier("amount".to_string())],
+                    name: "MAX".into(),
+                    args: vec![Expr::Identifier("amount".into())],
                     distinct: false
                 },
                 alias: None
             }
         ],
         from: Some(TableReference::Table {
-            name: "orders".to_string(),
+            name: "orders".into(),
             alias: None
         }),
         where_clause: None,
@@ -1391,9 +1395,9 @@ sql_test!(
     "SELECT * FROM users ORDER BY last_name ASC, first_name DESC;",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "users".to_string(),
+            name: "users".into(),
             alias: None
         }),
         where_clause: None,
@@ -1401,11 +1405,11 @@ sql_test!(
         having: None,
         order_by: vec![
             OrderByExpr {
-                expr: Expr::Identifier("last_name".to_string()),
+                expr: Expr::Identifier("last_name".into()),
                 asc: true
             },
             OrderByExpr {
-                expr: Expr::Identifier("first_name".to_string()),
+                expr: Expr::Identifier("first_name".into()),
                 asc: false
             }
         ],
@@ -1418,9 +1422,9 @@ sql_test!(
     "SELECT * FROM products ORDER BY price * discount DESC;",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "products".to_string(),
+            name: "products".into(),
             alias: None
         }),
         where_clause: None,
@@ -1428,9 +1432,9 @@ sql_test!(
         having: None,
         order_by: vec![OrderByExpr {
             expr: Expr::BinaryOp {
-                left: Box::new(Expr::Identifier("price".to_string())),
+                left: Box::new(Expr::Identifier("price".into())),
                 op: BinaryOperator::Multiply,
-                right: Box::new(Expr::Identifier("discount".to_string()))
+                right: Box::new(Expr::Identifier("discount".into()))
             },
             asc: false
         }],
@@ -1445,16 +1449,16 @@ sql_test!(
         distinct: false,
         columns: vec![
             SelectItem::ExprWithAlias {
-                expr: Expr::Identifier("category".to_string()),
+                expr: Expr::Identifier("category".into()),
                 alias: None
             },
             SelectItem::ExprWithAlias {
-                expr: Expr::Identifier("brand".to_string()),
+                expr: Expr::Identifier("brand".into()),
                 alias: None
             },
             SelectItem::ExprWithAlias {
                 expr: Expr::FunctionCall {
-                    name: "COUNT".to_string(),
+                    name: "COUNT".into(),
                     args: vec![Expr::Star],
                     distinct: false
                 },
@@ -1462,13 +1466,13 @@ sql_test!(
             }
         ],
         from: Some(TableReference::Table {
-            name: "products".to_string(),
+            name: "products".into(),
             alias: None
         }),
         where_clause: None,
         group_by: vec![
-            Expr::Identifier("category".to_string()),
-            Expr::Identifier("brand".to_string())
+            Expr::Identifier("category".into()),
+            Expr::Identifier("brand".into())
         ],
         having: None,
         order_by: vec![],
@@ -1484,15 +1488,15 @@ sql_test!(
         columns: vec![
             SelectItem::ExprWithAlias {
                 expr: Expr::FunctionCall {
-                    name: "YEAR".to_string(),
-                    args: vec![Expr::Identifier("date".to_string())],
+                    name: "YEAR".into(),
+                    args: vec![Expr::Identifier("date".into())],
                     distinct: false
                 },
                 alias: None
             },
             SelectItem::ExprWithAlias {
                 expr: Expr::FunctionCall {
-                    name: "COUNT".to_string(),
+                    name: "COUNT".into(),
                     args: vec![Expr::Star],
                     distinct: false
                 },
@@ -1500,13 +1504,13 @@ sql_test!(
             }
         ],
         from: Some(TableReference::Table {
-            name: "orders".to_string(),
+            name: "orders".into(),
             alias: None
         }),
         where_clause: None,
         group_by: vec![Expr::FunctionCall {
-            name: "YEAR".to_string(),
-            args: vec![Expr::Identifier("date".to_string())],
+            name: "YEAR".into(),
+            args: vec![Expr::Identifier("date".into())],
             distinct: false
         }],
         having: None,
@@ -1520,13 +1524,14 @@ sql_test!(
     "WITH RECURSIVE cte AS (SELECT 1 AS n) SELECT * FROM cte;",
     Statement::With(WithStatement {
         recursive: true,
-        ctes: vec![(
-            "cte".to_string(),
-            SelectStatement {
+        ctes: vec![Cte {
+            name: "cte".into(),
+            materialized: None,
+            query: SelectStatement {
                 distinct: false,
                 columns: vec![SelectItem::ExprWithAlias {
                     expr: Expr::Number(1.0),
-                    alias: Some("n".to_string())
+                    alias: Some("n".into())
                 }],
                 from: None,
                 where_clause: None,
@@ -1535,12 +1540,12 @@ sql_test!(
                 order_by: vec![],
                 limit: None
             }
-        )],
+        }],
         body: Box::new(SelectStatement {
             distinct: false,
-            columns: vec![SelectItem::Star],
+            columns: vec![SelectItem::Star(WildcardOptions::default())],
             from: Some(TableReference::Table {
-                name: "cte".to_string(),
+                name: "cte".into(),
                 alias: None
             }),
             where_clause: None,
@@ -1564,17 +1569,18 @@ sql_test!(
     Statement::With(WithStatement {
         recursive: false,
         ctes: vec![
-            (
-                "active_users".to_string(),
-                SelectStatement {
+            Cte {
+                name: "active_users".into(),
+                materialized: None,
+                query: SelectStatement {
                     distinct: false,
-                    columns: vec![SelectItem::Star],
+                    columns: vec![SelectItem::Star(WildcardOptions::default())],
                     from: Some(TableReference::Table {
-                        name: "users".to_string(),
+                        name: "users".into(),
                         alias: None
                     }),
                     where_clause: Some(Expr::BinaryOp {
-                        left: Box::new(Expr::Identifier("active".to_string())),
+                        left: Box::new(Expr::Identifier("active".into())),
                         op: BinaryOperator::Eq,
                         right: Box::new(Expr::Boolean(true))
                     }),
@@ -1583,44 +1589,45 @@ sql_test!(
                     order_by: vec![],
                     limit: None
                 }
-            ),
-            (
-                "recent_orders".to_string(),
-                SelectStatement {
+            },
+            Cte {
+                name: "recent_orders".into(),
+                materialized: None,
+                query: SelectStatement {
                     distinct: false,
-                    columns: vec![SelectItem::Star],
+                    columns: vec![SelectItem::Star(WildcardOptions::default())],
                     from: Some(TableReference::Table {
-                        name: "orders".to_string(),
+                        name: "orders".into(),
                         alias: None
                     }),
                     where_clause: Some(Expr::BinaryOp {
-                        left: Box::new(Expr::Identifier("date".to_string())),
+                        left: Box::new(Expr::Identifier("date".into())),
                         op: BinaryOperator::Gt,
-                        right: Box::new(Expr::String("2024-01-01".to_string()))
+                        right: Box::new(Expr::String("2024-01-01".into()))
                     }),
                     group_by: vec![],
                     having: None,
                     order_by: vec![],
                     limit: None
                 }
-            )
+            }
         ],
         body: Box::new(SelectStatement {
             distinct: false,
             columns: vec![
                 SelectItem::ExprWithAlias {
                     expr: Expr::QualifiedIdentifier {
-                        table: "u".to_string(),
-                        column: "name".to_string()
+                        table: "u".into(),
+                        column: "name".into()
                     },
                     alias: None
                 },
                 SelectItem::ExprWithAlias {
                     expr: Expr::FunctionCall {
-                        name: "COUNT".to_string(),
+                        name: "COUNT".into(),
                         args: vec![Expr::QualifiedIdentifier {
-                            table: "o".to_string(),
-                            column: "id".to_string()
+                            table: "o".into(),
+                            column: "id".into()
                         }],
                         distinct: false
                     },
@@ -1629,30 +1636,30 @@ sql_test!(
             ],
             from: Some(TableReference::Join {
                 left: Box::new(TableReference::Table {
-                    name: "active_users".to_string(),
-                    alias: Some("u".to_string())
+                    name: "active_users".into(),
+                    alias: Some("u".into())
                 }),
                 join_type: JoinType::Inner,
                 right: Box::new(TableReference::Table {
-                    name: "recent_orders".to_string(),
-                    alias: Some("o".to_string())
+                    name: "recent_orders".into(),
+                    alias: Some("o".into())
                 }),
-                on: Some(Expr::BinaryOp {
+                constraint: JoinConstraint::On(Expr::BinaryOp {
                     left: Box::new(Expr::QualifiedIdentifier {
-                        table: "u".to_string(),
-                        column: "id".to_string()
+                        table: "u".into(),
+                        column: "id".into()
                     }),
                     op: BinaryOperator::Eq,
                     right: Box::new(Expr::QualifiedIdentifier {
-                        table: "o".to_string(),
-                        column: "user_id".to_string()
+                        table: "o".into(),
+                        column: "user_id".into()
                     })
                 })
             }),
             where_clause: None,
             group_by: vec![Expr::QualifiedIdentifier {
-                table: "u".to_string(),
-                column: "name".to_string()
+                table: "u".into(),
+                column: "name".into()
             }],
             having: None,
             order_by: vec![],
@@ -1666,39 +1673,39 @@ sql_test!(
     "SELECT * FROM users WHERE id IN (SELECT user_id FROM orders WHERE product_id IN (SELECT id FROM products WHERE price > 100));",
     Statement::Select(SelectStatement {
         distinct: false,
-        columns: vec![SelectItem::Star],
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
         from: Some(TableReference::Table {
-            name: "users".to_string(),
+            name: "users".into(),
             alias: None
         }),
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("id".to_string())),
+            left: Box::new(Expr::Identifier("id".into())),
             op: BinaryOperator::In,
             right: Box::new(Expr::Subquery(Box::new(SelectStatement {
                 distinct: false,
                 columns: vec![SelectItem::ExprWithAlias {
-                    expr: Expr::Identifier("user_id".to_string()),
+                    expr: Expr::Identifier("user_id".into()),
                     alias: None
                 }],
                 from: Some(TableReference::Table {
-                    name: "orders".to_string(),
+                    name: "orders".into(),
                     alias: None
                 }),
                 where_clause: Some(Expr::BinaryOp {
-                    left: Box::new(Expr::Identifier("product_id".to_string())),
+                    left: Box::new(Expr::Identifier("product_id".into())),
                     op: BinaryOperator::In,
                     right: Box::new(Expr::Subquery(Box::new(SelectStatement {
                         distinct: false,
                         columns: vec![SelectItem::ExprWithAlias {
-                            expr: Expr::Identifier("id".to_string()),
+                            expr: Expr::Identifier("id".into()),
                             alias: None
                         }],
                         from: Some(TableReference::Table {
-                            name: "products".to_string(),
+                            name: "products".into(),
                             alias: None
                         }),
                         where_clause: Some(Expr::BinaryOp {
-                            left: Box::new(Expr::Identifier("price".to_string())),
+                            left: Box::new(Expr::Identifier("price".into())),
                             op: BinaryOperator::Gt,
                             right: Box::new(Expr::Number(100.0))
                         }),
@@ -1720,3 +1727,311 @@ sql_test!(
         limit: None
     })
 );
+
+sql_test!(
+    select_table_alias_with_as,
+    "SELECT * FROM users AS u;",
+    Statement::Select(SelectStatement {
+        distinct: false,
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
+        from: Some(TableReference::Table {
+            name: "users".into(),
+            alias: Some("u".into())
+        }),
+        where_clause: None,
+        group_by: vec![],
+        having: None,
+        order_by: vec![],
+        limit: None
+    })
+);
+
+sql_test!(
+    select_join_using,
+    "SELECT * FROM orders o LEFT OUTER JOIN users u USING (user_id, tenant_id);",
+    Statement::Select(SelectStatement {
+        distinct: false,
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
+        from: Some(TableReference::Join {
+            left: Box::new(TableReference::Table {
+                name: "orders".into(),
+                alias: Some("o".into())
+            }),
+            join_type: JoinType::Left,
+            right: Box::new(TableReference::Table {
+                name: "users".into(),
+                alias: Some("u".into())
+            }),
+            constraint: JoinConstraint::Using(vec![
+                "user_id".into(),
+                "tenant_id".into()
+            ])
+        }),
+        where_clause: None,
+        group_by: vec![],
+        having: None,
+        order_by: vec![],
+        limit: None
+    })
+);
+
+sql_test!(
+    select_natural_join,
+    "SELECT * FROM orders NATURAL JOIN users;",
+    Statement::Select(SelectStatement {
+        distinct: false,
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
+        from: Some(TableReference::Join {
+            left: Box::new(TableReference::Table {
+                name: "orders".into(),
+                alias: None
+            }),
+            join_type: JoinType::Inner,
+            right: Box::new(TableReference::Table {
+                name: "users".into(),
+                alias: None
+            }),
+            constraint: JoinConstraint::Natural
+        }),
+        where_clause: None,
+        group_by: vec![],
+        having: None,
+        order_by: vec![],
+        limit: None
+    })
+);
+
+sql_test!(
+    select_comma_separated_from,
+    "SELECT * FROM a, b, c WHERE a.id = b.id;",
+    Statement::Select(SelectStatement {
+        distinct: false,
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
+        from: Some(TableReference::Join {
+            left: Box::new(TableReference::Join {
+                left: Box::new(TableReference::Table {
+                    name: "a".into(),
+                    alias: None
+                }),
+                join_type: JoinType::Cross,
+                right: Box::new(TableReference::Table {
+                    name: "b".into(),
+                    alias: None
+                }),
+                constraint: JoinConstraint::None
+            }),
+            join_type: JoinType::Cross,
+            right: Box::new(TableReference::Table {
+                name: "c".into(),
+                alias: None
+            }),
+            constraint: JoinConstraint::None
+        }),
+        where_clause: Some(Expr::BinaryOp {
+            left: Box::new(Expr::QualifiedIdentifier {
+                table: "a".into(),
+                column: "id".into()
+            }),
+            op: BinaryOperator::Eq,
+            right: Box::new(Expr::QualifiedIdentifier {
+                table: "b".into(),
+                column: "id".into()
+            })
+        }),
+        group_by: vec![],
+        having: None,
+        order_by: vec![],
+        limit: None
+    })
+);
+
+sql_test!(
+    select_lateral_subquery,
+    "SELECT * FROM users u, LATERAL (SELECT total FROM orders WHERE user_id = u.id) AS o;",
+    Statement::Select(SelectStatement {
+        distinct: false,
+        columns: vec![SelectItem::Star(WildcardOptions::default())],
+        from: Some(TableReference::Join {
+            left: Box::new(TableReference::Table {
+                name: "users".into(),
+                alias: Some("u".into())
+            }),
+            join_type: JoinType::Cross,
+            right: Box::new(TableReference::Subquery {
+                query: Box::new(SelectStatement {
+                    distinct: false,
+                    columns: vec![SelectItem::ExprWithAlias {
+                        expr: Expr::Identifier("total".into()),
+                        alias: None
+                    }],
+                    from: Some(TableReference::Table {
+                        name: "orders".into(),
+                        alias: None
+                    }),
+                    where_clause: Some(Expr::BinaryOp {
+                        left: Box::new(Expr::Identifier("user_id".into())),
+                        op: BinaryOperator::Eq,
+                        right: Box::new(Expr::QualifiedIdentifier {
+                            table: "u".into(),
+                            column: "id".into()
+                        })
+                    }),
+                    group_by: vec![],
+                    having: None,
+                    order_by: vec![],
+                    limit: None
+                }),
+                alias: "o".into(),
+                lateral: true
+            }),
+            constraint: JoinConstraint::None
+        }),
+        where_clause: None,
+        group_by: vec![],
+        having: None,
+        order_by: vec![],
+        limit: None
+    })
+);
+
+sql_test!(
+    select_qualified_wildcard,
+    "SELECT u.*, o.total FROM users u JOIN orders o ON u.id = o.user_id;",
+    Statement::Select(SelectStatement {
+        distinct: false,
+        columns: vec![
+            SelectItem::QualifiedWildcard("u".into(), WildcardOptions::default()),
+            SelectItem::ExprWithAlias {
+                expr: Expr::QualifiedIdentifier {
+                    table: "o".into(),
+                    column: "total".into()
+                },
+                alias: None
+            }
+        ],
+        from: Some(TableReference::Join {
+            left: Box::new(TableReference::Table {
+                name: "users".into(),
+                alias: Some("u".into())
+            }),
+            join_type: JoinType::Inner,
+            right: Box::new(TableReference::Table {
+                name: "orders".into(),
+                alias: Some("o".into())
+            }),
+            constraint: JoinConstraint::On(Expr::BinaryOp {
+                left: Box::new(Expr::QualifiedIdentifier {
+                    table: "u".into(),
+                    column: "id".into()
+                }),
+                op: BinaryOperator::Eq,
+                right: Box::new(Expr::QualifiedIdentifier {
+                    table: "o".into(),
+                    column: "user_id".into()
+                })
+            })
+        }),
+        where_clause: None,
+        group_by: vec![],
+        having: None,
+        order_by: vec![],
+        limit: None
+    })
+);
+
+sql_test!(
+    select_star_with_modifiers,
+    "SELECT * EXCLUDE (password, salt) REPLACE (price * 2 AS price) RENAME id AS user_id FROM users;",
+    Statement::Select(SelectStatement {
+        distinct: false,
+        columns: vec![SelectItem::Star(WildcardOptions {
+            exclude: vec!["password".into(), "salt".into()],
+            except: vec![],
+            replace: vec![ReplaceItem {
+                expr: Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier("price".into())),
+                    op: BinaryOperator::Multiply,
+                    right: Box::new(Expr::Number(2.0))
+                },
+                column: "price".into()
+            }],
+            rename: vec![RenameItem {
+                column: "id".into(),
+                alias: "user_id".into()
+            }]
+        })],
+        from: Some(TableReference::Table {
+            name: "users".into(),
+            alias: None
+        }),
+        where_clause: None,
+        group_by: vec![],
+        having: None,
+        order_by: vec![],
+        limit: None
+    })
+);
+
+sql_test!(
+    select_qualified_wildcard_except,
+    "SELECT t.* EXCEPT (secret) FROM t;",
+    Statement::Select(SelectStatement {
+        distinct: false,
+        columns: vec![SelectItem::QualifiedWildcard(
+            "t".into(),
+            WildcardOptions {
+                except: vec!["secret".into()],
+                ..WildcardOptions::default()
+            }
+        )],
+        from: Some(TableReference::Table {
+            name: "t".into(),
+            alias: None
+        }),
+        where_clause: None,
+        group_by: vec![],
+        having: None,
+        order_by: vec![],
+        limit: None
+    })
+);
+
+sql_test!(
+    select_multi_part_names,
+    "SELECT analytics.public.events.id, public.events.kind, e.* FROM analytics.public.events e;",
+    Statement::Select(SelectStatement {
+        distinct: false,
+        columns: vec![
+            SelectItem::ExprWithAlias {
+                expr: Expr::QualifiedIdentifier {
+                    table: "analytics.public.events".into(),
+                    column: "id".into()
+                },
+                alias: None
+            },
+            SelectItem::ExprWithAlias {
+                expr: Expr::QualifiedIdentifier {
+                    table: "public.events".into(),
+                    column: "kind".into()
+                },
+                alias: None
+            },
+            SelectItem::QualifiedWildcard("e".into(), WildcardOptions::default())
+        ],
+        from: Some(TableReference::Table {
+            name: "analytics.public.events".into(),
+            alias: Some("e".into())
+        }),
+        where_clause: None,
+        group_by: vec![],
+        having: None,
+        order_by: vec![],
+        limit: None
+    })
+);
+
+#[test]
+fn select_too_many_name_parts() {
+    assert!(parse_sql("SELECT a.b.c.d.e FROM t;").is_err());
+    assert!(parse_sql("SELECT * FROM a.b.c.d;").is_err());
+}
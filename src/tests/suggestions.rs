@@ -0,0 +1,229 @@
+use crate::binder::Binder;
+use crate::catalog::Catalog;
+use crate::diagnostics::{edit_distance, suggest, suggest_keyword};
+use crate::{parse_script, parse_sql};
+
+fn catalog(ddl: &[&str]) -> Catalog {
+    let mut catalog = Catalog::new();
+    for sql in ddl {
+        catalog.apply(&parse_sql(sql).unwrap()).unwrap();
+    }
+    catalog
+}
+
+/// Binds a statement and returns the warnings reported on the way.
+fn warnings(catalog: &Catalog, sql: &str) -> Vec<String> {
+    let mut stmt = parse_sql(sql).unwrap();
+    let mut binder = Binder::new(catalog);
+    binder.bind(&mut stmt).unwrap();
+    binder.warnings().to_vec()
+}
+
+fn shop() -> Catalog {
+    catalog(&[
+        "CREATE TABLE users (id INTEGER, name TEXT, email TEXT);",
+        "CREATE TABLE orders (id INTEGER, user_id INTEGER, total REAL);",
+    ])
+}
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(edit_distance("select", "SELECT"), 0);
+    assert_eq!(edit_distance("SELCT", "select"), 1);
+    assert_eq!(edit_distance("FORM", "from"), 1);
+    assert_eq!(edit_distance("ordr", "order"), 1);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("", "abc"), 3);
+}
+
+#[test]
+fn test_suggest_closest_candidate() {
+    assert_eq!(suggest("nmae", ["id", "name", "email"]), Some("name"));
+    assert_eq!(suggest("emial", ["id", "name", "email"]), Some("email"));
+    assert_eq!(suggest("address", ["id", "name", "email"]), None);
+    // Short words are close to too many names to guess.
+    assert_eq!(suggest("nm", ["no", "name"]), None);
+    assert_eq!(suggest("name", ["name"]), None);
+    assert_eq!(suggest("Name", ["name"]), Some("name"));
+}
+
+#[test]
+fn test_suggest_keyword() {
+    assert_eq!(suggest_keyword("SELCT"), Some("SELECT".to_string()));
+    assert_eq!(suggest_keyword("form"), Some("FROM".to_string()));
+    assert_eq!(suggest_keyword("WHER"), Some("WHERE".to_string()));
+    assert_eq!(suggest_keyword("gropu"), Some("GROUP".to_string()));
+    assert_eq!(suggest_keyword("users"), None);
+}
+
+#[test]
+fn test_misspelled_statement_keyword() {
+    assert_eq!(
+        parse_sql("SELCT * FROM users;"),
+        Err("Unexpected statement type: SELCT, did you mean 'SELECT'?".to_string())
+    );
+    assert_eq!(
+        parse_sql("UPDTE users SET name = 'x';"),
+        Err("Unexpected statement type: UPDTE, did you mean 'UPDATE'?".to_string())
+    );
+    // Quoted identifiers are never taken for keywords.
+    assert_eq!(
+        parse_sql(r#""SELCT" 1;"#),
+        Err(r#"Unexpected statement type: "SELCT""#.to_string())
+    );
+}
+
+#[test]
+fn test_misspelled_clause_keyword() {
+    assert_eq!(
+        parse_sql("SELECT * FORM users;"),
+        Err("Unexpected token after end of statement: FORM, did you mean 'FROM'?".to_string())
+    );
+    assert_eq!(
+        parse_sql("SELECT * FROM users WHERE id = 1 ORDR BY id;"),
+        Err("Unexpected token after end of statement: ORDR, did you mean 'ORDER'?".to_string())
+    );
+}
+
+#[test]
+fn test_misspelled_keyword_taken_for_alias() {
+    // FORM is read as an alias of `name`, and the error is only found at `users`.
+    assert_eq!(
+        parse_sql("SELECT name FORM users;"),
+        Err(
+            "Unexpected token after end of statement: users, did you mean 'FROM' instead of 'FORM'?"
+                .to_string()
+        )
+    );
+    // WHER is read as an alias of the table.
+    assert_eq!(
+        parse_sql("SELECT * FROM users WHER id = 1;"),
+        Err(
+            "Unexpected token after end of statement: id, did you mean 'WHERE' instead of 'WHER'?"
+                .to_string()
+        )
+    );
+    // Aliases that are not close to a keyword are left alone.
+    assert_eq!(
+        parse_sql("SELECT name alias users;"),
+        Err("Unexpected token after end of statement: users".to_string())
+    );
+}
+
+#[test]
+fn test_suggestions_in_diagnostics() {
+    let (_, diagnostics) = parse_script("SELCT 1; SELECT a FROM t WHERE a = 1 GRUOP BY a;");
+    let messages: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Unexpected statement type: SELCT, did you mean 'SELECT'? at line 1, column 1",
+            "Unexpected token after end of statement: GRUOP, did you mean 'GROUP'? at line 1, column 38",
+        ]
+    );
+}
+
+#[test]
+fn test_unknown_table_suggestions() {
+    let catalog = shop();
+    assert_eq!(
+        warnings(&catalog, "SELECT * FROM usres;"),
+        vec!["Unknown table 'usres', did you mean 'users'?"]
+    );
+    assert_eq!(
+        warnings(
+            &catalog,
+            "WITH recent AS (SELECT * FROM orders) SELECT * FROM recnet;"
+        ),
+        vec!["Unknown table 'recnet', did you mean 'recent'?"]
+    );
+    assert_eq!(
+        warnings(&catalog, "SELECT * FROM public.ordrs;"),
+        vec!["Unknown table 'public.ordrs', did you mean 'orders'?"]
+    );
+    // Tables that are just missing from the catalog are expected.
+    assert!(warnings(&catalog, "SELECT * FROM events;").is_empty());
+}
+
+#[test]
+fn test_unknown_column_suggestions() {
+    let catalog = shop();
+    assert_eq!(
+        warnings(&catalog, "SELECT nmae FROM users WHERE emial LIKE '%@x';"),
+        vec![
+            "Unknown column 'nmae', did you mean 'name'?",
+            "Unknown column 'emial', did you mean 'email'?",
+        ]
+    );
+    assert_eq!(
+        warnings(
+            &catalog,
+            "SELECT u.nmae, o.totl FROM users u JOIN orders o ON u.id = o.user_id;"
+        ),
+        vec![
+            "Unknown column 'u.nmae', did you mean 'name'?",
+            "Unknown column 'o.totl', did you mean 'total'?",
+        ]
+    );
+    assert_eq!(
+        warnings(&catalog, "SELECT usr.name FROM users usr2;"),
+        vec!["Unknown table 'usr' in usr.name, did you mean 'usr2'?"]
+    );
+    assert_eq!(
+        warnings(&catalog, "SELECT address FROM users;"),
+        vec!["Unknown column 'address'"]
+    );
+}
+
+#[test]
+fn test_known_columns_are_not_reported() {
+    let catalog = shop();
+    // Select aliases, outer references and correlated subqueries all resolve.
+    assert!(warnings(
+        &catalog,
+        "SELECT name AS n FROM users u WHERE EXISTS (SELECT 1 FROM orders o WHERE o.user_id = u.id) ORDER BY n;"
+    )
+    .is_empty());
+    // Nothing is reported when a relation is unknown, as its columns could be anything.
+    assert!(warnings(&catalog, "SELECT nmae FROM users JOIN events ON true;").is_empty());
+    // LATERAL subqueries may refer to the relations before them.
+    assert!(
+        warnings(
+            &catalog,
+            "SELECT * FROM users, LATERAL (SELECT total FROM orders WHERE user_id = users.id) AS t;"
+        )
+        .is_empty()
+    );
+}
+
+#[test]
+fn test_unknown_columns_of_modified_tables() {
+    let catalog = shop();
+    assert_eq!(
+        warnings(&catalog, "INSERT INTO users (id, nmae) VALUES (1, 'a');"),
+        vec!["Unknown column 'nmae', did you mean 'name'?"]
+    );
+    assert_eq!(
+        warnings(&catalog, "UPDATE users SET name = 'a' WHERE emial = 'b';"),
+        vec!["Unknown column 'emial', did you mean 'email'?"]
+    );
+    assert_eq!(
+        warnings(&catalog, "DELETE FROM usres WHERE id = 1;"),
+        vec!["Unknown table 'usres', did you mean 'users'?"]
+    );
+    // The query of an INSERT does not see the columns of the table it writes to.
+    assert_eq!(
+        warnings(&catalog, "INSERT INTO users SELECT name FROM orders;"),
+        vec!["Unknown column 'name'"]
+    );
+}
+
+#[test]
+fn test_unknown_tables_with_complete_schema() {
+    let catalog = shop();
+    let mut stmt =
+        parse_sql("WITH recent AS (SELECT 1 AS x) SELECT * FROM recent, events;").unwrap();
+    let mut binder = Binder::new(&catalog).with_complete_schema();
+    binder.bind(&mut stmt).unwrap();
+    assert_eq!(binder.warnings(), ["Unknown table 'events'"]);
+}
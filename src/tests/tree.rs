@@ -0,0 +1,95 @@
+use crate::parse_sql;
+use crate::tree::{Tree, statement_tree};
+
+fn tree(sql: &str) -> String {
+    statement_tree(&parse_sql(sql).unwrap()).to_string()
+}
+
+#[test]
+fn test_tree_display() {
+    let tree = Tree::new("root")
+        .child(Tree::with_children(
+            "a",
+            vec![Tree::new("a1"), Tree::new("a2")],
+        ))
+        .child(Tree::with_children("b", vec![Tree::new("b1")]));
+    assert_eq!(
+        tree.to_string(),
+        "root\n├── a\n│   ├── a1\n│   └── a2\n└── b\n    └── b1\n"
+    );
+}
+
+#[test]
+fn test_select_tree() {
+    assert_eq!(
+        tree(
+            "SELECT DISTINCT u.name AS n, count(*) FROM users u JOIN orders o ON u.id = o.user_id GROUP BY u.name HAVING count(*) > 1 ORDER BY n DESC LIMIT 5;"
+        ),
+        "\
+SELECT DISTINCT
+├── Columns
+│   ├── AS n
+│   │   └── u.name
+│   └── count()
+│       └── *
+├── From
+│   └── JOIN
+│       ├── Table users AS u
+│       ├── Table orders AS o
+│       └── On
+│           └── =
+│               ├── u.id
+│               └── o.user_id
+├── Group By
+│   └── u.name
+├── Having
+│   └── >
+│       ├── count()
+│       │   └── *
+│       └── 1
+├── Order By
+│   └── DESC
+│       └── n
+└── Limit 5
+"
+    );
+}
+
+#[test]
+fn test_operator_chains_are_flat() {
+    assert_eq!(
+        tree("SELECT 1 FROM t WHERE a AND b AND (c OR d) AND NOT e;"),
+        "\
+SELECT
+├── Columns
+│   └── 1
+├── From
+│   └── Table t
+└── Where
+    └── AND
+        ├── a
+        ├── b
+        ├── OR
+        │   ├── c
+        │   └── d
+        └── NOT
+            └── e
+"
+    );
+    // Long chains do not nest.
+    let sql = format!("SELECT {};", vec!["x"; 5000].join(" + "));
+    assert_eq!(tree(&sql).lines().count(), 5003);
+}
+
+#[test]
+fn test_dml_and_ddl_trees() {
+    assert_eq!(
+        tree("INSERT INTO t (a, b) VALUES (1, 'x');"),
+        "INSERT INTO t (a, b)\n└── Values\n    └── Row\n        ├── 1\n        └── 'x'\n"
+    );
+    assert_eq!(
+        tree("UPDATE t SET a = a + 1 WHERE id = 2;"),
+        "UPDATE t\n├── Set a\n│   └── +\n│       ├── a\n│       └── 1\n└── Where\n    └── =\n        ├── id\n        └── 2\n"
+    );
+    assert_eq!(tree("DROP TABLE t;"), "DROP TABLE t\n");
+}
@@ -5,13 +5,13 @@ sql_test!(
     test_update_basic,
     "UPDATE my_table SET col1 = 5 WHERE col2 < 3;",
     Statement::Update(UpdateStatement {
-        table: "my_table".to_string(),
+        table: "my_table".into(),
         set_clauses: vec![SetClause {
-            column: "col1".to_string(),
+            column: "col1".into(),
             value: Expr::Number(5.0)
         }],
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("col2".to_string())),
+            left: Box::new(Expr::Identifier("col2".into())),
             op: BinaryOperator::Lt,
             right: Box::new(Expr::Number(3.0))
         })
@@ -22,23 +22,23 @@ sql_test!(
     test_update_multiple_columns,
     "UPDATE users SET name = 'John Doe', email = 'john.doe@example.com', updated_at = '2024-01-01' WHERE id = 1;",
     Statement::Update(UpdateStatement {
-        table: "users".to_string(),
+        table: "users".into(),
         set_clauses: vec![
             SetClause {
-                column: "name".to_string(),
-                value: Expr::String("John Doe".to_string())
+                column: "name".into(),
+                value: Expr::String("John Doe".into())
             },
             SetClause {
-                column: "email".to_string(),
-                value: Expr::String("john.doe@example.com".to_string())
+                column: "email".into(),
+                value: Expr::String("john.doe@example.com".into())
             },
             SetClause {
-                column: "updated_at".to_string(),
-                value: Expr::String("2024-01-01".to_string())
+                column: "updated_at".into(),
+                value: Expr::String("2024-01-01".into())
             }
         ],
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("id".to_string())),
+            left: Box::new(Expr::Identifier("id".into())),
             op: BinaryOperator::Eq,
             right: Box::new(Expr::Number(1.0))
         })
@@ -49,19 +49,19 @@ sql_test!(
     test_update_with_expression,
     "UPDATE products SET price = price * 1.1 WHERE category = 'electronics';",
     Statement::Update(UpdateStatement {
-        table: "products".to_string(),
+        table: "products".into(),
         set_clauses: vec![SetClause {
-            column: "price".to_string(),
+            column: "price".into(),
             value: Expr::BinaryOp {
-                left: Box::new(Expr::Identifier("price".to_string())),
+                left: Box::new(Expr::Identifier("price".into())),
                 op: BinaryOperator::Multiply,
                 right: Box::new(Expr::Number(1.1))
             }
         }],
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Identifier("category".to_string())),
+            left: Box::new(Expr::Identifier("category".into())),
             op: BinaryOperator::Eq,
-            right: Box::new(Expr::String("electronics".to_string()))
+            right: Box::new(Expr::String("electronics".into()))
         })
     })
 );
@@ -70,9 +70,9 @@ sql_test!(
     test_update_no_where,
     "UPDATE settings SET enabled = true;",
     Statement::Update(UpdateStatement {
-        table: "settings".to_string(),
+        table: "settings".into(),
         set_clauses: vec![SetClause {
-            column: "enabled".to_string(),
+            column: "enabled".into(),
             value: Expr::Boolean(true)
         }],
         where_clause: None
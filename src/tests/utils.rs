@@ -1,3 +1,6 @@
+use crate::ast::{Expr, SelectItem, Statement};
+use crate::{parse_sql, simplify_sql};
+
 #[macro_export]
 macro_rules! sql_test {
     ($name:ident, $sql:expr, $expected:expr) => {
@@ -9,3 +12,35 @@ macro_rules! sql_test {
         }
     };
 }
+
+/// Asserts that the expression simplifies to the expected one, in a select list.
+pub(crate) fn assert_simplified(expr: &str, expected: &str) {
+    assert_simplified_sql(
+        &format!("SELECT {expr} FROM t;"),
+        &format!("SELECT {expected} FROM t;"),
+    );
+}
+
+/// Asserts that the WHERE condition simplifies to the expected one.
+pub(crate) fn assert_simplified_condition(condition: &str, expected: &str) {
+    assert_simplified_sql(
+        &format!("SELECT * FROM t WHERE {condition};"),
+        &format!("SELECT * FROM t WHERE {expected};"),
+    );
+}
+
+/// Asserts that the statement simplifies to the expected one.
+pub(crate) fn assert_simplified_sql(sql: &str, expected: &str) {
+    assert_eq!(simplify_sql(sql), parse_sql(expected), "{sql}");
+}
+
+/// Parses a single expression, written as the only item of a select list.
+pub(crate) fn parse_expr(expr: &str) -> Expr {
+    let Ok(Statement::Select(select)) = parse_sql(&format!("SELECT {expr};")) else {
+        panic!("Expected a SELECT statement: {expr}");
+    };
+    match select.columns.into_iter().next() {
+        Some(SelectItem::ExprWithAlias { expr, .. }) => expr,
+        item => panic!("Expected an expression: {item:?}"),
+    }
+}
@@ -0,0 +1,48 @@
+use crate::dialect::Dialect;
+use crate::validation;
+
+fn is_complete(input: &str) -> bool {
+    validation::is_complete(input, Dialect::default())
+}
+
+#[test]
+fn test_complete_statements() {
+    assert!(is_complete("SELECT 1;"));
+    assert!(is_complete("SELECT a\nFROM t\nWHERE a = 1;  "));
+    assert!(is_complete("SELECT 1; SELECT 2;"));
+    assert!(is_complete("SELECT 1; -- done"));
+    // Syntax errors are reported after the input is submitted.
+    assert!(is_complete("SELECT (1;"));
+    assert!(is_complete("SELECT 1 2; SELECT FROM;"));
+}
+
+#[test]
+fn test_incomplete_statements() {
+    assert!(!is_complete("SELECT 1"));
+    assert!(!is_complete("SELECT 1; SELECT 2"));
+    assert!(!is_complete("SELECT 'a;"));
+    assert!(!is_complete("SELECT 'a;b'"));
+    assert!(!is_complete("SELECT 1 -- ;"));
+    assert!(!is_complete("SELECT 1 /* ; */"));
+    assert!(!is_complete("SELECT 1 /* ;"));
+    assert!(!is_complete("SELECT \"a;"));
+    assert!(!is_complete("SELECT $$ ; "));
+    // The parser runs out of input inside the statement.
+    assert!(!is_complete("SELECT * FROM t WHERE"));
+}
+
+#[test]
+fn test_complete_statements_in_the_session_dialect() {
+    assert!(validation::is_complete("SELECT `a;", Dialect::Ansi));
+    assert!(!validation::is_complete("SELECT `a;", Dialect::MySql));
+    assert!(validation::is_complete("SELECT `a;`;", Dialect::MySql));
+    assert!(!validation::is_complete("SELECT [a;", Dialect::MsSql));
+}
+
+#[test]
+fn test_meta_commands_and_empty_input_are_complete() {
+    assert!(is_complete(""));
+    assert!(is_complete("   "));
+    assert!(is_complete(".help"));
+    assert!(is_complete(".exit"));
+}
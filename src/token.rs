@@ -1,39 +1,143 @@
+use crate::ast::write_identifier;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fmt;
 
+/// Declares the keywords recognized by the lexer, split into reserved and non-reserved words.
+///
+/// Reserved keywords can never be used as identifiers unless they are quoted.
+/// Non-reserved keywords only have a special meaning in some contexts, and the parser
+/// accepts them anywhere an identifier is expected.
 macro_rules! keywords {
-    ( $( $tok:ident ),+ $(,)? ) => {
-        lazy_static::lazy_static! {
-            pub(crate) static ref KEYWORDS: std::collections::HashMap<String, Token> = {
-                let mut m = std::collections::HashMap::new();
-                $(
-                    m.insert(stringify!($tok).to_lowercase(), Token::$tok);
-                )+
-                m
-            };
-        }
+    (
+        reserved: $( $reserved:ident ),+ $(,)?;
+        non_reserved: $( $non_reserved:ident ),+ $(,)?;
+    ) => {
+        /// Every keyword with its spelling, which is the name of its token variant.
+        pub(crate) static KEYWORDS: &[(&str, Token<'static>)] = &[
+            $( (stringify!($reserved), Token::$reserved), )+
+            $( (stringify!($non_reserved), Token::$non_reserved), )+
+        ];
+
+        /// Keyword spellings in the same order as [KEYWORDS], for the compile-time hash table.
+        const KEYWORD_NAMES: &[&str] = &[
+            $( stringify!($reserved), )+
+            $( stringify!($non_reserved), )+
+        ];
+
+        impl Token<'_> {
+            /// Returns true if the token is a keyword that can also be used as an identifier.
+            pub(crate) fn is_non_reserved_keyword(&self) -> bool {
+                matches!(self, $( Token::$non_reserved )|+)
+            }
 
-        pub(crate) fn keyword_to_token(ident: &str) -> Token {
-            KEYWORDS.get(&ident.to_lowercase()[..])
-                .cloned()
-                .unwrap_or(Token::Identifier(ident.to_string()))
+            /// Returns true if the token is a keyword, reserved or not.
+            pub(crate) fn is_keyword(&self) -> bool {
+                self.is_non_reserved_keyword() || matches!(self, $( Token::$reserved )|+)
+            }
         }
     };
 }
 
+/// Number of slots in the keyword hash table. Must be a power of two.
+const KEYWORD_SLOTS: usize = 1024;
+
+/// Perfect hash table over [KEYWORD_NAMES], built at compile time.
+/// Each slot holds the index of a keyword plus one, or zero if the slot is empty.
+struct KeywordTable {
+    seed: u32,
+    slots: [u8; KEYWORD_SLOTS],
+}
+
+static KEYWORD_TABLE: KeywordTable = build_keyword_table(KEYWORD_NAMES);
+
+/// Length of the longest keyword. Longer words are never hashed.
+const MAX_KEYWORD_LEN: usize = max_len(KEYWORD_NAMES);
+
+/// Case-insensitive FNV-1a hash of a word, salted with a seed.
+const fn keyword_hash(word: &[u8], seed: u32) -> usize {
+    let mut hash = 0x811c_9dc5 ^ seed;
+    let mut i = 0;
+    while i < word.len() {
+        hash ^= word[i].to_ascii_lowercase() as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+        i += 1;
+    }
+    (hash ^ (hash >> 16)) as usize & (KEYWORD_SLOTS - 1)
+}
+
+/// Tries seeds until every keyword hashes to its own slot.
+const fn build_keyword_table(words: &[&str]) -> KeywordTable {
+    assert!(
+        words.len() < u8::MAX as usize,
+        "Too many keywords for the hash table"
+    );
+    let mut seed = 0;
+    loop {
+        let mut slots = [0; KEYWORD_SLOTS];
+        let mut i = 0;
+        while i < words.len() {
+            let slot = keyword_hash(words[i].as_bytes(), seed);
+            if slots[slot] != 0 {
+                break;
+            }
+            slots[slot] = i as u8 + 1;
+            i += 1;
+        }
+        if i == words.len() {
+            return KeywordTable { seed, slots };
+        }
+        seed += 1;
+    }
+}
+
+const fn max_len(words: &[&str]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < words.len() {
+        if words[i].len() > max {
+            max = words[i].len();
+        }
+        i += 1;
+    }
+    max
+}
+
+/// Returns the keyword token for a word, ignoring case, or None if it is not a keyword.
+/// The lookup hashes the word once and compares it against a single candidate, without allocating.
+pub(crate) fn lookup_keyword(word: &str) -> Option<Token<'static>> {
+    if word.len() > MAX_KEYWORD_LEN {
+        return None;
+    }
+    let slot = KEYWORD_TABLE.slots[keyword_hash(word.as_bytes(), KEYWORD_TABLE.seed)];
+    let (name, token) = KEYWORDS.get(usize::from(slot).checked_sub(1)?)?;
+    name.eq_ignore_ascii_case(word).then(|| token.clone())
+}
+
+// Reserved words follow the SQL:2016 list, except for COLUMN, DEFAULT and END, which are
+// common column names and are only keywords where the grammar expects them: COLUMN after
+// ADD, DROP and ALTER, DEFAULT among column constraints and END at the end of a CASE.
+// Any keyword may be used as an alias after AS and as a name after a '.'.
+// Keywords that are not part of the standard (INDEX, LIMIT, LOCK...) are non-reserved.
 keywords! {
-    Select, From, Where, And, Or, Not, Like, In, Between, Is, Null, True, False,
-    Case, When, Then, Else, End, Order, By, Group, Having, Asc, Desc, Insert,
-    Into, Values, Update, Set, Delete, Create, Table, Drop, Limit, Join,
-    Inner, Outer, Full, Left, Right, Cross, Exists, Any, All, Some, On, As,
-    Distinct, Union, Intersect, Except, With, Recursive, Primary, Key, Foreign,
-    References, Unique, Index, View, Procedure, Function, Trigger, Database,
-    Schema, Grant, Revoke, Commit, Rollback, Transaction, Begin, End, Constraint,
-    Default, Check, Alter, Add, Column, Modify, Rename, To, Lock
+    reserved:
+        Select, From, Where, And, Or, Not, Like, In, Between, Is, Null, True, False,
+        Case, When, Then, Else, Order, By, Group, Having, Insert, Into, Values,
+        Update, Set, Delete, Create, Table, Drop, Join, Inner, Outer, Full, Left, Right,
+        Cross, Exists, Any, All, Some, On, As, Distinct, Union, Intersect, Except, With,
+        Recursive, Primary, Foreign, References, Unique, Procedure, Function, Trigger,
+        Grant, Revoke, Commit, Rollback, Begin, Constraint, Check, Alter, Add,
+        To, Natural, Using, Lateral, Unknown, Similar, Escape;
+    non_reserved:
+        Asc, Desc, Limit, Key, Index, View, Database, Schema, Transaction, Modify,
+        Rename, Lock, ILike, Materialized, Column, Default, End;
 }
 
+/// A token read by the [Lexer](crate::lexer::Lexer).
+/// Identifiers and literals borrow their text from the input whenever it needs no unescaping.
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) enum Token {
+pub(crate) enum Token<'a> {
     // Keywords
     Select,
     From,
@@ -116,11 +220,25 @@ pub(crate) enum Token {
     Rename,
     To,
     Lock,
+    Natural,
+    Using,
+    Lateral,
+    Unknown,
+    Similar,
+    Escape,
+    ILike,
+    Materialized,
 
     // Identifiers and literals
-    Identifier(String),
-    StringLiteral(String),
+    Identifier(Word<'a>),
+    StringLiteral(Cow<'a, str>),
     NumberLiteral(f64),
+    EscapeStringLiteral(String),                         // E'...'
+    NationalStringLiteral(Cow<'a, str>),                 // N'...'
+    HexStringLiteral(Vec<u8>),                           // X'...'
+    BitStringLiteral(Cow<'a, str>),                      // B'...'
+    UnicodeStringLiteral(String),                        // U&'...'
+    DollarQuotedString { tag: &'a str, value: &'a str }, // $tag$...$tag$
 
     // Symbols and operators
     Star,      // *
@@ -140,12 +258,76 @@ pub(crate) enum Token {
     Slash,     // /
     Percent,   // %
     Concat,    // ||
+    Tilde,     // ~
+    TildeStar, // ~*
+
+    /// Optimizer hint comment: `/*+ ... */`. Holds the text between the markers.
+    Hint(&'a str),
+
+    /// Invalid input, such as an unterminated string or a stray character.
+    Error {
+        message: String,
+        location: Location,
+    },
 
     // End of file
     Eof,
 }
 
-impl fmt::Display for Token {
+/// An identifier as written in the input, possibly quoted.
+/// Quoted identifiers only own their text when a doubled quote had to be resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Word<'a> {
+    pub(crate) value: Cow<'a, str>,
+    pub(crate) quote_style: Option<char>,
+}
+
+impl<'a> Word<'a> {
+    /// Creates a word quoted with the given opening quote.
+    pub(crate) fn with_quote(value: impl Into<Cow<'a, str>>, quote: char) -> Self {
+        Self {
+            value: value.into(),
+            quote_style: Some(quote),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Word<'a> {
+    fn from(value: &'a str) -> Self {
+        Self {
+            value: Cow::Borrowed(value),
+            quote_style: None,
+        }
+    }
+}
+
+impl fmt::Display for Word<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_identifier(f, &self.value, self.quote_style)
+    }
+}
+
+/// Byte range of a token in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) struct Span {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+/// Position of a character in the input. Lines and columns start at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub(crate) struct Location {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+impl fmt::Display for Token<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Token::Identifier(s) => write!(f, "{}", s),
@@ -168,6 +350,10 @@ impl fmt::Display for Token {
             Token::Slash => write!(f, "/"),
             Token::Percent => write!(f, "%"),
             Token::Concat => write!(f, "||"),
+            Token::Tilde => write!(f, "~"),
+            Token::TildeStar => write!(f, "~*"),
+            Token::Hint(hint) => write!(f, "/*+ {} */", hint),
+            Token::Error { message, location } => write!(f, "{} at {}", message, location),
             Token::Eof => write!(f, "EOF"),
             _ => write!(f, "{:?}", self),
         }
@@ -0,0 +1,350 @@
+//! Tree views of statements, printed with box-drawing guides:
+//!
+//! ```text
+//! SELECT
+//! ├── Columns
+//! │   └── name
+//! └── From
+//!     └── Table users
+//! ```
+use crate::ast::*;
+use std::fmt::{self, Display, Formatter};
+use std::mem;
+
+/// A labelled node and its children.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Tree {
+    pub(crate) label: String,
+    pub(crate) children: Vec<Tree>,
+}
+
+impl Tree {
+    pub(crate) fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            children: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_children(label: impl Into<String>, children: Vec<Tree>) -> Self {
+        Self {
+            label: label.into(),
+            children,
+        }
+    }
+
+    /// Adds a child node.
+    pub(crate) fn child(mut self, child: Tree) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Writes the children of the node, with a stack of the nodes being written rather than
+    /// recursively, as joins nest as deep as they are long.
+    fn write(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut stack = vec![(self.children.iter(), String::new())];
+        while let Some((children, prefix)) = stack.last_mut() {
+            let Some(child) = children.next() else {
+                stack.pop();
+                continue;
+            };
+            let (branch, guide) = if children.len() == 0 {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            writeln!(f, "{prefix}{branch}{}", child.label)?;
+            let prefix = format!("{prefix}{guide}");
+            stack.push((child.children.iter(), prefix));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Tree {
+    fn drop(&mut self) {
+        // Descendants are detached before their parent is dropped, for the same reason.
+        let mut nodes = mem::take(&mut self.children);
+        while let Some(mut node) = nodes.pop() {
+            nodes.append(&mut node.children);
+        }
+    }
+}
+
+impl Display for Tree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.label)?;
+        self.write(f)
+    }
+}
+
+/// Builds the syntax tree of a statement. Statements without clauses, such as DDL, are shown as
+/// a single node with their SQL text.
+pub(crate) fn statement_tree(stmt: &Statement) -> Tree {
+    match stmt {
+        Statement::With(with) => {
+            let label = if with.recursive {
+                "WITH RECURSIVE"
+            } else {
+                "WITH"
+            };
+            let mut tree = Tree::new(label);
+            for cte in &with.ctes {
+                let materialized = match cte.materialized {
+                    Some(true) => " MATERIALIZED",
+                    Some(false) => " NOT MATERIALIZED",
+                    None => "",
+                };
+                tree = tree.child(Tree::with_children(
+                    format!("CTE {}{materialized}", cte.name),
+                    vec![select_tree(&cte.query)],
+                ));
+            }
+            tree.child(select_tree(&with.body))
+        }
+        Statement::Select(select) => select_tree(select),
+        Statement::Insert(insert) => {
+            let mut label = format!("INSERT INTO {}", insert.table);
+            if let Some(columns) = &insert.columns {
+                label.push_str(&format!(" ({})", join(columns)));
+            }
+            let values = match &insert.values {
+                Values::Values(rows) => Tree::with_children(
+                    "Values",
+                    rows.iter()
+                        .map(|row| Tree::with_children("Row", row.iter().map(expr_tree).collect()))
+                        .collect(),
+                ),
+                Values::Query(query) => select_tree(query),
+            };
+            Tree::with_children(label, vec![values])
+        }
+        Statement::Update(update) => {
+            let mut tree = Tree::new(format!("UPDATE {}", update.table));
+            for clause in &update.set_clauses {
+                tree = tree.child(Tree::with_children(
+                    format!("Set {}", clause.column),
+                    vec![expr_tree(&clause.value)],
+                ));
+            }
+            if let Some(expr) = &update.where_clause {
+                tree = tree.child(Tree::with_children("Where", vec![expr_tree(expr)]));
+            }
+            tree
+        }
+        Statement::Delete(delete) => {
+            let mut tree = Tree::new(format!("DELETE FROM {}", delete.table));
+            if let Some(expr) = &delete.where_clause {
+                tree = tree.child(Tree::with_children("Where", vec![expr_tree(expr)]));
+            }
+            tree
+        }
+        stmt => Tree::new(stmt.to_string()),
+    }
+}
+
+/// Builds the syntax tree of a query, with a node per clause.
+pub(crate) fn select_tree(select: &SelectStatement) -> Tree {
+    let label = if select.distinct {
+        "SELECT DISTINCT"
+    } else {
+        "SELECT"
+    };
+    let columns = select
+        .columns
+        .iter()
+        .map(|item| match item {
+            SelectItem::ExprWithAlias {
+                expr,
+                alias: Some(alias),
+            } => Tree::with_children(format!("AS {alias}"), vec![expr_tree(expr)]),
+            SelectItem::ExprWithAlias { expr, alias: None } => expr_tree(expr),
+            item => Tree::new(item.to_string()),
+        })
+        .collect();
+    let mut tree = Tree::new(label).child(Tree::with_children("Columns", columns));
+    if let Some(from) = &select.from {
+        tree = tree.child(Tree::with_children("From", vec![table_tree(from)]));
+    }
+    if let Some(expr) = &select.where_clause {
+        tree = tree.child(Tree::with_children("Where", vec![expr_tree(expr)]));
+    }
+    if !select.group_by.is_empty() {
+        tree = tree.child(Tree::with_children(
+            "Group By",
+            select.group_by.iter().map(expr_tree).collect(),
+        ));
+    }
+    if let Some(expr) = &select.having {
+        tree = tree.child(Tree::with_children("Having", vec![expr_tree(expr)]));
+    }
+    if !select.order_by.is_empty() {
+        let items = select
+            .order_by
+            .iter()
+            .map(|item| {
+                let direction = if item.asc { "ASC" } else { "DESC" };
+                Tree::with_children(direction, vec![expr_tree(&item.expr)])
+            })
+            .collect();
+        tree = tree.child(Tree::with_children("Order By", items));
+    }
+    if let Some(limit) = select.limit {
+        tree = tree.child(Tree::new(format!("Limit {limit}")));
+    }
+    tree
+}
+
+fn table_tree(table: &TableReference) -> Tree {
+    match table {
+        TableReference::Table { name, alias } => match alias {
+            Some(alias) => Tree::new(format!("Table {name} AS {alias}")),
+            None => Tree::new(format!("Table {name}")),
+        },
+        TableReference::Subquery {
+            query,
+            alias,
+            lateral,
+        } => {
+            let lateral = if *lateral { "LATERAL " } else { "" };
+            Tree::with_children(
+                format!("{lateral}Subquery AS {alias}"),
+                vec![select_tree(query)],
+            )
+        }
+        TableReference::Join { .. } => {
+            // Each join of a chain is the first child of the next one.
+            let (first, joins) = table.join_chain();
+            let mut tree = table_tree(first);
+            for (join_type, right, constraint) in joins {
+                tree = Tree::with_children(
+                    join_label(join_type, constraint),
+                    vec![tree, table_tree(right)],
+                );
+                match constraint {
+                    JoinConstraint::On(expr) => {
+                        tree = tree.child(Tree::with_children("On", vec![expr_tree(expr)]))
+                    }
+                    JoinConstraint::Using(columns) => {
+                        tree = tree.child(Tree::new(format!("Using ({})", join(columns))))
+                    }
+                    JoinConstraint::Natural | JoinConstraint::None => {}
+                }
+            }
+            tree
+        }
+        TableReference::Error => Tree::new("<error>"),
+    }
+}
+
+/// Name of a join, as written in SQL: `LEFT JOIN`, `NATURAL JOIN`...
+pub(crate) fn join_label(join_type: &JoinType, constraint: &JoinConstraint) -> String {
+    let join = match join_type {
+        JoinType::Inner => "JOIN",
+        JoinType::Left => "LEFT JOIN",
+        JoinType::Right => "RIGHT JOIN",
+        JoinType::Full => "FULL JOIN",
+        JoinType::Cross => "CROSS JOIN",
+    };
+    match constraint {
+        JoinConstraint::Natural => format!("NATURAL {join}"),
+        _ => join.to_string(),
+    }
+}
+
+/// Builds the tree of an expression. Chains of the same binary operator, such as `a AND b AND c`,
+/// become a single node with one child per operand, so long chains stay flat.
+fn expr_tree(expr: &Expr) -> Tree {
+    match expr {
+        Expr::BinaryOp { op, .. } => {
+            // Walk down the left spine of the chain, collecting the right operands.
+            let mut operands = Vec::new();
+            let mut current = expr;
+            while let Expr::BinaryOp {
+                left,
+                op: current_op,
+                right,
+            } = current
+                && current_op == op
+            {
+                operands.push(right.as_ref());
+                current = left;
+            }
+            operands.push(current);
+            Tree::with_children(
+                op.to_string(),
+                operands.into_iter().rev().map(expr_tree).collect(),
+            )
+        }
+        Expr::UnaryOp { op, expr } => {
+            let label = match op {
+                UnaryOperator::Plus => "+",
+                UnaryOperator::Minus => "-",
+                UnaryOperator::Not => "NOT",
+            };
+            Tree::with_children(label, vec![expr_tree(expr)])
+        }
+        Expr::FunctionCall {
+            name,
+            args,
+            distinct,
+        } => {
+            let label = if *distinct {
+                format!("{name}(DISTINCT)")
+            } else {
+                format!("{name}()")
+            };
+            Tree::with_children(label, args.iter().map(expr_tree).collect())
+        }
+        Expr::Case {
+            operand,
+            when_clauses,
+            else_clause,
+        } => {
+            let mut tree = Tree::new("CASE");
+            if let Some(operand) = operand {
+                tree = tree.child(expr_tree(operand));
+            }
+            for clause in when_clauses {
+                tree = tree.child(Tree::with_children(
+                    "WHEN",
+                    vec![
+                        expr_tree(&clause.condition),
+                        Tree::with_children("THEN", vec![expr_tree(&clause.result)]),
+                    ],
+                ));
+            }
+            if let Some(else_clause) = else_clause {
+                tree = tree.child(Tree::with_children("ELSE", vec![expr_tree(else_clause)]));
+            }
+            tree
+        }
+        Expr::Subquery(query) => Tree::with_children("Subquery", vec![select_tree(query)]),
+        Expr::Exists(query) => Tree::with_children("EXISTS", vec![select_tree(query)]),
+        Expr::List(items) => Tree::with_children("List", items.iter().map(expr_tree).collect()),
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => {
+            let label = if *negated { "NOT BETWEEN" } else { "BETWEEN" };
+            Tree::with_children(
+                label,
+                vec![expr_tree(expr), expr_tree(low), expr_tree(high)],
+            )
+        }
+        Expr::Escape { pattern, escape } => {
+            Tree::with_children("ESCAPE", vec![expr_tree(pattern), expr_tree(escape)])
+        }
+        expr => Tree::new(expr.to_string()),
+    }
+}
+
+fn join<T: Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,33 @@
+//! Decides when the REPL input forms complete statements, so that Enter either submits them or
+//! starts a new line.
+use crate::completion::SqlHelper;
+use crate::dialect::Dialect;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use rustyline::Result;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+
+/// Returns true if the input is ready to be submitted: it is empty, it is a meta-command such as
+/// `.help`, or the parser does not run out of input before the semicolon that ends the last
+/// statement.
+///
+/// The input is tokenized in the session dialect, so a `;` inside a string, a quoted identifier or
+/// a comment does not end anything, and input that ends inside one of them is never complete.
+/// Syntax errors do not make the input incomplete: they are reported once it is submitted.
+pub(crate) fn is_complete(input: &str, dialect: Dialect) -> bool {
+    let input = input.trim();
+    if input.is_empty() || input.starts_with('.') {
+        return true;
+    }
+    !Parser::new(Lexer::with_dialect(input, dialect)).ends_inside_statement()
+}
+
+impl Validator for SqlHelper {
+    fn validate(&self, ctx: &mut ValidationContext) -> Result<ValidationResult> {
+        Ok(if is_complete(ctx.input(), self.dialect) {
+            ValidationResult::Valid(None)
+        } else {
+            ValidationResult::Incomplete
+        })
+    }
+}
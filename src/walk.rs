@@ -0,0 +1,240 @@
+//! Traversal of every expression, or every query, of a statement, for rewrites that apply wherever
+//! an expression or a query appears, including nested queries.
+//!
+//! Children are visited before their parents, so a rewrite sees operands that have already been
+//! rewritten, as in [Simplify](crate::simplify::Simplify).
+use crate::ast::*;
+use std::mem;
+
+/// Calls `f` on every expression of the statement.
+pub(crate) fn walk_statement(stmt: &mut Statement, f: &mut dyn FnMut(&mut Expr)) {
+    match stmt {
+        Statement::With(with) => {
+            for cte in &mut with.ctes {
+                walk_select(&mut cte.query, f);
+            }
+            walk_select(&mut with.body, f);
+        }
+        Statement::Select(select) => walk_select(select, f),
+        Statement::Insert(insert) => match &mut insert.values {
+            Values::Values(rows) => {
+                for value in rows.iter_mut().flatten() {
+                    walk_expr(value, f);
+                }
+            }
+            Values::Query(query) => walk_select(query, f),
+        },
+        Statement::Update(update) => {
+            for clause in &mut update.set_clauses {
+                walk_expr(&mut clause.value, f);
+            }
+            if let Some(condition) = &mut update.where_clause {
+                walk_expr(condition, f);
+            }
+        }
+        Statement::Delete(delete) => {
+            if let Some(condition) = &mut delete.where_clause {
+                walk_expr(condition, f);
+            }
+        }
+        Statement::CreateTable(create) => {
+            for column in &mut create.columns {
+                walk_column(column, f);
+            }
+            for constraint in &mut create.constraints {
+                if let TableConstraint::Check(expr) = constraint {
+                    walk_expr(expr, f);
+                }
+            }
+        }
+        Statement::AlterTable(alter) => match &mut alter.action {
+            AlterAction::AddColumn(column) => walk_column(column, f),
+            AlterAction::AddConstraint(TableConstraint::Check(expr)) => walk_expr(expr, f),
+            AlterAction::AlterColumn(AlterColumnStatement {
+                action: AlterColumnAction::SetDefault(expr),
+                ..
+            }) => walk_expr(expr, f),
+            _ => {}
+        },
+        Statement::DropTable(_) | Statement::CreateIndex(_) | Statement::Transaction(_) => {}
+    }
+}
+
+/// Calls `f` on every expression of the query, in the order of its clauses.
+pub(crate) fn walk_select(select: &mut SelectStatement, f: &mut dyn FnMut(&mut Expr)) {
+    for item in &mut select.columns {
+        match item {
+            SelectItem::ExprWithAlias { expr, .. } => walk_expr(expr, f),
+            SelectItem::Star(options) | SelectItem::QualifiedWildcard(_, options) => {
+                for replace in &mut options.replace {
+                    walk_expr(&mut replace.expr, f);
+                }
+            }
+        }
+    }
+    if let Some(from) = &mut select.from {
+        walk_table(from, f);
+    }
+    if let Some(condition) = &mut select.where_clause {
+        walk_expr(condition, f);
+    }
+    for expr in &mut select.group_by {
+        walk_expr(expr, f);
+    }
+    if let Some(condition) = &mut select.having {
+        walk_expr(condition, f);
+    }
+    for item in &mut select.order_by {
+        walk_expr(&mut item.expr, f);
+    }
+}
+
+fn walk_table(table: &mut TableReference, f: &mut dyn FnMut(&mut Expr)) {
+    let (first, joins) = table.join_chain_mut();
+    if let TableReference::Subquery { query, .. } = first {
+        walk_select(query, f);
+    }
+    for (_, right, constraint) in joins {
+        walk_table(right, f);
+        if let JoinConstraint::On(condition) = constraint {
+            walk_expr(condition, f);
+        }
+    }
+}
+
+fn walk_column(column: &mut ColumnDef, f: &mut dyn FnMut(&mut Expr)) {
+    for constraint in &mut column.constraints {
+        if let ColumnConstraint::Check(expr) | ColumnConstraint::Default(expr) = constraint {
+            walk_expr(expr, f);
+        }
+    }
+}
+
+/// Calls `f` on the expression and every expression nested in it, including those of subqueries.
+pub(crate) fn walk_expr(expr: &mut Expr, f: &mut dyn FnMut(&mut Expr)) {
+    // Binary operators are detached from their left operands and visited from the innermost one,
+    // so that long chains of operators are walked with a loop.
+    let mut links = Vec::new();
+    let mut current = mem::replace(expr, Expr::Null);
+    while let Expr::BinaryOp { left, .. } = &mut current {
+        let left = mem::replace(&mut **left, Expr::Null);
+        links.push(current);
+        current = left;
+    }
+
+    if let Expr::Subquery(query) | Expr::Exists(query) = &mut current {
+        walk_select(query, f);
+    }
+    for child in current.children_mut() {
+        walk_expr(child, f);
+    }
+    f(&mut current);
+
+    while let Some(mut link) = links.pop() {
+        if let Expr::BinaryOp { left, right, .. } = &mut link {
+            walk_expr(right, f);
+            **left = current;
+        }
+        f(&mut link);
+        current = link;
+    }
+    *expr = current;
+}
+
+/// Calls `f` on every query of the statement, the queries nested in a query before it.
+pub(crate) fn walk_statement_queries(
+    stmt: &mut Statement,
+    f: &mut dyn FnMut(&mut SelectStatement),
+) {
+    match stmt {
+        Statement::With(with) => {
+            for cte in &mut with.ctes {
+                walk_queries(&mut cte.query, f);
+            }
+            walk_queries(&mut with.body, f);
+        }
+        Statement::Select(select) => walk_queries(select, f),
+        Statement::Insert(insert) => match &mut insert.values {
+            Values::Values(rows) => {
+                for query in nested_queries(rows.iter_mut().flatten().collect()) {
+                    walk_queries(query, f);
+                }
+            }
+            Values::Query(query) => walk_queries(query, f),
+        },
+        Statement::Update(update) => {
+            let mut exprs: Vec<&mut Expr> = update
+                .set_clauses
+                .iter_mut()
+                .map(|clause| &mut clause.value)
+                .collect();
+            exprs.extend(update.where_clause.as_mut());
+            for query in nested_queries(exprs) {
+                walk_queries(query, f);
+            }
+        }
+        Statement::Delete(delete) => {
+            for query in nested_queries(delete.where_clause.iter_mut().collect()) {
+                walk_queries(query, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Calls `f` on the query and every query nested in it, derived tables and subqueries, each one
+/// after the queries nested in it.
+pub(crate) fn walk_queries(select: &mut SelectStatement, f: &mut dyn FnMut(&mut SelectStatement)) {
+    let mut exprs: Vec<&mut Expr> = Vec::new();
+    let mut queries: Vec<&mut SelectStatement> = Vec::new();
+    for item in &mut select.columns {
+        match item {
+            SelectItem::ExprWithAlias { expr, .. } => exprs.push(expr),
+            SelectItem::Star(options) | SelectItem::QualifiedWildcard(_, options) => {
+                exprs.extend(options.replace.iter_mut().map(|replace| &mut replace.expr));
+            }
+        }
+    }
+    if let Some(from) = &mut select.from {
+        table_parts(from, &mut queries, &mut exprs);
+    }
+    exprs.extend(select.where_clause.as_mut());
+    exprs.extend(&mut select.group_by);
+    exprs.extend(select.having.as_mut());
+    exprs.extend(select.order_by.iter_mut().map(|item| &mut item.expr));
+    queries.extend(nested_queries(exprs));
+    for query in queries {
+        walk_queries(query, f);
+    }
+    f(select);
+}
+
+/// Collects the derived tables and the join conditions of a FROM clause.
+fn table_parts<'a>(
+    table: &'a mut TableReference,
+    queries: &mut Vec<&'a mut SelectStatement>,
+    exprs: &mut Vec<&'a mut Expr>,
+) {
+    let (first, joins) = table.join_chain_mut();
+    if let TableReference::Subquery { query, .. } = first {
+        queries.push(query);
+    }
+    for (_, right, constraint) in joins {
+        table_parts(right, queries, exprs);
+        if let JoinConstraint::On(condition) = constraint {
+            exprs.push(condition);
+        }
+    }
+}
+
+/// The subqueries of the expressions, without those nested in other subqueries.
+fn nested_queries(mut exprs: Vec<&mut Expr>) -> Vec<&mut SelectStatement> {
+    let mut queries = Vec::new();
+    while let Some(expr) = exprs.pop() {
+        match expr {
+            Expr::Subquery(query) | Expr::Exists(query) => queries.push(&mut **query),
+            expr => exprs.extend(expr.children_mut()),
+        }
+    }
+    queries
+}